@@ -1,4 +1,18 @@
+pub mod logging;
+pub mod render;
+
+use crate::core::FileType;
+use crate::core::config::Effort;
+use crate::core::report::ReportTemplate;
+use crate::core::site::Engine;
+use crate::core::transcript::sanitize_language;
+use crate::core::{
+    CleanOptions, DEFAULT_FETCH_CONCURRENCY, DEFAULT_PARAGRAPH_GAP, TranscriptFormat,
+};
+use chrono::NaiveDate;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(name = "vidio")]
@@ -11,14 +25,40 @@ pub struct Cli {
     /// Force CLI mode (skip TUI)
     #[arg(long)]
     pub cli: bool,
+
+    /// Draw the TUI with ASCII glyphs only, for terminals that show
+    /// checkboxes, emoji or box drawing as garbage
+    #[arg(long)]
+    pub ascii: bool,
+
+    /// Print long output directly instead of through $PAGER
+    #[arg(long, global = true)]
+    pub no_pager: bool,
+
+    /// Print results as JSON (list, info, channels list, trash list)
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Only print warnings and errors besides the command's output
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Also print debug messages, e.g. request retries (RUST_LOG overrides)
+    #[arg(short, long, global = true, conflicts_with = "quiet")]
+    pub verbose: bool,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
-    /// Download transcript and optionally generate report
+    /// Download transcripts and optionally generate reports
     Get {
-        /// YouTube video URL or video ID
-        video_id: String,
+        /// YouTube video URLs or video IDs
+        #[arg(required_unless_present = "resume", conflicts_with = "resume")]
+        video_ids: Vec<String>,
+
+        /// Maximum downloads in flight when several videos are given
+        #[arg(short, long, default_value_t = DEFAULT_FETCH_CONCURRENCY)]
+        jobs: usize,
 
         /// Preferred languages (comma-separated)
         #[arg(short, long, default_value = "en,es")]
@@ -28,20 +68,518 @@ pub enum Commands {
         #[arg(long)]
         preserve_formatting: bool,
 
+        /// Save a YouTube machine translation into this language (e.g. es),
+        /// as transcript_<id>.<lang>.txt
+        #[arg(long, value_name = "LANG", value_parser = parse_language)]
+        translate: Option<String>,
+
+        /// Fail instead of using auto-generated captions when a video has no
+        /// manual ones in the requested languages
+        #[arg(long)]
+        manual_only: bool,
+
+        /// Caption artifacts to strip before saving: all, none, or any of
+        /// cues ([Music], [Applause]), duplicates (repeated lines) and
+        /// entities (&amp;, &#39;), comma-separated
+        #[arg(long, value_name = "STEPS", default_value = "all", value_parser = parse_clean)]
+        clean: CleanOptions,
+
+        /// timestamped, plain to also save the transcript as running text in
+        /// transcript_<id>.plain.txt, or md to also save it as Markdown with a
+        /// heading per chapter or pause in transcript_<id>.md
+        #[arg(long, default_value = "timestamped", value_parser = parse_format, conflicts_with = "translate")]
+        format: TranscriptFormat,
+
+        /// Seconds of silence that start a new paragraph in plain text, or a
+        /// new section in Markdown when the video has no chapters
+        #[arg(long, value_name = "SECS", default_value_t = DEFAULT_PARAGRAPH_GAP, value_parser = parse_paragraph_gap)]
+        paragraph_gap: f64,
+
         /// Generate report after downloading transcript
         #[arg(short, long)]
         report: bool,
+
+        /// Report template: detailed, summary, bullets, qa, chapters or custom
+        #[arg(short, long, default_value = "detailed", value_parser = parse_template)]
+        template: ReportTemplate,
+
+        /// Your own report prompt, as for `vidio report`; implies --report
+        #[arg(long, value_name = "PATH", conflicts_with = "template")]
+        prompt_file: Option<PathBuf>,
+
+        /// Language to write the report in, e.g. en (default: the
+        /// transcript's language)
+        #[arg(long, value_name = "LANG", value_parser = parse_language)]
+        report_language: Option<String>,
+
+        /// OpenAI model for the report (default: $VIDIO_MODEL, then
+        /// vidio.toml, then gpt-5.2)
+        #[arg(long)]
+        model: Option<String>,
+
+        /// How long reasoning models think: low, medium or high (default:
+        /// vidio.toml, then high)
+        #[arg(long, value_name = "EFFORT", value_parser = parse_effort)]
+        reasoning_effort: Option<Effort>,
+
+        /// Most tokens the report may take, reasoning included (default:
+        /// vidio.toml, then 128000)
+        #[arg(long, value_name = "TOKENS", value_parser = clap::value_parser!(u32).range(1..))]
+        max_output_tokens: Option<u32>,
+
+        /// Generate the report without asking to confirm its estimated cost
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Generate the report again even if it is up to date with the
+        /// transcript; the old one is kept in reports/history
+        #[arg(long)]
+        force: bool,
+
+        /// Carry on with an interrupted batch from its batch_<time>.json
+        /// manifest (default: the newest unfinished one), skipping videos
+        /// already done and retrying failed ones
+        #[arg(long, value_name = "MANIFEST", num_args = 0..=1)]
+        resume: Option<Option<PathBuf>>,
+
+        /// Print the prompts the report would be generated with instead of
+        /// sending them; transcripts are still downloaded
+        #[arg(long, conflicts_with = "resume")]
+        dry_run: bool,
     },
 
     /// Generate report from existing transcript
     Report {
         /// Video ID of existing transcript
         video_id: String,
+
+        /// Report template: detailed, summary, bullets, qa, chapters or custom
+        #[arg(short, long, default_value = "detailed", value_parser = parse_template)]
+        template: ReportTemplate,
+
+        /// Your own prompt; it must contain {{transcript}} and may use
+        /// {{title}}, {{video_id}} and {{language}}. Saved as the custom template
+        #[arg(long, value_name = "PATH", conflicts_with = "template")]
+        prompt_file: Option<PathBuf>,
+
+        /// Language to write the report in, e.g. en (default: the
+        /// transcript's language)
+        #[arg(long, value_name = "LANG", value_parser = parse_language)]
+        report_language: Option<String>,
+
+        /// OpenAI model for the report (default: $VIDIO_MODEL, then
+        /// vidio.toml, then gpt-5.2)
+        #[arg(long)]
+        model: Option<String>,
+
+        /// How long reasoning models think: low, medium or high (default:
+        /// vidio.toml, then high)
+        #[arg(long, value_name = "EFFORT", value_parser = parse_effort)]
+        reasoning_effort: Option<Effort>,
+
+        /// Most tokens the report may take, reasoning included (default:
+        /// vidio.toml, then 128000)
+        #[arg(long, value_name = "TOKENS", value_parser = clap::value_parser!(u32).range(1..))]
+        max_output_tokens: Option<u32>,
+
+        /// Generate the report without asking to confirm its estimated cost
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Print the prompts the report would be generated with instead of
+        /// sending them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Generate the report again even if it is up to date with the
+        /// transcript; the old one is kept in reports/history
+        #[arg(long)]
+        force: bool,
     },
 
     /// List all downloaded transcripts and reports
-    List,
+    List {
+        /// Only list files matching a search saved in the TUI Browser
+        #[arg(long, value_name = "NAME")]
+        saved: Option<String>,
+
+        /// Only files modified on or after this day (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE", value_parser = parse_date)]
+        since: Option<NaiveDate>,
+
+        /// Only files modified on or before this day (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE", value_parser = parse_date)]
+        until: Option<NaiveDate>,
+
+        /// Only transcripts or only reports
+        #[arg(long = "type", value_name = "TYPE", value_parser = parse_file_type)]
+        file_type: Option<FileType>,
+    },
+
+    /// Print a saved report, warning about any corruption found in it
+    Show {
+        /// Video ID of the report
+        video_id: String,
+
+        /// Render the Markdown with terminal colors instead of printing it raw
+        #[arg(long)]
+        render: bool,
+
+        /// Report template: detailed, summary, bullets, qa, chapters or custom
+        #[arg(short, long, default_value = "detailed", value_parser = parse_template)]
+        template: ReportTemplate,
+    },
+
+    /// Show what changed in a report since its last saved version
+    Diff {
+        /// Video ID of the report
+        video_id: String,
+
+        /// Compare against this file instead of the newest copy in reports/history
+        #[arg(long)]
+        against: Option<PathBuf>,
+
+        /// Report template: detailed, summary, bullets, qa, chapters or custom
+        #[arg(short, long, default_value = "detailed", value_parser = parse_template)]
+        template: ReportTemplate,
+    },
+
+    /// Show what is stored for a video: transcript, report and their details
+    Info {
+        /// YouTube video URL or video ID
+        video_id: String,
+    },
+
+    /// Repair a corrupt report, keeping the damaged original in reports/history
+    Repair {
+        /// Video ID of the report
+        video_id: String,
+
+        /// Side to keep from sync conflict blocks (ours, theirs)
+        #[arg(long, default_value = "ours")]
+        keep: String,
+
+        /// Report template: detailed, summary, bullets, qa, chapters or custom
+        #[arg(short, long, default_value = "detailed", value_parser = parse_template)]
+        template: ReportTemplate,
+    },
+
+    /// Import an embedded subtitle track from a local video file as a transcript
+    ImportMedia {
+        /// Video ID or label to store the transcript under
+        video_id: String,
+
+        /// Path to the media file (mkv, mp4, ...)
+        path: PathBuf,
+
+        /// Subtitle stream index to import (prompted for when there are several)
+        #[arg(long)]
+        stream: Option<u32>,
+    },
+
+    /// Delete the transcript and report stored for a video
+    Delete {
+        /// YouTube video URL or video ID
+        video_id: String,
+
+        /// Only delete the transcript, with its metadata and bookmarks
+        #[arg(long, conflicts_with = "report_only")]
+        transcript_only: bool,
+
+        /// Only delete the report
+        #[arg(long)]
+        report_only: bool,
+
+        /// Do not ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Rename the transcript, reports and other files stored for a video
+    Rename {
+        /// YouTube video URL or video ID
+        video_id: String,
+
+        /// New name to store the files under instead of the video ID
+        #[arg(required_unless_present = "use_title", conflicts_with = "use_title")]
+        new_stem: Option<String>,
+
+        /// Name the files after the video title from its metadata
+        #[arg(long)]
+        use_title: bool,
+    },
+
+    /// Delete old transcripts and reports, or files left without a transcript
+    Clean {
+        /// Age limit, e.g. 30d, 12h or 2w
+        #[arg(long, value_parser = parse_age, required_unless_present = "orphans")]
+        older_than: Option<Duration>,
+
+        /// Delete reports, metadata and bookmarks whose transcript is gone
+        #[arg(long)]
+        orphans: bool,
+
+        /// Only print what would be removed
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Do not ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// List, restore or empty deleted files
+    Trash {
+        #[command(subcommand)]
+        action: TrashCommand,
+    },
+
+    /// Show how much disk space transcripts and reports take
+    #[command(visible_alias = "usage")]
+    Stats,
+
+    /// List the URLs recently submitted in the TUI, marking those already
+    /// transcribed
+    History {
+        /// Forget every URL instead
+        #[arg(long)]
+        clear: bool,
+    },
+
+    /// Export the library as content for a static site generator
+    ExportSite {
+        /// Directory to write the site content into
+        #[arg(long)]
+        dest: PathBuf,
+
+        /// Site generator to write for (hugo, zola, mdbook)
+        #[arg(long, default_value = "hugo", value_parser = parse_engine)]
+        engine: Engine,
+
+        /// Remove pages of videos no longer in the library
+        #[arg(long)]
+        prune: bool,
+    },
+
+    /// Watch YouTube channels and archive their new uploads
+    Channels {
+        #[command(subcommand)]
+        action: ChannelsCommand,
+    },
+
+    /// Manage timestamp bookmarks for a transcript
+    Bookmarks {
+        /// YouTube video URL or video ID
+        video_id: String,
+
+        /// Add a bookmark at a timestamp (e.g. 14:32 or 01:02:03)
+        #[arg(long, conflicts_with_all = ["list", "remove"])]
+        add: Option<String>,
+
+        /// Note to attach to the new bookmark
+        #[arg(long, requires = "add")]
+        note: Option<String>,
+
+        /// List bookmarks (default when no other option is given)
+        #[arg(long)]
+        list: bool,
+
+        /// Remove the bookmark with this number from --list
+        #[arg(long, conflicts_with = "list")]
+        remove: Option<usize>,
+    },
+
+    /// Render a report template against a fixture and lint the response
+    PromptTest {
+        /// Template to render (detailed, summary, bullets, qa, chapters)
+        #[arg(default_value = "detailed")]
+        template: String,
+
+        /// Transcript fixture file, or "demo" for the bundled one
+        #[arg(long, default_value = "demo")]
+        fixture: String,
+
+        /// Use a stored response instead of calling the model ("demo" for the bundled one)
+        #[arg(long)]
+        canned: Option<String>,
+
+        /// Lint profile to check against (detailed, basic)
+        #[arg(long, default_value = "detailed")]
+        expect: String,
+    },
 
     /// Open TUI interface
     Tui,
 }
+
+#[derive(Subcommand)]
+pub enum ChannelsCommand {
+    /// Start watching a channel
+    Add {
+        /// Channel URL, @handle or channel ID
+        channel: String,
+
+        /// Generate a report for each new upload
+        #[arg(short, long)]
+        report: bool,
+    },
+
+    /// List watched channels with their last sync
+    List,
+
+    /// Download transcripts for new uploads of every watched channel
+    Sync {
+        /// Only consider uploads from the last N days
+        #[arg(long)]
+        since: Option<u32>,
+
+        /// Preferred languages (comma-separated)
+        #[arg(short, long, default_value = "en,es")]
+        languages: String,
+    },
+
+    /// Never download a video during sync (e.g. one without captions)
+    Ignore {
+        /// YouTube video URL or video ID
+        video_id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TrashCommand {
+    /// List deleted files, most recent first
+    List,
+
+    /// Put a deleted file back where it was
+    Restore {
+        /// Name or number shown by `trash list`
+        name: String,
+    },
+
+    /// Permanently delete everything in the trash
+    Empty {
+        /// Do not ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+/// Parse an age like `30d`: a whole number followed by h (hours), d (days)
+/// or w (weeks).
+fn parse_age(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let invalid = || format!("invalid age '{value}', expected e.g. 12h, 30d or 2w");
+    let (split, _) = value.char_indices().last().ok_or_else(invalid)?;
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number.parse().map_err(|_| invalid())?;
+    let hours_per_unit = match unit {
+        "h" => 1,
+        "d" => 24,
+        "w" => 24 * 7,
+        _ => return Err(invalid()),
+    };
+    let seconds = number
+        .checked_mul(hours_per_unit * 60 * 60)
+        .ok_or_else(|| format!("age '{value}' is too large"))?;
+    Ok(Duration::from_secs(seconds))
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("invalid date '{value}', expected YYYY-MM-DD"))
+}
+
+fn parse_paragraph_gap(value: &str) -> Result<f64, String> {
+    match value.trim().parse::<f64>() {
+        Ok(secs) if secs.is_finite() && secs >= 0.0 => Ok(secs),
+        _ => Err(format!(
+            "invalid gap '{value}', expected a number of seconds, e.g. 2.5"
+        )),
+    }
+}
+
+fn parse_file_type(value: &str) -> Result<FileType, String> {
+    match value.trim() {
+        "transcript" | "transcripts" => Ok(FileType::Transcript),
+        "report" | "reports" => Ok(FileType::Report),
+        _ => Err(format!(
+            "unknown type '{value}', expected transcript or report"
+        )),
+    }
+}
+
+fn parse_template(value: &str) -> Result<ReportTemplate, String> {
+    ReportTemplate::parse(value).map_err(|e| e.to_string())
+}
+
+fn parse_clean(value: &str) -> Result<CleanOptions, String> {
+    CleanOptions::parse(value).map_err(|e| e.to_string())
+}
+
+fn parse_format(value: &str) -> Result<TranscriptFormat, String> {
+    TranscriptFormat::parse(value).map_err(|e| e.to_string())
+}
+
+fn parse_effort(value: &str) -> Result<Effort, String> {
+    Effort::from_name(value)
+        .ok_or_else(|| format!("unknown effort '{value}', expected low, medium or high"))
+}
+
+fn parse_language(value: &str) -> Result<String, String> {
+    sanitize_language(value).map_err(|e| e.to_string())
+}
+
+fn parse_engine(value: &str) -> Result<Engine, String> {
+    Engine::from_name(value)
+        .ok_or_else(|| format!("unknown engine '{value}', expected hugo, zola or mdbook"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_age, parse_date, parse_file_type, parse_paragraph_gap};
+    use crate::core::FileType;
+    use chrono::NaiveDate;
+    use std::time::Duration;
+
+    #[test]
+    fn parses_ages_in_hours_days_and_weeks() {
+        assert_eq!(parse_age("12h"), Ok(Duration::from_secs(12 * 3600)));
+        assert_eq!(parse_age("30d"), Ok(Duration::from_secs(30 * 86400)));
+        assert_eq!(parse_age("2w"), Ok(Duration::from_secs(14 * 86400)));
+        for bad in [
+            "",
+            "d",
+            "30",
+            "30m",
+            "-1d",
+            "1.5d",
+            "3é",
+            "18446744073709551615w",
+        ] {
+            assert!(parse_age(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn paragraph_gaps_are_finite_and_not_negative() {
+        assert_eq!(parse_paragraph_gap("2.5"), Ok(2.5));
+        assert_eq!(parse_paragraph_gap("0"), Ok(0.0));
+        for bad in ["", "-1", "NaN", "inf", "-inf", "2s"] {
+            assert!(parse_paragraph_gap(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn parses_list_filters() {
+        assert_eq!(
+            parse_date("2025-03-09"),
+            Ok(NaiveDate::from_ymd_opt(2025, 3, 9).unwrap())
+        );
+        for bad in ["", "2025-3", "09/03/2025", "2025-02-30"] {
+            assert!(parse_date(bad).is_err(), "{bad}");
+        }
+        assert_eq!(parse_file_type("report"), Ok(FileType::Report));
+        assert_eq!(parse_file_type("transcripts"), Ok(FileType::Transcript));
+        assert!(parse_file_type("video").is_err());
+    }
+}
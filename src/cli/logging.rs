@@ -0,0 +1,27 @@
+// Status messages from the core go through `log`. On the command line they
+// are printed to stderr, leaving stdout to what the command was asked for.
+use env_logger::{Builder, Env};
+use log::{Level, LevelFilter};
+use std::io::Write;
+
+/// Print vidio's info messages, only warnings with `quiet` or debug
+/// messages too with `verbose`. `RUST_LOG` takes precedence, e.g.
+/// `RUST_LOG=vidio=trace,reqwest=debug`.
+pub fn init(quiet: bool, verbose: bool) {
+    let level = if quiet {
+        LevelFilter::Warn
+    } else if verbose {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    };
+    Builder::new()
+        .filter_level(LevelFilter::Warn)
+        .filter_module("vidio", level)
+        .parse_env(Env::default())
+        .format(|buf, record| match record.level() {
+            Level::Info => writeln!(buf, "{}", record.args()),
+            level => writeln!(buf, "{}: {}", level.as_str().to_lowercase(), record.args()),
+        })
+        .init();
+}
@@ -0,0 +1,271 @@
+// Terminal output shared by CLI subcommands: Markdown rendered with the same
+// pulldown-cmark traversal as the TUI Viewer but emitted as ANSI escapes,
+// colorized unified diffs, and paging of long output through $PAGER.
+use crate::error::Result;
+use crate::tui::Theme;
+use crate::tui::components::viewer::parse_markdown_to_lines;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use similar::TextDiff;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+const DEFAULT_WIDTH: usize = 100;
+const DEFAULT_PAGER: &str = "less";
+const RESET: &str = "\x1b[0m";
+
+/// How output should be written, decided once per command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Output {
+    pub color: bool,
+    pub pager: bool,
+}
+
+impl Output {
+    /// Color only on a terminal and when `NO_COLOR` is unset or empty.
+    pub fn from_env(no_pager: bool) -> Self {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Self {
+            color: io::stdout().is_terminal() && !no_color,
+            pager: !no_pager,
+        }
+    }
+}
+
+/// Terminal width for wrapping rendered Markdown.
+pub fn terminal_width() -> usize {
+    crossterm::terminal::size()
+        .map(|(width, _)| width as usize)
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+/// Page when writing to a terminal whose height is known and the content does
+/// not fit on one screen.
+pub fn should_page(
+    output: Output,
+    is_tty: bool,
+    line_count: usize,
+    terminal_height: Option<u16>,
+) -> bool {
+    output.pager && is_tty && terminal_height.is_some_and(|height| line_count > height as usize)
+}
+
+/// Print `content`, through `$PAGER` when `should_page` says so. Falls back to
+/// plain stdout if the pager cannot be started.
+pub fn print_paged(content: &str, output: Output) -> Result<()> {
+    let stdout = io::stdout();
+    let height = crossterm::terminal::size().ok().map(|(_, height)| height);
+    if should_page(
+        output,
+        stdout.is_terminal(),
+        content.lines().count(),
+        height,
+    ) && let Some(mut child) = spawn_pager()
+    {
+        if let Some(mut stdin) = child.stdin.take() {
+            // The user quitting the pager early closes the pipe; not an error
+            let _ = stdin.write_all(content.as_bytes());
+        }
+        child.wait()?;
+        return Ok(());
+    }
+
+    let mut stdout = stdout.lock();
+    stdout.write_all(content.as_bytes())?;
+    if !content.ends_with('\n') {
+        stdout.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+fn spawn_pager() -> Option<std::process::Child> {
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+    let mut parts = pager.split_whitespace();
+    let mut command = Command::new(parts.next()?);
+    command.args(parts).stdin(Stdio::piped());
+    if std::env::var_os("LESS").is_none() {
+        // Pass colors through, and quit right away if it fits after all
+        command.env("LESS", "FRX");
+    }
+    command.spawn().ok()
+}
+
+/// Markdown rendered like the Viewer does it, one terminal line per line.
+pub fn markdown_to_ansi(src: &str, width: usize, theme: &Theme, color: bool) -> String {
+    parse_markdown_to_lines(src, width, theme)
+        .iter()
+        .map(|line| line_to_ansi(line, color))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn line_to_ansi(line: &Line, color: bool) -> String {
+    let mut out = String::new();
+    for span in &line.spans {
+        let codes = if color {
+            sgr_codes(line.style.patch(span.style))
+        } else {
+            Vec::new()
+        };
+        if codes.is_empty() {
+            out.push_str(&span.content);
+        } else {
+            out.push_str(&format!("\x1b[{}m{}{RESET}", codes.join(";"), span.content));
+        }
+    }
+    out
+}
+
+fn sgr_codes(style: Style) -> Vec<String> {
+    let mut codes = Vec::new();
+    for (modifier, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if style.add_modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    if let Some(fg) = style.fg.and_then(|c| color_code(c, false)) {
+        codes.push(fg);
+    }
+    if let Some(bg) = style.bg.and_then(|c| color_code(c, true)) {
+        codes.push(bg);
+    }
+    codes
+}
+
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let base = |code: u8| Some((if background { code + 10 } else { code }).to_string());
+    match color {
+        Color::Reset => None,
+        Color::Black => base(30),
+        Color::Red => base(31),
+        Color::Green => base(32),
+        Color::Yellow => base(33),
+        Color::Blue => base(34),
+        Color::Magenta => base(35),
+        Color::Cyan => base(36),
+        Color::Gray => base(37),
+        Color::DarkGray => base(90),
+        Color::LightRed => base(91),
+        Color::LightGreen => base(92),
+        Color::LightYellow => base(93),
+        Color::LightBlue => base(94),
+        Color::LightMagenta => base(95),
+        Color::LightCyan => base(96),
+        Color::White => base(97),
+        Color::Indexed(i) => Some(format!("{};5;{i}", if background { 48 } else { 38 })),
+        Color::Rgb(r, g, b) => Some(format!(
+            "{};2;{r};{g};{b}",
+            if background { 48 } else { 38 }
+        )),
+    }
+}
+
+/// Line-based unified diff with three lines of context.
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(old_label, new_label)
+        .to_string()
+}
+
+/// Color a unified diff the way git does: headers bold, hunks cyan, removals
+/// red, additions green.
+pub fn colorize_diff(diff: &str, color: bool) -> String {
+    if !color {
+        return diff.to_string();
+    }
+    diff.lines()
+        .map(|line| {
+            let code = if line.starts_with("+++") || line.starts_with("---") {
+                "1"
+            } else if line.starts_with("@@") {
+                "36"
+            } else if line.starts_with('+') {
+                "32"
+            } else if line.starts_with('-') {
+                "31"
+            } else {
+                return line.to_string();
+            };
+            format!("\x1b[{code}m{line}{RESET}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Output, Theme, colorize_diff, markdown_to_ansi, should_page, unified_diff};
+    use unicode_width::UnicodeWidthStr;
+
+    fn strip_ansi(s: &str) -> String {
+        let mut out = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                for c in chars.by_ref() {
+                    if c == 'm' {
+                        break;
+                    }
+                }
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    const REPORT: &str = "# Título\n\nUn párrafo con **negrita** y *cursiva* que ocupa más de una línea al envolverse.\n\n- uno\n- dos\n\n| Tema | Veces |\n|---|---|\n| Rust | 3 |\n";
+
+    #[test]
+    fn ansi_markdown_keeps_the_plain_text_width() {
+        let colored = markdown_to_ansi(REPORT, 30, &Theme::dark(), true);
+        let plain = markdown_to_ansi(REPORT, 30, &Theme::dark(), false);
+
+        assert!(colored.contains("\x1b[1;"), "headings are bold");
+        assert!(!plain.contains('\x1b'));
+        assert_eq!(strip_ansi(&colored), plain);
+        for line in plain.lines() {
+            assert!(line.width() <= 30, "{line:?} is wider than 30 columns");
+        }
+    }
+
+    #[test]
+    fn diff_colors_only_changed_lines() {
+        let diff = unified_diff("a\nb\nc\n", "a\nB\nc\n", "old", "new");
+        assert!(diff.starts_with("--- old\n+++ new\n@@ "));
+
+        let colored = colorize_diff(&diff, true);
+        assert!(colored.contains("\x1b[31m-b\x1b[0m"));
+        assert!(colored.contains("\x1b[32m+B\x1b[0m"));
+        assert!(colored.contains("\n a\n"));
+        assert_eq!(strip_ansi(&colored), diff.trim_end());
+        assert_eq!(colorize_diff(&diff, false), diff);
+    }
+
+    #[test]
+    fn pages_only_long_output_on_a_terminal() {
+        let on = Output {
+            color: true,
+            pager: true,
+        };
+        let off = Output { pager: false, ..on };
+
+        assert!(should_page(on, true, 50, Some(24)));
+        assert!(!should_page(on, true, 24, Some(24)));
+        assert!(!should_page(on, false, 50, Some(24)));
+        assert!(!should_page(on, true, 50, None));
+        assert!(!should_page(off, true, 50, Some(24)));
+    }
+}
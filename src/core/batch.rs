@@ -0,0 +1,220 @@
+// A `vidio get` run over several videos records how far each one got in a
+// `batch_<timestamp>.json` manifest, so an interrupted run can pick up where
+// it stopped with `--resume` instead of starting over.
+use crate::error::{Error, Result};
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PREFIX: &str = "batch_";
+const EXTENSION: &str = ".json";
+
+/// How far one video of a batch got.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum BatchStatus {
+    Pending,
+    /// The transcript is saved; the report, if wanted, is not.
+    Fetched,
+    Reported,
+    Failed {
+        error: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchEntry {
+    pub video_id: String,
+    #[serde(flatten)]
+    pub status: BatchStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchManifest {
+    /// Where the manifest is saved; not part of it.
+    #[serde(skip)]
+    pub path: PathBuf,
+    pub started_at: DateTime<Utc>,
+    /// Whether the run generates reports, so a fetched video is not done yet.
+    pub report: bool,
+    pub videos: Vec<BatchEntry>,
+}
+
+/// A manifest at a glance, for `vidio stats`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchSummary {
+    pub name: String,
+    pub started_at: DateTime<Utc>,
+    pub total: usize,
+    pub done: usize,
+    pub failed: usize,
+}
+
+impl BatchManifest {
+    /// A new manifest in `dir` with every video pending, not yet saved.
+    pub fn new(dir: &Path, video_ids: &[String], report: bool) -> Self {
+        let started_at = Utc::now();
+        let stamp = started_at.with_timezone(&Local).format("%Y%m%d-%H%M%S");
+        let mut path = dir.join(format!("{PREFIX}{stamp}{EXTENSION}"));
+        let mut n = 2;
+        while path.exists() {
+            path = dir.join(format!("{PREFIX}{stamp}-{n}{EXTENSION}"));
+            n += 1;
+        }
+        Self {
+            path,
+            started_at,
+            report,
+            videos: video_ids
+                .iter()
+                .map(|video_id| BatchEntry {
+                    video_id: video_id.clone(),
+                    status: BatchStatus::Pending,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            Error::custom(format!(
+                "Could not read batch manifest {}: {e}",
+                path.display()
+            ))
+        })?;
+        let mut manifest: Self = serde_json::from_str(&content).map_err(|e| {
+            Error::custom(format!("Invalid batch manifest {}: {e}", path.display()))
+        })?;
+        manifest.path = path.to_path_buf();
+        Ok(manifest)
+    }
+
+    /// Write the manifest through a temp file, so an interrupted run never
+    /// leaves half of one.
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| Error::custom(format!("Failed to serialize batch manifest: {e}")))?;
+        let temp = self.path.with_extension("json.tmp");
+        fs::write(&temp, content)?;
+        fs::rename(&temp, &self.path)?;
+        Ok(())
+    }
+
+    /// Record how far `video_id` got and save the manifest.
+    pub fn set(&mut self, video_id: &str, status: BatchStatus) -> Result<()> {
+        if let Some(entry) = self.videos.iter_mut().find(|e| e.video_id == video_id) {
+            entry.status = status;
+        }
+        self.save()
+    }
+
+    fn is_done(&self, entry: &BatchEntry) -> bool {
+        match entry.status {
+            BatchStatus::Reported => true,
+            BatchStatus::Fetched => !self.report,
+            BatchStatus::Pending | BatchStatus::Failed { .. } => false,
+        }
+    }
+
+    /// The videos still to do, failed ones included, in their first order.
+    pub fn remaining(&self) -> Vec<String> {
+        self.videos
+            .iter()
+            .filter(|entry| !self.is_done(entry))
+            .map(|entry| entry.video_id.clone())
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.videos.iter().all(|entry| self.is_done(entry))
+    }
+
+    pub fn summary(&self) -> BatchSummary {
+        BatchSummary {
+            name: self
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            started_at: self.started_at,
+            total: self.videos.len(),
+            done: self.videos.iter().filter(|e| self.is_done(e)).count(),
+            failed: self
+                .videos
+                .iter()
+                .filter(|e| matches!(e.status, BatchStatus::Failed { .. }))
+                .count(),
+        }
+    }
+
+    /// Every readable manifest in `dir`, newest first.
+    pub fn list(dir: &Path) -> Result<Vec<Self>> {
+        let mut manifests: Vec<Self> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(PREFIX) && name.ends_with(EXTENSION))
+            })
+            .filter_map(|path| Self::load(&path).ok())
+            .collect();
+        manifests.sort_by_key(|manifest| std::cmp::Reverse(manifest.started_at));
+        Ok(manifests)
+    }
+
+    /// The newest manifest in `dir` with videos left to do.
+    pub fn latest_incomplete(dir: &Path) -> Result<Option<Self>> {
+        Ok(Self::list(dir)?
+            .into_iter()
+            .find(|manifest| !manifest.is_complete()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BatchManifest, BatchStatus};
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn progress_is_saved_and_resumed() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manifest = BatchManifest::new(dir.path(), &ids(&["a", "b", "c", "d"]), true);
+        manifest.save().unwrap();
+        manifest.set("a", BatchStatus::Reported).unwrap();
+        manifest.set("b", BatchStatus::Fetched).unwrap();
+        let error = "Transcripts disabled".to_string();
+        manifest.set("c", BatchStatus::Failed { error }).unwrap();
+
+        let loaded = BatchManifest::load(&manifest.path).unwrap();
+        assert_eq!(loaded, manifest);
+        assert_eq!(loaded.remaining(), ["b", "c", "d"]);
+        let summary = loaded.summary();
+        assert_eq!((summary.total, summary.done, summary.failed), (4, 1, 1));
+
+        let json = std::fs::read_to_string(&manifest.path).unwrap();
+        assert!(json.contains("\"status\": \"failed\""), "{json}");
+    }
+
+    #[test]
+    fn finished_batches_are_not_resumed() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut fetched_only = BatchManifest::new(dir.path(), &ids(&["a"]), false);
+        fetched_only.set("a", BatchStatus::Fetched).unwrap();
+        assert!(fetched_only.is_complete());
+
+        let mut unfinished = BatchManifest::new(dir.path(), &ids(&["a", "b"]), true);
+        unfinished.set("a", BatchStatus::Reported).unwrap();
+        assert_ne!(unfinished.path, fetched_only.path);
+        assert_eq!(BatchManifest::list(dir.path()).unwrap().len(), 2);
+
+        let latest = BatchManifest::latest_incomplete(dir.path())
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.path, unfinished.path);
+        assert_eq!(latest.remaining(), ["b"]);
+    }
+}
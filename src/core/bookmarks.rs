@@ -0,0 +1,139 @@
+use crate::core::storage::{TRANSCRIPTS_DIR, write_atomic_sync};
+use crate::core::transcript;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const BOOKMARKS_PREFIX: &str = "bookmarks_";
+const BOOKMARKS_SUFFIX: &str = ".json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    /// Start of the bookmarked snippet, in seconds.
+    pub timestamp: f64,
+    pub note: Option<String>,
+    pub created: String,
+}
+
+/// Bookmarks for a single video, kept in a `bookmarks_<id>.json` sidecar next
+/// to its transcript and sorted by timestamp.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BookmarkList {
+    pub video_id: String,
+    pub entries: Vec<Bookmark>,
+}
+
+impl BookmarkList {
+    /// The video a bookmarks file name belongs to.
+    pub(crate) fn video_id_of(file_name: &str) -> Option<&str> {
+        file_name
+            .strip_prefix(BOOKMARKS_PREFIX)?
+            .strip_suffix(BOOKMARKS_SUFFIX)
+    }
+
+    pub(crate) fn path(video_id: &str) -> Result<PathBuf> {
+        let sanitized = transcript::sanitize_video_id(video_id)?;
+        Ok(PathBuf::from(TRANSCRIPTS_DIR)
+            .join(format!("{BOOKMARKS_PREFIX}{sanitized}{BOOKMARKS_SUFFIX}")))
+    }
+
+    /// Load a video's bookmarks; a missing sidecar is an empty list.
+    pub fn load(video_id: &str) -> Result<Self> {
+        let path = Self::path(video_id)?;
+        let entries = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| {
+                Error::custom(format!("Invalid bookmarks file {}: {e}", path.display()))
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            video_id: video_id.to_string(),
+            entries,
+        })
+    }
+
+    /// Number of bookmarks stored for a video, or 0 if none can be read.
+    pub fn count(video_id: &str) -> usize {
+        Self::load(video_id)
+            .map(|list| list.entries.len())
+            .unwrap_or(0)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path(&self.video_id)?;
+        if self.entries.is_empty() {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(&self.entries)
+            .map_err(|e| Error::custom(format!("Failed to serialize bookmarks: {e}")))?;
+        write_atomic_sync(&path, &content)
+    }
+
+    /// Insert a bookmark in timestamp order and return its index.
+    pub fn add(&mut self, timestamp: f64, note: Option<String>) -> usize {
+        let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        let index = self
+            .entries
+            .iter()
+            .position(|b| b.timestamp > timestamp)
+            .unwrap_or(self.entries.len());
+
+        self.entries.insert(
+            index,
+            Bookmark {
+                timestamp,
+                note,
+                created: chrono::Local::now().to_rfc3339(),
+            },
+        );
+        index
+    }
+
+    /// Remove the bookmark at a 1-based position, as shown by `--list`.
+    pub fn remove(&mut self, position: usize) -> Result<Bookmark> {
+        if position == 0 || position > self.entries.len() {
+            return Err(Error::custom(format!(
+                "No bookmark #{position}; there are {} bookmarks",
+                self.entries.len()
+            )));
+        }
+        Ok(self.entries.remove(position - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BookmarkList;
+
+    #[test]
+    fn add_keeps_timestamp_order_and_drops_blank_notes() {
+        let mut list = BookmarkList::default();
+        list.add(30.0, Some("later".to_string()));
+        let index = list.add(10.0, Some("   ".to_string()));
+
+        assert_eq!(index, 0);
+        assert_eq!(list.entries[0].timestamp, 10.0);
+        assert_eq!(list.entries[0].note, None);
+        assert_eq!(list.entries[1].note.as_deref(), Some("later"));
+    }
+
+    #[test]
+    fn remove_is_one_based() {
+        let mut list = BookmarkList::default();
+        list.add(5.0, None);
+
+        assert!(list.remove(0).is_err());
+        assert!(list.remove(2).is_err());
+        assert_eq!(list.remove(1).unwrap().timestamp, 5.0);
+    }
+}
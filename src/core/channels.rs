@@ -0,0 +1,385 @@
+// Watched YouTube channels whose new uploads `vidio channels sync` archives.
+// Uploads come from the public per-channel Atom feed (the 15 most recent
+// videos). Each channel keeps a cursor on the newest upload already processed,
+// so a sync that is interrupted or run again from cron only picks up what is
+// left.
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use html_escape::decode_html_entities;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+const CHANNELS_FILE: &str = "channels.json";
+const FEED_URL: &str = "https://www.youtube.com/feeds/videos.xml?channel_id=";
+const CHANNEL_ID_PREFIX: &str = "UC";
+const CHANNEL_ID_LEN: usize = 24;
+
+/// Pause between requests to YouTube during a sync.
+pub const SYNC_DELAY: Duration = Duration::from_secs(2);
+
+/// The newest upload a sync has finished with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncCursor {
+    pub video_id: String,
+    pub published: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Channel {
+    /// URL, handle or id as given to `channels add`.
+    pub input: String,
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Generate a report for each new upload.
+    #[serde(default)]
+    pub report: bool,
+    #[serde(default)]
+    pub cursor: Option<SyncCursor>,
+    #[serde(default)]
+    pub last_sync: Option<DateTime<Utc>>,
+    /// Transcripts saved by the last sync.
+    #[serde(default)]
+    pub last_new: usize,
+    /// Why the last sync could not read the channel, if it failed.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl Channel {
+    pub fn name(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.input)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelStore {
+    pub channels: Vec<Channel>,
+    /// Video ids that sync never downloads.
+    #[serde(default)]
+    pub ignored: Vec<String>,
+}
+
+impl ChannelStore {
+    /// Load the watched channels. Unlike the URL history a corrupt file is an
+    /// error: silently starting over would lose every cursor.
+    pub fn load() -> Result<Self> {
+        let path = Path::new(CHANNELS_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| Error::custom(format!("Invalid {CHANNELS_FILE}: {e}")))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| Error::custom(format!("Failed to serialize channels: {e}")))?;
+        // Written on every processed upload, so go through a rename to never
+        // leave a half-written file behind when the sync is killed
+        let temp = format!(".{CHANNELS_FILE}.{}.tmp", std::process::id());
+        fs::write(&temp, content)?;
+        fs::rename(&temp, CHANNELS_FILE)?;
+        Ok(())
+    }
+
+    /// Watch a channel. Returns `false` if it was already watched.
+    pub fn add(&mut self, input: &str, report: bool) -> bool {
+        let input = input.trim();
+        let channel_id = channel_id_from_input(input);
+        let exists = self.channels.iter().any(|channel| {
+            channel.input == input || (channel_id.is_some() && channel.channel_id == channel_id)
+        });
+        if exists {
+            return false;
+        }
+
+        self.channels.push(Channel {
+            input: input.to_string(),
+            channel_id,
+            title: None,
+            report,
+            cursor: None,
+            last_sync: None,
+            last_new: 0,
+            error: None,
+        });
+        true
+    }
+
+    pub fn ignore(&mut self, video_id: &str) -> bool {
+        if self.ignored.iter().any(|id| id == video_id) {
+            return false;
+        }
+        self.ignored.push(video_id.to_string());
+        true
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Upload {
+    pub video_id: String,
+    pub title: String,
+    pub published: DateTime<Utc>,
+}
+
+/// A channel id given directly or inside a `/channel/UC...` URL. Handles and
+/// custom URLs need the channel page to resolve.
+pub fn channel_id_from_input(input: &str) -> Option<String> {
+    let candidate = match input.find("/channel/") {
+        Some(pos) => &input[pos + "/channel/".len()..],
+        None => input,
+    };
+    let candidate = candidate.split(['/', '?', '#']).next()?;
+    is_channel_id(candidate).then(|| candidate.to_string())
+}
+
+fn is_channel_id(value: &str) -> bool {
+    value.len() == CHANNEL_ID_LEN
+        && value.starts_with(CHANNEL_ID_PREFIX)
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The URL to fetch when resolving a handle (`@name`) or custom channel URL.
+pub fn channel_page_url(input: &str) -> String {
+    if input.starts_with("http://") || input.starts_with("https://") {
+        input.to_string()
+    } else if input.starts_with('@') {
+        format!("https://www.youtube.com/{input}")
+    } else {
+        format!("https://www.youtube.com/@{input}")
+    }
+}
+
+/// Channel id from a channel page, via its canonical link or embedded metadata.
+pub fn channel_id_from_page(html: &str) -> Option<String> {
+    ["/channel/", "\"channelId\":\"", "\"externalId\":\""]
+        .iter()
+        .flat_map(|marker| html.match_indices(marker))
+        .filter_map(|(pos, marker)| {
+            let start = pos + marker.len();
+            html.get(start..start + CHANNEL_ID_LEN)
+        })
+        .find(|candidate| is_channel_id(candidate))
+        .map(|id| id.to_string())
+}
+
+fn tag_text<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&close)?;
+    Some(&xml[start..end])
+}
+
+/// The channel title and its uploads, newest first, from the Atom feed.
+pub fn parse_feed(xml: &str) -> Result<(Option<String>, Vec<Upload>)> {
+    let mut parts = xml.split("<entry>");
+    let header = parts
+        .next()
+        .filter(|header| header.contains("<feed"))
+        .ok_or_else(|| Error::custom("Channel feed is not an Atom feed"))?;
+    let title = tag_text(header, "title").map(|t| decode_html_entities(t).to_string());
+
+    let mut uploads = Vec::new();
+    for entry in parts {
+        let (Some(video_id), Some(published)) =
+            (tag_text(entry, "yt:videoId"), tag_text(entry, "published"))
+        else {
+            return Err(Error::custom("Channel feed entry without video id or date"));
+        };
+        let published = DateTime::parse_from_rfc3339(published.trim())
+            .map_err(|e| Error::custom(format!("Invalid date in channel feed: {e}")))?
+            .with_timezone(&Utc);
+        uploads.push(Upload {
+            video_id: video_id.trim().to_string(),
+            title: decode_html_entities(tag_text(entry, "title").unwrap_or_default()).to_string(),
+            published,
+        });
+    }
+    Ok((title, uploads))
+}
+
+/// Uploads a sync still has to process, oldest first so the cursor can move
+/// forward after each one: newer than the cursor and `since`, not ignored and
+/// not already stored.
+pub fn pending_uploads(
+    channel: &Channel,
+    uploads: &[Upload],
+    since: Option<DateTime<Utc>>,
+    ignored: &[String],
+    is_stored: impl Fn(&str) -> bool,
+) -> Vec<Upload> {
+    let mut pending: Vec<Upload> = uploads
+        .iter()
+        .filter(|upload| {
+            channel
+                .cursor
+                .as_ref()
+                .is_none_or(|cursor| upload.published > cursor.published)
+        })
+        .filter(|upload| since.is_none_or(|since| upload.published >= since))
+        .filter(|upload| !ignored.contains(&upload.video_id))
+        .filter(|upload| !is_stored(&upload.video_id))
+        .cloned()
+        .collect();
+    pending.sort_by_key(|upload| upload.published);
+    pending
+}
+
+/// HTTP access to channel pages and feeds.
+pub struct ChannelClient {
+    http: reqwest::Client,
+}
+
+impl ChannelClient {
+    pub fn new() -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| Error::custom(format!("Failed to create HTTP client: {e}")))?;
+        Ok(Self { http })
+    }
+
+    async fn get_text(&self, url: &str) -> Result<String> {
+        let response = self
+            .http
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| {
+                let message = format!("Request to {url} failed: {e}");
+                if e.is_status() {
+                    Error::Api(message)
+                } else {
+                    Error::Network(message)
+                }
+            })?;
+        response
+            .text()
+            .await
+            .map_err(|e| Error::Network(format!("Failed to read {url}: {e}")))
+    }
+
+    /// The channel id for a watched channel, fetching its page if needed.
+    pub async fn resolve(&self, channel: &Channel) -> Result<String> {
+        if let Some(id) = &channel.channel_id {
+            return Ok(id.clone());
+        }
+        let url = channel_page_url(&channel.input);
+        let html = self.get_text(&url).await?;
+        channel_id_from_page(&html)
+            .ok_or_else(|| Error::custom(format!("No channel found at {url}")))
+    }
+
+    pub async fn uploads(&self, channel_id: &str) -> Result<(Option<String>, Vec<Upload>)> {
+        let xml = self.get_text(&format!("{FEED_URL}{channel_id}")).await?;
+        parse_feed(&xml)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Channel, ChannelStore, SyncCursor, channel_id_from_input, channel_id_from_page, parse_feed,
+        pending_uploads,
+    };
+    use chrono::{TimeZone, Utc};
+
+    const FEED: &str = include_str!("../../fixtures/channels/feed.xml");
+    const CHANNEL_ID: &str = "UCabcdefghijklmnopqrstuv";
+
+    fn channel() -> Channel {
+        let mut store = ChannelStore::default();
+        store.add(CHANNEL_ID, false);
+        store.channels.remove(0)
+    }
+
+    #[test]
+    fn parses_feed_title_and_uploads() {
+        let (title, uploads) = parse_feed(FEED).unwrap();
+        assert_eq!(title.as_deref(), Some("Charlas & Código"));
+        assert_eq!(uploads.len(), 3);
+        assert_eq!(uploads[0].video_id, "vid0000003");
+        assert_eq!(uploads[0].title, "Tercera charla: \"async\" en Rust");
+        assert_eq!(
+            uploads[2].published,
+            Utc.with_ymd_and_hms(2026, 9, 28, 15, 0, 0).unwrap()
+        );
+        assert!(parse_feed("<html>not a feed</html>").is_err());
+    }
+
+    #[test]
+    fn finds_channel_ids_in_input_and_pages() {
+        assert_eq!(
+            channel_id_from_input(&format!(
+                "https://www.youtube.com/channel/{CHANNEL_ID}/videos"
+            ))
+            .as_deref(),
+            Some(CHANNEL_ID)
+        );
+        assert_eq!(channel_id_from_input("@charlas"), None);
+
+        let page = format!(
+            r#"<link rel="canonical" href="https://www.youtube.com/channel/{CHANNEL_ID}">"#
+        );
+        assert_eq!(channel_id_from_page(&page).as_deref(), Some(CHANNEL_ID));
+        assert_eq!(channel_id_from_page("<html></html>"), None);
+    }
+
+    #[test]
+    fn pending_uploads_are_oldest_first_after_the_cursor() {
+        let (_, uploads) = parse_feed(FEED).unwrap();
+        let mut channel = channel();
+
+        let ids = |pending: Vec<super::Upload>| -> Vec<String> {
+            pending.into_iter().map(|u| u.video_id).collect()
+        };
+        assert_eq!(
+            ids(pending_uploads(&channel, &uploads, None, &[], |_| false)),
+            vec!["vid0000001", "vid0000002", "vid0000003"]
+        );
+
+        channel.cursor = Some(SyncCursor {
+            video_id: "vid0000001".to_string(),
+            published: uploads[2].published,
+        });
+        let ignored = vec!["vid0000003".to_string()];
+        assert_eq!(
+            ids(pending_uploads(&channel, &uploads, None, &ignored, |_| {
+                false
+            })),
+            vec!["vid0000002"]
+        );
+        assert!(pending_uploads(&channel, &uploads, None, &[], |_| true).is_empty());
+
+        let since = Utc.with_ymd_and_hms(2026, 10, 10, 0, 0, 0).unwrap();
+        assert_eq!(
+            ids(pending_uploads(
+                &channel,
+                &uploads,
+                Some(since),
+                &[],
+                |_| false
+            )),
+            vec!["vid0000003"]
+        );
+    }
+
+    #[test]
+    fn adding_the_same_channel_twice_is_a_no_op() {
+        let mut store = ChannelStore::default();
+        assert!(store.add(CHANNEL_ID, true));
+        assert!(!store.add(&format!("https://youtube.com/channel/{CHANNEL_ID}"), false));
+        assert!(store.add("@otro", false));
+        assert_eq!(store.channels.len(), 2);
+        assert!(store.channels[0].report);
+    }
+}
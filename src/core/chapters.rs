@@ -0,0 +1,146 @@
+// YouTube builds a video's chapters from the timestamp lines in its
+// description (`0:00 Intro`), so they are read from there too.
+use crate::core::transcript::parse_timestamp;
+use yt_transcript_rs::FetchedTranscriptSnippet;
+
+/// YouTube shows no chapters for fewer timestamp lines than this.
+const MIN_CHAPTERS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    /// Seconds into the video.
+    pub start: f64,
+    pub title: String,
+}
+
+/// The chapters in a video description, or none when its timestamps do not
+/// make chapters the way YouTube reads them: at least three, the first at
+/// 0:00 and the rest in order.
+pub fn parse_chapters(description: &str) -> Vec<Chapter> {
+    let chapters: Vec<Chapter> = description.lines().filter_map(parse_line).collect();
+    let in_order = chapters
+        .windows(2)
+        .all(|pair| pair[0].start < pair[1].start);
+    if chapters.len() < MIN_CHAPTERS || chapters[0].start != 0.0 || !in_order {
+        return Vec::new();
+    }
+    chapters
+}
+
+/// A line starting with a timestamp, such as `12:30 Results` or
+/// `(1:02:03) - Q&A`.
+fn parse_line(line: &str) -> Option<Chapter> {
+    let (stamp, title) = line.trim().split_once(char::is_whitespace)?;
+    let stamp = stamp.trim_matches(|c| matches!(c, '(' | ')' | '[' | ']'));
+    if !stamp.contains(':') {
+        return None;
+    }
+    let start = parse_timestamp(stamp)?;
+    let title = title
+        .trim_start_matches(|c: char| c.is_whitespace() || matches!(c, '-' | '–' | '—' | '|' | ':'))
+        .trim();
+    (!title.is_empty()).then(|| Chapter {
+        start,
+        title: title.to_string(),
+    })
+}
+
+/// Each chapter as (caption index, chapter index): the chapter starts at the
+/// first caption lying mostly after its start time. A chapter without
+/// captions of its own gives way to the next one, and chapters after the
+/// last caption are dropped.
+pub fn chapter_starts(
+    snippets: &[FetchedTranscriptSnippet],
+    chapters: &[Chapter],
+) -> Vec<(usize, usize)> {
+    let mut starts: Vec<(usize, usize)> = Vec::new();
+    for (chapter, Chapter { start, .. }) in chapters.iter().enumerate() {
+        let index =
+            snippets.partition_point(|snippet| snippet.start + snippet.duration / 2.0 < *start);
+        if index == snippets.len() {
+            break;
+        }
+        match starts.last_mut() {
+            Some(last) if last.0 == index => last.1 = chapter,
+            _ => starts.push((index, chapter)),
+        }
+    }
+    starts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Chapter, chapter_starts, parse_chapters};
+    use yt_transcript_rs::FetchedTranscriptSnippet;
+
+    fn chapters(starts: &[f64]) -> Vec<Chapter> {
+        starts
+            .iter()
+            .map(|&start| Chapter {
+                start,
+                title: format!("at {start}"),
+            })
+            .collect()
+    }
+
+    fn snippets(starts: &[f64]) -> Vec<FetchedTranscriptSnippet> {
+        starts
+            .iter()
+            .map(|&start| FetchedTranscriptSnippet {
+                text: "text".to_string(),
+                start,
+                duration: 4.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chapters_come_from_description_timestamps() {
+        let description =
+            "Links below!\n\n0:00 Intro\n(1:30) - Setup\n1:02:03 | Q&A\nThanks 2:00 for watching\n";
+        assert_eq!(
+            parse_chapters(description),
+            [
+                Chapter {
+                    start: 0.0,
+                    title: "Intro".to_string()
+                },
+                Chapter {
+                    start: 90.0,
+                    title: "Setup".to_string()
+                },
+                Chapter {
+                    start: 3723.0,
+                    title: "Q&A".to_string()
+                },
+            ]
+        );
+
+        // YouTube wants three, from 0:00, in order
+        assert!(parse_chapters("0:00 Intro\n1:00 End").is_empty());
+        assert!(parse_chapters("0:10 Intro\n1:00 Middle\n2:00 End").is_empty());
+        assert!(parse_chapters("0:00 Intro\n2:00 Middle\n1:00 End").is_empty());
+    }
+
+    #[test]
+    fn chapters_map_onto_the_captions_they_start() {
+        // Captions of four seconds at 0, 4, 8, ...
+        let snippets = snippets(&[0.0, 4.0, 8.0, 12.0, 16.0, 20.0]);
+
+        // 7.0 falls inside the caption at 4, which lies mostly before it
+        assert_eq!(
+            chapter_starts(&snippets, &chapters(&[0.0, 7.0, 16.0])),
+            [(0, 0), (2, 1), (4, 2)]
+        );
+        // A chapter shorter than a caption yields to the next one
+        assert_eq!(
+            chapter_starts(&snippets, &chapters(&[0.0, 8.5, 9.0, 20.0])),
+            [(0, 0), (2, 2), (5, 3)]
+        );
+        // Chapters after the captions end have nothing to head
+        assert_eq!(
+            chapter_starts(&snippets, &chapters(&[0.0, 12.0, 60.0])),
+            [(0, 0), (3, 1)]
+        );
+    }
+}
@@ -0,0 +1,238 @@
+// User settings read from `vidio.toml` in the working directory, next to the
+// transcripts/ and reports/ folders. Every field has a default, so a missing
+// or partial file is fine.
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+
+pub const CONFIG_FILE: &str = "vidio.toml";
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Disable the TUI entries that need network access.
+    pub offline: bool,
+    /// Move deleted files to the trash instead of removing them.
+    pub safe_delete: bool,
+    /// Days a deleted file stays in the trash before vidio removes it on
+    /// startup; 0 keeps it until the trash is emptied.
+    pub trash_retention_days: u64,
+    /// Show a desktop notification when a TUI job finishes.
+    pub notify_on_complete: bool,
+    /// Milliseconds between TUI ticks, which redraw and drain job progress.
+    pub tick_rate_ms: u64,
+    /// TUI colors: `"dark"`, `"light"` or a theme defined under `[themes]`.
+    /// The Settings screen can override it.
+    pub theme: String,
+    /// Custom TUI themes by name.
+    pub themes: BTreeMap<String, ThemeConfig>,
+    /// Draw the TUI with ASCII glyphs only. Unset means only on Windows,
+    /// whose older consoles can't show checkboxes, emoji or box drawing.
+    pub ascii: Option<bool>,
+    pub home: HomeConfig,
+    pub tools: ToolsConfig,
+    pub openai: OpenAiConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            offline: false,
+            safe_delete: true,
+            trash_retention_days: 30,
+            notify_on_complete: true,
+            tick_rate_ms: 250,
+            theme: "dark".to_string(),
+            themes: BTreeMap::new(),
+            ascii: None,
+            home: HomeConfig::default(),
+            tools: ToolsConfig::default(),
+            openai: OpenAiConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct HomeConfig {
+    /// Home menu entry ids shown first, in order. Only the first three count.
+    pub pinned: Vec<String>,
+}
+
+/// A custom TUI theme. Colors are names such as `"lightblue"` or hex values
+/// such as `"#268bd2"`; those left out come from the `base` theme.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    /// `"dark"` (the default) or `"light"`.
+    pub base: Option<String>,
+    /// Titles, focused fields and the selected entry.
+    pub accent: Option<String>,
+    pub text: Option<String>,
+    /// Help lines and other secondary text.
+    pub secondary: Option<String>,
+    /// Placeholders and disabled entries.
+    pub dim: Option<String>,
+    pub border: Option<String>,
+    /// Background of the highlighted row.
+    pub highlight: Option<String>,
+    pub heading: Option<String>,
+    pub table_header: Option<String>,
+    pub code: Option<String>,
+    pub link: Option<String>,
+    /// Bookmarks, pins and checked files.
+    pub mark: Option<String>,
+    /// Background of the current search match.
+    pub current_match: Option<String>,
+    pub success: Option<String>,
+    pub error: Option<String>,
+}
+
+/// External binaries used by `vidio import-media`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ToolsConfig {
+    pub ffmpeg: String,
+    pub ffprobe: String,
+}
+
+/// Which API report requests are sent through.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportProvider {
+    /// OpenAI, or a server speaking its API at `base_url`.
+    #[default]
+    OpenAi,
+    /// Ollama's own API, at `base_url` or `http://localhost:11434`.
+    Ollama,
+}
+
+/// How long reasoning models think before they write a report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Effort {
+    Low,
+    Medium,
+    #[default]
+    High,
+}
+
+impl Effort {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "low" => Some(Self::Low),
+            "medium" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+}
+
+/// How reports are generated, by OpenAI or any server speaking its API.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct OpenAiConfig {
+    /// `"openai"` (the default) or `"ollama"`.
+    pub backend: ReportProvider,
+    /// Model used for reports unless `--model` or `VIDIO_MODEL` says otherwise.
+    pub model: Option<String>,
+    /// API root of an OpenAI-compatible server, e.g.
+    /// `http://localhost:11434/v1` for Ollama. Unset means OpenAI.
+    pub base_url: Option<String>,
+    /// Environment variable holding the API key (default `OPENAI_API_KEY`).
+    pub api_key_env: Option<String>,
+    /// Longest prompt sent in one request; longer transcripts are reported
+    /// on in parts. Defaults to three quarters of the model's context window.
+    pub max_input_tokens: Option<u64>,
+    /// Retries of a request that failed on the network, a server error or a
+    /// rate limit (default 3).
+    pub retries: Option<u32>,
+    /// `"low"`, `"medium"` or `"high"` (the default) for models that reason;
+    /// lower is faster and cheaper on long transcripts.
+    pub reasoning_effort: Option<Effort>,
+    /// Most tokens a report may take, reasoning included (default 128000).
+    /// Some models accept less.
+    pub max_output_tokens: Option<u32>,
+}
+
+impl Default for ToolsConfig {
+    fn default() -> Self {
+        Self {
+            ffmpeg: "ffmpeg".to_string(),
+            ffprobe: "ffprobe".to_string(),
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Result<Self> {
+        match fs::read_to_string(CONFIG_FILE) {
+            Ok(content) => Self::parse(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        toml::from_str(content).map_err(|e| Error::custom(format!("Invalid {CONFIG_FILE}: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, Effort, ReportProvider};
+
+    #[test]
+    fn partial_file_falls_back_to_defaults() {
+        let config =
+            Config::parse("[home]\npinned = [\"reports\"]\n[openai]\nmodel = \"gpt-4.1\"\n")
+                .unwrap();
+        assert!(!config.offline);
+        assert!(config.safe_delete);
+        assert!(config.notify_on_complete);
+        assert_eq!(config.tick_rate_ms, 250);
+        assert_eq!(config.trash_retention_days, 30);
+        assert_eq!(config.tools.ffprobe, "ffprobe");
+        assert_eq!(config.home.pinned, vec!["reports".to_string()]);
+        assert_eq!(config.openai.model.as_deref(), Some("gpt-4.1"));
+    }
+
+    #[test]
+    fn ollama_backend_is_chosen_by_name() {
+        let config = Config::parse("[openai]\nbackend = \"ollama\"\nmodel = \"llama3\"\n").unwrap();
+        assert_eq!(config.openai.backend, ReportProvider::Ollama);
+        assert_eq!(Config::default().openai.backend, ReportProvider::OpenAi);
+        assert!(Config::parse("[openai]\nbackend = \"llamafile\"\n").is_err());
+    }
+
+    #[test]
+    fn report_limits_are_read_by_name() {
+        let config =
+            Config::parse("[openai]\nreasoning_effort = \"medium\"\nmax_output_tokens = 32000\n")
+                .unwrap();
+        assert_eq!(config.openai.reasoning_effort, Some(Effort::Medium));
+        assert_eq!(config.openai.max_output_tokens, Some(32_000));
+        assert!(Config::parse("[openai]\nreasoning_effort = \"extreme\"\n").is_err());
+        assert_eq!(Effort::from_name(" Low"), Some(Effort::Low));
+        assert_eq!(Effort::default(), Effort::High);
+    }
+
+    #[test]
+    fn custom_themes_are_read_by_name() {
+        let config = Config::parse(
+            "theme = \"paper\"\n[themes.paper]\nbase = \"light\"\naccent = \"#268bd2\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.theme, "paper");
+        assert_eq!(config.themes["paper"].base.as_deref(), Some("light"));
+        assert_eq!(config.themes["paper"].accent.as_deref(), Some("#268bd2"));
+        assert_eq!(Config::default().theme, "dark");
+        assert!(Config::parse("[themes.paper]\nbackground = \"white\"\n").is_err());
+    }
+
+    #[test]
+    fn rejects_wrong_types() {
+        assert!(Config::parse("offline = \"yes\"").is_err());
+    }
+}
@@ -0,0 +1,188 @@
+use crate::core::searches::fold_for_search;
+use crate::core::storage::write_atomic_sync;
+use crate::core::transcript::extract_video_id;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const HISTORY_FILE: &str = "history.json";
+const MAX_HISTORY_ENTRIES: usize = 50;
+
+/// Recently submitted video URLs, newest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UrlHistory {
+    entries: Vec<HistoryEntry>,
+}
+
+/// A submitted URL and the video's title, when it was known.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "StoredEntry")]
+pub struct HistoryEntry {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// Histories written before titles were recorded hold bare URLs.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredEntry {
+    Url(String),
+    Titled {
+        url: String,
+        #[serde(default)]
+        title: Option<String>,
+    },
+}
+
+impl From<StoredEntry> for HistoryEntry {
+    fn from(stored: StoredEntry) -> Self {
+        match stored {
+            StoredEntry::Url(url) => Self { url, title: None },
+            StoredEntry::Titled { url, title } => Self { url, title },
+        }
+    }
+}
+
+impl HistoryEntry {
+    pub fn video_id(&self) -> Option<String> {
+        extract_video_id(&self.url)
+    }
+
+    /// Whether every character of `query` appears in the URL or title in
+    /// order, ignoring case and accents, so "rstconf" finds "Rust Conference".
+    pub fn matches(&self, query: &str) -> bool {
+        let text = fold_for_search(&format!(
+            "{} {}",
+            self.url,
+            self.title.as_deref().unwrap_or("")
+        ));
+        let mut text = text.chars();
+        fold_for_search(query)
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .all(|wanted| text.any(|c| c == wanted))
+    }
+}
+
+impl UrlHistory {
+    /// Load the persisted history. A missing or unreadable file yields an
+    /// empty history rather than an error: losing recall is not fatal.
+    pub fn load() -> Self {
+        fs::read_to_string(HISTORY_FILE)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| Error::custom(format!("Failed to serialize URL history: {e}")))?;
+        write_atomic_sync(Path::new(HISTORY_FILE), &content)
+    }
+
+    /// Record a URL as the most recent entry, moving it up if already present.
+    /// Without a title, one recorded earlier for the URL is kept.
+    pub fn push(&mut self, url: &str, title: Option<&str>) {
+        let url = url.trim();
+        if url.is_empty() {
+            return;
+        }
+
+        let earlier = self
+            .entries
+            .iter()
+            .position(|entry| entry.url == url)
+            .map(|index| self.entries.remove(index));
+        let title = title
+            .map(str::to_string)
+            .or_else(|| earlier.and_then(|entry| entry.title));
+        self.entries.insert(
+            0,
+            HistoryEntry {
+                url: url.to_string(),
+                title,
+            },
+        );
+        self.entries.truncate(MAX_HISTORY_ENTRIES);
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(|entry| entry.url.as_str())
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// The entries matching `query`, newest first; all of them for an empty
+    /// query.
+    pub fn search(&self, query: &str) -> Vec<&HistoryEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.matches(query))
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_HISTORY_ENTRIES, UrlHistory};
+
+    #[test]
+    fn push_moves_duplicates_to_front() {
+        let mut history = UrlHistory::default();
+        history.push("https://youtu.be/a", Some("Talk A"));
+        history.push("https://youtu.be/b", None);
+        history.push(" https://youtu.be/a ", None);
+
+        assert_eq!(history.entries.len(), 2);
+        assert_eq!(history.get(0), Some("https://youtu.be/a"));
+        assert_eq!(history.get(1), Some("https://youtu.be/b"));
+        assert_eq!(history.entries()[0].title.as_deref(), Some("Talk A"));
+    }
+
+    #[test]
+    fn push_keeps_a_bounded_ring() {
+        let mut history = UrlHistory::default();
+        for i in 0..MAX_HISTORY_ENTRIES + 5 {
+            history.push(&format!("id{i}"), None);
+        }
+
+        assert_eq!(history.entries.len(), MAX_HISTORY_ENTRIES);
+        assert_eq!(history.get(0), Some("id54"));
+    }
+
+    #[test]
+    fn old_histories_load_and_search_fuzzily() {
+        let history: UrlHistory = serde_json::from_str(
+            r#"{"entries": ["https://youtu.be/dQw4w9WgXcQ",
+                {"url": "https://youtu.be/abcdefghijk", "title": "Rust Conference keynote"},
+                {"url": "https://youtu.be/bcdefghijkl", "title": "Canción de Año Nuevo"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(history.entries()[0].title, None);
+        assert_eq!(
+            history.entries()[1].video_id().as_deref(),
+            Some("abcdefghijk")
+        );
+
+        let urls = |query| -> Vec<&str> {
+            history
+                .search(query)
+                .iter()
+                .map(|entry| entry.url.as_str())
+                .collect()
+        };
+        assert_eq!(urls("RST conf"), ["https://youtu.be/abcdefghijk"]);
+        assert_eq!(urls("dqw"), ["https://youtu.be/dQw4w9WgXcQ"]);
+        assert_eq!(urls("cancion ano"), ["https://youtu.be/bcdefghijkl"]);
+        assert_eq!(urls("").len(), 3);
+        assert!(urls("keynote rust").is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+// The TUI's processing log only keeps the current queue, so every line is
+// also appended to `logs/vidio.log`, where a failure from an earlier run can
+// still be read. At about a megabyte the file moves to `vidio.log.1`,
+// replacing the one before.
+use crate::error::Result;
+use chrono::Local;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const LOG_DIR: &str = "logs";
+const LOG_FILE: &str = "vidio.log";
+const MAX_SIZE: u64 = 1024 * 1024;
+const SNAPSHOT_PREFIX: &str = "processing_";
+
+#[derive(Debug, Clone)]
+pub struct LogFile {
+    path: PathBuf,
+    max_size: u64,
+}
+
+impl LogFile {
+    /// The log in `dir`, created on the first line written.
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            path: dir.join(LOG_FILE),
+            max_size: MAX_SIZE,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `line` with the time and, during a job, its video ID.
+    pub fn append(&self, video_id: Option<&str>, line: &str) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        if fs::metadata(&self.path).is_ok_and(|m| m.len() >= self.max_size) {
+            fs::rename(&self.path, self.path.with_extension("log.1"))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
+        match video_id {
+            Some(video_id) => writeln!(file, "{timestamp} [{video_id}] {line}")?,
+            None => writeln!(file, "{timestamp} {line}")?,
+        }
+        Ok(())
+    }
+}
+
+/// Save the processing log as shown, e.g. to attach to a bug report, as
+/// `processing_<time>.log` in `dir`. Returns where it went.
+pub fn save_snapshot(dir: &Path, lines: &[String]) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    let mut path = dir.join(format!("{SNAPSHOT_PREFIX}{stamp}.log"));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{SNAPSHOT_PREFIX}{stamp}-{n}.log"));
+        n += 1;
+    }
+    let mut content = lines.join("\n");
+    content.push('\n');
+    fs::write(&path, content)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LogFile, save_snapshot};
+    use std::fs;
+
+    #[test]
+    fn full_log_moves_aside_keeping_one_old_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = LogFile {
+            max_size: 60,
+            ..LogFile::new(&dir.path().join("logs"))
+        };
+        log.append(Some("dQw4w9WgXcQ"), "Fetching transcript...")
+            .unwrap();
+        log.append(None, "Report failed: rate limited").unwrap();
+        let first = fs::read_to_string(log.path()).unwrap();
+        assert!(
+            first.contains(" [dQw4w9WgXcQ] Fetching transcript...\n"),
+            "{first}"
+        );
+
+        let old = log.path().with_extension("log.1");
+        for line in ["second file", "still the second", "third file"] {
+            log.append(None, line).unwrap();
+        }
+        let old = fs::read_to_string(old).unwrap();
+        assert!(
+            old.contains("second file") && !old.contains("Fetching"),
+            "{old}"
+        );
+        let current = fs::read_to_string(log.path()).unwrap();
+        assert!(current.ends_with(" third file\n"), "{current}");
+    }
+
+    #[test]
+    fn snapshots_never_overwrite_each_other() {
+        let dir = tempfile::tempdir().unwrap();
+        let lines = [
+            "[10:00:00] Fetching".to_string(),
+            "[10:00:01] Error: 429".to_string(),
+        ];
+        let first = save_snapshot(dir.path(), &lines).unwrap();
+        let second = save_snapshot(dir.path(), &lines[..1]).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(
+            fs::read_to_string(first).unwrap(),
+            "[10:00:00] Fetching\n[10:00:01] Error: 429\n"
+        );
+    }
+}
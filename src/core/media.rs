@@ -0,0 +1,280 @@
+// Subtitle import from local media files through ffprobe/ffmpeg. Probing,
+// stream selection and command construction are kept apart from running the
+// binaries so they can be tested against recorded ffprobe output.
+use crate::core::transcript::{format_timestamp, parse_timestamp};
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+
+/// Subtitle codecs ffmpeg can convert to SRT. Everything else (PGS, VobSub,
+/// DVB) is a bitmap format that would need OCR.
+const TEXT_CODECS: &[&str] = &["subrip", "srt", "ass", "ssa", "webvtt", "mov_text", "text"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleStream {
+    pub index: u32,
+    pub codec: String,
+    pub language: Option<String>,
+    pub title: Option<String>,
+}
+
+impl SubtitleStream {
+    pub fn is_text(&self) -> bool {
+        TEXT_CODECS.contains(&self.codec.as_str())
+    }
+
+    pub fn label(&self) -> String {
+        let mut label = format!(
+            "#{} {} [{}]",
+            self.index,
+            self.language.as_deref().unwrap_or("und"),
+            self.codec
+        );
+        if let Some(title) = &self.title {
+            label.push_str(&format!(" {title}"));
+        }
+        label
+    }
+}
+
+#[derive(Deserialize)]
+struct ProbeOutput {
+    #[serde(default)]
+    streams: Vec<ProbeStream>,
+}
+
+#[derive(Deserialize)]
+struct ProbeStream {
+    index: u32,
+    #[serde(default)]
+    codec_name: String,
+    #[serde(default)]
+    codec_type: String,
+    #[serde(default)]
+    tags: ProbeTags,
+}
+
+#[derive(Default, Deserialize)]
+struct ProbeTags {
+    language: Option<String>,
+    title: Option<String>,
+}
+
+/// Subtitle streams from `ffprobe -of json` output.
+pub fn parse_probe_output(json: &str) -> Result<Vec<SubtitleStream>> {
+    let output: ProbeOutput = serde_json::from_str(json)
+        .map_err(|e| Error::custom(format!("Unexpected ffprobe output: {e}")))?;
+
+    Ok(output
+        .streams
+        .into_iter()
+        .filter(|stream| stream.codec_type == "subtitle")
+        .map(|stream| SubtitleStream {
+            index: stream.index,
+            codec: stream.codec_name,
+            language: stream.tags.language,
+            title: stream.tags.title,
+        })
+        .collect())
+}
+
+/// The streams that can be imported, or an error explaining why none can.
+pub fn text_streams(streams: &[SubtitleStream]) -> Result<Vec<SubtitleStream>> {
+    if streams.is_empty() {
+        return Err(Error::custom("The file has no subtitle streams"));
+    }
+
+    let text: Vec<SubtitleStream> = streams.iter().filter(|s| s.is_text()).cloned().collect();
+    if text.is_empty() {
+        let codecs: Vec<&str> = streams.iter().map(|s| s.codec.as_str()).collect();
+        return Err(Error::custom(format!(
+            "Only bitmap subtitles found ({}); they cannot be converted to text",
+            codecs.join(", ")
+        )));
+    }
+
+    Ok(text)
+}
+
+pub fn probe_command(ffprobe: &str, media: &Path) -> Command {
+    let mut command = Command::new(ffprobe);
+    command
+        .args(["-v", "error", "-select_streams", "s"])
+        .args([
+            "-show_entries",
+            "stream=index,codec_name,codec_type:stream_tags=language,title",
+        ])
+        .args(["-of", "json"])
+        .arg(media);
+    command
+}
+
+pub fn extract_command(ffmpeg: &str, media: &Path, stream: u32, output: &Path) -> Command {
+    let mut command = Command::new(ffmpeg);
+    command
+        .args(["-v", "error", "-y", "-i"])
+        .arg(media)
+        .args(["-map", &format!("0:{stream}"), "-c:s", "srt"])
+        .arg(output);
+    command
+}
+
+/// Run an external tool and return its stdout, naming the config key to fix
+/// when the binary cannot be found.
+pub fn run_tool(mut command: Command, config_key: &str) -> Result<String> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let output = command.output().map_err(|e| match e.kind() {
+        ErrorKind::NotFound => Error::custom(format!(
+            "'{program}' was not found; install it or set {config_key} in vidio.toml"
+        )),
+        _ => Error::custom(format!("Failed to run '{program}': {e}")),
+    })?;
+
+    if !output.status.success() {
+        return Err(Error::custom(format!(
+            "'{program}' failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Convert SRT cues to the `[start - end] text` lines used for transcripts.
+pub fn srt_to_transcript(srt: &str) -> Result<Vec<String>> {
+    let mut lines = Vec::new();
+    let srt = srt.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+
+    for cue in srt.split("\n\n").filter(|cue| !cue.trim().is_empty()) {
+        let mut cue_lines = cue.lines().skip_while(|line| !line.contains("-->"));
+        let Some(timing) = cue_lines.next() else {
+            continue;
+        };
+        let (start, end) = timing
+            .split_once("-->")
+            .and_then(|(start, end)| Some((srt_time(start)?, srt_time(end)?)))
+            .ok_or_else(|| Error::custom(format!("Invalid SRT timing line: {timing}")))?;
+
+        let text = cue_lines
+            .map(strip_tags)
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !text.is_empty() {
+            lines.push(format!(
+                "[{} - {}] {text}",
+                format_timestamp(start),
+                format_timestamp(end)
+            ));
+        }
+    }
+
+    if lines.is_empty() {
+        return Err(Error::custom("The subtitle stream contained no text"));
+    }
+    Ok(lines)
+}
+
+fn srt_time(value: &str) -> Option<f64> {
+    // Ignore position hints some encoders append after the end time
+    let value = value.split_whitespace().next()?;
+    parse_timestamp(&value.replace(',', "."))
+}
+
+fn strip_tags(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut in_tag = false;
+    for c in line.chars() {
+        match c {
+            '<' | '{' => in_tag = true,
+            '>' | '}' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        extract_command, parse_probe_output, probe_command, run_tool, srt_to_transcript,
+        text_streams,
+    };
+    use std::path::Path;
+
+    const MIXED: &str = include_str!("../../fixtures/ffprobe/mixed.json");
+    const BITMAP_ONLY: &str = include_str!("../../fixtures/ffprobe/bitmap_only.json");
+    const NO_SUBTITLES: &str = include_str!("../../fixtures/ffprobe/no_subtitles.json");
+    const SAMPLE_SRT: &str = include_str!("../../fixtures/sample.srt");
+
+    #[test]
+    fn parses_recorded_probe_output() {
+        let streams = parse_probe_output(MIXED).unwrap();
+        assert_eq!(streams.len(), 3);
+        assert_eq!(streams[0].label(), "#2 eng [subrip] English");
+
+        let text = text_streams(&streams).unwrap();
+        let indexes: Vec<u32> = text.iter().map(|s| s.index).collect();
+        assert_eq!(indexes, vec![2, 4]);
+    }
+
+    #[test]
+    fn explains_bitmap_only_and_missing_subtitles() {
+        let err = text_streams(&parse_probe_output(BITMAP_ONLY).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("hdmv_pgs_subtitle, dvd_subtitle"));
+
+        let err = text_streams(&parse_probe_output(NO_SUBTITLES).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("no subtitle streams"));
+    }
+
+    #[test]
+    fn builds_probe_and_extract_commands() {
+        let media = Path::new("talk.mkv");
+        let probe = probe_command("/opt/ffprobe", media);
+        assert_eq!(probe.get_program(), "/opt/ffprobe");
+        assert_eq!(probe.get_args().last().unwrap(), "talk.mkv");
+
+        let extract = extract_command("ffmpeg", media, 4, Path::new("/tmp/out.srt"));
+        let args: Vec<_> = extract.get_args().map(|a| a.to_string_lossy()).collect();
+        assert_eq!(
+            args,
+            vec![
+                "-v",
+                "error",
+                "-y",
+                "-i",
+                "talk.mkv",
+                "-map",
+                "0:4",
+                "-c:s",
+                "srt",
+                "/tmp/out.srt"
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_binary_names_the_config_key() {
+        let command = probe_command("vidio-no-such-ffprobe", Path::new("x.mkv"));
+        let err = run_tool(command, "tools.ffprobe").unwrap_err();
+        assert!(err.to_string().contains("tools.ffprobe"));
+    }
+
+    #[test]
+    fn converts_srt_cues_to_transcript_lines() {
+        assert_eq!(
+            srt_to_transcript(SAMPLE_SRT).unwrap(),
+            vec![
+                "[00:01.000 - 00:04.200] Hola a todos y bienvenidos.",
+                "[00:04.200 - 00:09.800] Hoy vamos a hablar de cómo organizar el tiempo.",
+                "[01:02:03.500 - 01:02:05.000] Gracias por ver.",
+            ]
+        );
+        assert!(srt_to_transcript("").is_err());
+    }
+}
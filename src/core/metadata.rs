@@ -0,0 +1,172 @@
+use crate::core::storage::{TRANSCRIPTS_DIR, write_atomic_sync};
+use crate::core::transcript::{self, FetchedVideo};
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const METADATA_PREFIX: &str = "meta_";
+const METADATA_SUFFIX: &str = ".json";
+
+pub const AUTO_GENERATED_WARNING: &str =
+    "Transcript uses YouTube's auto-generated captions, which may be inaccurate";
+/// ISO 639 code for a subtitle track that names no language.
+const UNKNOWN_LANGUAGE: &str = "und";
+
+/// Where a transcript's text came from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptSource {
+    #[default]
+    YouTube,
+    /// A subtitle track of a local media file, from `vidio import-media`.
+    Embedded,
+}
+
+/// What YouTube said about a downloaded transcript, kept in a `meta_<id>.json`
+/// sidecar next to it. Imported transcripts have none, except those taken from
+/// a media file's subtitles.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptMetadata {
+    pub language: String,
+    pub language_code: String,
+    pub is_generated: bool,
+    pub fetched_at: DateTime<Utc>,
+    /// Missing from sidecars written before titles were recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<u64>,
+    /// `YYYY-MM-DD`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upload_date: Option<String>,
+    /// Caption lines dropped as artifacts before saving, so the saved
+    /// transcript is known to be shorter than YouTube's.
+    #[serde(default)]
+    pub removed_lines: usize,
+    /// Missing from sidecars written before embedded subtitles were imported.
+    #[serde(default)]
+    pub source: TranscriptSource,
+}
+
+impl TranscriptMetadata {
+    pub fn from_video(video: &FetchedVideo) -> Self {
+        let transcript = &video.transcript;
+        let metadata = &video.metadata;
+        Self {
+            language: transcript.language.clone(),
+            language_code: transcript.language_code.clone(),
+            is_generated: transcript.is_generated,
+            fetched_at: Utc::now(),
+            title: non_empty(&metadata.title),
+            channel: non_empty(&metadata.author),
+            channel_id: metadata.channel_id.clone(),
+            duration_secs: metadata.duration_secs,
+            upload_date: metadata.upload_date.clone(),
+            removed_lines: video.removed_lines,
+            source: TranscriptSource::YouTube,
+        }
+    }
+
+    /// A transcript imported from a media file's subtitle track, in the
+    /// track's language if it has one.
+    pub fn embedded(language: Option<&str>) -> Self {
+        let language = language.unwrap_or(UNKNOWN_LANGUAGE).to_string();
+        Self {
+            language_code: language.clone(),
+            language,
+            is_generated: false,
+            fetched_at: Utc::now(),
+            title: None,
+            channel: None,
+            channel_id: None,
+            duration_secs: None,
+            upload_date: None,
+            removed_lines: 0,
+            source: TranscriptSource::Embedded,
+        }
+    }
+
+    /// The transcript's language code, unless its track named none.
+    pub fn known_language(&self) -> Option<&str> {
+        Some(self.language_code.as_str()).filter(|code| *code != UNKNOWN_LANGUAGE)
+    }
+
+    /// Whether a saved transcript came from YouTube's auto-generated
+    /// captions, as far as its sidecar says.
+    pub fn is_auto_generated(video_id: &str) -> bool {
+        Self::load(video_id)
+            .ok()
+            .flatten()
+            .is_some_and(|metadata| metadata.is_generated)
+    }
+
+    /// The video a sidecar file name belongs to.
+    pub(crate) fn video_id_of(file_name: &str) -> Option<&str> {
+        file_name
+            .strip_prefix(METADATA_PREFIX)?
+            .strip_suffix(METADATA_SUFFIX)
+    }
+
+    pub fn path(video_id: &str) -> Result<PathBuf> {
+        let sanitized = transcript::sanitize_video_id(video_id)?;
+        Ok(PathBuf::from(TRANSCRIPTS_DIR)
+            .join(format!("{METADATA_PREFIX}{sanitized}{METADATA_SUFFIX}")))
+    }
+
+    /// The sidecar for a video, or `None` if it has never been written.
+    pub fn load(video_id: &str) -> Result<Option<Self>> {
+        let path = Self::path(video_id)?;
+        match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).map(Some).map_err(|e| {
+                Error::custom(format!("Invalid metadata file {}: {e}", path.display()))
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, video_id: &str) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| Error::custom(format!("Failed to serialize metadata: {e}")))?;
+        write_atomic_sync(&Self::path(video_id)?, &content)
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TranscriptMetadata, TranscriptSource};
+
+    #[test]
+    fn sidecars_without_a_source_came_from_youtube() {
+        let old: TranscriptMetadata = serde_json::from_str(
+            r#"{"language":"Spanish","language_code":"es","is_generated":true,
+                "fetched_at":"2026-09-21T14:13:20Z"}"#,
+        )
+        .unwrap();
+        assert_eq!(old.source, TranscriptSource::YouTube);
+        assert_eq!(old.known_language(), Some("es"));
+
+        let untagged = TranscriptMetadata::embedded(None);
+        let json = serde_json::to_string(&untagged).unwrap();
+        assert!(json.contains(r#""source":"embedded""#), "{json}");
+        assert_eq!(untagged.known_language(), None);
+        assert_eq!(
+            TranscriptMetadata::embedded(Some("spa")).known_language(),
+            Some("spa")
+        );
+    }
+}
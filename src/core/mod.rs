@@ -1,7 +1,24 @@
+pub mod batch;
+pub mod bookmarks;
+pub mod channels;
+pub mod chapters;
+pub mod config;
+pub mod history;
+pub mod log_file;
+pub mod media;
+pub mod metadata;
+pub mod pipeline;
+pub mod prompt_test;
 pub mod report;
+pub mod searches;
+pub mod settings;
+pub mod site;
 pub mod storage;
 pub mod transcript;
 
+pub use bookmarks::*;
+pub use config::*;
+pub use history::*;
 pub use report::*;
 pub use storage::*;
 pub use transcript::*;
@@ -0,0 +1,1050 @@
+//! The fetch → save → estimate → report → save sequence shared by `vidio get` and the TUI
+//! worker. Cancellation is only honoured between stages, or while a stage is
+//! waiting on the network, so a cancelled job never leaves storage half written.
+
+use crate::core::metadata::TranscriptMetadata;
+use crate::core::{
+    ChunkProgress, CostEstimate, FetchedVideo, GeneratedReport, PromptSource, PromptVideo,
+    ReportInputs, ReportService, ReportTemplate, ReportText, StorageService, TranscriptService,
+    report_language,
+};
+use crate::error::{Error, Result};
+use std::fmt;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// How long a cancelled job gets to reach its next safe point before it is
+/// aborted outright.
+pub const CANCEL_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Fetch,
+    SaveTranscript,
+    /// Price the report and wait for the go-ahead to pay it.
+    EstimateCost,
+    GenerateReport,
+    SaveReport,
+}
+
+impl Stage {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Stage::Fetch => "fetching transcript",
+            Stage::SaveTranscript => "saving transcript",
+            Stage::EstimateCost => "estimating the cost",
+            Stage::GenerateReport => "generating report",
+            Stage::SaveReport => "saving report",
+        }
+    }
+
+    /// Stages that only wait on the network write nothing, so they can stop
+    /// as soon as the token fires. The others always run to the end.
+    fn is_interruptible(self) -> bool {
+        matches!(
+            self,
+            Stage::Fetch | Stage::EstimateCost | Stage::GenerateReport
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    Started(Stage),
+    Finished(Stage),
+    Skipped(Stage),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The transcript, and the report if one was asked for, were already stored.
+    AlreadyProcessed,
+    Completed,
+}
+
+#[derive(Debug)]
+pub enum JobError {
+    Cancelled(Stage),
+    Failed(Stage, Error),
+}
+
+impl fmt::Display for JobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JobError::Cancelled(stage) => write!(f, "Cancelled before {}", stage.describe()),
+            JobError::Failed(stage, e) => write!(f, "Error {}: {e}", stage.describe()),
+        }
+    }
+}
+
+impl From<JobError> for Error {
+    fn from(err: JobError) -> Self {
+        Error::custom(err.to_string())
+    }
+}
+
+/// Undo for the writes of a stage that failed part way.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cleanup {
+    Remove(PathBuf),
+    RestoreReport {
+        video_id: String,
+        template: ReportTemplate,
+        rotated: PathBuf,
+    },
+}
+
+pub struct Job {
+    pub video_id: String,
+    pub languages: Vec<String>,
+    pub preserve_formatting: bool,
+    pub generate_report: bool,
+    /// The report's prompt, which also decides where it is saved.
+    pub prompt: PromptSource,
+    /// Language code to write the report in; `None` follows the transcript.
+    pub report_language: Option<String>,
+    /// Save a machine translation into this language instead of the original.
+    pub translate: Option<String>,
+    /// Replace an existing report even if it is up to date; the old one is
+    /// rotated into the history. A stale report is replaced regardless.
+    pub overwrite_report: bool,
+}
+
+pub trait JobStorage: Sync {
+    fn transcript_exists(&self, video_id: &str, translation: Option<&str>) -> bool;
+    fn report_exists(&self, video_id: &str, template: ReportTemplate) -> bool;
+    /// Whether the report was written from another transcript than the saved
+    /// one, or from other `inputs`.
+    fn report_is_stale(
+        &self,
+        video_id: &str,
+        template: ReportTemplate,
+        translation: Option<&str>,
+        inputs: &ReportInputs,
+    ) -> bool;
+    /// Every file `save_transcript` may create, so a failed save can be undone.
+    fn transcript_files(&self, video_id: &str, translation: Option<&str>) -> Result<Vec<PathBuf>>;
+    fn load_transcript(
+        &self,
+        video_id: &str,
+        translation: Option<&str>,
+    ) -> impl Future<Output = Result<String>> + Send;
+    /// The metadata sidecar of a saved transcript, if it has a readable one.
+    fn metadata(&self, video_id: &str) -> Option<TranscriptMetadata>;
+    fn save_transcript(&self, video: &FetchedVideo) -> impl Future<Output = Result<()>> + Send;
+    fn rotate_report(
+        &self,
+        video_id: &str,
+        template: ReportTemplate,
+    ) -> impl Future<Output = Result<PathBuf>> + Send;
+    fn save_report(
+        &self,
+        video_id: &str,
+        template: ReportTemplate,
+        report: &GeneratedReport,
+    ) -> impl Future<Output = Result<()>> + Send;
+    fn undo(&self, cleanup: &Cleanup) -> impl Future<Output = Result<()>> + Send;
+}
+
+pub trait JobServices: Sync {
+    fn fetch(
+        &self,
+        video_id: &str,
+        languages: &[String],
+        preserve_formatting: bool,
+        translate: Option<&str>,
+    ) -> impl Future<Output = Result<FetchedVideo>> + Send;
+    /// The model, prompt and language a report written now would record.
+    fn report_inputs(&self, prompt: &PromptSource, language: &str) -> ReportInputs;
+    /// Fails if the report cannot be generated, e.g. it is too long for the model.
+    fn estimate_report(
+        &self,
+        transcript: &str,
+        prompt: &PromptSource,
+        video: PromptVideo<'_>,
+    ) -> Result<CostEstimate>;
+    /// Whether the user agrees to pay for the report.
+    fn approve_cost(&self, estimate: CostEstimate) -> impl Future<Output = Result<bool>> + Send;
+    fn generate_report(
+        &self,
+        transcript: &str,
+        prompt: &PromptSource,
+        video: PromptVideo<'_>,
+    ) -> impl Future<Output = Result<GeneratedReport>> + Send;
+}
+
+/// The files under `transcripts/` and `reports/`.
+pub struct FileStorage;
+
+impl JobStorage for FileStorage {
+    fn transcript_exists(&self, video_id: &str, translation: Option<&str>) -> bool {
+        StorageService::transcript_exists(video_id, translation)
+    }
+
+    fn report_exists(&self, video_id: &str, template: ReportTemplate) -> bool {
+        StorageService::report_exists(video_id, template)
+    }
+
+    /// A report or transcript that cannot be read is left as it is.
+    fn report_is_stale(
+        &self,
+        video_id: &str,
+        template: ReportTemplate,
+        translation: Option<&str>,
+        inputs: &ReportInputs,
+    ) -> bool {
+        StorageService::report_is_stale(video_id, template, translation, inputs).unwrap_or(false)
+    }
+
+    fn transcript_files(&self, video_id: &str, translation: Option<&str>) -> Result<Vec<PathBuf>> {
+        let mut files = vec![StorageService::transcript_path(video_id, translation)?];
+        // Translations leave the original's sidecar and copies alone
+        if translation.is_none() {
+            files.push(TranscriptMetadata::path(video_id)?);
+            files.extend(StorageService::transcript_copy_paths(video_id)?);
+        }
+        Ok(files)
+    }
+
+    async fn load_transcript(&self, video_id: &str, translation: Option<&str>) -> Result<String> {
+        StorageService::load_transcript(video_id, translation).await
+    }
+
+    fn metadata(&self, video_id: &str) -> Option<TranscriptMetadata> {
+        TranscriptMetadata::load(video_id).ok().flatten()
+    }
+
+    async fn save_transcript(&self, video: &FetchedVideo) -> Result<()> {
+        StorageService::save_transcript(video).await.map(|_| ())
+    }
+
+    async fn rotate_report(&self, video_id: &str, template: ReportTemplate) -> Result<PathBuf> {
+        StorageService::rotate_report(video_id, template).await
+    }
+
+    async fn save_report(
+        &self,
+        video_id: &str,
+        template: ReportTemplate,
+        report: &GeneratedReport,
+    ) -> Result<()> {
+        StorageService::save_report(video_id, template, report)
+            .await
+            .map(|_| ())
+    }
+
+    async fn undo(&self, cleanup: &Cleanup) -> Result<()> {
+        match cleanup {
+            Cleanup::Remove(path) => match tokio::fs::remove_file(path).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            },
+            Cleanup::RestoreReport {
+                video_id,
+                template,
+                rotated,
+            } => StorageService::restore_report(video_id, *template, rotated).await,
+        }
+    }
+}
+
+/// Asks whether a report may be generated at its estimated cost; the answer
+/// arrives on the returned receiver, and a dropped sender means no.
+pub type ApproveCost<'a> = dyn Fn(CostEstimate) -> oneshot::Receiver<Result<bool>> + Sync + 'a;
+
+pub struct Services<'a> {
+    pub transcript: &'a TranscriptService,
+    pub report: &'a ReportService,
+    pub approve: &'a ApproveCost<'a>,
+    /// Told about each request of a report sent in parts.
+    pub on_chunk: &'a (dyn Fn(ChunkProgress) + Sync),
+    /// Given the report's text as the model writes it.
+    pub on_text: &'a (dyn Fn(ReportText<'_>) + Sync),
+}
+
+impl JobServices for Services<'_> {
+    async fn fetch(
+        &self,
+        video_id: &str,
+        languages: &[String],
+        preserve_formatting: bool,
+        translate: Option<&str>,
+    ) -> Result<FetchedVideo> {
+        let languages: Vec<&str> = languages.iter().map(|s| s.as_str()).collect();
+        self.transcript
+            .fetch_video(video_id, &languages, preserve_formatting, translate)
+            .await
+    }
+
+    fn report_inputs(&self, prompt: &PromptSource, language: &str) -> ReportInputs {
+        self.report.inputs(prompt, language)
+    }
+
+    fn estimate_report(
+        &self,
+        transcript: &str,
+        prompt: &PromptSource,
+        video: PromptVideo<'_>,
+    ) -> Result<CostEstimate> {
+        self.report.estimate(transcript, prompt, video)
+    }
+
+    async fn approve_cost(&self, estimate: CostEstimate) -> Result<bool> {
+        if estimate.is_free() {
+            return Ok(true);
+        }
+        (self.approve)(estimate).await.unwrap_or(Ok(false))
+    }
+
+    async fn generate_report(
+        &self,
+        transcript: &str,
+        prompt: &PromptSource,
+        video: PromptVideo<'_>,
+    ) -> Result<GeneratedReport> {
+        self.report
+            .generate_report_stream(transcript, prompt, video, self.on_chunk, self.on_text)
+            .await
+    }
+}
+
+/// A stage that has started writing, with the cleanups to run if it fails.
+struct Pending {
+    stage: Stage,
+    cleanups: Vec<Cleanup>,
+}
+
+impl Pending {
+    fn on_failure(&mut self, cleanup: Cleanup) {
+        self.cleanups.push(cleanup);
+    }
+}
+
+struct Stages<'a, S> {
+    storage: &'a S,
+    token: &'a CancellationToken,
+    progress: &'a mut (dyn FnMut(Progress) + Send),
+}
+
+impl<S: JobStorage> Stages<'_, S> {
+    /// The safe point before a stage: stop here if the job was cancelled.
+    fn begin(&mut self, stage: Stage) -> std::result::Result<Pending, JobError> {
+        if self.token.is_cancelled() {
+            return Err(JobError::Cancelled(stage));
+        }
+        (self.progress)(Progress::Started(stage));
+        Ok(Pending {
+            stage,
+            cleanups: Vec::new(),
+        })
+    }
+
+    /// Run a stage that writes nothing, giving up as soon as the token fires.
+    async fn wait<T>(
+        &mut self,
+        stage: Stage,
+        work: impl Future<Output = Result<T>>,
+    ) -> std::result::Result<T, JobError> {
+        debug_assert!(stage.is_interruptible());
+        let pending = self.begin(stage)?;
+        let result = tokio::select! {
+            result = work => result,
+            _ = self.token.cancelled() => return Err(JobError::Cancelled(stage)),
+        };
+        self.finish(pending, result).await
+    }
+
+    /// Close a stage, undoing its writes newest first if it failed.
+    async fn finish<T>(
+        &mut self,
+        pending: Pending,
+        result: Result<T>,
+    ) -> std::result::Result<T, JobError> {
+        match result {
+            Ok(value) => {
+                (self.progress)(Progress::Finished(pending.stage));
+                Ok(value)
+            }
+            Err(e) => {
+                let mut failed_cleanups = Vec::new();
+                for cleanup in pending.cleanups.iter().rev() {
+                    if let Err(cleanup_err) = self.storage.undo(cleanup).await {
+                        failed_cleanups.push(format!("{cleanup:?}: {cleanup_err}"));
+                    }
+                }
+                let e = if failed_cleanups.is_empty() {
+                    e
+                } else {
+                    Error::custom(format!(
+                        "{e} (cleanup also failed: {})",
+                        failed_cleanups.join("; ")
+                    ))
+                };
+                Err(JobError::Failed(pending.stage, e))
+            }
+        }
+    }
+
+    fn skip(&mut self, stage: Stage) {
+        (self.progress)(Progress::Skipped(stage));
+    }
+}
+
+pub async fn run_job<S: JobStorage, V: JobServices>(
+    storage: &S,
+    services: &V,
+    job: &Job,
+    token: &CancellationToken,
+    progress: &mut (dyn FnMut(Progress) + Send),
+) -> std::result::Result<Outcome, JobError> {
+    let video_id = job.video_id.as_str();
+    let translation = job.translate.as_deref();
+    let template = job.prompt.template();
+    let transcript_exists = storage.transcript_exists(video_id, translation);
+    let report_exists = storage.report_exists(video_id, template);
+    let is_stale = || {
+        let language = report_language(
+            job.report_language.as_deref(),
+            saved_language(storage, video_id, translation).as_deref(),
+        );
+        let inputs = services.report_inputs(&job.prompt, &language);
+        storage.report_is_stale(video_id, template, translation, &inputs)
+    };
+    let needs_report = job.generate_report
+        && (job.overwrite_report || !report_exists || (transcript_exists && is_stale()));
+
+    if transcript_exists && !needs_report {
+        return Ok(Outcome::AlreadyProcessed);
+    }
+
+    let mut stages = Stages {
+        storage,
+        token,
+        progress,
+    };
+
+    let fetched = if transcript_exists {
+        stages.skip(Stage::Fetch);
+        stages.skip(Stage::SaveTranscript);
+        None
+    } else {
+        let video = stages
+            .wait(
+                Stage::Fetch,
+                services.fetch(
+                    video_id,
+                    &job.languages,
+                    job.preserve_formatting,
+                    translation,
+                ),
+            )
+            .await?;
+
+        let mut pending = stages.begin(Stage::SaveTranscript)?;
+        let saved = match storage.transcript_files(video_id, translation) {
+            Ok(files) => {
+                for path in files {
+                    pending.on_failure(Cleanup::Remove(path));
+                }
+                storage.save_transcript(&video).await
+            }
+            Err(e) => Err(e),
+        };
+        stages.finish(pending, saved).await?;
+
+        Some((
+            video.saved_text(),
+            video.metadata.title,
+            Some(video.transcript.language_code),
+            video.removed_lines,
+        ))
+    };
+
+    if !needs_report {
+        stages.skip(Stage::GenerateReport);
+        stages.skip(Stage::SaveReport);
+        return Ok(Outcome::Completed);
+    }
+
+    let (transcript_text, title, transcript_language, removed_lines) = match fetched {
+        Some(fetched) => fetched,
+        None => {
+            let text = storage
+                .load_transcript(video_id, translation)
+                .await
+                .map_err(|e| JobError::Failed(Stage::GenerateReport, e))?;
+            let metadata = storage.metadata(video_id);
+            (
+                text,
+                metadata.as_ref().and_then(|m| m.title.clone()),
+                saved_language(storage, video_id, translation),
+                metadata.map_or(0, |m| m.removed_lines),
+            )
+        }
+    };
+    let language = report_language(
+        job.report_language.as_deref(),
+        transcript_language.as_deref(),
+    );
+    let video = PromptVideo {
+        video_id,
+        title: title.as_deref(),
+        language: &language,
+        removed_lines,
+    };
+    let approved = stages
+        .wait(Stage::EstimateCost, async {
+            let estimate = services.estimate_report(&transcript_text, &job.prompt, video)?;
+            services.approve_cost(estimate).await
+        })
+        .await?;
+    if !approved {
+        return Err(JobError::Cancelled(Stage::GenerateReport));
+    }
+    let report = stages
+        .wait(
+            Stage::GenerateReport,
+            services.generate_report(&transcript_text, &job.prompt, video),
+        )
+        .await?;
+
+    let mut pending = stages.begin(Stage::SaveReport)?;
+    let mut saved = Ok(());
+    if report_exists {
+        match storage.rotate_report(video_id, template).await {
+            Ok(rotated) => pending.on_failure(Cleanup::RestoreReport {
+                video_id: video_id.to_string(),
+                template,
+                rotated,
+            }),
+            Err(e) => saved = Err(e),
+        }
+    }
+    if saved.is_ok() {
+        saved = storage.save_report(video_id, template, &report).await;
+    }
+    stages.finish(pending, saved).await?;
+
+    Ok(Outcome::Completed)
+}
+
+/// The language of a saved transcript. The sidecar describes the original,
+/// not a translation of it.
+fn saved_language<S: JobStorage>(
+    storage: &S,
+    video_id: &str,
+    translation: Option<&str>,
+) -> Option<String> {
+    match translation {
+        Some(language) => Some(language.to_string()),
+        None => storage
+            .metadata(video_id)
+            .and_then(|m| m.known_language().map(str::to_string)),
+    }
+}
+
+/// Cancel a running job and give it `CANCEL_GRACE` to reach a safe point;
+/// abort it only if it has not finished by then.
+pub async fn stop(token: &CancellationToken, mut task: JoinHandle<()>) {
+    token.cancel();
+    if tokio::time::timeout(CANCEL_GRACE, &mut task).await.is_err() {
+        task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Cleanup, CostEstimate, FetchedVideo, GeneratedReport, Job, JobError, JobServices,
+        JobStorage, Outcome, Progress, PromptSource, PromptVideo, ReportInputs, ReportTemplate,
+        Stage, TranscriptMetadata, run_job,
+    };
+    use crate::core::{DEFAULT_PARAGRAPH_GAP, ReportMetadata, TranscriptFormat, VideoMetadata};
+    use crate::error::{Error, Result};
+    use chrono::Local;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tokio_util::sync::CancellationToken;
+    use yt_transcript_rs::FetchedTranscript;
+
+    const ID: &str = "abcdefghijk";
+    const TRANSCRIPT: &str = "transcripts/transcript_abcdefghijk.txt";
+    const METADATA: &str = "transcripts/meta_abcdefghijk.json";
+    const REPORT: &str = "reports/report_abcdefghijk.md";
+    const ROTATED: &str = "reports/history/report_abcdefghijk.1.md";
+
+    /// Every point where the mocks can fail or cancel the job.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Point {
+        Fetch,
+        WriteTranscript,
+        WriteMetadata,
+        Approve,
+        Generate,
+        Rotate,
+        WriteReport,
+    }
+
+    const POINTS: [Point; 7] = [
+        Point::Fetch,
+        Point::WriteTranscript,
+        Point::WriteMetadata,
+        Point::Approve,
+        Point::Generate,
+        Point::Rotate,
+        Point::WriteReport,
+    ];
+
+    /// In-memory files plus the failure or cancellation to inject.
+    struct Mock {
+        files: Mutex<BTreeMap<PathBuf, String>>,
+        token: CancellationToken,
+        fail_at: Option<Point>,
+        cancel_at: Option<Point>,
+        /// Say no to the cost estimate.
+        decline: bool,
+        /// The saved report was written from another transcript.
+        stale: bool,
+    }
+
+    impl Mock {
+        fn new(files: &[(&str, &str)]) -> Self {
+            Self {
+                files: Mutex::new(
+                    files
+                        .iter()
+                        .map(|(path, content)| (PathBuf::from(path), content.to_string()))
+                        .collect(),
+                ),
+                token: CancellationToken::new(),
+                fail_at: None,
+                cancel_at: None,
+                decline: false,
+                stale: false,
+            }
+        }
+
+        fn reach(&self, point: Point) -> Result<()> {
+            if self.cancel_at == Some(point) {
+                self.token.cancel();
+            }
+            if self.fail_at == Some(point) {
+                return Err(Error::custom(format!("injected failure at {point:?}")));
+            }
+            Ok(())
+        }
+
+        /// Network calls cancelled mid-way hang until the pipeline gives up on them.
+        async fn reach_network(&self, point: Point) -> Result<()> {
+            self.reach(point)?;
+            if self.cancel_at == Some(point) {
+                std::future::pending::<()>().await;
+            }
+            Ok(())
+        }
+
+        fn write(&self, path: &str, content: &str) {
+            self.files
+                .lock()
+                .unwrap()
+                .insert(PathBuf::from(path), content.to_string());
+        }
+
+        fn get(&self, path: &str) -> Option<String> {
+            self.files
+                .lock()
+                .unwrap()
+                .get(&PathBuf::from(path))
+                .cloned()
+        }
+
+        fn paths(&self) -> Vec<String> {
+            self.files
+                .lock()
+                .unwrap()
+                .keys()
+                .map(|path| path.display().to_string())
+                .collect()
+        }
+    }
+
+    impl JobStorage for Mock {
+        fn transcript_exists(&self, _video_id: &str, _translation: Option<&str>) -> bool {
+            self.get(TRANSCRIPT).is_some()
+        }
+
+        fn report_exists(&self, _video_id: &str, _template: ReportTemplate) -> bool {
+            self.get(REPORT).is_some()
+        }
+
+        fn report_is_stale(
+            &self,
+            _video_id: &str,
+            _template: ReportTemplate,
+            _translation: Option<&str>,
+            _inputs: &ReportInputs,
+        ) -> bool {
+            self.stale
+        }
+
+        fn transcript_files(
+            &self,
+            _video_id: &str,
+            _translation: Option<&str>,
+        ) -> Result<Vec<PathBuf>> {
+            Ok(vec![PathBuf::from(TRANSCRIPT), PathBuf::from(METADATA)])
+        }
+
+        async fn load_transcript(
+            &self,
+            _video_id: &str,
+            _translation: Option<&str>,
+        ) -> Result<String> {
+            self.get(TRANSCRIPT)
+                .ok_or_else(|| Error::custom("no transcript"))
+        }
+
+        fn metadata(&self, _video_id: &str) -> Option<TranscriptMetadata> {
+            None
+        }
+
+        async fn save_transcript(&self, _video: &FetchedVideo) -> Result<()> {
+            self.reach(Point::WriteTranscript)?;
+            self.write(TRANSCRIPT, "new transcript");
+            self.reach(Point::WriteMetadata)?;
+            self.write(METADATA, "{}");
+            Ok(())
+        }
+
+        async fn rotate_report(
+            &self,
+            _video_id: &str,
+            _template: ReportTemplate,
+        ) -> Result<PathBuf> {
+            self.reach(Point::Rotate)?;
+            let mut files = self.files.lock().unwrap();
+            let old = files.remove(&PathBuf::from(REPORT)).unwrap();
+            files.insert(PathBuf::from(ROTATED), old);
+            Ok(PathBuf::from(ROTATED))
+        }
+
+        async fn save_report(
+            &self,
+            _video_id: &str,
+            _template: ReportTemplate,
+            report: &GeneratedReport,
+        ) -> Result<()> {
+            self.reach(Point::WriteReport)?;
+            self.write(REPORT, &report.content);
+            Ok(())
+        }
+
+        async fn undo(&self, cleanup: &Cleanup) -> Result<()> {
+            let mut files = self.files.lock().unwrap();
+            match cleanup {
+                Cleanup::Remove(path) => {
+                    files.remove(path);
+                }
+                Cleanup::RestoreReport { rotated, .. } => {
+                    let old = files.remove(rotated).unwrap();
+                    files.insert(PathBuf::from(REPORT), old);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    impl JobServices for Mock {
+        async fn fetch(
+            &self,
+            video_id: &str,
+            _languages: &[String],
+            _preserve_formatting: bool,
+            _translate: Option<&str>,
+        ) -> Result<FetchedVideo> {
+            self.reach_network(Point::Fetch).await?;
+            Ok(FetchedVideo {
+                transcript: FetchedTranscript {
+                    snippets: Vec::new(),
+                    video_id: video_id.to_string(),
+                    language: "English".to_string(),
+                    language_code: "en".to_string(),
+                    is_generated: false,
+                },
+                metadata: VideoMetadata::default(),
+                translation: None,
+                chapters: Vec::new(),
+                format: TranscriptFormat::Timestamped,
+                paragraph_gap: DEFAULT_PARAGRAPH_GAP,
+                removed_lines: 0,
+            })
+        }
+
+        fn report_inputs(&self, prompt: &PromptSource, language: &str) -> ReportInputs {
+            ReportInputs {
+                model: "gpt-5.2".to_string(),
+                prompt_sha256: prompt.hash(),
+                language: language.to_string(),
+            }
+        }
+
+        fn estimate_report(
+            &self,
+            _transcript: &str,
+            _prompt: &PromptSource,
+            _video: PromptVideo<'_>,
+        ) -> Result<CostEstimate> {
+            Ok(CostEstimate {
+                model: "mock".to_string(),
+                input_tokens: 1,
+                output_tokens: 1,
+                cost: None,
+                requests: 1,
+            })
+        }
+
+        async fn approve_cost(&self, _estimate: CostEstimate) -> Result<bool> {
+            self.reach_network(Point::Approve).await?;
+            Ok(!self.decline)
+        }
+
+        async fn generate_report(
+            &self,
+            _transcript: &str,
+            _prompt: &PromptSource,
+            _video: PromptVideo<'_>,
+        ) -> Result<GeneratedReport> {
+            self.reach_network(Point::Generate).await?;
+            Ok(GeneratedReport {
+                content: "new report".to_string(),
+                metadata: ReportMetadata {
+                    model: "gpt-5.2".to_string(),
+                    template: None,
+                    created_at: Local::now().fixed_offset(),
+                    input_tokens: None,
+                    output_tokens: None,
+                    duration: Duration::ZERO,
+                    transcript_sha256: None,
+                    prompt_sha256: None,
+                    language: None,
+                },
+            })
+        }
+    }
+
+    fn job(overwrite_report: bool) -> Job {
+        Job {
+            video_id: ID.to_string(),
+            languages: vec!["en".to_string()],
+            preserve_formatting: false,
+            generate_report: true,
+            prompt: PromptSource::default(),
+            report_language: None,
+            translate: None,
+            overwrite_report,
+        }
+    }
+
+    async fn run(
+        mock: &Mock,
+        job: &Job,
+    ) -> (std::result::Result<Outcome, JobError>, Vec<Progress>) {
+        let mut events = Vec::new();
+        let result = run_job(mock, mock, job, &mock.token, &mut |event| {
+            events.push(event)
+        })
+        .await;
+        (result, events)
+    }
+
+    fn stage_of(result: &std::result::Result<Outcome, JobError>) -> Option<(bool, Stage)> {
+        match result {
+            Ok(_) => None,
+            Err(JobError::Cancelled(stage)) => Some((true, *stage)),
+            Err(JobError::Failed(stage, _)) => Some((false, *stage)),
+        }
+    }
+
+    /// A transcript never exists without its metadata, and the old report is
+    /// either still in place or safely rotated next to its replacement.
+    fn assert_consistent(mock: &Mock) {
+        let paths = mock.paths();
+        assert_eq!(
+            mock.get(TRANSCRIPT).is_some(),
+            mock.get(METADATA).is_some(),
+            "{paths:?}"
+        );
+        if let Some(rotated) = mock.get(ROTATED) {
+            assert_eq!(rotated, "old report");
+            assert_eq!(mock.get(REPORT).as_deref(), Some("new report"));
+        }
+    }
+
+    #[tokio::test]
+    async fn fresh_job_writes_every_file() {
+        let mock = Mock::new(&[]);
+        let (result, events) = run(&mock, &job(false)).await;
+
+        assert_eq!(result.unwrap(), Outcome::Completed);
+        assert_eq!(mock.paths(), [REPORT, METADATA, TRANSCRIPT]);
+        assert_eq!(events.len(), 10);
+        assert_eq!(events[9], Progress::Finished(Stage::SaveReport));
+    }
+
+    #[tokio::test]
+    async fn stored_video_is_left_alone() {
+        let mock = Mock::new(&[(TRANSCRIPT, "t"), (METADATA, "{}"), (REPORT, "old report")]);
+        let (result, events) = run(&mock, &job(false)).await;
+
+        assert_eq!(result.unwrap(), Outcome::AlreadyProcessed);
+        assert!(events.is_empty());
+        assert_eq!(mock.get(REPORT).as_deref(), Some("old report"));
+    }
+
+    #[tokio::test]
+    async fn failures_leave_storage_consistent() {
+        let expected = [
+            (Point::Fetch, Stage::Fetch, vec![]),
+            (Point::WriteTranscript, Stage::SaveTranscript, vec![]),
+            (Point::WriteMetadata, Stage::SaveTranscript, vec![]),
+            (
+                Point::Approve,
+                Stage::EstimateCost,
+                vec![METADATA, TRANSCRIPT],
+            ),
+            (
+                Point::Generate,
+                Stage::GenerateReport,
+                vec![METADATA, TRANSCRIPT],
+            ),
+            (
+                Point::WriteReport,
+                Stage::SaveReport,
+                vec![METADATA, TRANSCRIPT],
+            ),
+        ];
+        for (point, stage, paths) in expected {
+            let mut mock = Mock::new(&[]);
+            mock.fail_at = Some(point);
+            let (result, _) = run(&mock, &job(false)).await;
+
+            assert_eq!(stage_of(&result), Some((false, stage)), "{point:?}");
+            assert_eq!(mock.paths(), paths, "{point:?}");
+            assert_consistent(&mock);
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_overwrite_keeps_the_old_report() {
+        for point in [
+            Point::Approve,
+            Point::Generate,
+            Point::Rotate,
+            Point::WriteReport,
+        ] {
+            let mut mock =
+                Mock::new(&[(TRANSCRIPT, "t"), (METADATA, "{}"), (REPORT, "old report")]);
+            mock.fail_at = Some(point);
+            let (result, _) = run(&mock, &job(true)).await;
+
+            assert!(result.is_err(), "{point:?}");
+            assert_eq!(mock.get(REPORT).as_deref(), Some("old report"), "{point:?}");
+            assert_eq!(mock.get(ROTATED), None, "{point:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelling_at_any_point_stops_at_a_safe_boundary() {
+        let expected = [
+            (Point::Fetch, Some(Stage::Fetch), vec![REPORT]),
+            (
+                Point::WriteTranscript,
+                Some(Stage::EstimateCost),
+                vec![REPORT, METADATA, TRANSCRIPT],
+            ),
+            (
+                Point::WriteMetadata,
+                Some(Stage::EstimateCost),
+                vec![REPORT, METADATA, TRANSCRIPT],
+            ),
+            (
+                Point::Approve,
+                Some(Stage::EstimateCost),
+                vec![REPORT, METADATA, TRANSCRIPT],
+            ),
+            (
+                Point::Generate,
+                Some(Stage::GenerateReport),
+                vec![REPORT, METADATA, TRANSCRIPT],
+            ),
+            // Once the report is being saved the stage finishes
+            (
+                Point::Rotate,
+                None,
+                vec![ROTATED, REPORT, METADATA, TRANSCRIPT],
+            ),
+            (
+                Point::WriteReport,
+                None,
+                vec![ROTATED, REPORT, METADATA, TRANSCRIPT],
+            ),
+        ];
+        assert_eq!(expected.len(), POINTS.len());
+
+        for (point, cancelled, paths) in expected {
+            let mut mock = Mock::new(&[(REPORT, "old report")]);
+            mock.cancel_at = Some(point);
+            let (result, _) = run(&mock, &job(true)).await;
+
+            assert_eq!(
+                stage_of(&result),
+                cancelled.map(|stage| (true, stage)),
+                "{point:?}"
+            );
+            assert_eq!(mock.paths(), paths, "{point:?}");
+            assert_consistent(&mock);
+            if cancelled.is_some() {
+                assert_eq!(mock.get(REPORT).as_deref(), Some("old report"), "{point:?}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn declined_cost_keeps_the_transcript_and_the_old_report() {
+        let mut mock = Mock::new(&[(REPORT, "old report")]);
+        mock.decline = true;
+        let (result, _) = run(&mock, &job(true)).await;
+
+        assert_eq!(stage_of(&result), Some((true, Stage::GenerateReport)));
+        assert_eq!(mock.paths(), [REPORT, METADATA, TRANSCRIPT]);
+        assert_eq!(mock.get(REPORT).as_deref(), Some("old report"));
+    }
+
+    #[tokio::test]
+    async fn stale_report_is_generated_again() {
+        let mut mock = Mock::new(&[(TRANSCRIPT, "t"), (METADATA, "{}"), (REPORT, "old report")]);
+        mock.stale = true;
+        let (result, _) = run(&mock, &job(false)).await;
+
+        assert_eq!(result.unwrap(), Outcome::Completed);
+        assert_eq!(mock.get(ROTATED).as_deref(), Some("old report"));
+        assert_eq!(mock.get(REPORT).as_deref(), Some("new report"));
+    }
+
+    #[tokio::test]
+    async fn cancelled_job_does_not_start() {
+        let mock = Mock::new(&[]);
+        mock.token.cancel();
+        let (result, events) = run(&mock, &job(false)).await;
+
+        assert_eq!(stage_of(&result), Some((true, Stage::Fetch)));
+        assert!(events.is_empty());
+        assert!(mock.paths().is_empty());
+    }
+}
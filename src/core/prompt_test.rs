@@ -0,0 +1,91 @@
+// Prompt template test harness: render a template around a transcript
+// fixture, obtain a response (live or canned) and lint its structure.
+use crate::core::report::lint::{self, Check, LintProfile};
+use crate::core::report::prompt::DEFAULT_LANGUAGE;
+use crate::core::report::{ReportService, ReportTemplate, ensure_table_headers};
+use crate::error::{Error, Result};
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+pub const BUNDLED_FIXTURE: &str = "demo";
+const DEMO_TRANSCRIPT: &str = include_str!("../../fixtures/demo_transcript.txt");
+const DEMO_REPORT: &str = include_str!("../../fixtures/demo_report.md");
+pub const RESULTS_FILE: &str = "prompt-tests.jsonl";
+
+pub struct PromptTestOptions {
+    pub template: String,
+    pub fixture: String,
+    pub canned: Option<String>,
+    pub profile: LintProfile,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptTestRecord {
+    pub timestamp: String,
+    pub template: String,
+    pub fixture: String,
+    pub canned: Option<String>,
+    pub profile: LintProfile,
+    pub prompt_chars: usize,
+    pub passed: bool,
+    pub checks: Vec<Check>,
+}
+
+pub async fn run_prompt_test(
+    report_service: &ReportService,
+    options: PromptTestOptions,
+) -> Result<PromptTestRecord> {
+    let template = ReportTemplate::parse(&options.template)?;
+    if template == ReportTemplate::Custom {
+        return Err(Error::custom(
+            "prompt-test runs the built-in templates; pick detailed, summary or chapters",
+        ));
+    }
+
+    let transcript = load_fixture(&options.fixture, DEMO_TRANSCRIPT)?;
+    let prompt = template.user_prompt(&transcript, DEFAULT_LANGUAGE, 0);
+
+    let response = match options.canned.as_deref() {
+        Some(canned) => load_fixture(canned, DEMO_REPORT)?,
+        None => {
+            report_service
+                .complete(template.system_prompt(), &prompt)
+                .await?
+        }
+    };
+
+    let report = ensure_table_headers(&response, template);
+    let checks = lint::lint_report(&report, options.profile);
+    let passed = checks.iter().all(|c| c.passed);
+
+    Ok(PromptTestRecord {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        template: options.template,
+        fixture: options.fixture,
+        canned: options.canned,
+        profile: options.profile,
+        prompt_chars: prompt.chars().count(),
+        passed,
+        checks,
+    })
+}
+
+/// Append a result line so successive template iterations can be compared.
+pub fn append_record(path: &Path, record: &PromptTestRecord) -> Result<()> {
+    let line = serde_json::to_string(record)
+        .map_err(|e| Error::custom(format!("Failed to serialize prompt test result: {e}")))?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")?;
+    Ok(())
+}
+
+fn load_fixture(name: &str, bundled: &str) -> Result<String> {
+    if name == BUNDLED_FIXTURE {
+        return Ok(bundled.to_string());
+    }
+
+    std::fs::read_to_string(name)
+        .map_err(|e| Error::custom(format!("Failed to read fixture '{name}': {e}")))
+}
@@ -0,0 +1,133 @@
+// Transcripts too long for one request are reported on part by part, and
+// the partial reports merged into one with the same prompt.
+use super::cost::estimate_tokens;
+
+/// Room left in each part for the note that says which part it is.
+pub const PART_NOTE_TOKENS: u64 = 40;
+
+/// Split a transcript into parts of at most `budget` tokens, breaking
+/// between lines, or between words for a line that is longer on its own.
+pub fn split_transcript(text: &str, budget: u64) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut used = 0;
+    for piece in pieces(text, budget) {
+        // The line break before the piece is a token of its own
+        let tokens = estimate_tokens(&piece) + 1;
+        if used + tokens > budget && !current.is_empty() {
+            parts.push(std::mem::take(&mut current));
+            used = 0;
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(&piece);
+        used += tokens;
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+/// The lines of `text`, with any line over `budget` cut into word runs.
+fn pieces(text: &str, budget: u64) -> Vec<String> {
+    let mut pieces = Vec::new();
+    for line in text.lines() {
+        if estimate_tokens(line) <= budget {
+            pieces.push(line.to_string());
+            continue;
+        }
+        let mut run = String::new();
+        let mut used = 0;
+        for word in line.split_whitespace() {
+            let tokens = estimate_tokens(word);
+            if used + tokens > budget && !run.is_empty() {
+                pieces.push(std::mem::take(&mut run));
+                used = 0;
+            }
+            if !run.is_empty() {
+                run.push(' ');
+            }
+            run.push_str(word);
+            used += tokens;
+        }
+        if !run.is_empty() {
+            pieces.push(run);
+        }
+    }
+    pieces
+}
+
+/// What goes in the prompt in place of the transcript for one part.
+pub fn part_text(part: &str, index: usize, total: usize) -> String {
+    format!(
+        "(Parte {index} de {total} de una transcripción más larga. Analiza solo esta parte; \
+         otra solicitud unirá los reportes parciales.)\n\n{part}"
+    )
+}
+
+/// What goes in the prompt in place of the transcript for the merge pass.
+pub fn merge_text(partials: &[String]) -> String {
+    let mut text = format!(
+        "(La transcripción era demasiado larga para una sola solicitud. Estos son los reportes \
+         parciales de sus {} partes consecutivas, en orden. Únelos en un único reporte con la \
+         estructura pedida: elimina repeticiones, conserva las marcas de tiempo y no inventes \
+         contenido.)\n",
+        partials.len()
+    );
+    for (i, partial) in partials.iter().enumerate() {
+        text.push_str(&format!(
+            "\n### Reporte parcial {}\n\n{}\n",
+            i + 1,
+            partial.trim()
+        ));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge_text, part_text, split_transcript};
+    use crate::core::report::cost::estimate_tokens;
+
+    #[test]
+    fn parts_break_between_lines_and_stay_in_budget() {
+        let transcript = (0..100)
+            .map(|i| format!("[00:{i:02}] linea numero {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let parts = split_transcript(&transcript, 120);
+
+        assert!(parts.len() > 1);
+        assert!(parts.iter().all(|part| estimate_tokens(part) <= 120));
+        assert_eq!(parts.join("\n"), transcript);
+    }
+
+    #[test]
+    fn a_single_long_line_is_cut_between_words() {
+        let line = "palabra ".repeat(100);
+        let parts = split_transcript(line.trim(), 50);
+
+        assert_eq!(parts.len(), 4);
+        assert!(parts.iter().all(|part| estimate_tokens(part) <= 50));
+        assert_eq!(parts.join(" "), line.trim());
+    }
+
+    #[test]
+    fn short_transcripts_stay_whole() {
+        assert_eq!(split_transcript("hola\nmundo", 100), ["hola\nmundo"]);
+        assert!(split_transcript("", 100).is_empty());
+    }
+
+    #[test]
+    fn part_and_merge_texts_number_the_parts() {
+        assert!(part_text("hola", 2, 3).starts_with("(Parte 2 de 3"));
+        assert!(part_text("hola", 2, 3).ends_with("\n\nhola"));
+
+        let merged = merge_text(&["uno\n".to_string(), "dos".to_string()]);
+        assert!(merged.contains("sus 2 partes"));
+        assert!(merged.contains("### Reporte parcial 1\n\nuno\n"));
+        assert!(merged.ends_with("### Reporte parcial 2\n\ndos\n"));
+    }
+}
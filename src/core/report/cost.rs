@@ -0,0 +1,261 @@
+// Rough token counts and prices for a report, worked out before anything is
+// uploaded so a long transcript never costs more than the user agreed to.
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Reports never come out shorter than this, however short the transcript.
+const MIN_OUTPUT_TOKENS: u64 = 2_000;
+
+/// What a model costs, in US dollars per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+    /// Most tokens the model accepts as input.
+    pub context_window: u64,
+}
+
+/// The built-in prices, keyed by model name or prefix. Entries in
+/// `settings.json` are looked up over these.
+pub fn default_pricing() -> BTreeMap<String, ModelPricing> {
+    let price = |input_per_million, output_per_million, context_window| ModelPricing {
+        input_per_million,
+        output_per_million,
+        context_window,
+    };
+    [
+        ("gpt-5.2", price(1.75, 14.0, 400_000)),
+        ("gpt-5", price(1.25, 10.0, 400_000)),
+        ("gpt-5-mini", price(0.25, 2.0, 400_000)),
+        ("gpt-5-nano", price(0.05, 0.40, 400_000)),
+        ("gpt-4.1", price(2.0, 8.0, 1_047_576)),
+        ("gpt-4.1-mini", price(0.40, 1.60, 1_047_576)),
+        ("gpt-4o", price(2.50, 10.0, 128_000)),
+        ("gpt-4o-mini", price(0.15, 0.60, 128_000)),
+        ("o3", price(2.0, 8.0, 200_000)),
+        ("o4-mini", price(1.10, 4.40, 200_000)),
+    ]
+    .into_iter()
+    .map(|(model, pricing)| (model.to_string(), pricing))
+    .collect()
+}
+
+/// The price of `model`: its own entry, else the longest entry it starts
+/// with, so `gpt-4o-2024-08-06` is priced as `gpt-4o`. `overrides` replace
+/// or add to the built-in prices.
+pub fn pricing_for(
+    model: &str,
+    overrides: &BTreeMap<String, ModelPricing>,
+) -> Option<ModelPricing> {
+    let mut table = default_pricing();
+    table.extend(
+        overrides
+            .iter()
+            .map(|(name, pricing)| (name.clone(), *pricing)),
+    );
+    table
+        .iter()
+        .filter(|(name, _)| model.starts_with(name.as_str()))
+        .max_by_key(|(name, _)| name.len())
+        .map(|(_, pricing)| *pricing)
+}
+
+/// Tokens in `text` by o200k_base, the tokenizer of GPT-4o and later. Older
+/// and non-OpenAI models split text a little differently, which an estimate
+/// can live with.
+pub fn estimate_tokens(text: &str) -> u64 {
+    tiktoken_rs::o200k_base_singleton()
+        .encode_ordinary(text)
+        .len() as u64
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostEstimate {
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// In US dollars; `None` for a model missing from the pricing table.
+    pub cost: Option<f64>,
+    /// More than one for a transcript reported on in parts.
+    pub requests: usize,
+}
+
+/// Estimate the tokens and price of one request whose answer may take up
+/// to `max_output_tokens`. Fails when the prompt would not fit in the
+/// model's context window, rather than uploading it anyway.
+pub fn estimate(
+    model: &str,
+    pricing: Option<ModelPricing>,
+    max_output_tokens: u32,
+    system_prompt: &str,
+    user_prompt: &str,
+) -> Result<CostEstimate> {
+    let input_tokens = estimate_tokens(system_prompt) + estimate_tokens(user_prompt);
+    estimate_request(model, pricing, max_output_tokens, input_tokens)
+}
+
+/// Like [`estimate`] for a prompt already counted.
+pub fn estimate_request(
+    model: &str,
+    pricing: Option<ModelPricing>,
+    max_output_tokens: u32,
+    input_tokens: u64,
+) -> Result<CostEstimate> {
+    // Reports, reasoning included, run to about half their transcript
+    let output_tokens = (input_tokens / 2)
+        .max(MIN_OUTPUT_TOKENS)
+        .min(max_output_tokens.into());
+
+    if let Some(pricing) = pricing
+        && input_tokens > pricing.context_window
+    {
+        return Err(Error::custom(format!(
+            "The prompt is about {} tokens, more than the {} token context window of {model}; choose a larger model with --model",
+            thousands(input_tokens),
+            thousands(pricing.context_window)
+        )));
+    }
+
+    Ok(CostEstimate {
+        model: model.to_string(),
+        input_tokens,
+        output_tokens,
+        cost: pricing.map(|pricing| {
+            (input_tokens as f64 * pricing.input_per_million
+                + output_tokens as f64 * pricing.output_per_million)
+                / 1_000_000.0
+        }),
+        requests: 1,
+    })
+}
+
+impl CostEstimate {
+    /// Both requests together.
+    pub fn combine(self, other: Self) -> Self {
+        Self {
+            model: self.model,
+            input_tokens: self.input_tokens + other.input_tokens,
+            output_tokens: self.output_tokens + other.output_tokens,
+            cost: self.cost.zip(other.cost).map(|(a, b)| a + b),
+            requests: self.requests + other.requests,
+        }
+    }
+
+    /// A model on this machine costs nothing to run.
+    pub fn is_free(&self) -> bool {
+        self.cost == Some(0.0)
+    }
+}
+
+impl fmt::Display for CostEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.cost {
+            Some(_) if self.is_free() => write!(f, "Estimated cost: free")?,
+            Some(cost) if cost < 0.01 => write!(f, "Estimated cost: under $0.01")?,
+            Some(cost) => write!(f, "Estimated cost: ${cost:.2}")?,
+            None => write!(f, "Estimated cost: unknown, {} has no price", self.model)?,
+        }
+        write!(
+            f,
+            " (~{} input + ~{} output tokens",
+            thousands(self.input_tokens),
+            thousands(self.output_tokens),
+        )?;
+        if self.requests > 1 {
+            write!(f, " in {} requests", self.requests)?;
+        }
+        write!(f, " on {})", self.model)
+    }
+}
+
+pub fn thousands(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ModelPricing, default_pricing, estimate, estimate_tokens, pricing_for};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn tokens_are_counted_with_o200k_base() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("hola mundo"), 2);
+        assert_eq!(estimate_tokens("[00:01:23] Bienvenidos"), 10);
+    }
+
+    #[test]
+    fn models_are_priced_by_their_longest_prefix() {
+        let none = BTreeMap::new();
+        let mini = pricing_for("gpt-4o-mini-2024-07-18", &none).unwrap();
+        assert_eq!(mini.input_per_million, 0.15);
+        assert_eq!(
+            pricing_for("gpt-5.1", &none),
+            default_pricing().get("gpt-5").copied()
+        );
+        assert_eq!(pricing_for("llama3", &none), None);
+    }
+
+    #[test]
+    fn overrides_are_looked_up_over_the_defaults() {
+        let local = ModelPricing {
+            input_per_million: 0.0,
+            output_per_million: 0.0,
+            context_window: 8_192,
+        };
+        let overrides =
+            BTreeMap::from([("gpt-4o".to_string(), local), ("llama3".to_string(), local)]);
+        assert_eq!(pricing_for("gpt-4o-2024-08-06", &overrides), Some(local));
+        assert_eq!(pricing_for("llama3.1", &overrides), Some(local));
+        // Models without an override keep their built-in price
+        assert_eq!(
+            pricing_for("gpt-4o-mini", &overrides).map(|p| p.input_per_million),
+            Some(0.15)
+        );
+    }
+
+    #[test]
+    fn estimates_price_input_and_output() {
+        let pricing = pricing_for("gpt-4o", &default_pricing());
+        let transcript = " palabra".repeat(80_000);
+        let report = estimate("gpt-4o", pricing, 128_000, "", &transcript).unwrap();
+        assert_eq!(report.input_tokens, 80_000);
+        assert_eq!(report.output_tokens, 40_000);
+        assert_eq!(
+            report.to_string(),
+            "Estimated cost: $0.60 (~80,000 input + ~40,000 output tokens on gpt-4o)"
+        );
+
+        let unknown = estimate("local", None, 128_000, "", "hola").unwrap();
+        assert_eq!(unknown.output_tokens, 2_000);
+        let capped = estimate("gpt-4o", pricing, 16_000, "", &transcript).unwrap();
+        assert_eq!(capped.output_tokens, 16_000);
+        assert_eq!(unknown.cost, None);
+        assert!(unknown.to_string().contains("local has no price"));
+
+        let twice = report.clone().combine(report);
+        assert_eq!(
+            twice.to_string(),
+            "Estimated cost: $1.20 (~160,000 input + ~80,000 output tokens in 2 requests on gpt-4o)"
+        );
+    }
+
+    #[test]
+    fn prompts_over_the_context_window_are_refused() {
+        let pricing = pricing_for("gpt-4o", &default_pricing());
+        let transcript = " palabra".repeat(200_000);
+        let error = estimate("gpt-4o", pricing, 128_000, "", &transcript).unwrap_err();
+        assert!(error.to_string().contains("200,000 tokens"), "{error}");
+        assert!(error.to_string().contains("128,000 token context window"));
+    }
+}
@@ -0,0 +1,277 @@
+// Saved reports open with a YAML front-matter block recording how they were
+// generated, so an old report still says which model wrote it and what it used.
+use chrono::{DateTime, FixedOffset};
+use serde::{Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::time::Duration;
+
+use super::cost::thousands;
+
+const FENCE: &str = "---";
+
+/// How a report was generated.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportMetadata {
+    pub model: String,
+    /// Name of the [`ReportTemplate`](super::ReportTemplate) that wrote the
+    /// report; `None` for reports saved before it was recorded.
+    pub template: Option<String>,
+    pub created_at: DateTime<FixedOffset>,
+    /// `None` when the server did not say what it used.
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+    /// Time spent generating, across every request.
+    #[serde(rename = "duration_secs", serialize_with = "seconds")]
+    pub duration: Duration,
+    /// [`transcript_hash`] of the transcript the report was written from;
+    /// `None` for reports saved before it was recorded.
+    pub transcript_sha256: Option<String>,
+    /// [`PromptSource::hash`](super::PromptSource::hash) of the prompt;
+    /// `None` for reports saved before it was recorded.
+    pub prompt_sha256: Option<String>,
+    /// Language code the report was asked for in.
+    pub language: Option<String>,
+}
+
+/// What a run would write a report from besides the transcript. A saved
+/// report made from anything else is out of date.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportInputs {
+    pub model: String,
+    pub prompt_sha256: String,
+    pub language: String,
+}
+
+/// SHA-256 of a transcript's text, in hex.
+pub fn transcript_hash(transcript: &str) -> String {
+    Sha256::digest(transcript.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn seconds<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(round_tenths(duration))
+}
+
+fn round_tenths(duration: &Duration) -> f64 {
+    (duration.as_secs_f64() * 10.0).round() / 10.0
+}
+
+impl ReportMetadata {
+    /// The block to put before the report.
+    pub fn to_front_matter(&self) -> String {
+        let mut block = format!("{FENCE}\nmodel: {}\n", self.model);
+        if let Some(template) = &self.template {
+            let _ = writeln!(block, "template: {template}");
+        }
+        let _ = writeln!(block, "created_at: {}", self.created_at.to_rfc3339());
+        if let Some(tokens) = self.input_tokens {
+            let _ = writeln!(block, "input_tokens: {tokens}");
+        }
+        if let Some(tokens) = self.output_tokens {
+            let _ = writeln!(block, "output_tokens: {tokens}");
+        }
+        let _ = writeln!(block, "duration_secs: {}", round_tenths(&self.duration));
+        if let Some(hash) = &self.transcript_sha256 {
+            let _ = writeln!(block, "transcript_sha256: {hash}");
+        }
+        if let Some(hash) = &self.prompt_sha256 {
+            let _ = writeln!(block, "prompt_sha256: {hash}");
+        }
+        if let Some(language) = &self.language {
+            let _ = writeln!(block, "language: {language}");
+        }
+        block.push_str(FENCE);
+        block.push_str("\n\n");
+        block
+    }
+
+    /// Read the metadata back from a saved report, if it has any.
+    pub fn parse(report: &str) -> Option<Self> {
+        let (fields, _) = split(report);
+        let field = |key: &str| {
+            fields
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| *value)
+        };
+        let tokens = |key: &str| field(key).and_then(|value| value.parse().ok());
+        Some(Self {
+            model: field("model")?.to_string(),
+            template: field("template").map(str::to_string),
+            created_at: DateTime::parse_from_rfc3339(field("created_at")?).ok()?,
+            input_tokens: tokens("input_tokens"),
+            output_tokens: tokens("output_tokens"),
+            duration: field("duration_secs")
+                .and_then(|value| value.parse().ok())
+                .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                .unwrap_or_default(),
+            transcript_sha256: field("transcript_sha256").map(str::to_string),
+            prompt_sha256: field("prompt_sha256").map(str::to_string),
+            language: field("language").map(str::to_string),
+        })
+    }
+
+    /// Whether the report was written from another transcript than
+    /// `transcript`, or with another model, prompt or language than `inputs`.
+    /// Only what the report recorded is compared; the rest is taken as
+    /// unchanged, so older reports are not paid for again.
+    pub fn is_stale(&self, transcript: &str, inputs: &ReportInputs) -> bool {
+        let differs = |recorded: &Option<String>, current: &str| {
+            recorded
+                .as_deref()
+                .is_some_and(|recorded| recorded != current)
+        };
+        differs(&self.transcript_sha256, &transcript_hash(transcript))
+            || self.model != inputs.model
+            || differs(&self.prompt_sha256, &inputs.prompt_sha256)
+            || differs(&self.language, &inputs.language)
+    }
+
+    /// One line for `vidio info`, e.g. "gpt-5.2 on 2026-10-16 12:00,
+    /// 12,000 in + 2,000 out tokens, 1m 33s".
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} on {}",
+            self.model,
+            self.created_at.format("%Y-%m-%d %H:%M")
+        );
+        if let (Some(input), Some(output)) = (self.input_tokens, self.output_tokens) {
+            let _ = write!(
+                summary,
+                ", {} in + {} out tokens",
+                thousands(input),
+                thousands(output)
+            );
+        }
+        let secs = self.duration.as_secs();
+        if secs >= 60 {
+            let _ = write!(summary, ", {}m {}s", secs / 60, secs % 60);
+        } else {
+            let _ = write!(summary, ", {secs}s");
+        }
+        summary
+    }
+}
+
+/// Split a report into its front-matter fields and the Markdown after them.
+/// A report that does not open with a block of `key: value` lines between
+/// `---` fences has no fields.
+pub fn split(report: &str) -> (Vec<(&str, &str)>, &str) {
+    let Some(rest) = report
+        .strip_prefix(FENCE)
+        .and_then(|rest| rest.strip_prefix('\n').or(rest.strip_prefix("\r\n")))
+    else {
+        return (Vec::new(), report);
+    };
+
+    let mut fields = Vec::new();
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        let line = line.trim_end();
+        if line == FENCE {
+            let body = rest[offset..].trim_start_matches(['\r', '\n']);
+            return (fields, body);
+        }
+        match line.split_once(':') {
+            Some((key, value)) if !key.is_empty() && !key.contains(' ') => {
+                fields.push((key, value.trim()));
+            }
+            _ => break,
+        }
+    }
+    (Vec::new(), report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReportInputs, ReportMetadata, split};
+    use chrono::DateTime;
+    use std::time::Duration;
+
+    fn metadata() -> ReportMetadata {
+        ReportMetadata {
+            model: "gpt-5.2".to_string(),
+            template: Some("bullets".to_string()),
+            created_at: DateTime::parse_from_rfc3339("2026-10-16T12:30:00+02:00").unwrap(),
+            input_tokens: Some(12_345),
+            output_tokens: Some(2_345),
+            duration: Duration::from_millis(93_420),
+            transcript_sha256: Some(super::transcript_hash("[00:00:05] hola")),
+            prompt_sha256: Some(super::transcript_hash("Resume {transcript}")),
+            language: Some("es".to_string()),
+        }
+    }
+
+    fn inputs() -> ReportInputs {
+        ReportInputs {
+            model: "gpt-5.2".to_string(),
+            prompt_sha256: super::transcript_hash("Resume {transcript}"),
+            language: "es".to_string(),
+        }
+    }
+
+    #[test]
+    fn any_changed_input_makes_a_report_stale() {
+        let report = metadata();
+        assert!(!report.is_stale("[00:00:05] hola", &inputs()));
+        assert!(report.is_stale("[00:00:05] hola!", &inputs()));
+
+        let changes: [fn(&mut ReportInputs); 3] = [
+            |inputs| inputs.model = "gpt-4.1".to_string(),
+            |inputs| inputs.prompt_sha256 = super::transcript_hash("Summarize {transcript}"),
+            |inputs| inputs.language = "en".to_string(),
+        ];
+        for change in changes {
+            let mut changed = inputs();
+            change(&mut changed);
+            assert!(report.is_stale("[00:00:05] hola", &changed));
+        }
+
+        let legacy = ReportMetadata {
+            transcript_sha256: None,
+            prompt_sha256: None,
+            language: None,
+            ..metadata()
+        };
+        assert!(!legacy.is_stale("[00:00:05] hola!", &inputs()));
+    }
+
+    #[test]
+    fn front_matter_round_trips() {
+        let report = format!("{}#### 1. Metadata\n", metadata().to_front_matter());
+        assert!(report.starts_with(
+            "---\nmodel: gpt-5.2\ntemplate: bullets\ncreated_at: 2026-10-16T12:30:00+02:00\n"
+        ));
+        assert!(report.contains("\nduration_secs: 93.4\ntranscript_sha256: "));
+        assert!(report.contains("\n---\n\n#### 1."));
+
+        let parsed = ReportMetadata::parse(&report).unwrap();
+        assert_eq!(parsed.model, "gpt-5.2");
+        assert_eq!(parsed.template.as_deref(), Some("bullets"));
+        assert_eq!(parsed.input_tokens, Some(12_345));
+        assert_eq!(parsed.duration, Duration::from_millis(93_400));
+        assert_eq!(parsed.transcript_sha256, metadata().transcript_sha256);
+        assert_eq!(parsed.prompt_sha256, metadata().prompt_sha256);
+        assert_eq!(parsed.language.as_deref(), Some("es"));
+        assert_eq!(split(&report).1, "#### 1. Metadata\n");
+        assert_eq!(
+            parsed.summary(),
+            "gpt-5.2 on 2026-10-16 12:30, 12,345 in + 2,345 out tokens, 1m 33s"
+        );
+    }
+
+    #[test]
+    fn reports_without_front_matter_are_left_whole() {
+        let plain = "#### 1. Metadata\n| Campo | Valor |\n";
+        assert_eq!(split(plain), (Vec::new(), plain));
+        assert_eq!(ReportMetadata::parse(plain), None);
+
+        // A leading rule followed by prose is not front matter
+        let ruled = "---\nUn reporte sin metadatos.\n---\n";
+        assert_eq!(split(ruled), (Vec::new(), ruled));
+    }
+}
@@ -0,0 +1,306 @@
+// Structural checks for generated reports: section parsing, table parsing and
+// a rough language guess. Used by the prompt test harness to compare template
+// iterations without eyeballing every output.
+use crate::error::{Error, Result};
+use serde::Serialize;
+
+const SECTION_PREFIX: &str = "#### ";
+const DETAILED_SECTION_COUNT: u32 = 11;
+const DETAILED_TABLE_SECTIONS: &[u32] = &[1, 3, 4];
+const DETAILED_LANGUAGE: &str = "es";
+
+const SPANISH_STOPWORDS: &[&str] = &[
+    "el", "la", "de", "que", "y", "en", "los", "las", "del", "por", "una", "para", "con", "es",
+    "se", "lo", "como", "pero",
+];
+const ENGLISH_STOPWORDS: &[&str] = &[
+    "the", "and", "of", "to", "is", "in", "that", "it", "for", "with", "this", "you", "are", "was",
+    "on", "but",
+];
+const MIN_STOPWORD_HITS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub number: Option<u32>,
+    pub title: String,
+    pub lines: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintProfile {
+    /// Everything the built-in detailed prompt asks for.
+    Detailed,
+    /// Any sections, well-formed tables and a recognizable language.
+    Basic,
+}
+
+impl LintProfile {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "detailed" => Ok(Self::Detailed),
+            "basic" => Ok(Self::Basic),
+            other => Err(Error::custom(format!(
+                "Unknown lint profile '{other}'; expected 'detailed' or 'basic'"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, passed: bool, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            passed,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Split a report into `####` sections. Text before the first heading is dropped.
+pub fn parse_sections(report: &str) -> Vec<Section> {
+    let mut sections: Vec<Section> = Vec::new();
+
+    for line in report.lines() {
+        if let Some(heading) = line.trim_start().strip_prefix(SECTION_PREFIX) {
+            let heading = heading.trim();
+            let number = heading
+                .split_once('.')
+                .and_then(|(num, _)| num.trim().parse::<u32>().ok());
+            sections.push(Section {
+                number,
+                title: heading.to_string(),
+                lines: Vec::new(),
+            });
+        } else if let Some(section) = sections.last_mut() {
+            section.lines.push(line.to_string());
+        }
+    }
+
+    sections
+}
+
+/// Parse every contiguous block of `|` lines. Malformed blocks are returned as
+/// errors describing what is wrong with them.
+pub fn parse_tables(lines: &[String]) -> Vec<std::result::Result<Table, String>> {
+    let mut tables = Vec::new();
+    let mut block: Vec<&str> = Vec::new();
+
+    for line in lines.iter().map(|l| l.trim()).chain(std::iter::once("")) {
+        if line.starts_with('|') {
+            block.push(line);
+        } else if !block.is_empty() {
+            tables.push(parse_table(&block));
+            block.clear();
+        }
+    }
+
+    tables
+}
+
+fn parse_table(block: &[&str]) -> std::result::Result<Table, String> {
+    if block.len() < 2 {
+        return Err("table has no separator row".to_string());
+    }
+
+    let header = split_row(block[0]);
+    if !is_separator_row(block[1]) {
+        return Err("second table row is not a |---| separator".to_string());
+    }
+    if split_row(block[1]).len() != header.len() {
+        return Err("separator column count does not match header".to_string());
+    }
+
+    let mut rows = Vec::new();
+    for (idx, line) in block.iter().enumerate().skip(2) {
+        let row = split_row(line);
+        if row.len() != header.len() {
+            return Err(format!(
+                "row {} has {} columns, header has {}",
+                idx - 1,
+                row.len(),
+                header.len()
+            ));
+        }
+        rows.push(row);
+    }
+
+    Ok(Table { header, rows })
+}
+
+fn split_row(line: &str) -> Vec<String> {
+    let inner = line.trim();
+    let inner = inner.strip_prefix('|').unwrap_or(inner);
+    let inner = inner.strip_suffix('|').unwrap_or(inner);
+    inner
+        .split('|')
+        .map(|cell| cell.trim().to_string())
+        .collect()
+}
+
+fn is_separator_row(line: &str) -> bool {
+    line.contains('-')
+        && line
+            .chars()
+            .all(|c| matches!(c, '|' | '-' | ':') || c.is_whitespace())
+}
+
+/// Guess the dominant language from stopword frequency. Returns `None` when
+/// the text is too short or too mixed to call.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let mut spanish = 0;
+    let mut english = 0;
+
+    for word in text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+    {
+        let word = word.to_lowercase();
+        if SPANISH_STOPWORDS.contains(&word.as_str()) {
+            spanish += 1;
+        }
+        if ENGLISH_STOPWORDS.contains(&word.as_str()) {
+            english += 1;
+        }
+    }
+
+    if spanish.max(english) < MIN_STOPWORD_HITS || spanish == english {
+        None
+    } else if spanish > english {
+        Some("es")
+    } else {
+        Some("en")
+    }
+}
+
+pub fn lint_report(report: &str, profile: LintProfile) -> Vec<Check> {
+    let sections = parse_sections(report);
+    let mut checks = Vec::new();
+
+    match profile {
+        LintProfile::Detailed => {
+            for number in 1..=DETAILED_SECTION_COUNT {
+                let present = sections.iter().any(|s| s.number == Some(number));
+                checks.push(Check::new(
+                    format!("section {number} present"),
+                    present,
+                    if present { "found" } else { "missing heading" },
+                ));
+            }
+
+            for &number in DETAILED_TABLE_SECTIONS {
+                let (passed, detail) = match sections.iter().find(|s| s.number == Some(number)) {
+                    Some(section) => match parse_tables(&section.lines).into_iter().next() {
+                        Some(Ok(table)) => (true, format!("{} rows", table.rows.len())),
+                        Some(Err(err)) => (false, err),
+                        None => (false, "no table found".to_string()),
+                    },
+                    None => (false, "section missing".to_string()),
+                };
+                checks.push(Check::new(
+                    format!("section {number} table parses"),
+                    passed,
+                    detail,
+                ));
+            }
+        }
+        LintProfile::Basic => {
+            checks.push(Check::new(
+                "sections present",
+                !sections.is_empty(),
+                format!("{} sections", sections.len()),
+            ));
+
+            let all_lines: Vec<String> = report.lines().map(|l| l.to_string()).collect();
+            let tables = parse_tables(&all_lines);
+            let broken: Vec<String> = tables.iter().filter_map(|t| t.clone().err()).collect();
+            checks.push(Check::new(
+                "tables parse",
+                broken.is_empty(),
+                if broken.is_empty() {
+                    format!("{} tables", tables.len())
+                } else {
+                    broken.join("; ")
+                },
+            ));
+        }
+    }
+
+    let language = detect_language(report);
+    let language_ok = match profile {
+        LintProfile::Detailed => language == Some(DETAILED_LANGUAGE),
+        LintProfile::Basic => language.is_some(),
+    };
+    checks.push(Check::new(
+        "language detected",
+        language_ok,
+        language.unwrap_or("unknown"),
+    ));
+
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LintProfile, detect_language, lint_report, parse_sections, parse_tables};
+
+    #[test]
+    fn parses_numbered_sections() {
+        let sections = parse_sections("intro\n#### 1. Metadata\nbody\n#### Sin número\n");
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].number, Some(1));
+        assert_eq!(sections[0].lines, vec!["body".to_string()]);
+        assert_eq!(sections[1].number, None);
+    }
+
+    #[test]
+    fn reports_ragged_table_rows() {
+        let lines: Vec<String> = ["| a | b |", "|---|---|", "| 1 | 2 |", "| 3 |"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        let tables = parse_tables(&lines);
+        assert_eq!(tables.len(), 1);
+        assert!(tables[0].is_err());
+    }
+
+    #[test]
+    fn detects_spanish_and_english() {
+        assert_eq!(
+            detect_language(
+                "El vídeo explica que la idea es simple y que los datos de la prueba se ven bien"
+            ),
+            Some("es")
+        );
+        assert_eq!(
+            detect_language(
+                "The video explains that the idea is simple and that it works for this case"
+            ),
+            Some("en")
+        );
+        assert_eq!(detect_language("12:00 — 13:00"), None);
+    }
+
+    #[test]
+    fn basic_profile_flags_missing_sections() {
+        let checks = lint_report("no headings at all", LintProfile::Basic);
+        assert!(
+            checks
+                .iter()
+                .any(|c| c.name == "sections present" && !c.passed)
+        );
+    }
+}
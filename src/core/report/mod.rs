@@ -0,0 +1,759 @@
+pub mod chunking;
+pub mod cost;
+pub mod front_matter;
+pub mod lint;
+pub mod ollama;
+pub mod openai;
+pub mod prompt;
+pub mod recovery;
+pub mod retry;
+pub mod template;
+
+pub use cost::{CostEstimate, ModelPricing};
+pub use front_matter::{ReportInputs, ReportMetadata};
+pub use prompt::{CustomPrompt, PromptSource, PromptVideo, report_language};
+pub use template::ReportTemplate;
+
+use crate::core::config::{Config, OpenAiConfig, ReportProvider};
+use crate::core::settings::ReportConfig;
+use crate::error::{Error, Result};
+use chrono::Local;
+use futures::future::BoxFuture;
+use ollama::OllamaBackend;
+use openai::OpenAiBackend;
+use std::env;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const MODEL_ENV: &str = "VIDIO_MODEL";
+pub const DEFAULT_MODEL: &str = "gpt-5.2";
+/// Upper bound on what a report may cost in output tokens, unless
+/// `[openai] max_output_tokens` says otherwise.
+const DEFAULT_MAX_OUTPUT_TOKENS: u32 = 128_000;
+/// How long the server may take to answer the check before a job.
+const CONNECTION_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The model to generate reports with: `flag` (from `--model`), else
+/// `VIDIO_MODEL`, else `vidio.toml`, else [`DEFAULT_MODEL`].
+pub fn configured_model(flag: Option<String>, config: &Config) -> String {
+    let from_env = env::var(MODEL_ENV).ok();
+    choose_model(flag, from_env, config.openai.model.clone())
+}
+
+fn choose_model(
+    flag: Option<String>,
+    from_env: Option<String>,
+    from_config: Option<String>,
+) -> String {
+    [flag, from_env, from_config]
+        .into_iter()
+        .flatten()
+        .map(|model| model.trim().to_string())
+        .find(|model| !model.is_empty())
+        .unwrap_or_else(|| DEFAULT_MODEL.to_string())
+}
+
+/// How far a report on a transcript sent in parts has got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkProgress {
+    /// Part `index` (from 1) of `total` is being reported on.
+    Part { index: usize, total: usize },
+    /// The `total` partial reports are being merged.
+    Merge { total: usize },
+}
+
+impl fmt::Display for ChunkProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Part { index, total } => write!(f, "Reporting on part {index} of {total}..."),
+            Self::Merge { total } => write!(f, "Merging {total} partial reports..."),
+        }
+    }
+}
+
+/// A report request with its prompts fully rendered.
+#[derive(Debug, Clone, Copy)]
+pub struct Prompt<'a> {
+    pub system: &'a str,
+    pub user: &'a str,
+}
+
+/// A report's text as the model writes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportText<'a> {
+    /// A request is starting, or starting over after a failure; the text
+    /// received so far is void.
+    Restart,
+    /// The next piece of the answer.
+    Delta(&'a str),
+}
+
+/// Tokens a request used, as the server counted them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// A model's answer to one request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub text: String,
+    /// `None` when the server did not report it.
+    pub usage: Option<TokenUsage>,
+}
+
+/// A finished report and how it was generated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedReport {
+    pub content: String,
+    pub metadata: ReportMetadata,
+}
+
+/// Where report prompts are answered: OpenAI or a server speaking its API,
+/// or a canned backend in tests.
+pub trait ReportBackend: Send + Sync {
+    /// The model's raw answer to `prompt`, also passed to `on_text` piece
+    /// by piece as it arrives.
+    fn generate<'a>(
+        &'a self,
+        prompt: Prompt<'a>,
+        on_text: &'a (dyn Fn(ReportText<'_>) + Sync),
+    ) -> BoxFuture<'a, Result<Completion>>;
+
+    /// Whether prompts go to a server the user runs, which makes reports free.
+    fn is_local(&self) -> bool;
+
+    /// Fail early, before a transcript is fetched, if requests are bound
+    /// to be refused for want of a key or consent.
+    fn check_credentials(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Whether transcripts may be sent, without failing when they may not.
+    fn is_opted_in(&self) -> bool {
+        true
+    }
+
+    /// Reach the server once, so one that is down or refuses the key is
+    /// found out before a transcript is fetched.
+    fn check_connection(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Send `request` and fail only if `server` cannot be reached or turns the
+/// key down; any other answer, even a 404, shows it is there.
+async fn check_reachable(request: reqwest::RequestBuilder, server: &str) -> Result<()> {
+    let response = request
+        .timeout(CONNECTION_CHECK_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| Error::Network(format!("Could not reach {server} ({e})")))?;
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Err(Error::Api(format!(
+            "{server} refused the API key ({status})"
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct ReportService {
+    backend: Arc<dyn ReportBackend>,
+    model: String,
+    /// `None` when the pricing table has no entry for the model.
+    pricing: Option<ModelPricing>,
+    max_input_tokens: Option<u64>,
+    max_output_tokens: u32,
+}
+
+impl ReportService {
+    pub fn new(model: &str, endpoint: &OpenAiConfig, report: &ReportConfig) -> Self {
+        let backend: Arc<dyn ReportBackend> = match endpoint.backend {
+            ReportProvider::OpenAi => Arc::new(OpenAiBackend::new(model, endpoint)),
+            ReportProvider::Ollama => Arc::new(OllamaBackend::new(model, endpoint)),
+        };
+        Self::with_backend(backend, model, endpoint, report)
+    }
+
+    /// A service whose requests go to `backend` rather than OpenAI.
+    pub fn with_backend(
+        backend: Arc<dyn ReportBackend>,
+        model: &str,
+        endpoint: &OpenAiConfig,
+        report: &ReportConfig,
+    ) -> Self {
+        Self {
+            backend,
+            model: model.to_string(),
+            pricing: cost::pricing_for(model, &report.pricing),
+            max_input_tokens: endpoint.max_input_tokens,
+            max_output_tokens: endpoint
+                .max_output_tokens
+                .unwrap_or(DEFAULT_MAX_OUTPUT_TOKENS),
+        }
+    }
+
+    /// Whether reports can be requested at all, naming what to set if not.
+    pub fn check_credentials(&self) -> Result<()> {
+        self.backend.check_credentials()
+    }
+
+    /// Everything a job's report needs before the job starts: the key and
+    /// consent, then an answer from the server.
+    pub async fn check_ready(&self) -> Result<()> {
+        self.check_credentials()?;
+        self.backend.check_connection().await
+    }
+
+    /// Whether the user agreed to send transcripts to the report server;
+    /// always so for one on this machine.
+    pub fn is_opted_in(&self) -> bool {
+        self.backend.is_opted_in()
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// What a report written now from `prompt` in `language` is made from,
+    /// to compare with a saved report's metadata.
+    pub fn inputs(&self, prompt: &PromptSource, language: &str) -> ReportInputs {
+        ReportInputs {
+            model: self.model.clone(),
+            prompt_sha256: prompt.hash(),
+            language: language.to_string(),
+        }
+    }
+
+    /// Most prompt tokens sent in one request: `max_input_tokens`, else
+    /// three quarters of the context window, leaving the rest for the
+    /// report. An unknown model is sent everything at once.
+    fn input_budget(&self) -> Option<u64> {
+        self.max_input_tokens
+            .or(self.pricing.map(|pricing| pricing.context_window / 4 * 3))
+    }
+
+    /// The parts to report on separately, or `None` when the whole
+    /// transcript fits in one request.
+    fn parts(
+        &self,
+        transcript_text: &str,
+        prompt: &PromptSource,
+        video: PromptVideo<'_>,
+    ) -> Result<Option<Vec<String>>> {
+        let Some(budget) = self.input_budget() else {
+            return Ok(None);
+        };
+        let (system_prompt, user_prompt) = prompt.render(transcript_text, video);
+        if cost::estimate_tokens(system_prompt) + cost::estimate_tokens(&user_prompt) <= budget {
+            return Ok(None);
+        }
+        let (system_prompt, frame) = prompt.render("", video);
+        let overhead = cost::estimate_tokens(system_prompt)
+            + cost::estimate_tokens(&frame)
+            + chunking::PART_NOTE_TOKENS;
+        if overhead >= budget {
+            return Err(Error::custom(format!(
+                "max_input_tokens ({budget}) leaves no room for the transcript next to the prompt"
+            )));
+        }
+        Ok(Some(chunking::split_transcript(
+            transcript_text,
+            budget - overhead,
+        )))
+    }
+
+    /// What generating this report would cost, without sending anything.
+    /// Fails if the prompt does not fit the model's context window.
+    pub fn estimate(
+        &self,
+        transcript_text: &str,
+        prompt: &PromptSource,
+        video: PromptVideo<'_>,
+    ) -> Result<CostEstimate> {
+        let Some(parts) = self.parts(transcript_text, prompt, video)? else {
+            let (system_prompt, user_prompt) = prompt.render(transcript_text, video);
+            let mut estimate = cost::estimate(
+                &self.model,
+                self.pricing,
+                self.max_output_tokens,
+                system_prompt,
+                &user_prompt,
+            )?;
+            if self.backend.is_local() {
+                estimate.cost = Some(0.0);
+            }
+            return Ok(estimate);
+        };
+
+        let mut requests = Vec::new();
+        for (i, part) in parts.iter().enumerate() {
+            let text = chunking::part_text(part, i + 1, parts.len());
+            let (system_prompt, user_prompt) = prompt.render(&text, video);
+            requests.push(cost::estimate(
+                &self.model,
+                self.pricing,
+                self.max_output_tokens,
+                system_prompt,
+                &user_prompt,
+            )?);
+        }
+        // The merge pass reads every partial report
+        let (system_prompt, frame) = prompt.render(&chunking::merge_text(&[]), video);
+        let merge_input = cost::estimate_tokens(system_prompt)
+            + cost::estimate_tokens(&frame)
+            + requests.iter().map(|r| r.output_tokens).sum::<u64>();
+        requests.push(cost::estimate_request(
+            &self.model,
+            self.pricing,
+            self.max_output_tokens,
+            merge_input,
+        )?);
+
+        let mut estimate = requests
+            .into_iter()
+            .reduce(CostEstimate::combine)
+            .expect("at least the merge request");
+        if self.backend.is_local() {
+            estimate.cost = Some(0.0);
+        }
+        Ok(estimate)
+    }
+
+    /// The system and user prompts a report starts with, exactly as they are
+    /// sent: one request, or one per part when the transcript is too long.
+    /// The merge request after the parts is built from their answers.
+    pub fn build_prompts(
+        &self,
+        transcript_text: &str,
+        prompt: &PromptSource,
+        video: PromptVideo<'_>,
+    ) -> Result<Vec<(&'static str, String)>> {
+        let Some(parts) = self.parts(transcript_text, prompt, video)? else {
+            return Ok(vec![prompt.render(transcript_text, video)]);
+        };
+        let total = parts.len();
+        Ok(parts
+            .iter()
+            .enumerate()
+            .map(|(i, part)| prompt.render(&chunking::part_text(part, i + 1, total), video))
+            .collect())
+    }
+
+    /// Generate a report for a video, in parts merged at the end when the
+    /// transcript is too long for one request. Custom prompts have an
+    /// unknown structure, so their tables are left as the model wrote them.
+    pub async fn generate_report_text(
+        &self,
+        transcript_text: &str,
+        prompt: &PromptSource,
+        video: PromptVideo<'_>,
+        on_chunk: &(dyn Fn(ChunkProgress) + Sync),
+    ) -> Result<GeneratedReport> {
+        self.generate_report_stream(transcript_text, prompt, video, on_chunk, &|_| {})
+            .await
+    }
+
+    /// Like [`generate_report_text`](Self::generate_report_text), passing
+    /// each request's text to `on_text` as the model writes it.
+    pub async fn generate_report_stream(
+        &self,
+        transcript_text: &str,
+        prompt: &PromptSource,
+        video: PromptVideo<'_>,
+        on_chunk: &(dyn Fn(ChunkProgress) + Sync),
+        on_text: &(dyn Fn(ReportText<'_>) + Sync),
+    ) -> Result<GeneratedReport> {
+        let started = Instant::now();
+        // Token counts add up over the parts, unless one of them has none
+        let mut usage = Some(TokenUsage::default());
+        let mut request = async |system_prompt: &str, user_prompt: &str| -> Result<String> {
+            let completion = self.request(system_prompt, user_prompt, on_text).await?;
+            usage = usage.zip(completion.usage).map(|(total, used)| TokenUsage {
+                input_tokens: total.input_tokens + used.input_tokens,
+                output_tokens: total.output_tokens + used.output_tokens,
+            });
+            Ok(completion.text)
+        };
+        let prompts = self.build_prompts(transcript_text, prompt, video)?;
+        let content = if let [(system_prompt, user_prompt)] = prompts.as_slice() {
+            request(system_prompt, user_prompt).await?
+        } else {
+            let total = prompts.len();
+            let mut partials = Vec::with_capacity(total);
+            for (i, (system_prompt, user_prompt)) in prompts.iter().enumerate() {
+                on_chunk(ChunkProgress::Part {
+                    index: i + 1,
+                    total,
+                });
+                partials.push(request(system_prompt, user_prompt).await?);
+            }
+            on_chunk(ChunkProgress::Merge { total });
+            let text = chunking::merge_text(&partials);
+            let (system_prompt, user_prompt) = prompt.render(&text, video);
+            request(system_prompt, &user_prompt).await?
+        };
+        let content = match prompt {
+            PromptSource::Template(template) => ensure_table_headers(&content, *template),
+            PromptSource::Custom(_) => content,
+        };
+        Ok(GeneratedReport {
+            content,
+            metadata: ReportMetadata {
+                model: self.model.clone(),
+                template: Some(prompt.template().name().to_string()),
+                created_at: Local::now().fixed_offset(),
+                input_tokens: usage.map(|usage| usage.input_tokens),
+                output_tokens: usage.map(|usage| usage.output_tokens),
+                duration: started.elapsed(),
+                transcript_sha256: Some(front_matter::transcript_hash(transcript_text)),
+                prompt_sha256: Some(prompt.hash()),
+                language: Some(video.language.to_string()),
+            },
+        })
+    }
+
+    /// Send fully rendered prompts and return the raw model output. An
+    /// empty answer is an error, so it is never saved as a report.
+    pub async fn complete(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
+        let completion = self.request(system_prompt, user_prompt, &|_| {}).await?;
+        Ok(completion.text)
+    }
+
+    async fn request(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        on_text: &(dyn Fn(ReportText<'_>) + Sync),
+    ) -> Result<Completion> {
+        let prompt = Prompt {
+            system: system_prompt,
+            user: user_prompt,
+        };
+        let completion = self.backend.generate(prompt, on_text).await?;
+        if completion.text.trim().is_empty() {
+            return Err(Error::custom(format!(
+                "{} returned no text; nothing was saved",
+                self.model
+            )));
+        }
+        Ok(completion)
+    }
+}
+
+struct TableTemplate {
+    section_marker: &'static str,
+    header_lines: &'static [&'static str],
+    header_keywords: &'static [&'static str],
+}
+
+/// Insert the header rows the model sometimes drops from the tables
+/// `template` asks for, in the language the report is written in.
+pub fn ensure_table_headers(report: &str, template: ReportTemplate) -> String {
+    ensure_table_headers_in(report, template, lint::detect_language(report))
+}
+
+/// Like [`ensure_table_headers`] for a known language. A report in an
+/// unknown language is returned untouched rather than given foreign headers.
+pub fn ensure_table_headers_in(
+    report: &str,
+    template: ReportTemplate,
+    language: Option<&str>,
+) -> String {
+    let Some(tables) = language.and_then(|language| template.tables(language)) else {
+        return report.to_string();
+    };
+
+    let mut lines: Vec<String> = report.lines().map(|l| l.to_string()).collect();
+
+    for table in tables {
+        if let Some(section_idx) = lines
+            .iter()
+            .position(|line| line.trim_start().starts_with(table.section_marker))
+        {
+            let mut insert_idx = section_idx + 1;
+            while insert_idx < lines.len() && lines[insert_idx].trim().is_empty() {
+                insert_idx += 1;
+            }
+
+            let header_present =
+                if insert_idx < lines.len() && lines[insert_idx].trim_start().starts_with('|') {
+                    let normalized = lines[insert_idx].to_lowercase();
+                    table
+                        .header_keywords
+                        .iter()
+                        .all(|kw| normalized.contains(kw))
+                } else {
+                    false
+                };
+
+            if !header_present {
+                let insert_lines: Vec<String> =
+                    table.header_lines.iter().map(|s| s.to_string()).collect();
+                lines.splice(insert_idx..insert_idx, insert_lines);
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ChunkProgress, Completion, Prompt, PromptSource, PromptVideo, ReportBackend, ReportService,
+        ReportTemplate, ReportText, TokenUsage, choose_model, chunking, cost::estimate_tokens,
+        ensure_table_headers, ensure_table_headers_in,
+    };
+    use crate::core::config::OpenAiConfig;
+    use crate::core::settings::ReportConfig;
+    use crate::error::Result;
+    use futures::future::BoxFuture;
+    use std::sync::{Arc, Mutex};
+
+    const DETAILED: ReportTemplate = ReportTemplate::Detailed;
+    const SUMMARY: PromptSource = PromptSource::Template(ReportTemplate::Summary);
+    const VIDEO: PromptVideo<'static> = PromptVideo {
+        video_id: "dQw4w9WgXcQ",
+        title: None,
+        language: "es",
+        removed_lines: 0,
+    };
+
+    /// Answers every prompt with `answer`, `{n}` replaced by the request
+    /// number, and keeps the user prompts it was sent.
+    struct MockBackend {
+        answer: &'static str,
+        prompts: Mutex<Vec<String>>,
+    }
+
+    impl ReportBackend for MockBackend {
+        fn generate<'a>(
+            &'a self,
+            prompt: Prompt<'a>,
+            on_text: &'a (dyn Fn(ReportText<'_>) + Sync),
+        ) -> BoxFuture<'a, Result<Completion>> {
+            let mut prompts = self.prompts.lock().unwrap();
+            prompts.push(prompt.user.to_string());
+            let answer = self.answer.replace("{n}", &prompts.len().to_string());
+            Box::pin(async move {
+                on_text(ReportText::Restart);
+                for word in answer.split_inclusive(' ') {
+                    on_text(ReportText::Delta(word));
+                }
+                Ok(Completion {
+                    text: answer,
+                    usage: Some(TokenUsage {
+                        input_tokens: 100,
+                        output_tokens: 10,
+                    }),
+                })
+            })
+        }
+
+        fn is_local(&self) -> bool {
+            true
+        }
+    }
+
+    fn mock(
+        answer: &'static str,
+        max_input_tokens: Option<u64>,
+    ) -> (ReportService, Arc<MockBackend>) {
+        let backend = Arc::new(MockBackend {
+            answer,
+            prompts: Mutex::default(),
+        });
+        let endpoint = OpenAiConfig {
+            max_input_tokens,
+            ..OpenAiConfig::default()
+        };
+        let service = ReportService::with_backend(
+            backend.clone(),
+            "mock",
+            &endpoint,
+            &ReportConfig::default(),
+        );
+        (service, backend)
+    }
+
+    #[tokio::test]
+    async fn short_transcripts_take_one_free_request() {
+        let (service, backend) = mock("Reporte {n}", None);
+        let report = service
+            .generate_report_text("hola mundo", &SUMMARY, VIDEO, &|_| panic!("not in parts"))
+            .await
+            .unwrap();
+
+        assert_eq!(report.content, "Reporte 1");
+        assert_eq!(report.metadata.model, "mock");
+        assert_eq!(report.metadata.input_tokens, Some(100));
+        let prompts = backend.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 1);
+        assert!(prompts[0].contains("hola mundo"));
+        assert!(
+            service
+                .estimate("hola mundo", &SUMMARY, VIDEO)
+                .unwrap()
+                .is_free()
+        );
+    }
+
+    #[tokio::test]
+    async fn long_transcripts_are_reported_in_parts_then_merged() {
+        let (system_prompt, frame) = SUMMARY.render("", VIDEO);
+        let overhead =
+            estimate_tokens(system_prompt) + estimate_tokens(&frame) + chunking::PART_NOTE_TOKENS;
+        let (service, backend) = mock("Reporte {n}", Some(overhead + 100));
+        let transcript = (0..100)
+            .map(|i| format!("[00:{i:02}] linea numero {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let progress = Mutex::new(Vec::new());
+        let report = service
+            .generate_report_text(&transcript, &SUMMARY, VIDEO, &|event| {
+                progress.lock().unwrap().push(event)
+            })
+            .await
+            .unwrap();
+
+        let progress = progress.into_inner().unwrap();
+        let Some(&ChunkProgress::Merge { total }) = progress.last() else {
+            panic!("no merge in {progress:?}");
+        };
+        assert!(total > 1);
+        assert_eq!(progress.len(), total + 1);
+        let prompts = backend.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), total + 1);
+        assert!(prompts[0].contains(&format!("(Parte 1 de {total}")));
+        assert!(prompts[total].contains("### Reporte parcial 1\n\nReporte 1"));
+        assert_eq!(report.content, format!("Reporte {}", total + 1));
+        // Usage covers every part and the merge
+        assert_eq!(report.metadata.input_tokens, Some(100 * (total as u64 + 1)));
+        assert_eq!(report.metadata.output_tokens, Some(10 * (total as u64 + 1)));
+
+        let estimate = service.estimate(&transcript, &SUMMARY, VIDEO).unwrap();
+        assert_eq!(estimate.requests, total + 1);
+    }
+
+    #[test]
+    fn prompts_are_built_without_sending_anything() {
+        let (service, backend) = mock("Reporte {n}", None);
+        let prompts = service
+            .build_prompts("[00:01] hola mundo", &SUMMARY, VIDEO)
+            .unwrap();
+
+        assert_eq!(prompts.len(), 1);
+        assert_eq!(prompts[0], SUMMARY.render("[00:01] hola mundo", VIDEO));
+        assert!(prompts[0].1.contains("[00:01] hola mundo"));
+        assert!(backend.prompts.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn text_streams_in_as_it_is_written() {
+        let (service, _) = mock("Reporte {n} listo", None);
+        let streamed = Mutex::new(Vec::new());
+        let report = service
+            .generate_report_stream("hola mundo", &SUMMARY, VIDEO, &|_| {}, &|text| {
+                streamed.lock().unwrap().push(match text {
+                    ReportText::Restart => None,
+                    ReportText::Delta(delta) => Some(delta.to_string()),
+                })
+            })
+            .await
+            .unwrap();
+
+        let streamed = streamed.into_inner().unwrap();
+        assert_eq!(streamed[0], None);
+        assert_eq!(streamed.len(), 4);
+        assert_eq!(
+            streamed.into_iter().flatten().collect::<String>(),
+            report.content
+        );
+    }
+
+    #[tokio::test]
+    async fn empty_answers_are_not_reports() {
+        let (service, _) = mock(" \n", None);
+        let error = service
+            .generate_report_text("hola mundo", &SUMMARY, VIDEO, &|_| {})
+            .await
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("mock returned no text"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn model_flag_beats_environment_beats_config() {
+        let some = |model: &str| Some(model.to_string());
+        assert_eq!(
+            choose_model(some("o4-mini"), some("gpt-4.1"), None),
+            "o4-mini"
+        );
+        assert_eq!(choose_model(None, some(" "), some("gpt-4o")), "gpt-4o");
+        assert_eq!(choose_model(None, None, None), "gpt-5.2");
+    }
+
+    const SPANISH: &str = include_str!("../../../fixtures/headers/es_missing.md");
+    const ENGLISH: &str = include_str!("../../../fixtures/headers/en_missing.md");
+    const FRENCH: &str = include_str!("../../../fixtures/headers/fr_missing.md");
+
+    fn line_after<'a>(report: &'a str, marker: &str) -> &'a str {
+        report
+            .lines()
+            .skip_while(|line| !line.starts_with(marker))
+            .nth(1)
+            .unwrap()
+    }
+
+    #[test]
+    fn inserts_spanish_headers_into_spanish_reports() {
+        let fixed = ensure_table_headers(SPANISH, DETAILED);
+        assert_eq!(line_after(&fixed, "#### 1. Metadata"), "| Campo | Valor |");
+        assert!(line_after(&fixed, "#### 3. Desglose").contains("Orador"));
+        assert!(!fixed.contains("| Field |"));
+        // Already complete tables are left alone
+        assert_eq!(fixed.matches("| Entidad |").count(), 1);
+    }
+
+    #[test]
+    fn inserts_english_headers_into_english_reports() {
+        let fixed = ensure_table_headers(ENGLISH, DETAILED);
+        assert_eq!(line_after(&fixed, "#### 1. Metadata"), "| Field | Value |");
+        assert!(line_after(&fixed, "#### 3. Line-by-line").contains("Speaker"));
+        assert!(line_after(&fixed, "#### 4. Entities").contains("Entity"));
+        assert!(!fixed.contains("Campo"));
+    }
+
+    #[test]
+    fn unknown_language_is_left_untouched() {
+        assert_eq!(ensure_table_headers(FRENCH, DETAILED), FRENCH);
+        assert_eq!(
+            ensure_table_headers_in(ENGLISH, DETAILED, Some("de")),
+            ENGLISH
+        );
+        assert_eq!(ensure_table_headers_in(ENGLISH, DETAILED, None), ENGLISH);
+    }
+
+    #[test]
+    fn headers_follow_the_template() {
+        let summary = "#### 1. Resumen ejecutivo\n- Uno\n\n#### 2. Ideas clave\n| Que es Rust y para que sirve | 00:10 |";
+        let fixed = ensure_table_headers_in(summary, ReportTemplate::Summary, Some("es"));
+        assert_eq!(line_after(&fixed, "#### 2. Ideas clave"), "| Idea | ⏱ |");
+        // The detailed headers do not apply to a summary, nor any to chapters
+        assert_eq!(
+            ensure_table_headers_in(summary, DETAILED, Some("es")),
+            summary
+        );
+        assert_eq!(
+            ensure_table_headers_in(summary, ReportTemplate::Chapters, Some("es")),
+            summary
+        );
+    }
+}
@@ -0,0 +1,252 @@
+// Reports from Ollama's own chat API, for users who keep transcripts on
+// hardware they run. No key and no upload opt-in: nothing goes to OpenAI.
+use super::openai::is_local;
+use super::{
+    Completion, MODEL_ENV, Prompt, ReportBackend, ReportText, TokenUsage, check_reachable, retry,
+};
+use crate::core::config::OpenAiConfig;
+use crate::error::{Error, Result};
+use futures::StreamExt;
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use serde_json::json;
+
+/// Where `ollama serve` listens unless `base_url` says otherwise.
+const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+/// Room for the report on top of `max_input_tokens` in the context window.
+const OUTPUT_ROOM: u64 = 8_192;
+
+pub struct OllamaBackend {
+    client: reqwest::Client,
+    model: String,
+    base_url: String,
+    /// Ollama's default context is a few thousand tokens and it cuts longer
+    /// prompts silently, so it is raised to fit `max_input_tokens`.
+    num_ctx: Option<u64>,
+    /// Only set when `max_output_tokens` is; Ollama has its own default.
+    num_predict: Option<u32>,
+    retries: u32,
+}
+
+/// One line of the newline-delimited JSON that `/api/chat` streams.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ChatLine {
+    message: Option<ChatMessage>,
+    done: bool,
+    prompt_eval_count: Option<u64>,
+    eval_count: Option<u64>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+/// Why a streamed answer could not be read.
+#[derive(Debug)]
+enum ChatError {
+    Unreadable(serde_json::Error),
+    /// Ollama's own `error` field.
+    Reported(String),
+}
+
+/// The answer so far, put together line by line.
+#[derive(Debug, Default)]
+struct ChatAnswer {
+    content: String,
+    usage: Option<TokenUsage>,
+}
+
+impl ChatAnswer {
+    /// Take in one line of the stream; blank ones are skipped.
+    fn read_line(
+        &mut self,
+        line: &[u8],
+        on_text: &(dyn Fn(ReportText<'_>) + Sync),
+    ) -> std::result::Result<(), ChatError> {
+        if line.iter().all(u8::is_ascii_whitespace) {
+            return Ok(());
+        }
+        let line: ChatLine = serde_json::from_slice(line).map_err(ChatError::Unreadable)?;
+        if let Some(error) = line.error {
+            return Err(ChatError::Reported(error));
+        }
+        if let Some(message) = line.message
+            && !message.content.is_empty()
+        {
+            on_text(ReportText::Delta(&message.content));
+            self.content.push_str(&message.content);
+        }
+        if line.done {
+            self.usage =
+                line.prompt_eval_count
+                    .zip(line.eval_count)
+                    .map(|(input_tokens, output_tokens)| TokenUsage {
+                        input_tokens,
+                        output_tokens,
+                    });
+        }
+        Ok(())
+    }
+}
+
+impl OllamaBackend {
+    pub fn new(model: &str, endpoint: &OpenAiConfig) -> Self {
+        let base_url = endpoint.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL);
+        Self {
+            client: reqwest::Client::new(),
+            model: model.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            num_ctx: endpoint.max_input_tokens.map(|tokens| tokens + OUTPUT_ROOM),
+            num_predict: endpoint.max_output_tokens,
+            retries: endpoint.retries.unwrap_or(retry::DEFAULT_RETRIES),
+        }
+    }
+
+    /// One chat, tried again while Ollama can't be reached or drops the
+    /// answer midway.
+    async fn chat(
+        &self,
+        prompt: Prompt<'_>,
+        on_text: &(dyn Fn(ReportText<'_>) + Sync),
+    ) -> Result<Completion> {
+        retry::retry(self.retries, retry::BASE_DELAY, || {
+            self.chat_once(prompt, on_text)
+        })
+        .await
+    }
+
+    async fn chat_once(
+        &self,
+        prompt: Prompt<'_>,
+        on_text: &(dyn Fn(ReportText<'_>) + Sync),
+    ) -> Result<Completion> {
+        let mut body = json!({
+            "model": self.model,
+            "stream": true,
+            "messages": [
+                { "role": "system", "content": prompt.system },
+                { "role": "user", "content": prompt.user },
+            ],
+        });
+        if let Some(num_ctx) = self.num_ctx {
+            body["options"]["num_ctx"] = json!(num_ctx);
+        }
+        if let Some(num_predict) = self.num_predict {
+            body["options"]["num_predict"] = json!(num_predict);
+        }
+
+        let url = format!("{}/api/chat", self.base_url);
+        let response = self
+            .client
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| {
+                Error::Network(format!(
+                    "Could not reach Ollama at {} ({e}). Is `ollama serve` running?",
+                    self.base_url
+                ))
+            })?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            let line: ChatLine = serde_json::from_str(&text).unwrap_or_default();
+            return Err(self.explain(ChatError::Reported(
+                line.error.unwrap_or(format!("{status} {text}")),
+            )));
+        }
+
+        on_text(ReportText::Restart);
+        let mut answer = ChatAnswer::default();
+        let mut pending = Vec::new();
+        let mut bytes = response.bytes_stream();
+        while let Some(chunk) = bytes.next().await {
+            let chunk = chunk
+                .map_err(|e| Error::Network(format!("Ollama stopped answering mid-report: {e}")))?;
+            pending.extend_from_slice(&chunk);
+            // A line may be split across chunks; keep the tail for the next one
+            while let Some(end) = pending.iter().position(|&byte| byte == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                answer
+                    .read_line(&line, on_text)
+                    .map_err(|e| self.explain(e))?;
+            }
+        }
+        // The last line need not end in a newline
+        answer
+            .read_line(&pending, on_text)
+            .map_err(|e| self.explain(e))?;
+        Ok(Completion {
+            text: answer.content,
+            usage: answer.usage,
+        })
+    }
+
+    fn explain(&self, error: ChatError) -> Error {
+        let error = match error {
+            ChatError::Unreadable(e) => {
+                return Error::Api(format!("Unreadable answer from Ollama: {e}"));
+            }
+            ChatError::Reported(error) => error,
+        };
+        if error.contains("not found") {
+            Error::Api(format!(
+                "Ollama has no model '{}' ({error}). Run `ollama pull {}` or choose another with --model, {MODEL_ENV} or [openai] model in vidio.toml",
+                self.model, self.model
+            ))
+        } else {
+            Error::Api(format!("Ollama at {}: {error}", self.base_url))
+        }
+    }
+}
+
+impl ReportBackend for OllamaBackend {
+    fn generate<'a>(
+        &'a self,
+        prompt: Prompt<'a>,
+        on_text: &'a (dyn Fn(ReportText<'_>) + Sync),
+    ) -> BoxFuture<'a, Result<Completion>> {
+        Box::pin(self.chat(prompt, on_text))
+    }
+
+    fn is_local(&self) -> bool {
+        is_local(&self.base_url)
+    }
+
+    fn check_connection(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let url = format!("{}/api/tags", self.base_url);
+            check_reachable(
+                self.client.get(url),
+                &format!("Ollama at {}", self.base_url),
+            )
+            .await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChatAnswer;
+
+    #[test]
+    fn the_last_line_counts_without_a_newline() {
+        let mut answer = ChatAnswer::default();
+        let lines = [
+            "{\"message\":{\"content\":\"Hola\"},\"done\":false}\n",
+            "\n",
+            // What is left over once the stream ends
+            "{\"message\":{\"content\":\" mundo\"},\"done\":true,\"prompt_eval_count\":12,\"eval_count\":3}",
+        ];
+        for line in lines {
+            answer.read_line(line.as_bytes(), &|_| {}).unwrap();
+        }
+        assert_eq!(answer.content, "Hola mundo");
+        let usage = answer.usage.unwrap();
+        assert_eq!((usage.input_tokens, usage.output_tokens), (12, 3));
+    }
+}
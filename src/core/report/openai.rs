@@ -0,0 +1,607 @@
+// Reports from OpenAI, or from any server that speaks its API: the Responses
+// API where there is one, chat completions where there is not.
+use super::{
+    Completion, DEFAULT_MAX_OUTPUT_TOKENS, MODEL_ENV, Prompt, ReportBackend, ReportText,
+    TokenUsage, check_reachable, retry,
+};
+use crate::core::config::{Effort, OpenAiConfig};
+use crate::error::{Error, Result};
+use async_openai::{
+    self,
+    config::OpenAIConfig,
+    error::{ApiError, OpenAIError},
+    types::chat::{
+        ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+        ChatCompletionStreamOptions, CompletionUsage, CreateChatCompletionRequestArgs,
+    },
+    types::responses::{
+        CreateResponse, CreateResponseArgs, EasyInputMessageArgs, ErrorObject, InputItem,
+        InputParam, OutputItem, OutputMessageContent, ReasoningArgs, ReasoningEffort,
+        ResponseStreamEvent, ResponseUsage, Role,
+    },
+};
+use futures::StreamExt;
+use futures::future::BoxFuture;
+use log::{debug, warn};
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use url::{Host, Url};
+
+const OPENAI_OPT_IN_ENV: &str = "YTRANSCRIPT_ALLOW_OPENAI";
+/// Where the client looks for the key unless `[openai] api_key_env` says otherwise.
+const DEFAULT_API_KEY_ENV: &str = "OPENAI_API_KEY";
+const OPENAI_API_BASE: &str = "https://api.openai.com/v1";
+
+/// Whether `base_url` points at this machine, so nothing leaves it.
+pub(super) fn is_local(base_url: &str) -> bool {
+    let Ok(url) = Url::parse(base_url) else {
+        return false;
+    };
+    match url.host() {
+        Some(Host::Domain(domain)) => domain == "localhost",
+        Some(Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    }
+}
+
+/// Whether `base_url` is OpenAI's own API, which always takes a key.
+fn is_openai(base_url: &str) -> bool {
+    Url::parse(base_url).is_ok_and(|url| url.host_str() == Some("api.openai.com"))
+}
+
+/// Whether a compatible server rejected `/responses` because it does not
+/// have it. Their 404s are rarely OpenAI error objects, so an unreadable
+/// reply counts too.
+fn lacks_responses_api(err: &OpenAIError) -> bool {
+    match err {
+        OpenAIError::JSONDeserialize(..) => true,
+        OpenAIError::ApiError(e) => {
+            let message = e.message.to_lowercase();
+            e.code.as_deref() != Some("model_not_found")
+                && (message.contains("404") || message.contains("not found"))
+        }
+        _ => false,
+    }
+}
+
+/// Whether OpenAI refused to stream, as it does for some models until the
+/// organization is verified.
+fn refuses_streaming(err: &OpenAIError) -> bool {
+    matches!(err, OpenAIError::ApiError(e) if e.param.as_deref() == Some("stream"))
+}
+
+/// The error a response that failed part way through carries.
+/// The connection closed in the middle of a streamed report.
+fn cut_short() -> Error {
+    Error::Network("The report stream ended before the report was finished".to_string())
+}
+
+fn failure(error: Option<ErrorObject>) -> OpenAIError {
+    let (message, code) = match error {
+        Some(error) => (error.message, Some(error.code)),
+        None => ("The response failed".to_string(), None),
+    };
+    OpenAIError::ApiError(ApiError {
+        message,
+        r#type: None,
+        param: None,
+        code,
+    })
+}
+
+fn response_usage(usage: ResponseUsage) -> TokenUsage {
+    TokenUsage {
+        input_tokens: usage.input_tokens.into(),
+        output_tokens: usage.output_tokens.into(),
+    }
+}
+
+fn chat_usage(usage: CompletionUsage) -> TokenUsage {
+    TokenUsage {
+        input_tokens: usage.prompt_tokens.into(),
+        output_tokens: usage.completion_tokens.into(),
+    }
+}
+
+/// Models that take a reasoning effort. Others reject the parameter.
+fn supports_reasoning(model: &str) -> bool {
+    model.starts_with("gpt-5")
+        || model
+            .strip_prefix('o')
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+pub struct OpenAiBackend {
+    client: async_openai::Client<OpenAIConfig>,
+    model: String,
+    retries: u32,
+    effort: Effort,
+    max_output_tokens: u32,
+    /// Set for an OpenAI-compatible server instead of OpenAI.
+    base_url: Option<String>,
+    api_key_env: String,
+    /// OpenAI itself, or a server whose key variable was named in the
+    /// config. Other servers may well take no key at all.
+    key_required: bool,
+    /// The server turned out to have no Responses API, so a batch only
+    /// finds out once.
+    chat_only: AtomicBool,
+    /// OpenAI refused to stream this model; likewise remembered.
+    no_stream: AtomicBool,
+}
+
+impl OpenAiBackend {
+    pub fn new(model: &str, endpoint: &OpenAiConfig) -> Self {
+        let mut config = OpenAIConfig::new();
+        if let Some(base_url) = &endpoint.base_url {
+            config = config.with_api_base(base_url.trim_end_matches('/'));
+        }
+        if let Some(name) = &endpoint.api_key_env {
+            // Local servers usually take no key at all
+            config = config.with_api_key(env::var(name).unwrap_or_default());
+        }
+        // Retries are ours, so they also cover dropped connections
+        let no_backoff = backoff::ExponentialBackoff {
+            max_elapsed_time: Some(Duration::ZERO),
+            ..Default::default()
+        };
+        Self {
+            client: async_openai::Client::with_config(config).with_backoff(no_backoff),
+            model: model.to_string(),
+            retries: endpoint.retries.unwrap_or(retry::DEFAULT_RETRIES),
+            effort: endpoint.reasoning_effort.unwrap_or_default(),
+            max_output_tokens: endpoint
+                .max_output_tokens
+                .unwrap_or(DEFAULT_MAX_OUTPUT_TOKENS),
+            base_url: endpoint.base_url.clone(),
+            api_key_env: endpoint
+                .api_key_env
+                .clone()
+                .unwrap_or_else(|| DEFAULT_API_KEY_ENV.to_string()),
+            key_required: endpoint.api_key_env.is_some()
+                || endpoint.base_url.as_deref().is_none_or(is_openai),
+            chat_only: AtomicBool::new(false),
+            no_stream: AtomicBool::new(false),
+        }
+    }
+
+    /// One request, retried while it fails for a passing reason, with its
+    /// text passed to `on_text` as it arrives. Servers without the Responses
+    /// API are asked through chat completions instead.
+    async fn request(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        on_text: &(dyn Fn(ReportText<'_>) + Sync),
+    ) -> Result<Completion> {
+        if !self.chat_only.load(Ordering::Relaxed) {
+            let response = retry::retry(self.retries, retry::BASE_DELAY, || {
+                on_text(ReportText::Restart);
+                self.respond(system_prompt, user_prompt, on_text)
+            })
+            .await;
+            match response {
+                Err(Error::OpenAi(e)) if self.base_url.is_some() && lacks_responses_api(&e) => {
+                    debug!("{e}; falling back to chat completions");
+                    self.chat_only.store(true, Ordering::Relaxed);
+                }
+                response => return response,
+            }
+        }
+        retry::retry(self.retries, retry::BASE_DELAY, || {
+            on_text(ReportText::Restart);
+            self.stream_chat(system_prompt, user_prompt, on_text)
+        })
+        .await
+    }
+
+    /// Ask the Responses API, streaming unless OpenAI will not stream this
+    /// model, in which case the text arrives all at once.
+    async fn respond(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        on_text: &(dyn Fn(ReportText<'_>) + Sync),
+    ) -> Result<Completion> {
+        if !self.no_stream.load(Ordering::Relaxed) {
+            match self
+                .stream_response(system_prompt, user_prompt, on_text)
+                .await
+            {
+                Err(Error::OpenAi(e)) if refuses_streaming(&e) => {
+                    self.no_stream.store(true, Ordering::Relaxed)
+                }
+                response => return response,
+            }
+        }
+        let completion = self.complete_response(system_prompt, user_prompt).await?;
+        on_text(ReportText::Delta(&completion.text));
+        Ok(completion)
+    }
+
+    fn response_request(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+    ) -> std::result::Result<CreateResponse, OpenAIError> {
+        let mut request = CreateResponseArgs::default();
+        if supports_reasoning(&self.model) {
+            request.reasoning(
+                ReasoningArgs::default()
+                    .effort(match self.effort {
+                        Effort::Low => ReasoningEffort::Low,
+                        Effort::Medium => ReasoningEffort::Medium,
+                        Effort::High => ReasoningEffort::High,
+                    })
+                    // .summary(ReasoningSummary::Detailed)
+                    .build()?,
+            );
+        }
+        request
+            .max_output_tokens(self.max_output_tokens)
+            .model(&self.model)
+            .input(InputParam::Items(vec![
+                InputItem::EasyMessage(
+                    EasyInputMessageArgs::default()
+                        .role(Role::System)
+                        .content(system_prompt)
+                        .build()?,
+                ),
+                InputItem::EasyMessage(
+                    EasyInputMessageArgs::default()
+                        .role(Role::User)
+                        .content(user_prompt)
+                        .build()?,
+                ),
+            ]))
+            .build()
+    }
+
+    /// Stream a response. Dropping the future, as cancelling a job does,
+    /// closes the stream. A stream that stops before the response is done
+    /// is a network error, so the request is retried rather than the text so
+    /// far taken for the report.
+    async fn stream_response(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        on_text: &(dyn Fn(ReportText<'_>) + Sync),
+    ) -> Result<Completion> {
+        let request = self.response_request(system_prompt, user_prompt)?;
+        let mut stream = self.client.responses().create_stream(request).await?;
+
+        let mut content = String::new();
+        let mut started = false;
+        while let Some(event) = stream.next().await {
+            let event = match event {
+                Ok(event) => event,
+                // Events newer than this client are of no interest
+                Err(OpenAIError::JSONDeserialize(..)) if started => continue,
+                Err(e) => return Err(e.into()),
+            };
+            started = true;
+            match event {
+                ResponseStreamEvent::ResponseOutputTextDelta(text) => {
+                    on_text(ReportText::Delta(&text.delta));
+                    content.push_str(&text.delta);
+                }
+                ResponseStreamEvent::ResponseFailed(failed) => {
+                    return Err(failure(failed.response.error).into());
+                }
+                ResponseStreamEvent::ResponseError(e) => {
+                    return Err(OpenAIError::ApiError(ApiError {
+                        message: e.message,
+                        r#type: None,
+                        param: e.param,
+                        code: e.code,
+                    })
+                    .into());
+                }
+                ResponseStreamEvent::ResponseCompleted(done) => {
+                    return Ok(Completion {
+                        text: content,
+                        usage: done.response.usage.map(response_usage),
+                    });
+                }
+                ResponseStreamEvent::ResponseIncomplete(done) => {
+                    return Ok(Completion {
+                        text: content,
+                        usage: done.response.usage.map(response_usage),
+                    });
+                }
+                _ => {}
+            }
+        }
+        Err(cut_short())
+    }
+
+    async fn complete_response(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+    ) -> std::result::Result<Completion, OpenAIError> {
+        let request = self.response_request(system_prompt, user_prompt)?;
+        let response = self.client.responses().create(request).await?;
+        let usage = response.usage.map(response_usage);
+
+        let mut content = String::new();
+        for output in response.output {
+            if let OutputItem::Message(out) = output {
+                for c in out.content {
+                    match c {
+                        OutputMessageContent::OutputText(text) => content.push_str(&text.text),
+                        _ => {
+                            warn!("Unexpected content type: {c:?}");
+                            continue;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Completion {
+            text: content,
+            usage,
+        })
+    }
+
+    /// Stream a chat completion; like [`Self::stream_response`], one that
+    /// stops before a choice says why it finished is retried.
+    async fn stream_chat(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        on_text: &(dyn Fn(ReportText<'_>) + Sync),
+    ) -> Result<Completion> {
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.model)
+            .max_completion_tokens(self.max_output_tokens)
+            .stream_options(ChatCompletionStreamOptions {
+                include_usage: Some(true),
+                include_obfuscation: None,
+            })
+            .messages([
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(system_prompt)
+                    .build()?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(user_prompt)
+                    .build()?
+                    .into(),
+            ])
+            .build()?;
+
+        let mut stream = self.client.chat().create_stream(request).await?;
+        let mut content = String::new();
+        let mut usage = None;
+        let mut finished = false;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            // Only the last chunk has it
+            if let Some(used) = chunk.usage {
+                usage = Some(chat_usage(used));
+            }
+            for choice in chunk.choices {
+                if let Some(text) = choice.delta.content {
+                    on_text(ReportText::Delta(&text));
+                    content.push_str(&text);
+                }
+                finished |= choice.finish_reason.is_some();
+            }
+        }
+        if !finished {
+            return Err(cut_short());
+        }
+        Ok(Completion {
+            text: content,
+            usage,
+        })
+    }
+
+    fn explain(&self, err: Error) -> Error {
+        match err {
+            Error::OpenAi(e) => match *e {
+                OpenAIError::ApiError(e) if e.code.as_deref() == Some("model_not_found") => {
+                    Error::custom(format!(
+                        "Model '{}' is not available to this account ({}). Choose another with --model, {MODEL_ENV} or [openai] model in vidio.toml",
+                        self.model, e.message
+                    ))
+                }
+                e => e.into(),
+            },
+            err => err,
+        }
+    }
+
+    /// Transcripts only leave this machine with explicit consent; a server
+    /// on localhost needs none.
+    fn enforce_upload_opt_in(&self) -> Result<()> {
+        if self.is_opted_in() {
+            return Ok(());
+        }
+        Err(Error::OptInRequired(format!(
+            "Report generation requires explicit opt-in. Set {OPENAI_OPT_IN_ENV}=1 to enable uploads to {}.",
+            self.base_url.as_deref().unwrap_or("OpenAI")
+        )))
+    }
+}
+
+impl ReportBackend for OpenAiBackend {
+    fn generate<'a>(
+        &'a self,
+        prompt: Prompt<'a>,
+        on_text: &'a (dyn Fn(ReportText<'_>) + Sync),
+    ) -> BoxFuture<'a, Result<Completion>> {
+        Box::pin(async move {
+            self.enforce_upload_opt_in()?;
+            self.request(prompt.system, prompt.user, on_text)
+                .await
+                .map_err(|e| self.explain(e))
+        })
+    }
+
+    fn is_local(&self) -> bool {
+        self.base_url.as_deref().is_some_and(is_local)
+    }
+
+    fn check_credentials(&self) -> Result<()> {
+        if self.is_local() {
+            return Ok(());
+        }
+        let has_key = env::var(&self.api_key_env).is_ok_and(|key| !key.trim().is_empty());
+        if self.key_required && !has_key {
+            return Err(Error::custom(format!(
+                "No API key for reports: set {} to your {} API key",
+                self.api_key_env,
+                self.base_url.as_deref().unwrap_or("OpenAI")
+            )));
+        }
+        self.enforce_upload_opt_in()
+    }
+
+    fn check_connection(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let base = self.base_url.as_deref().unwrap_or(OPENAI_API_BASE);
+            let mut request =
+                reqwest::Client::new().get(format!("{}/models", base.trim_end_matches('/')));
+            if let Ok(key) = env::var(&self.api_key_env)
+                && !key.trim().is_empty()
+            {
+                request = request.bearer_auth(key.trim());
+            }
+            check_reachable(request, self.base_url.as_deref().unwrap_or("OpenAI")).await
+        })
+    }
+
+    fn is_opted_in(&self) -> bool {
+        self.is_local()
+            || env::var(OPENAI_OPT_IN_ENV).is_ok_and(|val| {
+                matches!(
+                    val.trim().to_ascii_lowercase().as_str(),
+                    "1" | "true" | "yes"
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OpenAiBackend, is_local, lacks_responses_api, supports_reasoning};
+    use crate::core::config::OpenAiConfig;
+    use crate::core::report::ReportBackend;
+    use async_openai::error::{ApiError, OpenAIError};
+
+    #[test]
+    fn missing_keys_are_named_before_anything_is_sent() {
+        let endpoint = OpenAiConfig {
+            api_key_env: Some("VIDIO_TEST_UNSET_KEY".to_string()),
+            ..OpenAiConfig::default()
+        };
+        let error = OpenAiBackend::new("gpt-5.2", &endpoint)
+            .check_credentials()
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("set VIDIO_TEST_UNSET_KEY to your OpenAI API key"),
+            "{error}"
+        );
+
+        let local = OpenAiConfig {
+            base_url: Some("http://localhost:11434/v1".to_string()),
+            ..endpoint
+        };
+        assert!(
+            OpenAiBackend::new("llama3", &local)
+                .check_credentials()
+                .is_ok()
+        );
+        // Nothing leaves the machine, so no opt-in is asked for either
+        assert!(OpenAiBackend::new("llama3", &local).is_opted_in());
+
+        // A remote server named without a key variable may take none
+        let remote = OpenAiConfig {
+            base_url: Some("https://llm.example.com/v1".to_string()),
+            ..OpenAiConfig::default()
+        };
+        let error = OpenAiBackend::new("llama3", &remote)
+            .check_credentials()
+            .err()
+            .map(|e| e.to_string())
+            .unwrap_or_default();
+        assert!(!error.contains("No API key"), "{error}");
+
+        let openai = OpenAiConfig {
+            base_url: Some("https://api.openai.com/v1/".to_string()),
+            ..OpenAiConfig::default()
+        };
+        assert!(OpenAiBackend::new("gpt-5.2", &openai).key_required);
+    }
+
+    #[tokio::test]
+    async fn unreachable_servers_fail_the_connection_check() {
+        // A port nothing listens on any more
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+        drop(listener);
+
+        let endpoint = OpenAiConfig {
+            base_url: Some(base_url.clone()),
+            ..OpenAiConfig::default()
+        };
+        let error = OpenAiBackend::new("llama3", &endpoint)
+            .check_connection()
+            .await
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains(&format!("Could not reach {base_url}")),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn reasoning_effort_only_for_reasoning_models() {
+        assert!(supports_reasoning("gpt-5-mini"));
+        assert!(supports_reasoning("o3"));
+        assert!(!supports_reasoning("gpt-4o"));
+        assert!(!supports_reasoning("omni-moderation-latest"));
+    }
+
+    #[test]
+    fn only_loopback_servers_are_local() {
+        assert!(is_local("http://localhost:11434/v1"));
+        assert!(is_local("http://127.0.0.1:1234/v1"));
+        assert!(is_local("http://[::1]:8080/v1"));
+        assert!(!is_local("https://api.openai.com/v1"));
+        assert!(!is_local("http://192.168.1.20:11434/v1"));
+        assert!(!is_local("localhost:11434"));
+    }
+
+    #[test]
+    fn missing_responses_endpoint_is_recognised() {
+        let api_error = |message: &str, code: Option<&str>| {
+            OpenAIError::ApiError(ApiError {
+                message: message.to_string(),
+                r#type: None,
+                param: None,
+                code: code.map(str::to_string),
+            })
+        };
+        let not_json = serde_json::from_str::<u8>("404 page not found").unwrap_err();
+        assert!(lacks_responses_api(&OpenAIError::JSONDeserialize(
+            not_json,
+            "404 page not found".to_string()
+        )));
+        assert!(lacks_responses_api(&api_error("Not Found", None)));
+        assert!(!lacks_responses_api(&api_error(
+            "The model `x` does not exist or was not found",
+            Some("model_not_found")
+        )));
+        assert!(!lacks_responses_api(&api_error("Rate limit reached", None)));
+    }
+}
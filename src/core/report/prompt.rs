@@ -0,0 +1,233 @@
+// Where a report's prompt comes from: a built-in template or a Markdown file
+// the user wrote, with placeholders filled in per video.
+use super::ReportTemplate;
+use super::front_matter::transcript_hash;
+use crate::core::settings::Settings;
+use crate::error::{Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Prompts may write each placeholder with single or double braces; parsing
+// turns the double form into the single one
+const TRANSCRIPT_PLACEHOLDER: &str = "{transcript}";
+const TITLE_PLACEHOLDER: &str = "{title}";
+const VIDEO_ID_PLACEHOLDER: &str = "{video_id}";
+const LANGUAGE_PLACEHOLDER: &str = "{language}";
+const PLACEHOLDERS: [&str; 4] = [
+    TRANSCRIPT_PLACEHOLDER,
+    TITLE_PLACEHOLDER,
+    VIDEO_ID_PLACEHOLDER,
+    LANGUAGE_PLACEHOLDER,
+];
+
+/// The language reports are written in when neither the user nor the
+/// transcript says otherwise; it is the language of the built-in prompts.
+pub const DEFAULT_LANGUAGE: &str = "es";
+
+/// What a prompt knows about the video besides its transcript.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PromptVideo<'a> {
+    pub video_id: &'a str,
+    pub title: Option<&'a str>,
+    /// Language code the report is written in.
+    pub language: &'a str,
+    /// Caption lines cleaned out of the transcript before it was saved.
+    pub removed_lines: usize,
+}
+
+/// The report language for a video: the one asked for, else the language
+/// of the transcript, else [`DEFAULT_LANGUAGE`].
+pub fn report_language(requested: Option<&str>, transcript: Option<&str>) -> String {
+    requested
+        .or(transcript)
+        .unwrap_or(DEFAULT_LANGUAGE)
+        .to_string()
+}
+
+/// A user prompt read from a file. It must contain `{{transcript}}` and may
+/// refer to `{{title}}`, `{{video_id}}` and `{{language}}`, each also
+/// accepted with single braces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomPrompt {
+    path: PathBuf,
+    text: String,
+}
+
+impl CustomPrompt {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).map_err(|e| {
+            Error::custom(match e.kind() {
+                std::io::ErrorKind::NotFound => {
+                    format!("Prompt file {} does not exist", path.display())
+                }
+                _ => format!("Cannot read prompt file {}: {e}", path.display()),
+            })
+        })?;
+        Self::parse(path, text)
+    }
+
+    fn parse(path: &Path, text: String) -> Result<Self> {
+        let text = PLACEHOLDERS.iter().fold(text, |text, placeholder| {
+            text.replace(&format!("{{{placeholder}}}"), placeholder)
+        });
+        if !text.contains(TRANSCRIPT_PLACEHOLDER) {
+            return Err(Error::custom(format!(
+                "Prompt file {} has no {{{{transcript}}}} placeholder",
+                path.display()
+            )));
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            text,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The prompt for one video. The transcript goes in last, so text in it
+    /// that looks like a placeholder is left alone. An unknown title becomes
+    /// the video ID.
+    pub fn render(&self, transcript_text: &str, video: PromptVideo) -> String {
+        self.text
+            .replace(TITLE_PLACEHOLDER, video.title.unwrap_or(video.video_id))
+            .replace(VIDEO_ID_PLACEHOLDER, video.video_id)
+            .replace(LANGUAGE_PLACEHOLDER, video.language)
+            .replace(TRANSCRIPT_PLACEHOLDER, transcript_text)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptSource {
+    Template(ReportTemplate),
+    Custom(CustomPrompt),
+}
+
+impl Default for PromptSource {
+    fn default() -> Self {
+        Self::Template(ReportTemplate::default())
+    }
+}
+
+impl PromptSource {
+    /// The prompt for a run: `prompt_file` if given, otherwise the template.
+    /// The custom template without a file uses the one set in Settings.
+    pub fn resolve(template: ReportTemplate, prompt_file: Option<&Path>) -> Result<Self> {
+        if let Some(path) = prompt_file {
+            return CustomPrompt::load(path).map(Self::Custom);
+        }
+        if template != ReportTemplate::Custom {
+            return Ok(Self::Template(template));
+        }
+        match Settings::load()?.prompt_file {
+            Some(path) => CustomPrompt::load(&path).map(Self::Custom),
+            None => Err(Error::custom(
+                "The custom template needs --prompt-file or a default prompt file in Settings",
+            )),
+        }
+    }
+
+    /// The template the report is saved under.
+    pub fn template(&self) -> ReportTemplate {
+        match self {
+            Self::Template(template) => *template,
+            Self::Custom(_) => ReportTemplate::Custom,
+        }
+    }
+
+    /// SHA-256 of the prompt with its placeholders left in, so a report can
+    /// tell whether it was written from the prompt a run would use.
+    pub fn hash(&self) -> String {
+        let (system_prompt, user_prompt) = self.render(
+            TRANSCRIPT_PLACEHOLDER,
+            PromptVideo {
+                video_id: VIDEO_ID_PLACEHOLDER,
+                title: Some(TITLE_PLACEHOLDER),
+                language: LANGUAGE_PLACEHOLDER,
+                removed_lines: 0,
+            },
+        );
+        transcript_hash(&format!("{system_prompt}\n{user_prompt}"))
+    }
+
+    /// The system and user prompts around a transcript.
+    pub fn render(&self, transcript_text: &str, video: PromptVideo) -> (&'static str, String) {
+        let template = self.template();
+        let user_prompt = match self {
+            Self::Template(template) => {
+                template.user_prompt(transcript_text, video.language, video.removed_lines)
+            }
+            Self::Custom(prompt) => prompt.render(transcript_text, video),
+        };
+        (template.system_prompt(), user_prompt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CustomPrompt, PromptSource, PromptVideo, report_language};
+    use crate::core::report::ReportTemplate;
+    use std::path::Path;
+
+    #[test]
+    fn custom_prompts_need_the_transcript_placeholder() {
+        let path = Path::new("my_prompt.md");
+        let error = CustomPrompt::parse(path, "Resume {{title}}".to_string()).unwrap_err();
+        assert!(error.to_string().contains("{{transcript}}"));
+
+        let missing = CustomPrompt::load(Path::new("fixtures/no_such_prompt.md")).unwrap_err();
+        assert!(missing.to_string().contains("does not exist"));
+    }
+
+    #[test]
+    fn placeholders_are_filled_but_not_inside_the_transcript() {
+        let prompt = CustomPrompt::parse(
+            Path::new("my_prompt.md"),
+            "{{title}} ({{video_id}}, {{language}})\n{{transcript}}".to_string(),
+        )
+        .unwrap();
+        let mut video = PromptVideo {
+            video_id: "5_EJwYeQusM",
+            title: Some("Rust"),
+            language: "en",
+            removed_lines: 0,
+        };
+        assert_eq!(
+            prompt.render("say {{title}}", video),
+            "Rust (5_EJwYeQusM, en)\nsay {{title}}"
+        );
+        video.title = None;
+        assert_eq!(prompt.render("", video), "5_EJwYeQusM (5_EJwYeQusM, en)\n");
+
+        let source = PromptSource::Custom(prompt);
+        assert_eq!(source.template(), ReportTemplate::Custom);
+        assert_eq!(
+            source.render("hola", video).1,
+            "5_EJwYeQusM (5_EJwYeQusM, en)\nhola"
+        );
+    }
+
+    #[test]
+    fn single_and_double_braces_both_work() {
+        let prompt = CustomPrompt::parse(
+            Path::new("my_prompt.md"),
+            "{title}: {{transcript}} / {transcript}".to_string(),
+        )
+        .unwrap();
+        let video = PromptVideo {
+            video_id: "5_EJwYeQusM",
+            title: Some("Rust"),
+            language: "en",
+            removed_lines: 0,
+        };
+        assert_eq!(prompt.render("hola", video), "Rust: hola / hola");
+    }
+
+    #[test]
+    fn report_language_prefers_the_request_then_the_transcript() {
+        assert_eq!(report_language(Some("en"), Some("de")), "en");
+        assert_eq!(report_language(None, Some("de")), "de");
+        assert_eq!(report_language(None, None), "es");
+    }
+}
@@ -0,0 +1,312 @@
+// Detection and repair of damaged report files: truncated writes that leave a
+// code fence or table open at EOF, and merge conflict markers left behind by
+// sync tools. Detection is cheap enough to run every time a report is opened.
+use crate::core::report::{ReportTemplate, ensure_table_headers, lint};
+use crate::error::{Error, Result};
+use std::fmt;
+
+const CONFLICT_START: &str = "<<<<<<<";
+const CONFLICT_BASE: &str = "|||||||";
+const CONFLICT_SPLIT: &str = "=======";
+const CONFLICT_END: &str = ">>>>>>>";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Corruption {
+    /// `<<<<<<<` / `=======` / `>>>>>>>` blocks; `offset` is the first marker.
+    ConflictMarkers { offset: usize, blocks: usize },
+    /// A code fence opened at `offset` is never closed.
+    UnterminatedFence { offset: usize },
+    /// The table row starting at `offset` is cut off at end of file.
+    TruncatedTable { offset: usize },
+}
+
+impl fmt::Display for Corruption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ConflictMarkers { offset, blocks } => write!(
+                f,
+                "{blocks} sync conflict block(s), first marker at byte {offset}"
+            ),
+            Self::UnterminatedFence { offset } => {
+                write!(f, "unterminated code fence opened at byte {offset}")
+            }
+            Self::TruncatedTable { offset } => {
+                write!(f, "table cut off at end of file, last row at byte {offset}")
+            }
+        }
+    }
+}
+
+/// Which side of a conflict block to keep when stripping markers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictSide {
+    Ours,
+    Theirs,
+}
+
+impl ConflictSide {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "ours" => Ok(Self::Ours),
+            "theirs" => Ok(Self::Theirs),
+            other => Err(Error::custom(format!(
+                "Unknown conflict side '{other}'; expected 'ours' or 'theirs'"
+            ))),
+        }
+    }
+}
+
+/// Lines with the byte offset at which each one starts.
+fn lines_with_offsets(report: &str) -> impl Iterator<Item = (usize, &str)> {
+    report.split_inclusive('\n').scan(0, |offset, raw| {
+        let start = *offset;
+        *offset += raw.len();
+        Some((start, raw.trim_end_matches(['\n', '\r'])))
+    })
+}
+
+fn fence_marker(line: &str) -> Option<&'static str> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("```") {
+        Some("```")
+    } else if trimmed.starts_with("~~~") {
+        Some("~~~")
+    } else {
+        None
+    }
+}
+
+/// Offset of the fence left open at end of file, if any.
+fn open_fence(report: &str) -> Option<(usize, &'static str)> {
+    let mut open: Option<(usize, &'static str)> = None;
+    for (offset, line) in lines_with_offsets(report) {
+        if let Some(marker) = fence_marker(line) {
+            open = match open {
+                Some((_, current)) if current == marker => None,
+                Some(current) => Some(current),
+                None => Some((offset, marker)),
+            };
+        }
+    }
+    open
+}
+
+/// The trailing table block, if the report ends inside one, as
+/// `(offset of last row, block lines)`.
+fn trailing_table(report: &str) -> Option<(usize, Vec<String>)> {
+    let lines: Vec<(usize, &str)> = lines_with_offsets(report).collect();
+    let last = lines
+        .iter()
+        .rposition(|(_, line)| !line.trim().is_empty())?;
+    if !lines[last].1.trim_start().starts_with('|') {
+        return None;
+    }
+
+    let first = lines[..=last]
+        .iter()
+        .rposition(|(_, line)| !line.trim_start().starts_with('|'))
+        .map_or(0, |idx| idx + 1);
+    let block = lines[first..=last]
+        .iter()
+        .map(|(_, line)| line.to_string())
+        .collect();
+    Some((lines[last].0, block))
+}
+
+fn table_is_truncated(block: &[String]) -> bool {
+    let last = block.last().map(|l| l.trim()).unwrap_or_default();
+    !last.ends_with('|') || lint::parse_tables(block).iter().any(|t| t.is_err())
+}
+
+pub fn detect(report: &str) -> Vec<Corruption> {
+    let mut found = Vec::new();
+
+    let mut first_marker = None;
+    let mut blocks = 0;
+    for (offset, line) in lines_with_offsets(report) {
+        if line.starts_with(CONFLICT_START) {
+            first_marker.get_or_insert(offset);
+            blocks += 1;
+        }
+    }
+    if let Some(offset) = first_marker {
+        found.push(Corruption::ConflictMarkers { offset, blocks });
+    }
+
+    if let Some((offset, _)) = open_fence(report) {
+        found.push(Corruption::UnterminatedFence { offset });
+    } else if let Some((offset, block)) = trailing_table(report)
+        && table_is_truncated(&block)
+    {
+        found.push(Corruption::TruncatedTable { offset });
+    }
+
+    found
+}
+
+fn strip_conflicts(report: &str, side: ConflictSide) -> String {
+    #[derive(PartialEq)]
+    enum Region {
+        Outside,
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    let mut region = Region::Outside;
+    let mut kept = Vec::new();
+    for line in report.lines() {
+        if line.starts_with(CONFLICT_START) {
+            region = Region::Ours;
+        } else if region != Region::Outside && line.starts_with(CONFLICT_BASE) {
+            region = Region::Base;
+        } else if region != Region::Outside && line.starts_with(CONFLICT_SPLIT) {
+            region = Region::Theirs;
+        } else if region != Region::Outside && line.starts_with(CONFLICT_END) {
+            region = Region::Outside;
+        } else {
+            let keep = match region {
+                Region::Outside => true,
+                Region::Ours => side == ConflictSide::Ours,
+                Region::Theirs => side == ConflictSide::Theirs,
+                Region::Base => false,
+            };
+            if keep {
+                kept.push(line);
+            }
+        }
+    }
+    kept.join("\n")
+}
+
+/// Close a truncated last row: add the missing trailing pipe and pad it with
+/// empty cells up to the header width so the table parses again.
+fn close_truncated_row(report: &str) -> String {
+    let Some((_, block)) = trailing_table(report) else {
+        return report.to_string();
+    };
+    let header_cells = block[0].trim().trim_matches('|').split('|').count();
+
+    let mut lines: Vec<String> = report
+        .trim_end()
+        .lines()
+        .map(|line| line.to_string())
+        .collect();
+    if let Some(last) = lines.last_mut() {
+        let mut row = last.trim_end().to_string();
+        if !row.ends_with('|') {
+            row.push_str(" |");
+        }
+        let cells = row.trim().trim_matches('|').split('|').count();
+        for _ in cells..header_cells {
+            row.push_str("  |");
+        }
+        *last = row;
+    }
+    lines.join("\n")
+}
+
+/// Fix everything `detect` reports and re-run the table header repair for
+/// the template the report was written with. Returns the repaired content and
+/// a description of each fix applied.
+pub fn repair(report: &str, side: ConflictSide, template: ReportTemplate) -> (String, Vec<String>) {
+    let mut content = report.to_string();
+    let mut fixes = Vec::new();
+
+    for corruption in detect(report) {
+        match corruption {
+            Corruption::ConflictMarkers { blocks, .. } => {
+                content = strip_conflicts(&content, side);
+                let kept = match side {
+                    ConflictSide::Ours => "ours",
+                    ConflictSide::Theirs => "theirs",
+                };
+                fixes.push(format!("stripped {blocks} conflict block(s), kept {kept}"));
+            }
+            Corruption::UnterminatedFence { .. } => {
+                if let Some((_, marker)) = open_fence(&content) {
+                    content = format!("{}\n{marker}", content.trim_end());
+                    fixes.push(format!("closed unterminated {marker} fence"));
+                }
+            }
+            Corruption::TruncatedTable { .. } => {
+                content = close_truncated_row(&content);
+                fixes.push("closed truncated table row".to_string());
+            }
+        }
+    }
+
+    let content = ensure_table_headers(&content, template);
+    (format!("{}\n", content.trim_end()), fixes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConflictSide, Corruption, ReportTemplate, detect, repair};
+
+    const CONFLICT: &str = include_str!("../../../fixtures/recovery/conflict.md");
+    const UNTERMINATED_FENCE: &str =
+        include_str!("../../../fixtures/recovery/unterminated_fence.md");
+    const TRUNCATED_TABLE: &str = include_str!("../../../fixtures/recovery/truncated_table.md");
+
+    #[test]
+    fn detects_conflict_markers_with_offset() {
+        let offset = CONFLICT.find("<<<<<<<").unwrap();
+        assert_eq!(
+            detect(CONFLICT),
+            vec![Corruption::ConflictMarkers { offset, blocks: 1 }]
+        );
+    }
+
+    #[test]
+    fn repairs_conflict_keeping_either_side() {
+        let (ours, fixes) = repair(CONFLICT, ConflictSide::Ours, ReportTemplate::Detailed);
+        assert_eq!(fixes.len(), 1);
+        assert!(ours.contains("presenta la idea"));
+        assert!(!ours.contains("con un ejemplo"));
+        assert!(!ours.contains("======="));
+
+        let (theirs, _) = repair(CONFLICT, ConflictSide::Theirs, ReportTemplate::Detailed);
+        assert!(theirs.contains("con un ejemplo"));
+        assert!(detect(&theirs).is_empty());
+    }
+
+    #[test]
+    fn detects_and_closes_unterminated_fence() {
+        let offset = UNTERMINATED_FENCE.find("```").unwrap();
+        assert_eq!(
+            detect(UNTERMINATED_FENCE),
+            vec![Corruption::UnterminatedFence { offset }]
+        );
+
+        let (repaired, _) = repair(
+            UNTERMINATED_FENCE,
+            ConflictSide::Ours,
+            ReportTemplate::Detailed,
+        );
+        assert!(repaired.ends_with("cortada\n```\n"));
+        assert!(detect(&repaired).is_empty());
+    }
+
+    #[test]
+    fn detects_and_closes_truncated_table() {
+        let offset = TRUNCATED_TABLE.rfind("| Ratatui").unwrap();
+        assert_eq!(
+            detect(TRUNCATED_TABLE),
+            vec![Corruption::TruncatedTable { offset }]
+        );
+
+        let (repaired, _) = repair(
+            TRUNCATED_TABLE,
+            ConflictSide::Ours,
+            ReportTemplate::Detailed,
+        );
+        assert!(repaired.contains("| Ratatui | librería | 2 |  |"));
+        assert!(detect(&repaired).is_empty());
+    }
+
+    #[test]
+    fn clean_report_has_nothing_to_detect() {
+        assert!(detect("#### 1. Metadata\n| a | b |\n|---|---|\n| 1 | 2 |\n").is_empty());
+    }
+}
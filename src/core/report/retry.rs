@@ -0,0 +1,273 @@
+// Report requests run for minutes, so a dropped connection, a 5xx or a rate
+// limit is waited out and retried instead of failing the whole report.
+use crate::error::Error;
+use async_openai::error::{OpenAIError, StreamError};
+use log::debug;
+use reqwest_eventsource::Error as EventSourceError;
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+
+/// Retries after the first attempt unless `[openai] retries` says otherwise.
+pub const DEFAULT_RETRIES: u32 = 3;
+/// First wait between attempts; each retry doubles it.
+pub const BASE_DELAY: Duration = Duration::from_secs(2);
+/// No wait is longer than this, whatever the server asks for.
+const MAX_DELAY: Duration = Duration::from_secs(120);
+
+/// An error `retry` can judge: whether another attempt may succeed, and how
+/// long the server asked to wait first.
+pub trait Retryable: Display {
+    fn is_transient(&self) -> bool;
+    fn retry_after(&self) -> Option<Duration>;
+}
+
+impl Retryable for OpenAIError {
+    fn is_transient(&self) -> bool {
+        is_transient(self)
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        retry_after(self)
+    }
+}
+
+impl Retryable for Error {
+    fn is_transient(&self) -> bool {
+        Error::is_transient(self)
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::OpenAi(e) => retry_after(e),
+            _ => None,
+        }
+    }
+}
+
+/// Run `call` until it succeeds, fails for good or has been retried
+/// `retries` times.
+pub async fn retry<T, E, Fut>(
+    retries: u32,
+    base_delay: Duration,
+    mut call: impl FnMut() -> Fut,
+) -> Result<T, E>
+where
+    E: Retryable,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut attempt = 0;
+    loop {
+        let err = match call().await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+        if attempt >= retries || !err.is_transient() {
+            return Err(err);
+        }
+        let delay = err
+            .retry_after()
+            .unwrap_or_else(|| backoff(base_delay, attempt))
+            .min(MAX_DELAY);
+        debug!(
+            "Retrying in {}s ({} of {retries}): {err}",
+            delay.as_secs(),
+            attempt + 1
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// `base_delay` doubled once per earlier retry, capped at [`MAX_DELAY`] so a
+/// large `retries` can't overflow it.
+fn backoff(base_delay: Duration, attempt: u32) -> Duration {
+    2_u32
+        .checked_pow(attempt)
+        .map_or(MAX_DELAY, |factor| base_delay.saturating_mul(factor))
+        .min(MAX_DELAY)
+}
+
+/// Errors worth another attempt: the connection failing, a server error
+/// (which the client reports without a type or code, and a stream as
+/// `server_error`) or a rate limit, but not an exhausted quota or a missing
+/// endpoint.
+pub(crate) fn is_transient(err: &OpenAIError) -> bool {
+    match err {
+        OpenAIError::Reqwest(e) => {
+            e.is_timeout() || e.is_connect() || e.is_request() || e.is_body()
+        }
+        OpenAIError::StreamError(e) => matches!(
+            **e,
+            StreamError::ReqwestEventSource(EventSourceError::Transport(_))
+        ),
+        OpenAIError::ApiError(e) => match (e.r#type.as_deref(), e.code.as_deref()) {
+            (None, None) => {
+                let message = e.message.to_lowercase();
+                !message.contains("404") && !message.contains("not found")
+            }
+            (Some("insufficient_quota"), _) | (_, Some("insufficient_quota")) => false,
+            (_, Some("rate_limit_exceeded" | "server_error"))
+            | (Some("requests" | "tokens"), _) => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// The wait a rate limit asks for. The client drops the `Retry-After`
+/// header, but OpenAI repeats it in the message: "Please try again in 1.5s".
+fn retry_after(err: &OpenAIError) -> Option<Duration> {
+    let OpenAIError::ApiError(e) = err else {
+        return None;
+    };
+    let (_, rest) = e.message.split_once("try again in ")?;
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(rest.len());
+    let value: f64 = rest[..end].parse().ok()?;
+    let seconds = if rest[end..].starts_with("ms") {
+        value / 1000.0
+    } else if rest[end..].starts_with('s') {
+        value
+    } else {
+        return None;
+    };
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_DELAY, backoff, is_transient, retry, retry_after};
+    use crate::error::Error;
+    use async_openai::error::{ApiError, OpenAIError};
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    fn api_error(message: &str, r#type: Option<&str>, code: Option<&str>) -> OpenAIError {
+        OpenAIError::ApiError(ApiError {
+            message: message.to_string(),
+            r#type: r#type.map(str::to_string),
+            param: None,
+            code: code.map(str::to_string),
+        })
+    }
+
+    fn server_error() -> OpenAIError {
+        api_error("502 Bad Gateway", None, None)
+    }
+
+    #[test]
+    fn only_transient_errors_are_retried() {
+        assert!(is_transient(&server_error()));
+        assert!(is_transient(&api_error(
+            "The server had an error while processing your request",
+            None,
+            Some("server_error")
+        )));
+        assert!(is_transient(&api_error(
+            "Rate limit reached",
+            Some("tokens"),
+            Some("rate_limit_exceeded")
+        )));
+        assert!(!is_transient(&api_error(
+            "You exceeded your current quota",
+            Some("insufficient_quota"),
+            Some("insufficient_quota")
+        )));
+        assert!(!is_transient(&api_error(
+            "Invalid model",
+            Some("invalid_request_error"),
+            Some("model_not_found")
+        )));
+        assert!(!is_transient(&api_error("404 page not found", None, None)));
+        assert!(!is_transient(&OpenAIError::InvalidArgument(
+            "bad".to_string()
+        )));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let base = Duration::from_secs(2);
+        assert_eq!(backoff(base, 0), base);
+        assert_eq!(backoff(base, 3), Duration::from_secs(16));
+        assert_eq!(backoff(base, 40), MAX_DELAY);
+        assert_eq!(backoff(Duration::MAX, 1), MAX_DELAY);
+    }
+
+    #[test]
+    fn rate_limits_say_how_long_to_wait() {
+        let limited = |message: &str| api_error(message, Some("tokens"), None);
+        assert_eq!(
+            retry_after(&limited(
+                "Limit reached. Please try again in 1.5s. Visit ..."
+            )),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(
+            retry_after(&limited("Please try again in 250ms.")),
+            Some(Duration::from_millis(250))
+        );
+        assert_eq!(retry_after(&limited("Please try again later")), None);
+        assert_eq!(retry_after(&server_error()), None);
+    }
+
+    #[tokio::test]
+    async fn retries_until_success_or_the_limit() {
+        let calls = Cell::new(0);
+        let flaky = || {
+            calls.set(calls.get() + 1);
+            let result = if calls.get() < 3 {
+                Err(server_error())
+            } else {
+                Ok("report")
+            };
+            async move { result }
+        };
+        assert_eq!(retry(3, Duration::ZERO, flaky).await.unwrap(), "report");
+        assert_eq!(calls.get(), 3);
+
+        calls.set(0);
+        let down = || {
+            calls.set(calls.get() + 1);
+            async { Err::<(), _>(server_error()) }
+        };
+        assert!(retry(2, Duration::ZERO, down).await.is_err());
+        assert_eq!(calls.get(), 3);
+
+        calls.set(0);
+        let refused = || {
+            calls.set(calls.get() + 1);
+            async { Err::<(), _>(api_error("No", Some("invalid_request_error"), None)) }
+        };
+        assert!(retry(5, Duration::ZERO, refused).await.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn network_errors_are_retried_and_answers_are_not() {
+        let calls = Cell::new(0);
+        let unreachable = || {
+            calls.set(calls.get() + 1);
+            async { Err::<(), _>(Error::Network("connection refused".to_string())) }
+        };
+        assert!(retry(2, Duration::ZERO, unreachable).await.is_err());
+        assert_eq!(calls.get(), 3);
+
+        calls.set(0);
+        let refused = || {
+            calls.set(calls.get() + 1);
+            async { Err::<(), _>(Error::Api("no such model".to_string())) }
+        };
+        assert!(retry(2, Duration::ZERO, refused).await.is_err());
+        assert_eq!(calls.get(), 1);
+
+        calls.set(0);
+        let overloaded = || {
+            calls.set(calls.get() + 1);
+            async { Err::<(), _>(Error::from(server_error())) }
+        };
+        assert!(retry(1, Duration::ZERO, overloaded).await.is_err());
+        assert_eq!(calls.get(), 2);
+    }
+}
@@ -0,0 +1,504 @@
+// Built-in report templates: what to ask the model for and which table
+// headers to put back when it drops them.
+use super::TableTemplate;
+use crate::error::{Error, Result};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportTemplate {
+    /// Every element of the video, line by line. Saved as `report_<id>.md`.
+    #[default]
+    Detailed,
+    /// A short executive summary.
+    Summary,
+    /// The video as a list of points to skim.
+    Bullets,
+    /// What the video teaches, as questions and answers.
+    Qa,
+    /// Chapter markers ready to paste into a YouTube description.
+    Chapters,
+    /// A prompt file the user wrote; see [`super::PromptSource`].
+    Custom,
+}
+
+impl ReportTemplate {
+    pub const ALL: [ReportTemplate; 6] = [
+        Self::Detailed,
+        Self::Summary,
+        Self::Bullets,
+        Self::Qa,
+        Self::Chapters,
+        Self::Custom,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Detailed => "detailed",
+            Self::Summary => "summary",
+            Self::Bullets => "bullets",
+            Self::Qa => "qa",
+            Self::Chapters => "chapters",
+            Self::Custom => "custom",
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        // What the detailed template is often called
+        if name == "full" {
+            return Ok(Self::Detailed);
+        }
+        Self::ALL
+            .into_iter()
+            .find(|template| template.name() == name)
+            .ok_or_else(|| {
+                Error::custom(format!(
+                    "Unknown template '{name}'; expected detailed, summary, bullets, qa, chapters or custom"
+                ))
+            })
+    }
+
+    /// The next template, wrapping around; used by the TUI selector.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|t| *t == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// What report file names add after the video ID. The detailed template
+    /// keeps the plain `report_<id>.md` it has always used.
+    pub fn file_suffix(self) -> Option<&'static str> {
+        match self {
+            Self::Detailed => None,
+            other => Some(other.name()),
+        }
+    }
+
+    pub fn system_prompt(self) -> &'static str {
+        match self {
+            Self::Detailed => "Eres un ANALISTA DE CONTENIDO ULTRA-DETALLISTA",
+            Self::Summary => "Eres un ANALISTA DE CONTENIDO que resume con precisión y brevedad",
+            Self::Bullets => "Eres un ANALISTA DE CONTENIDO que sintetiza en puntos claros",
+            Self::Qa => "Eres un DOCENTE que convierte contenido en preguntas y respuestas",
+            Self::Chapters => "Eres un EDITOR DE VÍDEO que divide contenido en capítulos",
+            Self::Custom => "Eres un ANALISTA DE CONTENIDO",
+        }
+    }
+
+    /// The user prompt around a transcript, asking for a report in
+    /// `language`. `removed_lines` were cleaned out of the transcript before
+    /// saving. Custom has no instructions of its own; its prompt comes from
+    /// the user's file.
+    pub fn user_prompt(
+        self,
+        transcript_text: &str,
+        language: &str,
+        removed_lines: usize,
+    ) -> String {
+        let instructions = match self {
+            Self::Detailed => DETAILED_PROMPT,
+            Self::Summary => SUMMARY_PROMPT,
+            Self::Bullets => BULLETS_PROMPT,
+            Self::Qa => QA_PROMPT,
+            Self::Chapters => CHAPTERS_PROMPT,
+            Self::Custom => "",
+        };
+        let language_instructions = self.language_instructions(language);
+        let cleaning_note = self.cleaning_note(removed_lines);
+        format!(
+            "{instructions}{language_instructions}{cleaning_note}### Ejecución
+Analiza ahora el contenido entre las etiquetas:
+
+<TRANSCRIPT>
+{transcript_text}
+</TRANSCRIPT>
+"
+        )
+    }
+
+    /// What to add to the prompt for a report that is not in Spanish, the
+    /// language the prompts are written in. Tables get their exact headings
+    /// where they are known, so `ensure_table_headers` can still repair them.
+    fn language_instructions(self, language: &str) -> String {
+        let primary = primary_language(language);
+        if primary == "es" || self == Self::Custom {
+            return String::new();
+        }
+
+        let mut text = format!(
+            "### Idioma del reporte
+Escribe todo el reporte en el idioma con código «{language}», incluidos los títulos de sección y los encabezados de tabla. Las citas literales se mantienen en el idioma del transcript.
+"
+        );
+        if let Some(tables) = self.tables(&primary) {
+            text.push_str("Usa exactamente estos títulos y encabezados para las tablas:\n");
+            for table in tables {
+                text.push_str(&format!(
+                    "{}\n{}\n",
+                    table.section_marker, table.header_lines[0]
+                ));
+            }
+        }
+        text.push('\n');
+        text
+    }
+
+    /// Tell the detailed report, whose Metadata table counts the lines, that
+    /// the transcript it gets is already shorter than YouTube's captions.
+    fn cleaning_note(self, removed_lines: usize) -> String {
+        if self != Self::Detailed || removed_lines == 0 {
+            return String::new();
+        }
+        format!(
+            "### Limpieza previa
+Antes del análisis se quitaron {removed_lines} líneas de artefactos (marcas como [Música], líneas repetidas). En «Número de líneas» cuenta las líneas de la transcripción recibida y añade «({removed_lines} eliminadas en la limpieza)».
+
+"
+        )
+    }
+
+    /// Headers for the tables this template asks for, in a report language;
+    /// `None` when it has no tables or no headers for that language.
+    pub(super) fn tables(self, language: &str) -> Option<&'static [TableTemplate]> {
+        match (self, language) {
+            (Self::Detailed, "es") => Some(DETAILED_TABLES_ES),
+            (Self::Detailed, "en") => Some(DETAILED_TABLES_EN),
+            (Self::Summary, "es") => Some(SUMMARY_TABLES_ES),
+            (Self::Summary, "en") => Some(SUMMARY_TABLES_EN),
+            _ => None,
+        }
+    }
+}
+
+/// The language of a code like `en-GB`, which is what table headers are
+/// keyed by.
+pub(super) fn primary_language(language: &str) -> String {
+    language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+const DETAILED_PROMPT: &str = "### rol
+Tu misión: extraer **cada** elemento significativo del vídeo sin omitir nada, con precisión milimétrica.
+
+### Entrada
+A continuación recibirás la transcripción completa entre las marcas <TRANSCRIPT> … </TRANSCRIPT>.
+No añadas contexto externo: todo debe provenir del texto entregado.
+
+### Reglas de extracción
+1. **Cero resúmenes.** No sintetices. Incluye cada idea tal como aparece.
+2. Mantén el **orden cronológico** original.
+3. Si el transcript incluye marcas de tiempo, consérvalas; si no, indica \"⏱ n/a\".
+4. Preserva las citas literales relevantes (\"texto exacto\").
+5. No añadas opiniones ni interpretación subjetiva.
+6. Si la transcripción empieza con un bloque `---` de datos del vídeo (title, author, duration_secs, upload_date), úsalos en la tabla Metadata en lugar de estimarlos.
+
+### Formato de salida
+Devuelve un reporte en Markdown con los siguientes bloques:
+
+#### 1. Metadata
+| Campo | Valor |
+|-------|-------|
+| Duración aproximada | X min |
+| Número de líneas | N |
+| Idioma predominante | … |
+| Voz principal (si se infiere) | … |
+| Otros participantes | … |
+
+#### 2. Índice cronológico de secciones
+Lista cada cambio de tema o segmento importante tal como se detecte en la transcripción.
+*Ejemplo*:
+- 00:00 - 01:42 Introducción del canal
+- 01:43 - 05:20 Historia personal sobre productividad
+
+#### 3. Desglose línea por línea
+| # | ⏱ | Orador* | Texto literal | Palabras clave | Tonalidad** |
+|---|----|---------|---------------|----------------|-------------|
+| 1 | 00:00 | Host | \"Bienvenidos…\" | bienvenidos, canal | amigable |
+| 2 | 00:08 | Host | … | … | … |
+
+* Si no hay speaker tags, usa \"Unk\".
+** Tonalidad: informativo, persuasivo, anecdótico, humor, etc.
+
+#### 4. Entidades y conceptos mencionados
+| Entidad | Tipo (persona, marca, lugar…) | Nº de menciones | Primera mención ⏱ |
+|---------|------------------------------|-----------------|-------------------|
+
+#### 5. Preguntas planteadas
+Lista literal de todas las preguntas que formula el orador, con su timestamp.
+
+#### 6. Citas \"clave\" (≥ 15 palabras)
+Incluye cada cita textual larga; útil para captions o destacados.
+
+#### 7. Llamados a la acción (CTA)
+Cada vez que se invita al espectador a suscribirse, comentar, comprar, etc., con su timestamp y texto exacto.
+
+#### 8. Recursos externos
+Links, referencias a libros, cursos, herramientas, etc. (solo si aparecen en la transcripción).
+
+#### 9. Estructura retórica
+- **Hook inicial**: ⏱ …
+- **Conflicto / Problema expuesto**: ⏱ …
+- **Solución / Clímax**: ⏱ …
+- **Cierre**: ⏱ …
+
+#### 10. Lista completa de palabras clave (frecuencia ≥ 2)
+Ordenadas por frecuencia descendente.
+
+#### 11. resumen ejecutivo detallado de todo el contenido, sin omitir nada.
+---
+
+";
+
+const SUMMARY_PROMPT: &str = "### rol
+Tu misión: resumir el vídeo para alguien que no tiene tiempo de verlo.
+
+### Entrada
+A continuación recibirás la transcripción completa entre las marcas <TRANSCRIPT> … </TRANSCRIPT>.
+No añadas contexto externo: todo debe provenir del texto entregado.
+
+### Reglas
+1. Sé breve: el reporte completo debe leerse en dos minutos.
+2. Mantén el orden en que aparecen las ideas.
+3. Si el transcript incluye marcas de tiempo, úsalas; si no, indica \"⏱ n/a\".
+4. No añadas opiniones ni interpretación subjetiva.
+
+### Formato de salida
+Devuelve un reporte en Markdown con los siguientes bloques:
+
+#### 1. Resumen ejecutivo
+Entre 5 y 8 viñetas con lo esencial del vídeo.
+
+#### 2. Ideas clave
+| Idea | ⏱ |
+|------|----|
+| … | 00:00 |
+
+#### 3. Conclusión
+Un párrafo con la conclusión o el mensaje final del vídeo.
+---
+
+";
+
+const BULLETS_PROMPT: &str = "### rol
+Tu misión: convertir el vídeo en una lista de puntos que se lea de un vistazo.
+
+### Entrada
+A continuación recibirás la transcripción completa entre las marcas <TRANSCRIPT> … </TRANSCRIPT>.
+No añadas contexto externo: todo debe provenir del texto entregado.
+
+### Reglas
+1. Un punto por idea, de una o dos líneas como máximo.
+2. Agrupa los puntos por tema, en el orden en que aparecen.
+3. Si el transcript incluye marcas de tiempo, empieza cada tema con la suya; si no, indica \"⏱ n/a\".
+4. No añadas opiniones ni interpretación subjetiva.
+
+### Formato de salida
+Devuelve solo este bloque en Markdown, sin texto adicional:
+
+#### Puntos clave
+- **00:00 Tema**
+  - Idea …
+  - Idea …
+---
+
+";
+
+const QA_PROMPT: &str = "### rol
+Tu misión: recoger en preguntas y respuestas lo que el vídeo enseña, para repasarlo o estudiarlo.
+
+### Entrada
+A continuación recibirás la transcripción completa entre las marcas <TRANSCRIPT> … </TRANSCRIPT>.
+No añadas contexto externo: todo debe provenir del texto entregado.
+
+### Reglas
+1. Entre 5 y 15 preguntas, en el orden en que el vídeo trata cada tema.
+2. Cada respuesta sale solo de la transcripción; cita literalmente cuando ayude.
+3. Si el transcript incluye marcas de tiempo, indica dónde se responde cada pregunta; si no, indica \"⏱ n/a\".
+4. No añadas opiniones ni interpretación subjetiva.
+
+### Formato de salida
+Devuelve solo este bloque en Markdown, sin texto adicional:
+
+#### Preguntas y respuestas
+**1. ¿Pregunta? (⏱ 00:00)**
+Respuesta …
+
+**2. ¿Pregunta? (⏱ 01:42)**
+Respuesta …
+---
+
+";
+
+const CHAPTERS_PROMPT: &str = "### rol
+Tu misión: dividir el vídeo en capítulos para la descripción de YouTube.
+
+### Entrada
+A continuación recibirás la transcripción completa entre las marcas <TRANSCRIPT> … </TRANSCRIPT>.
+No añadas contexto externo: todo debe provenir del texto entregado.
+
+### Reglas
+1. El primer capítulo empieza en 00:00.
+2. Al menos tres capítulos, cada uno de 10 segundos o más.
+3. Usa las marcas de tiempo de la transcripción, en orden ascendente.
+4. Títulos cortos (máximo 60 caracteres) en el idioma del vídeo.
+
+### Formato de salida
+Devuelve solo este bloque en Markdown, sin texto adicional:
+
+#### Capítulos
+00:00 Introducción
+01:42 …
+---
+
+";
+
+const DETAILED_TABLES_ES: &[TableTemplate] = &[
+    TableTemplate {
+        section_marker: "#### 1. Metadata",
+        header_lines: &["| Campo | Valor |", "|-------|-------|"],
+        header_keywords: &["campo", "valor"],
+    },
+    TableTemplate {
+        section_marker: "#### 3. Desglose",
+        header_lines: &[
+            "| # | ⏱ | Orador* | Texto literal | Palabras clave | Tonalidad** |",
+            "|---|----|---------|---------------|----------------|-------------|",
+        ],
+        header_keywords: &["#", "⏱", "orador", "texto", "palabras", "tonalidad"],
+    },
+    TableTemplate {
+        section_marker: "#### 4. Entidades",
+        header_lines: &[
+            "| Entidad | Tipo (persona, marca, lugar…) | Nº de menciones | Primera mención ⏱ |",
+            "|---------|------------------------------|-----------------|-------------------|",
+        ],
+        header_keywords: &["entidad", "tipo", "mención"],
+    },
+    TableTemplate {
+        section_marker: "#### 5. Preguntas",
+        header_lines: &["| Pregunta | Timestamp |", "|----------|-----------|"],
+        header_keywords: &["pregunta", "timestamp"],
+    },
+];
+
+const DETAILED_TABLES_EN: &[TableTemplate] = &[
+    TableTemplate {
+        section_marker: "#### 1. Metadata",
+        header_lines: &["| Field | Value |", "|-------|-------|"],
+        header_keywords: &["field", "value"],
+    },
+    TableTemplate {
+        section_marker: "#### 3. Line-by-line",
+        header_lines: &[
+            "| # | ⏱ | Speaker* | Verbatim text | Keywords | Tone** |",
+            "|---|----|----------|---------------|----------|--------|",
+        ],
+        header_keywords: &["#", "⏱", "speaker", "text", "keywords", "tone"],
+    },
+    TableTemplate {
+        section_marker: "#### 4. Entities",
+        header_lines: &[
+            "| Entity | Type (person, brand, place…) | Mentions | First mention ⏱ |",
+            "|--------|------------------------------|----------|-----------------|",
+        ],
+        header_keywords: &["entity", "type", "mention"],
+    },
+    TableTemplate {
+        section_marker: "#### 5. Questions",
+        header_lines: &["| Question | Timestamp |", "|----------|-----------|"],
+        header_keywords: &["question", "timestamp"],
+    },
+];
+
+const SUMMARY_TABLES_ES: &[TableTemplate] = &[TableTemplate {
+    section_marker: "#### 2. Ideas clave",
+    header_lines: &["| Idea | ⏱ |", "|------|----|"],
+    header_keywords: &["idea", "⏱"],
+}];
+
+const SUMMARY_TABLES_EN: &[TableTemplate] = &[TableTemplate {
+    section_marker: "#### 2. Key ideas",
+    header_lines: &["| Idea | ⏱ |", "|------|----|"],
+    header_keywords: &["idea", "⏱"],
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::{DETAILED_PROMPT, ReportTemplate};
+
+    #[test]
+    fn names_round_trip_and_cycle() {
+        for template in ReportTemplate::ALL {
+            assert_eq!(ReportTemplate::parse(template.name()).unwrap(), template);
+        }
+        assert_eq!(
+            ReportTemplate::parse(" Summary ").unwrap(),
+            ReportTemplate::Summary
+        );
+        assert_eq!(
+            ReportTemplate::parse("full").unwrap(),
+            ReportTemplate::Detailed
+        );
+        assert!(ReportTemplate::parse("short").is_err());
+        assert_eq!(ReportTemplate::Chapters.next(), ReportTemplate::Custom);
+        assert_eq!(ReportTemplate::Custom.next(), ReportTemplate::Detailed);
+    }
+
+    #[test]
+    fn only_detailed_keeps_the_plain_file_name() {
+        assert_eq!(ReportTemplate::Detailed.file_suffix(), None);
+        assert_eq!(ReportTemplate::Summary.file_suffix(), Some("summary"));
+        assert_eq!(ReportTemplate::Chapters.file_suffix(), Some("chapters"));
+        assert_eq!(ReportTemplate::Qa.file_suffix(), Some("qa"));
+        assert_eq!(ReportTemplate::Custom.file_suffix(), Some("custom"));
+    }
+
+    #[test]
+    fn prompts_wrap_the_transcript() {
+        for template in ReportTemplate::ALL {
+            let prompt = template.user_prompt("[00:00 - 00:05] hola", "es", 0);
+            assert!(prompt.ends_with("<TRANSCRIPT>\n[00:00 - 00:05] hola\n</TRANSCRIPT>\n"));
+        }
+        assert!(
+            ReportTemplate::Chapters
+                .user_prompt("", "es", 0)
+                .contains("#### Capítulos")
+        );
+    }
+
+    #[test]
+    fn other_languages_get_the_headings_header_repair_expects() {
+        let spanish = ReportTemplate::Detailed.user_prompt("", "es", 0);
+        assert!(!spanish.contains("### Idioma del reporte"));
+
+        let english = ReportTemplate::Detailed.user_prompt("", "en-GB", 0);
+        assert!(english.contains("«en-GB»"));
+        assert!(english.contains("#### 3. Line-by-line\n| # | ⏱ | Speaker*"));
+        assert!(english.ends_with(
+            &ReportTemplate::Detailed.user_prompt("", "es", 0)[DETAILED_PROMPT.len()..]
+        ));
+
+        let german = ReportTemplate::Chapters.user_prompt("", "de", 0);
+        assert!(german.contains("«de»"));
+        assert!(!german.contains("Usa exactamente"));
+    }
+
+    #[test]
+    fn detailed_reports_hear_about_cleaned_lines() {
+        let cleaned = ReportTemplate::Detailed.user_prompt("", "es", 12);
+        assert!(cleaned.contains("se quitaron 12 líneas"));
+        assert!(
+            !ReportTemplate::Detailed
+                .user_prompt("", "es", 0)
+                .contains("Limpieza previa")
+        );
+        assert!(
+            !ReportTemplate::Summary
+                .user_prompt("", "es", 12)
+                .contains("Limpieza previa")
+        );
+    }
+}
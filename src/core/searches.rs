@@ -0,0 +1,370 @@
+use crate::core::storage::{FileEntry, FileType, write_atomic_sync};
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
+
+const SEARCHES_FILE: &str = "saved_searches.json";
+
+/// Which kind of stored file the Browser shows.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum FileFilter {
+    #[default]
+    All,
+    Transcripts,
+    Reports,
+}
+
+impl FileFilter {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FileFilter::All => "all",
+            FileFilter::Transcripts => "transcripts",
+            FileFilter::Reports => "reports",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [
+            FileFilter::All,
+            FileFilter::Transcripts,
+            FileFilter::Reports,
+        ]
+        .into_iter()
+        .find(|filter| filter.name() == name)
+    }
+
+    pub fn matches(&self, file: &FileEntry) -> bool {
+        match self {
+            FileFilter::All => true,
+            FileFilter::Transcripts => file.file_type == FileType::Transcript,
+            FileFilter::Reports => file.file_type == FileType::Report,
+        }
+    }
+}
+
+/// Order of a file listing. Each mode has a natural direction (newest, A to Z,
+/// largest first) that `reverse` flips.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortMode {
+    #[default]
+    Date,
+    Name,
+    Size,
+}
+
+impl SortMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            SortMode::Date => "date",
+            SortMode::Name => "name",
+            SortMode::Size => "size",
+        }
+    }
+
+    /// The mode after this one, cycling back to the first.
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::Date => SortMode::Name,
+            SortMode::Name => SortMode::Size,
+            SortMode::Size => SortMode::Date,
+        }
+    }
+
+    /// Sort stably, so files that tie keep their listing order.
+    pub fn sort(self, files: &mut [FileEntry], reverse: bool) {
+        files.sort_by(|a, b| {
+            let order = match self {
+                SortMode::Date => b.modified.cmp(&a.modified),
+                SortMode::Name => a
+                    .display_name()
+                    .to_lowercase()
+                    .cmp(&b.display_name().to_lowercase()),
+                SortMode::Size => b.size.cmp(&a.size),
+            };
+            if reverse { order.reverse() } else { order }
+        });
+    }
+}
+
+/// A named Browser search and filter, shown as a chip above the file list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    #[serde(default)]
+    pub query: String,
+    /// Stored by name so a filter that no longer exists does not make the
+    /// whole file unreadable; see [`SavedSearch::filter`].
+    #[serde(default)]
+    filter: String,
+}
+
+impl SavedSearch {
+    pub fn new(name: &str, query: &str, filter: &FileFilter) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            query: query.trim().to_string(),
+            filter: filter.name().to_string(),
+        }
+    }
+
+    /// The saved filter, or `All` if it names one that has since been removed.
+    pub fn filter(&self) -> FileFilter {
+        FileFilter::from_name(&self.filter).unwrap_or(FileFilter::All)
+    }
+
+    /// Whether the saved filter still exists.
+    pub fn filter_is_known(&self) -> bool {
+        FileFilter::from_name(&self.filter).is_some()
+    }
+
+    pub fn matches(&self, file: &FileEntry) -> bool {
+        self.filter().matches(file) && matches_query(file, &self.query)
+    }
+}
+
+/// Case- and accent-insensitive search over the file name and video title,
+/// as typed in the Browser.
+pub fn matches_query(file: &FileEntry, query: &str) -> bool {
+    let query = fold_for_search(query.trim());
+    fold_for_search(&file.name).contains(&query)
+        || file
+            .title
+            .as_ref()
+            .is_some_and(|title| fold_for_search(title).contains(&query))
+}
+
+/// `s` lowercased and without accents, so "cancion" finds "Canción": each
+/// character is decomposed and its combining marks dropped.
+pub fn fold_for_search(s: &str) -> String {
+    s.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Saved searches in the order they were created.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearches {
+    pub searches: Vec<SavedSearch>,
+}
+
+impl SavedSearches {
+    /// Load the saved searches; a missing file means there are none. Unlike
+    /// the URL history these are curated by hand, so a corrupt file is an
+    /// error rather than silently emptied.
+    pub fn load() -> Result<Self> {
+        match fs::read_to_string(SEARCHES_FILE) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| Error::custom(format!("Invalid {SEARCHES_FILE}: {e}"))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| Error::custom(format!("Failed to serialize saved searches: {e}")))?;
+        write_atomic_sync(Path::new(SEARCHES_FILE), &content)
+    }
+
+    /// Names are matched case-insensitively, as they are typed by hand.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        let name = name.trim().to_lowercase();
+        self.searches
+            .iter()
+            .position(|search| search.name.to_lowercase() == name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SavedSearch> {
+        self.position(name).map(|index| &self.searches[index])
+    }
+
+    /// Save a search, replacing one with the same name in place. Returns its
+    /// position.
+    pub fn save_search(&mut self, search: SavedSearch) -> Result<usize> {
+        if search.name.is_empty() {
+            return Err(Error::custom("A saved search needs a name"));
+        }
+        match self.position(&search.name) {
+            Some(index) => {
+                self.searches[index] = search;
+                Ok(index)
+            }
+            None => {
+                self.searches.push(search);
+                Ok(self.searches.len() - 1)
+            }
+        }
+    }
+
+    /// Rename the search at `index`, refusing a name another search already has.
+    pub fn rename(&mut self, index: usize, name: &str) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::custom("A saved search needs a name"));
+        }
+        if self.position(name).is_some_and(|other| other != index) {
+            return Err(Error::custom(format!(
+                "A saved search named '{name}' already exists"
+            )));
+        }
+        let search = self
+            .searches
+            .get_mut(index)
+            .ok_or_else(|| Error::custom("No such saved search"))?;
+        search.name = name.to_string();
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<SavedSearch> {
+        (index < self.searches.len()).then(|| self.searches.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FileFilter, SavedSearch, SavedSearches, SortMode, fold_for_search};
+    use crate::core::storage::{FileEntry, FileType};
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    fn entry(name: &str, file_type: FileType) -> FileEntry {
+        FileEntry {
+            path: PathBuf::from(name),
+            name: name.to_string(),
+            file_type,
+            size: 0,
+            modified: SystemTime::UNIX_EPOCH,
+            bookmarks: 0,
+            title: None,
+            auto_generated: false,
+        }
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let mut saved = SavedSearches::default();
+        saved
+            .save_search(SavedSearch::new(" rust ", " Async ", &FileFilter::Reports))
+            .unwrap();
+
+        let json = serde_json::to_string(&saved).unwrap();
+        assert_eq!(
+            json,
+            r#"{"searches":[{"name":"rust","query":"Async","filter":"reports"}]}"#
+        );
+        assert_eq!(serde_json::from_str::<SavedSearches>(&json).unwrap(), saved);
+    }
+
+    #[test]
+    fn same_name_replaces_and_rename_refuses_collisions() {
+        let mut saved = SavedSearches::default();
+        saved
+            .save_search(SavedSearch::new("rust", "rust", &FileFilter::All))
+            .unwrap();
+        saved
+            .save_search(SavedSearch::new("talks", "talk", &FileFilter::All))
+            .unwrap();
+
+        let index = saved
+            .save_search(SavedSearch::new("RUST", "tokio", &FileFilter::Reports))
+            .unwrap();
+        assert_eq!(index, 0);
+        assert_eq!(saved.searches.len(), 2);
+        assert_eq!(saved.get("rust").unwrap().query, "tokio");
+
+        assert!(saved.rename(1, "Rust").is_err());
+        assert!(saved.rename(1, "  ").is_err());
+        saved.rename(0, "Rust").unwrap();
+        assert_eq!(saved.searches[0].name, "Rust");
+        assert!(
+            saved
+                .save_search(SavedSearch::new("", "x", &FileFilter::All))
+                .is_err()
+        );
+
+        assert_eq!(saved.remove(0).unwrap().name, "Rust");
+        assert!(saved.remove(5).is_none());
+        assert_eq!(saved.searches.len(), 1);
+    }
+
+    #[test]
+    fn removed_filters_fall_back_to_all() {
+        let saved: SavedSearches = serde_json::from_str(
+            r#"{"searches":[{"name":"old","query":"rust","filter":"bookmarked"},{"name":"bare"}]}"#,
+        )
+        .unwrap();
+
+        let old = saved.get("old").unwrap();
+        assert!(!old.filter_is_known());
+        assert_eq!(old.filter(), FileFilter::All);
+        assert!(old.matches(&entry("report_rust.md", FileType::Report)));
+        assert!(!old.matches(&entry("report_go.md", FileType::Report)));
+
+        let bare = saved.get("bare").unwrap();
+        assert_eq!(bare.filter(), FileFilter::All);
+        assert!(bare.matches(&entry("transcript_any.txt", FileType::Transcript)));
+    }
+
+    #[test]
+    fn sort_modes_and_reverse() {
+        let mut files: Vec<FileEntry> = [("b.md", 30, 1), ("a.md", 10, 3), ("C.md", 20, 2)]
+            .into_iter()
+            .map(|(name, size, age)| FileEntry {
+                size,
+                modified: SystemTime::UNIX_EPOCH + Duration::from_secs(100 - age),
+                ..entry(name, FileType::Report)
+            })
+            .collect();
+        let names = |files: &[FileEntry]| -> Vec<String> {
+            files.iter().map(|file| file.name.clone()).collect()
+        };
+
+        SortMode::Name.sort(&mut files, false);
+        assert_eq!(names(&files), ["a.md", "b.md", "C.md"]);
+        SortMode::Size.sort(&mut files, false);
+        assert_eq!(names(&files), ["b.md", "C.md", "a.md"]);
+        SortMode::Date.sort(&mut files, false);
+        assert_eq!(names(&files), ["b.md", "C.md", "a.md"]);
+        SortMode::Date.sort(&mut files, true);
+        assert_eq!(names(&files), ["a.md", "C.md", "b.md"]);
+        SortMode::Name.sort(&mut files, true);
+        assert_eq!(names(&files), ["C.md", "b.md", "a.md"]);
+        assert_eq!(SortMode::Size.next(), SortMode::Date);
+    }
+
+    #[test]
+    fn matches_filter_and_query() {
+        let search = SavedSearch::new("talks", "TALK", &FileFilter::Transcripts);
+        assert!(search.matches(&entry("transcript_talk1.txt", FileType::Transcript)));
+        assert!(!search.matches(&entry("report_talk1.md", FileType::Report)));
+        assert!(!search.matches(&entry("transcript_demo.txt", FileType::Transcript)));
+
+        let mut titled = entry("transcript_demo.txt", FileType::Transcript);
+        titled.title = Some("Conference Talk".to_string());
+        assert!(search.matches(&titled));
+    }
+
+    #[test]
+    fn search_ignores_case_and_accents() {
+        assert_eq!(
+            fold_for_search("Canción de AÑO Nuevo, pingüino"),
+            "cancion de ano nuevo, pinguino"
+        );
+
+        let mut titled = entry("transcript_demo.txt", FileType::Transcript);
+        titled.title = Some("La Canción del Año".to_string());
+        for query in ["cancion del ano", "CANCIÓN", "cancíon"] {
+            let search = SavedSearch::new("songs", query, &FileFilter::All);
+            assert!(search.matches(&titled), "{query}");
+        }
+
+        let named = entry("report_Árbol_genealógico.md", FileType::Report);
+        let search = SavedSearch::new("trees", "arbol genealogico", &FileFilter::All);
+        assert!(!search.matches(&named));
+        let search = SavedSearch::new("trees", "arbol_genealogico", &FileFilter::All);
+        assert!(search.matches(&named));
+    }
+}
@@ -0,0 +1,71 @@
+// Preferences edited on the TUI Settings screen. Unlike `vidio.toml`, which
+// is written by hand, this file is rewritten by the app on every save.
+use crate::core::report::ModelPricing;
+use crate::core::storage::write_atomic_sync;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Prompt file used by the custom report template when no
+    /// `--prompt-file` is given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_file: Option<PathBuf>,
+    pub report: ReportConfig,
+    /// Whether the TUI's processing log is also written to `logs/vidio.log`.
+    pub file_log: bool,
+    /// Whether the New Transcript form remembers submitted URLs in
+    /// `history.json`.
+    pub url_history: bool,
+    /// TUI theme chosen on the Settings screen, over `theme` in `vidio.toml`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            prompt_file: None,
+            report: ReportConfig::default(),
+            file_log: true,
+            url_history: true,
+            theme: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportConfig {
+    /// Language code reports are written in. Unset follows the transcript.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Prices for the cost estimate shown before each report, keyed by model
+    /// name or prefix, over the built-in ones. Only these are saved.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub pricing: BTreeMap<String, ModelPricing>,
+}
+
+impl Settings {
+    /// Load the settings; a missing file means the defaults.
+    pub fn load() -> Result<Self> {
+        match fs::read_to_string(SETTINGS_FILE) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| Error::custom(format!("Invalid {SETTINGS_FILE}: {e}"))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| Error::custom(format!("Failed to serialize settings: {e}")))?;
+        write_atomic_sync(Path::new(SETTINGS_FILE), &content)
+    }
+}
@@ -0,0 +1,761 @@
+//! `vidio export-site`: the whole library as a content tree for a static site
+//! generator. Each video's pages are only rewritten when its sources change,
+//! tracked by hashes in a manifest at the root of the destination.
+
+use crate::core::bookmarks::{Bookmark, BookmarkList};
+use crate::core::metadata::TranscriptMetadata;
+use crate::core::report::{ReportTemplate, front_matter};
+use crate::core::storage::{FileType, StorageService, write_atomic_sync};
+use crate::core::transcript::format_timestamp;
+use crate::error::{Error, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILE: &str = ".vidio-site.json";
+const STYLESHEET: &str = include_str!("vidio.css");
+const LIBRARY_TITLE: &str = "Video library";
+const UNKNOWN_CHANNEL: &str = "Unknown channel";
+/// Bump when the generated pages change shape, so the next export rewrites
+/// every page instead of only those whose sources changed.
+const FORMAT_VERSION: u32 = 1;
+
+/// Site generator to write pages for. Each one lays out files, front matter
+/// and links differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Engine {
+    Hugo,
+    Zola,
+    Mdbook,
+}
+
+impl Engine {
+    pub fn name(self) -> &'static str {
+        match self {
+            Engine::Hugo => "hugo",
+            Engine::Zola => "zola",
+            Engine::Mdbook => "mdbook",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Engine::Hugo, Engine::Zola, Engine::Mdbook]
+            .into_iter()
+            .find(|engine| engine.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Where pages go, relative to the destination.
+    fn content_dir(self) -> &'static str {
+        match self {
+            Engine::Hugo | Engine::Zola => "content",
+            Engine::Mdbook => "src",
+        }
+    }
+
+    fn stylesheet_path(self) -> &'static str {
+        match self {
+            Engine::Hugo | Engine::Zola => "static/css/vidio.css",
+            Engine::Mdbook => "css/vidio.css",
+        }
+    }
+
+    /// The library index, relative to the content directory.
+    fn index_page(self) -> &'static str {
+        match self {
+            Engine::Hugo | Engine::Zola => "_index.md",
+            Engine::Mdbook => "README.md",
+        }
+    }
+
+    /// A page's opening lines: front matter where the engine reads it, a
+    /// heading for mdBook, which takes titles from the page itself.
+    fn page_header(self, title: &str, fields: &[(&str, String)]) -> String {
+        match self {
+            Engine::Hugo => {
+                let mut header = format!("---\ntitle: {}\n", quote(title));
+                for (key, value) in fields {
+                    header.push_str(&format!("{key}: {value}\n"));
+                }
+                header.push_str("---\n");
+                header
+            }
+            Engine::Zola => {
+                let (date, extra): (Vec<_>, Vec<_>) =
+                    fields.iter().partition(|(key, _)| *key == "date");
+                let mut header = format!("+++\ntitle = {}\n", quote(title));
+                for (key, value) in date {
+                    header.push_str(&format!("{key} = {value}\n"));
+                }
+                if !extra.is_empty() {
+                    header.push_str("\n[extra]\n");
+                    for (key, value) in extra {
+                        header.push_str(&format!("{key} = {value}\n"));
+                    }
+                }
+                header.push_str("+++\n");
+                header
+            }
+            Engine::Mdbook => format!("# {title}\n"),
+        }
+    }
+
+    /// A link from the page at `from` to the page at `to`, both relative to
+    /// the content directory.
+    fn link(self, from: &str, to: &str) -> String {
+        match self {
+            Engine::Hugo => format!("{{{{< ref \"{to}\" >}}}}"),
+            Engine::Zola => format!("@/{to}"),
+            Engine::Mdbook => {
+                let dir = Path::new(from).parent().unwrap_or(Path::new(""));
+                Path::new(to)
+                    .strip_prefix(dir)
+                    .unwrap_or(Path::new(to))
+                    .to_string_lossy()
+                    .into_owned()
+            }
+        }
+    }
+}
+
+/// Brackets in a title would end the link text early.
+fn link_text(title: &str) -> String {
+    title.replace('[', "\\[").replace(']', "\\]")
+}
+
+/// A double-quoted string, valid in both YAML and TOML front matter.
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// One video of the library with everything its pages are made from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SiteVideo {
+    pub video_id: String,
+    pub title: Option<String>,
+    pub channel: Option<String>,
+    /// When the transcript was fetched, or the newest file's time without a
+    /// metadata sidecar.
+    pub date: DateTime<Utc>,
+    pub report: Option<String>,
+    pub transcript: Option<String>,
+    /// Listed on the transcript page, so only kept with a transcript.
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl SiteVideo {
+    pub fn new(
+        video_id: &str,
+        metadata: Option<TranscriptMetadata>,
+        fallback_date: DateTime<Utc>,
+        report: Option<String>,
+        transcript: Option<String>,
+        bookmarks: Vec<Bookmark>,
+    ) -> Self {
+        let non_empty = |content: Option<String>| content.filter(|c| !c.trim().is_empty());
+        let transcript = non_empty(transcript);
+        Self {
+            video_id: video_id.to_string(),
+            title: metadata.as_ref().and_then(|m| m.title.clone()),
+            channel: metadata.as_ref().and_then(|m| m.channel.clone()),
+            date: metadata.map_or(fallback_date, |m| m.fetched_at),
+            report: non_empty(report),
+            bookmarks: if transcript.is_some() {
+                bookmarks
+            } else {
+                Vec::new()
+            },
+            transcript,
+        }
+    }
+
+    fn title(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.video_id)
+    }
+
+    fn page(&self) -> String {
+        format!("videos/{}.md", self.video_id)
+    }
+
+    fn transcript_page(&self) -> String {
+        format!("videos/{}-transcript.md", self.video_id)
+    }
+
+    /// Changes whenever any page of this video would render differently.
+    fn source_hash(&self, engine: Engine) -> String {
+        let mut hasher = Sha256::new();
+        for part in [
+            &FORMAT_VERSION.to_string(),
+            engine.name(),
+            &self.video_id,
+            self.title(),
+            self.channel.as_deref().unwrap_or_default(),
+            &self.date.to_rfc3339(),
+            self.report.as_deref().unwrap_or_default(),
+            self.transcript.as_deref().unwrap_or_default(),
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        for bookmark in &self.bookmarks {
+            hasher.update(bookmark.timestamp.to_bits().to_le_bytes());
+            hasher.update(bookmark.note.as_deref().unwrap_or_default().as_bytes());
+            hasher.update([0]);
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+}
+
+/// Every stored video, newest first.
+pub fn load_library() -> Result<Vec<SiteVideo>> {
+    let mut files: BTreeMap<String, Vec<_>> = BTreeMap::new();
+    // Pages show the original transcript and the detailed report
+    for file in StorageService::list_files()? {
+        let exported = match file.file_type {
+            FileType::Transcript => file.translation().is_none(),
+            FileType::Report => file.template() == Some(ReportTemplate::Detailed),
+        };
+        if exported && let Some(video_id) = file.video_id() {
+            files.entry(video_id).or_default().push(file);
+        }
+    }
+
+    let mut videos = Vec::new();
+    for (video_id, files) in files {
+        let mut report = None;
+        let mut transcript = None;
+        for file in &files {
+            let content = Some(fs::read_to_string(&file.path)?);
+            match file.file_type {
+                // The generation details are for `vidio info`, not readers,
+                // and the video's are on its page already
+                FileType::Report => {
+                    report = content.map(|report| front_matter::split(&report).1.to_string())
+                }
+                FileType::Transcript => {
+                    transcript =
+                        content.map(|transcript| front_matter::split(&transcript).1.to_string())
+                }
+            }
+        }
+        let newest = files
+            .iter()
+            .map(|file| file.modified)
+            .max()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_default();
+        videos.push(SiteVideo::new(
+            &video_id,
+            TranscriptMetadata::load(&video_id)?,
+            newest,
+            report,
+            transcript,
+            BookmarkList::load(&video_id)?.entries,
+        ));
+    }
+    sort_newest_first(&mut videos);
+    Ok(videos)
+}
+
+fn sort_newest_first(videos: &mut [SiteVideo]) {
+    videos.sort_by(newest_first);
+}
+
+fn newest_first(a: &SiteVideo, b: &SiteVideo) -> Ordering {
+    b.date
+        .cmp(&a.date)
+        .then_with(|| a.video_id.cmp(&b.video_id))
+}
+
+/// The pages of one video, keyed by path relative to the destination.
+pub fn render_video(engine: Engine, video: &SiteVideo) -> BTreeMap<PathBuf, String> {
+    let content = Path::new(engine.content_dir());
+    let page = video.page();
+    let mut pages = BTreeMap::new();
+
+    let mut fields = vec![
+        (
+            "date",
+            video.date.to_rfc3339_opts(SecondsFormat::Secs, true),
+        ),
+        ("video_id", quote(&video.video_id)),
+    ];
+    if let Some(channel) = &video.channel {
+        fields.push(("channel", quote(channel)));
+    }
+
+    let mut body = engine.page_header(video.title(), &fields);
+    body.push('\n');
+    let mut details = Vec::new();
+    if engine == Engine::Mdbook {
+        details.push(
+            video
+                .channel
+                .as_deref()
+                .unwrap_or(UNKNOWN_CHANNEL)
+                .to_string(),
+        );
+        details.push(video.date.format("%Y-%m-%d").to_string());
+    }
+    details.push(format!(
+        "[Watch on YouTube](https://www.youtube.com/watch?v={})",
+        video.video_id
+    ));
+    if video.transcript.is_some() {
+        details.push(format!(
+            "[Transcript]({})",
+            engine.link(&page, &video.transcript_page())
+        ));
+    }
+    body.push_str(&details.join(" · "));
+    body.push_str("\n\n");
+    match &video.report {
+        Some(report) => body.push_str(report.trim()),
+        None => body.push_str("*No report yet.*"),
+    }
+    body.push('\n');
+    pages.insert(content.join(&page), body);
+
+    if let Some(transcript) = &video.transcript {
+        let transcript_page = video.transcript_page();
+        // Date and video id only; the channel is on the video's page
+        let mut body = engine.page_header(&format!("Transcript: {}", video.title()), &fields[..2]);
+        body.push('\n');
+        body.push_str(&format!(
+            "Back to [{}]({})\n\n",
+            link_text(video.title()),
+            engine.link(&transcript_page, &page)
+        ));
+        if !video.bookmarks.is_empty() {
+            body.push_str("## Bookmarks\n\n");
+            for bookmark in &video.bookmarks {
+                body.push_str(&bookmark_item(&video.video_id, bookmark));
+                body.push('\n');
+            }
+            body.push_str("\n## Transcript\n\n");
+        }
+        for line in transcript.lines().filter(|line| !line.trim().is_empty()) {
+            body.push_str(&transcript_item(line));
+            body.push('\n');
+        }
+        pages.insert(content.join(transcript_page), body);
+    }
+    pages
+}
+
+/// A bookmark as a list item whose time opens the video there.
+fn bookmark_item(video_id: &str, bookmark: &Bookmark) -> String {
+    let mut item = format!(
+        "- [`{}`](https://www.youtube.com/watch?v={video_id}&t={}s)",
+        format_timestamp(bookmark.timestamp),
+        bookmark.timestamp.max(0.0) as u64
+    );
+    if let Some(note) = &bookmark.note {
+        item.push(' ');
+        item.push_str(note);
+    }
+    item
+}
+
+/// A `[start - end] text` transcript line as a list item with the time range
+/// set apart; lines without one are kept as they are.
+fn transcript_item(line: &str) -> String {
+    let line = line.trim();
+    match line
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once("] "))
+    {
+        Some((range, text)) => format!("- `{range}` {text}"),
+        None => format!("- {line}"),
+    }
+}
+
+/// Pages shared by the whole library (index, table of contents, stylesheet),
+/// keyed by path relative to the destination.
+pub fn render_shared(engine: Engine, videos: &[SiteVideo]) -> BTreeMap<PathBuf, String> {
+    let content = Path::new(engine.content_dir());
+    let index = engine.index_page();
+    let mut pages = BTreeMap::new();
+
+    let mut body = engine.page_header(LIBRARY_TITLE, &[]);
+    for (month, channels) in group_by_month_and_channel(videos) {
+        body.push_str(&format!("\n## {month}\n"));
+        for (channel, videos) in channels {
+            body.push_str(&format!("\n### {channel}\n\n"));
+            for video in videos {
+                body.push_str(&format!(
+                    "- [{}]({})",
+                    link_text(video.title()),
+                    engine.link(index, &video.page())
+                ));
+                if video.transcript.is_some() {
+                    body.push_str(&format!(
+                        " · [transcript]({})",
+                        engine.link(index, &video.transcript_page())
+                    ));
+                }
+                body.push('\n');
+            }
+        }
+    }
+    pages.insert(content.join(index), body);
+
+    match engine {
+        Engine::Hugo => {}
+        Engine::Zola => {
+            // Zola needs a section for the pages under videos/; transparent
+            // so they are listed with the index
+            pages.insert(
+                content.join("videos/_index.md"),
+                "+++\ntitle = \"Videos\"\ntransparent = true\n+++\n".to_string(),
+            );
+        }
+        Engine::Mdbook => {
+            let mut summary = format!("# Summary\n\n[{LIBRARY_TITLE}]({index})\n");
+            for (month, channels) in group_by_month_and_channel(videos) {
+                summary.push_str(&format!("\n# {month}\n\n"));
+                for video in channels.into_iter().flat_map(|(_, videos)| videos) {
+                    summary.push_str(&format!(
+                        "- [{}]({})\n",
+                        link_text(video.title()),
+                        video.page()
+                    ));
+                    if video.transcript.is_some() {
+                        summary.push_str(&format!(
+                            "    - [Transcript]({})\n",
+                            video.transcript_page()
+                        ));
+                    }
+                }
+            }
+            pages.insert(content.join("SUMMARY.md"), summary);
+        }
+    }
+
+    pages.insert(
+        PathBuf::from(engine.stylesheet_path()),
+        STYLESHEET.to_string(),
+    );
+    pages
+}
+
+type ChannelGroup<'a> = (String, Vec<&'a SiteVideo>);
+/// Keyed so that videos without a channel sort after every named one.
+type ByChannel<'a> = BTreeMap<(bool, String), Vec<&'a SiteVideo>>;
+
+/// Months newest first; within a month, channels by name with unknown ones
+/// last, each keeping the videos' order.
+fn group_by_month_and_channel(videos: &[SiteVideo]) -> Vec<(String, Vec<ChannelGroup<'_>>)> {
+    let mut sorted: Vec<&SiteVideo> = videos.iter().collect();
+    sorted.sort_by(|a, b| newest_first(a, b));
+    let mut months: Vec<(String, ByChannel)> = Vec::new();
+    for video in sorted {
+        let month = video.date.format("%B %Y").to_string();
+        if months.last().is_none_or(|(last, _)| *last != month) {
+            months.push((month, BTreeMap::new()));
+        }
+        let channel = (
+            video.channel.is_none(),
+            video.channel.clone().unwrap_or_default(),
+        );
+        if let Some((_, channels)) = months.last_mut() {
+            channels.entry(channel).or_default().push(video);
+        }
+    }
+
+    months
+        .into_iter()
+        .map(|(month, channels)| {
+            let channels = channels
+                .into_iter()
+                .map(|((unknown, name), videos)| {
+                    let name = if unknown {
+                        UNKNOWN_CHANNEL.to_string()
+                    } else {
+                        name
+                    };
+                    (name, videos)
+                })
+                .collect();
+            (month, channels)
+        })
+        .collect()
+}
+
+/// Every file of an export, keyed by path relative to the destination.
+#[cfg(test)]
+fn render_site(engine: Engine, videos: &[SiteVideo]) -> BTreeMap<PathBuf, String> {
+    let mut pages = render_shared(engine, videos);
+    for video in videos {
+        pages.extend(render_video(engine, video));
+    }
+    pages
+}
+
+/// What was exported before, so the next run can skip unchanged videos and
+/// `--prune` knows which files it may remove.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    engine: Option<Engine>,
+    #[serde(default)]
+    videos: BTreeMap<String, ExportedVideo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExportedVideo {
+    hash: String,
+    files: Vec<PathBuf>,
+}
+
+impl Manifest {
+    fn load(dest: &Path) -> Result<Self> {
+        let path = dest.join(MANIFEST_FILE);
+        match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| Error::custom(format!("Invalid {}: {e}", path.display()))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, dest: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| Error::custom(format!("Failed to serialize site manifest: {e}")))?;
+        write_atomic_sync(&dest.join(MANIFEST_FILE), &content)
+    }
+}
+
+/// What an export did, for the summary line.
+#[derive(Debug, Default, PartialEq)]
+pub struct ExportSummary {
+    pub updated: usize,
+    pub unchanged: usize,
+    pub pruned: usize,
+    /// Videos gone from the library whose pages were kept (no `--prune`).
+    pub stale: usize,
+}
+
+/// Write the library's pages under `dest`, skipping videos whose sources have
+/// not changed since the last export there.
+pub fn export(
+    dest: &Path,
+    engine: Engine,
+    videos: &[SiteVideo],
+    prune: bool,
+) -> Result<ExportSummary> {
+    fs::create_dir_all(dest)?;
+    let mut manifest = Manifest::load(dest)?;
+    if let Some(previous) = manifest.engine
+        && previous != engine
+    {
+        return Err(Error::custom(format!(
+            "{} was exported for {}; export {} to another directory",
+            dest.display(),
+            previous.name(),
+            engine.name()
+        )));
+    }
+    manifest.engine = Some(engine);
+
+    let mut summary = ExportSummary::default();
+    for video in videos {
+        let hash = video.source_hash(engine);
+        let previous = manifest.videos.get(&video.video_id);
+        let up_to_date = previous.is_some_and(|previous| {
+            previous.hash == hash && previous.files.iter().all(|file| dest.join(file).is_file())
+        });
+        if up_to_date {
+            summary.unchanged += 1;
+            continue;
+        }
+
+        let pages = render_video(engine, video);
+        // e.g. the transcript page of a video whose transcript was deleted
+        if let Some(previous) = previous {
+            for file in previous.files.iter().filter(|f| !pages.contains_key(*f)) {
+                remove_file(&dest.join(file))?;
+            }
+        }
+        for (path, content) in &pages {
+            write_if_changed(&dest.join(path), content)?;
+        }
+        manifest.videos.insert(
+            video.video_id.clone(),
+            ExportedVideo {
+                hash,
+                files: pages.into_keys().collect(),
+            },
+        );
+        summary.updated += 1;
+    }
+
+    let gone: Vec<String> = manifest
+        .videos
+        .keys()
+        .filter(|id| !videos.iter().any(|video| &video.video_id == *id))
+        .cloned()
+        .collect();
+    for video_id in gone {
+        if !prune {
+            summary.stale += 1;
+            continue;
+        }
+        if let Some(exported) = manifest.videos.remove(&video_id) {
+            for file in &exported.files {
+                remove_file(&dest.join(file))?;
+            }
+            summary.pruned += 1;
+        }
+    }
+
+    for (path, content) in render_shared(engine, videos) {
+        write_if_changed(&dest.join(path), &content)?;
+    }
+    manifest.save(dest)?;
+    Ok(summary)
+}
+
+fn write_if_changed(path: &Path, content: &str) -> Result<()> {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+fn remove_file(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Engine, SiteVideo, export, render_site};
+    use crate::core::bookmarks::Bookmark;
+    use crate::core::metadata::TranscriptMetadata;
+    use chrono::{DateTime, Utc};
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    const LIBRARY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/site/library");
+    const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/site");
+
+    fn read(path: PathBuf) -> Option<String> {
+        fs::read_to_string(path).ok()
+    }
+
+    /// The two fixture videos: one with a report, metadata, transcript and
+    /// bookmarks, one imported transcript with nothing else.
+    fn library() -> Vec<SiteVideo> {
+        let root = Path::new(LIBRARY);
+        ["5_EJwYeQusM", "imported001"]
+            .into_iter()
+            .map(|id| {
+                let metadata = read(root.join(format!("transcripts/meta_{id}.json")))
+                    .map(|json| serde_json::from_str::<TranscriptMetadata>(&json).unwrap());
+                SiteVideo::new(
+                    id,
+                    metadata,
+                    "2026-08-30T09:00:00Z".parse::<DateTime<Utc>>().unwrap(),
+                    read(root.join(format!("reports/report_{id}.md"))),
+                    read(root.join(format!("transcripts/transcript_{id}.txt"))),
+                    read(root.join(format!("transcripts/bookmarks_{id}.json")))
+                        .map(|json| serde_json::from_str::<Vec<Bookmark>>(&json).unwrap())
+                        .unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    fn read_tree(root: &Path, dir: &Path, files: &mut BTreeMap<PathBuf, String>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                read_tree(root, &path, files);
+            } else {
+                let relative = path.strip_prefix(root).unwrap().to_path_buf();
+                files.insert(relative, fs::read_to_string(&path).unwrap());
+            }
+        }
+    }
+
+    fn assert_matches_golden(engine: Engine) {
+        let rendered = render_site(engine, &library());
+        let root = Path::new(GOLDEN).join(engine.name());
+        let mut golden = BTreeMap::new();
+        read_tree(&root, &root, &mut golden);
+
+        assert_eq!(
+            rendered.keys().collect::<Vec<_>>(),
+            golden.keys().collect::<Vec<_>>()
+        );
+        for (path, content) in &rendered {
+            assert_eq!(content, &golden[path], "{} differs", path.display());
+        }
+    }
+
+    #[test]
+    fn hugo_matches_golden_files() {
+        assert_matches_golden(Engine::Hugo);
+    }
+
+    #[test]
+    fn zola_matches_golden_files() {
+        assert_matches_golden(Engine::Zola);
+    }
+
+    #[test]
+    fn mdbook_matches_golden_files() {
+        assert_matches_golden(Engine::Mdbook);
+    }
+
+    #[test]
+    fn reexport_only_rewrites_changed_videos_and_prunes_on_request() {
+        let dest = tempfile::tempdir().unwrap();
+        let mut videos = library();
+
+        let first = export(dest.path(), Engine::Hugo, &videos, false).unwrap();
+        assert_eq!((first.updated, first.unchanged), (2, 0));
+
+        videos[0].report = Some("Updated report".to_string());
+        let second = export(dest.path(), Engine::Hugo, &videos, false).unwrap();
+        assert_eq!((second.updated, second.unchanged), (1, 1));
+        let page = dest.path().join("content/videos/5_EJwYeQusM.md");
+        assert!(
+            fs::read_to_string(&page)
+                .unwrap()
+                .contains("Updated report")
+        );
+
+        videos[0].bookmarks.clear();
+        let bookmarks_gone = export(dest.path(), Engine::Hugo, &videos, false).unwrap();
+        assert_eq!((bookmarks_gone.updated, bookmarks_gone.unchanged), (1, 1));
+
+        let removed = videos.remove(0);
+        let third = export(dest.path(), Engine::Hugo, &videos, false).unwrap();
+        assert_eq!((third.stale, third.pruned), (1, 0));
+        assert!(page.exists());
+
+        let fourth = export(dest.path(), Engine::Hugo, &videos, true).unwrap();
+        assert_eq!((fourth.unchanged, fourth.pruned), (1, 1));
+        assert!(!page.exists());
+        let transcript = format!("content/videos/{}-transcript.md", removed.video_id);
+        assert!(!dest.path().join(transcript).exists());
+
+        assert!(export(dest.path(), Engine::Zola, &videos, false).is_err());
+    }
+}
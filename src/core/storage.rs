@@ -1,18 +1,34 @@
-use crate::core::transcript;
+use crate::core::batch::BatchSummary;
+use crate::core::bookmarks::BookmarkList;
+use crate::core::metadata::TranscriptMetadata;
+use crate::core::report::{GeneratedReport, ReportInputs, ReportMetadata, ReportTemplate};
+use crate::core::transcript::{self, FetchedVideo, TranscriptFormat};
 use crate::error::{Error, Result};
+use chrono::{DateTime, Local, NaiveDate};
+use log::info;
 use serde::{Deserialize, Serialize};
 use std::fs as std_fs;
 use std::path::{Path, PathBuf};
-use yt_transcript_rs::FetchedTranscript;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use tokio::fs;
 
-const TRANSCRIPTS_DIR: &str = "transcripts";
+pub(crate) const TRANSCRIPTS_DIR: &str = "transcripts";
 const REPORTS_DIR: &str = "reports";
+const REPORT_HISTORY_DIR: &str = "history";
+/// Deleted files wait here until the trash is emptied.
+const TRASH_DIR: &str = ".trash";
+const TRASH_STAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+const TRASH_STAMP_LEN: usize = "20250101-120000".len();
 const TRANSCRIPT_PREFIX: &str = "transcript_";
 const TRANSCRIPT_SUFFIX: &str = ".txt";
+/// Copies of a transcript in other formats; companions, not translations.
+const PLAIN_SUFFIX: &str = ".plain.txt";
+const MARKDOWN_SUFFIX: &str = ".md";
 const REPORT_PREFIX: &str = "report_";
 const REPORT_SUFFIX: &str = ".md";
+/// Longest file name stem made from a video title.
+const MAX_SLUG_LEN: usize = 80;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileEntry {
@@ -20,7 +36,62 @@ pub struct FileEntry {
     pub name: String,
     pub file_type: FileType,
     pub size: u64,
+    #[serde(with = "rfc3339")]
     pub modified: std::time::SystemTime,
+    #[serde(default)]
+    pub bookmarks: usize,
+    /// Video title from the transcript's metadata sidecar, if it has one.
+    #[serde(default)]
+    pub title: Option<String>,
+    /// A transcript from YouTube's auto-generated captions.
+    #[serde(default)]
+    pub auto_generated: bool,
+}
+
+/// A stored file that may or may not exist yet.
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredFile {
+    pub path: PathBuf,
+    pub exists: bool,
+    pub size: Option<u64>,
+    pub modified: Option<DateTime<Local>>,
+}
+
+impl StoredFile {
+    fn at(path: PathBuf) -> Self {
+        let metadata = std_fs::metadata(&path).ok().filter(|m| m.is_file());
+        Self {
+            exists: has_content(&path),
+            size: metadata.as_ref().map(|m| m.len()),
+            modified: metadata
+                .and_then(|m| m.modified().ok())
+                .map(DateTime::<Local>::from),
+            path,
+        }
+    }
+}
+
+/// Everything stored for one video, as shown by `vidio info`.
+#[derive(Debug, Clone, Serialize)]
+pub struct VideoInfo {
+    pub video_id: String,
+    pub transcript: StoredFile,
+    pub report: StoredFile,
+    pub metadata: Option<TranscriptMetadata>,
+    /// How the report was generated, if it records it.
+    pub report_metadata: Option<ReportMetadata>,
+    pub bookmarks: usize,
+}
+
+/// A deleted file in the trash.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashEntry {
+    /// The name inside the trash, as given to `restore_from_trash`.
+    pub name: String,
+    /// Where the file is restored to.
+    pub original: PathBuf,
+    pub size: u64,
+    pub deleted: Option<DateTime<Local>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -29,140 +100,562 @@ pub enum FileType {
     Report,
 }
 
+/// Which files `list_files_filtered` keeps. Dates are local calendar days and
+/// both ends are inclusive.
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+    pub file_type: Option<FileType>,
+}
+
+impl ListOptions {
+    pub fn matches(&self, file: &FileEntry) -> bool {
+        let day = DateTime::<Local>::from(file.modified).date_naive();
+        self.since.is_none_or(|since| day >= since)
+            && self.until.is_none_or(|until| day <= until)
+            && self
+                .file_type
+                .as_ref()
+                .is_none_or(|file_type| file.file_type == *file_type)
+    }
+}
+
 pub struct StorageService;
 
 impl StorageService {
+    /// The directory holding `transcripts/` and `reports/`: the working
+    /// directory, since every data path is relative to it.
+    pub fn data_dir() -> Result<PathBuf> {
+        Ok(std::env::current_dir()?)
+    }
+
     fn ensure_directories() -> Result<()> {
         ensure_directory(Path::new(TRANSCRIPTS_DIR))?;
         ensure_directory(Path::new(REPORTS_DIR))?;
         Ok(())
     }
 
-    fn transcript_path(video_id: &str) -> Result<PathBuf> {
+    /// `transcript_<id>.txt`, or `transcript_<id>.<lang>.txt` for a
+    /// translation.
+    pub(crate) fn transcript_path(video_id: &str, translation: Option<&str>) -> Result<PathBuf> {
         let sanitized = transcript::sanitize_video_id(video_id)?;
-        Ok(Path::new(TRANSCRIPTS_DIR)
-            .join(format!("{TRANSCRIPT_PREFIX}{sanitized}{TRANSCRIPT_SUFFIX}")))
+        let language = match translation {
+            Some(language) => format!(".{}", transcript::sanitize_language(language)?),
+            None => String::new(),
+        };
+        Ok(Path::new(TRANSCRIPTS_DIR).join(format!(
+            "{TRANSCRIPT_PREFIX}{sanitized}{language}{TRANSCRIPT_SUFFIX}"
+        )))
     }
 
-    fn report_path(video_id: &str) -> Result<PathBuf> {
+    /// `transcript_<id>.plain.txt` or `transcript_<id>.md`, the copy of the
+    /// original transcript in `format`. The timestamped format has none.
+    pub(crate) fn transcript_copy_path(
+        video_id: &str,
+        format: TranscriptFormat,
+    ) -> Result<Option<PathBuf>> {
+        let suffix = match format {
+            TranscriptFormat::Timestamped => return Ok(None),
+            TranscriptFormat::Plain => PLAIN_SUFFIX,
+            TranscriptFormat::Markdown => MARKDOWN_SUFFIX,
+        };
         let sanitized = transcript::sanitize_video_id(video_id)?;
-        Ok(Path::new(REPORTS_DIR).join(format!("{REPORT_PREFIX}{sanitized}{REPORT_SUFFIX}")))
+        Ok(Some(
+            Path::new(TRANSCRIPTS_DIR).join(format!("{TRANSCRIPT_PREFIX}{sanitized}{suffix}")),
+        ))
+    }
+
+    /// Every copy `save_transcript` may write for `video_id`.
+    pub(crate) fn transcript_copy_paths(video_id: &str) -> Result<Vec<PathBuf>> {
+        TranscriptFormat::ALL
+            .into_iter()
+            .filter_map(|format| Self::transcript_copy_path(video_id, format).transpose())
+            .collect()
     }
 
-    pub fn transcript_exists(video_id: &str) -> bool {
+    /// `report_<id>.md` for the detailed template, `report_<id>.<template>.md`
+    /// for the others.
+    pub(crate) fn report_path(video_id: &str, template: ReportTemplate) -> Result<PathBuf> {
+        Ok(Path::new(REPORTS_DIR).join(format!(
+            "{}{REPORT_SUFFIX}",
+            report_stem(video_id, template)?
+        )))
+    }
+
+    pub fn transcript_exists(video_id: &str, translation: Option<&str>) -> bool {
         if Self::ensure_directories().is_err() {
             return false;
         }
-        Self::transcript_path(video_id)
-            .map(|path| path.exists())
+        Self::transcript_path(video_id, translation)
+            .map(|path| has_content(&path))
             .unwrap_or(false)
     }
 
-    pub fn report_exists(video_id: &str) -> bool {
+    pub fn report_exists(video_id: &str, template: ReportTemplate) -> bool {
         if Self::ensure_directories().is_err() {
             return false;
         }
-        Self::report_path(video_id)
-            .map(|path| path.exists())
+        Self::report_path(video_id, template)
+            .map(|path| has_content(&path))
             .unwrap_or(false)
     }
 
-    pub async fn save_transcript(transcript: &FetchedTranscript) -> Result<PathBuf> {
+    /// Whether the report was written from another transcript than the one
+    /// saved now, e.g. captions fetched again after YouTube fixed them, or
+    /// with another model, prompt or language than `inputs`. Reports without
+    /// front matter predate this record and count as current.
+    pub fn report_is_stale(
+        video_id: &str,
+        template: ReportTemplate,
+        translation: Option<&str>,
+        inputs: &ReportInputs,
+    ) -> Result<bool> {
+        let report = std_fs::read_to_string(Self::report_path(video_id, template)?)?;
+        let Some(metadata) = ReportMetadata::parse(&report) else {
+            return Ok(false);
+        };
+        let transcript = std_fs::read_to_string(Self::transcript_path(video_id, translation)?)?;
+        Ok(metadata.is_stale(&transcript, inputs))
+    }
+
+    /// Save a fetched transcript with its metadata sidecar, and a copy in
+    /// another format if asked for. A translation goes next to the original and leaves
+    /// the sidecar and the copy, which describe the original, alone.
+    pub async fn save_transcript(video: &FetchedVideo) -> Result<PathBuf> {
+        let transcript = &video.transcript;
         Self::ensure_directories()?;
-        let sanitized_id = transcript::sanitize_video_id(&transcript.video_id)?;
-        let path = Self::transcript_path(&sanitized_id)?;
+        let path = Self::transcript_path(&transcript.video_id, video.translation.as_deref())?;
+        write_atomic(&path, &video.saved_text()).await?;
+        info!("Transcript saved to: {}", path.display());
+        if video.translation.is_none() {
+            if let Some(copy_path) = Self::transcript_copy_path(&transcript.video_id, video.format)?
+            {
+                let copy = if video.format == TranscriptFormat::Markdown {
+                    transcript::TranscriptService::format_transcript_markdown(
+                        transcript,
+                        video.metadata.title.as_deref(),
+                        &video.chapters,
+                        video.paragraph_gap,
+                    )
+                } else {
+                    transcript::TranscriptService::format_transcript_plain(
+                        transcript,
+                        video.paragraph_gap,
+                    )
+                };
+                write_atomic(&copy_path, &copy).await?;
+                info!("Transcript copy saved to: {}", copy_path.display());
+            }
+            TranscriptMetadata::from_video(video).save(&transcript.video_id)?;
+        }
+        Ok(path)
+    }
 
-        let formatted_transcript = transcript::TranscriptService::format_transcript(transcript);
-        let content = formatted_transcript.join("\n");
-        fs::write(&path, &content).await?;
-        println!("Transcript saved to: {}", path.display());
+    pub fn video_info(video_id: &str) -> Result<VideoInfo> {
+        let report_path = Self::report_path(video_id, ReportTemplate::Detailed)?;
+        let report_metadata = std_fs::read_to_string(&report_path)
+            .ok()
+            .and_then(|report| ReportMetadata::parse(&report));
+        Ok(VideoInfo {
+            video_id: video_id.to_string(),
+            transcript: StoredFile::at(Self::transcript_path(video_id, None)?),
+            report: StoredFile::at(report_path),
+            metadata: TranscriptMetadata::load(video_id)?,
+            report_metadata,
+            bookmarks: BookmarkList::count(video_id),
+        })
+    }
 
+    /// Save already formatted `[start - end] text` lines, e.g. from an import.
+    pub async fn save_transcript_lines(video_id: &str, lines: &[String]) -> Result<PathBuf> {
+        Self::ensure_directories()?;
+        let path = Self::transcript_path(video_id, None)?;
+        write_transcript(&path, lines).await?;
         Ok(path)
     }
 
-    pub async fn save_report(video_id: &str, content: &str) -> Result<PathBuf> {
+    /// Save a generated report, with how it was generated as front matter.
+    pub async fn save_report(
+        video_id: &str,
+        template: ReportTemplate,
+        report: &GeneratedReport,
+    ) -> Result<PathBuf> {
+        let content = format!("{}{}", report.metadata.to_front_matter(), report.content);
+        Self::save_report_text(video_id, template, &content).await
+    }
+
+    /// Save report text as is, e.g. a repaired report that keeps its own front matter.
+    pub async fn save_report_text(
+        video_id: &str,
+        template: ReportTemplate,
+        content: &str,
+    ) -> Result<PathBuf> {
         Self::ensure_directories()?;
 
-        let path = Self::report_path(video_id)?;
+        let path = Self::report_path(video_id, template)?;
 
-        fs::write(&path, content).await?;
-        println!("Report saved to: {}", path.display());
+        write_atomic(&path, content).await?;
+        info!("Report saved to: {}", path.display());
 
         Ok(path)
     }
 
-    pub async fn load_transcript(video_id: &str) -> Result<String> {
-        let path = Self::transcript_path(video_id)?;
+    pub async fn load_transcript(video_id: &str, translation: Option<&str>) -> Result<String> {
+        let path = Self::transcript_path(video_id, translation)?;
         let content = fs::read_to_string(path).await?;
         Ok(content)
     }
 
-    #[allow(dead_code)]
-    pub async fn load_report(video_id: &str) -> Result<String> {
-        let path = Self::report_path(video_id)?;
+    pub async fn load_report(video_id: &str, template: ReportTemplate) -> Result<String> {
+        let path = Self::report_path(video_id, template)?;
         let content = fs::read_to_string(path).await?;
         Ok(content)
     }
 
+    /// Move the current report into `reports/history/` under a timestamped
+    /// name, so it can be replaced without losing the original.
+    pub async fn rotate_report(video_id: &str, template: ReportTemplate) -> Result<PathBuf> {
+        let path = Self::report_path(video_id, template)?;
+        let history_dir = Path::new(REPORTS_DIR).join(REPORT_HISTORY_DIR);
+        ensure_directory(&history_dir)?;
+
+        let stem = report_stem(video_id, template)?;
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let rotated = history_dir.join(format!("{stem}.{stamp}{REPORT_SUFFIX}"));
+        fs::rename(&path, &rotated).await?;
+        Ok(rotated)
+    }
+
+    /// Put a rotated report back in place, undoing `rotate_report`.
+    pub async fn restore_report(
+        video_id: &str,
+        template: ReportTemplate,
+        rotated: &Path,
+    ) -> Result<()> {
+        fs::rename(rotated, Self::report_path(video_id, template)?).await?;
+        Ok(())
+    }
+
+    /// The most recently rotated copy of a report, if it has one.
+    pub fn latest_report_version(
+        video_id: &str,
+        template: ReportTemplate,
+    ) -> Result<Option<PathBuf>> {
+        let prefix = format!("{}.", report_stem(video_id, template)?);
+        let history_dir = Path::new(REPORTS_DIR).join(REPORT_HISTORY_DIR);
+        let Ok(entries) = std_fs::read_dir(&history_dir) else {
+            return Ok(None);
+        };
+
+        // Timestamps in the names sort chronologically
+        Ok(entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_prefix(&prefix)?.strip_suffix(REPORT_SUFFIX))
+                    // Only a timestamp may follow, not another template's name
+                    .is_some_and(|stamp| stamp.chars().all(|c| c.is_ascii_digit() || c == '-'))
+            })
+            .max())
+    }
+
     pub fn list_files() -> Result<Vec<FileEntry>> {
+        Self::list_files_filtered(&ListOptions::default())
+    }
+
+    /// The files `options` keeps, newest first.
+    pub fn list_files_filtered(options: &ListOptions) -> Result<Vec<FileEntry>> {
         Self::ensure_directories()?;
         let mut files = Vec::new();
 
-        // Check transcripts folder
-        if let Ok(entries) = std_fs::read_dir("transcripts") {
-            for entry in entries {
-                let entry = entry?;
-                let path = entry.path();
-
-                if let Some(name) = path.file_name().and_then(|n| n.to_str())
-                    && name.starts_with("transcript_")
-                    && name.ends_with(".txt")
-                {
-                    let metadata = entry.metadata()?;
-                    files.push(FileEntry {
-                        path: path.clone(),
-                        name: name.to_string(),
-                        file_type: FileType::Transcript,
-                        size: metadata.len(),
-                        modified: metadata.modified()?,
-                    });
+        for dir in [TRANSCRIPTS_DIR, REPORTS_DIR] {
+            if let Ok(entries) = std_fs::read_dir(dir) {
+                for entry in entries {
+                    let entry = entry?;
+                    files.extend(stored_file(entry.path(), &entry.metadata()?)?);
                 }
             }
         }
 
-        // Check reports folder
-        if let Ok(entries) = std_fs::read_dir("reports") {
-            for entry in entries {
-                let entry = entry?;
-                let path = entry.path();
-
-                if let Some(name) = path.file_name().and_then(|n| n.to_str())
-                    && name.starts_with("report_")
-                    && name.ends_with(".md")
-                {
-                    let metadata = entry.metadata()?;
-                    files.push(FileEntry {
-                        path: path.clone(),
-                        name: name.to_string(),
-                        file_type: FileType::Report,
-                        size: metadata.len(),
-                        modified: metadata.modified()?,
-                    });
-                }
+        Ok(sorted_files(files, options))
+    }
+
+    /// `list_files` on a blocking thread, for callers that must not block,
+    /// like the TUI's event loop: each file's sidecars are read as it is
+    /// listed.
+    pub async fn list_files_async() -> Result<Vec<FileEntry>> {
+        tokio::task::spawn_blocking(Self::list_files)
+            .await
+            .map_err(|e| Error::custom(format!("File listing task failed: {e}")))?
+    }
+
+    /// Files that only make sense next to a transcript: its metadata sidecar
+    /// and bookmarks.
+    fn transcript_companions(video_id: &str) -> Result<Vec<PathBuf>> {
+        let mut companions = vec![
+            TranscriptMetadata::path(video_id)?,
+            BookmarkList::path(video_id)?,
+        ];
+        companions.extend(Self::transcript_copy_paths(video_id)?);
+        Ok(companions)
+    }
+
+    /// The files stored for a video that exist on disk: the transcript with its
+    /// translations and companions, the report, or both.
+    pub fn video_files(video_id: &str, transcript: bool, report: bool) -> Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        if transcript {
+            paths.push(Self::transcript_path(video_id, None)?);
+            paths.extend(
+                Self::list_files()?
+                    .into_iter()
+                    .filter(|file| {
+                        file.translation().is_some() && file.video_id().as_deref() == Some(video_id)
+                    })
+                    .map(|file| file.path),
+            );
+            paths.extend(Self::transcript_companions(video_id)?);
+        }
+        if report {
+            for template in ReportTemplate::ALL {
+                paths.push(Self::report_path(video_id, template)?);
             }
         }
+        Ok(paths.into_iter().filter(|path| path.is_file()).collect())
+    }
 
-        // Sort by modification time (newest first)
-        files.sort_by(|a, b| b.modified.cmp(&a.modified));
+    /// Transcripts and reports last modified before `cutoff`, plus the
+    /// companions of those transcripts.
+    pub fn files_older_than(cutoff: std::time::SystemTime) -> Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        for entry in Self::list_files()? {
+            if entry.modified >= cutoff {
+                continue;
+            }
+            if entry.file_type == FileType::Transcript
+                && entry.translation().is_none()
+                && let Some(Ok(companions)) =
+                    entry.video_id().map(|id| Self::transcript_companions(&id))
+            {
+                paths.extend(companions.into_iter().filter(|path| path.is_file()));
+            }
+            paths.push(entry.path);
+        }
+        Ok(paths)
+    }
 
-        Ok(files)
+    /// Files left behind by a deleted transcript: reports of videos without
+    /// any transcript, and metadata, bookmarks or copies without the original
+    /// one.
+    pub fn orphaned_files() -> Result<Vec<PathBuf>> {
+        let files = Self::list_files()?;
+        let mut companions = Vec::new();
+        for entry in std_fs::read_dir(TRANSCRIPTS_DIR)? {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if let Some(video_id) = TranscriptMetadata::video_id_of(name)
+                .or_else(|| BookmarkList::video_id_of(name))
+                .or_else(|| copy_video_id_of(name))
+            {
+                companions.push((video_id.to_string(), path.clone()));
+            }
+        }
+        Ok(find_orphans(&files, companions))
     }
 
-    pub fn delete_file(path: &Path) -> Result<()> {
+    /// Delete a managed file. With `safe_delete` it is moved to the trash
+    /// instead, from where `restore_from_trash` can bring it back under the
+    /// returned name.
+    pub fn delete_file(path: &Path, safe_delete: bool) -> Result<Option<String>> {
         Self::ensure_directories()?;
         ensure_managed_path(path)?;
-        std_fs::remove_file(path)?;
-        Ok(())
+        if safe_delete {
+            let trashed = Self::move_to_trash(path)?;
+            Ok(trashed
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned()))
+        } else {
+            std_fs::remove_file(path)?;
+            Ok(None)
+        }
+    }
+
+    /// Move a file into the trash under its name plus a timestamp, adding a
+    /// counter if something was trashed under that name the same second.
+    fn move_to_trash(path: &Path) -> Result<PathBuf> {
+        let trash_dir = Path::new(TRASH_DIR);
+        ensure_directory(trash_dir)?;
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| Error::custom("Cannot trash a file without a name"))?;
+        let stamp = Local::now().format(TRASH_STAMP_FORMAT).to_string();
+
+        let mut target = trash_dir.join(format!("{name}.{stamp}"));
+        let mut counter = 1;
+        while target.exists() {
+            counter += 1;
+            target = trash_dir.join(format!("{name}.{stamp}-{counter}"));
+        }
+        std_fs::rename(path, &target)?;
+        Ok(target)
+    }
+
+    /// The files in the trash, most recently deleted first.
+    pub fn trash_entries() -> Result<Vec<TrashEntry>> {
+        let Ok(entries) = std_fs::read_dir(TRASH_DIR) else {
+            return Ok(Vec::new());
+        };
+        let mut trash = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            let Some((original, deleted)) = parse_trash_name(&name) else {
+                continue;
+            };
+            if metadata.is_file() {
+                trash.push(TrashEntry {
+                    original,
+                    deleted,
+                    size: metadata.len(),
+                    name,
+                });
+            }
+        }
+        trash.sort_by(|a, b| b.deleted.cmp(&a.deleted).then_with(|| b.name.cmp(&a.name)));
+        Ok(trash)
+    }
+
+    /// Move a trashed file back where it was deleted from. An existing file
+    /// there is never overwritten.
+    pub fn restore_from_trash(name: &str) -> Result<PathBuf> {
+        let (original, _) = parse_trash_name(name)
+            .filter(|_| Path::new(name).file_name() == Some(name.as_ref()))
+            .ok_or_else(|| Error::custom(format!("'{name}' is not a trash entry")))?;
+        let trashed = Path::new(TRASH_DIR).join(name);
+        ensure_managed_path(&trashed)
+            .map_err(|_| Error::custom(format!("No '{name}' in the trash")))?;
+        if original.exists() {
+            return Err(Error::custom(format!(
+                "{} already exists; delete it before restoring",
+                original.display()
+            )));
+        }
+        Self::ensure_directories()?;
+        std_fs::rename(&trashed, &original)?;
+        Ok(original)
+    }
+
+    /// Rename a stored transcript or report in its folder. The new name keeps
+    /// the `transcript_<id>….txt` or `report_<id>….md` shape, so the file is
+    /// still listed as what it is, and an existing file is never overwritten.
+    /// A transcript takes its metadata, bookmarks, copies and reports along,
+    /// so none of them is left behind under the old name.
+    pub fn rename_file(old: &Path, new_name: &str) -> Result<PathBuf> {
+        Self::ensure_directories()?;
+        ensure_managed_path(old)?;
+        let old_name = old
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| Error::custom("Cannot rename a file without a name"))?;
+        let (prefix, suffix) = [
+            (TRANSCRIPT_PREFIX, TRANSCRIPT_SUFFIX),
+            (REPORT_PREFIX, REPORT_SUFFIX),
+        ]
+        .into_iter()
+        .find(|(prefix, suffix)| split_file_name(old_name, prefix, suffix).is_some())
+        .ok_or_else(|| Error::custom("Only transcripts and reports can be renamed"))?;
+
+        let new_name = new_name.trim();
+        let valid = Path::new(new_name).file_name() == Some(new_name.as_ref())
+            && split_file_name(new_name, prefix, suffix).is_some_and(|(stem, variant)| {
+                transcript::sanitize_video_id(stem).is_ok()
+                    && variant.is_none_or(|variant| {
+                        variant
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+                    })
+            });
+        if !valid {
+            return Err(Error::custom(format!(
+                "'{new_name}' must look like {prefix}<name>{suffix}, with only letters, numbers, '-' or '_' in the name"
+            )));
+        }
+
+        let new = old.with_file_name(new_name);
+        let mut renames = vec![(old.to_path_buf(), new.clone())];
+        if prefix == TRANSCRIPT_PREFIX
+            && let Some((old_id, None)) = split_file_name(old_name, prefix, suffix)
+            && let Some((new_id, _)) = split_file_name(new_name, prefix, suffix)
+            && new_id != old_id
+        {
+            let reports = ReportTemplate::ALL
+                .into_iter()
+                .map(|template| Self::report_path(old_id, template))
+                .collect::<Result<Vec<_>>>()?;
+            for companion in Self::transcript_companions(old_id)?
+                .into_iter()
+                .chain(reports)
+            {
+                if companion.is_file() {
+                    let renamed = renamed_path(&companion, old_id, new_id)?;
+                    renames.push((companion, renamed));
+                }
+            }
+        }
+        rename_all(&renames)?;
+        Ok(new)
+    }
+
+    /// Rename everything stored for a video, from the transcript and its
+    /// companions to the reports, so it is stored as `new_stem` instead of
+    /// its video ID. Nothing is renamed if any new name is taken.
+    pub fn rename_video(video_id: &str, new_stem: &str) -> Result<Vec<(PathBuf, PathBuf)>> {
+        let video_id = transcript::sanitize_video_id(video_id)?;
+        let new_stem = transcript::sanitize_video_id(new_stem)?;
+        let renames = Self::video_files(&video_id, true, true)?
+            .into_iter()
+            .map(|old| {
+                let new = renamed_path(&old, &video_id, &new_stem)?;
+                Ok((old, new))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        rename_all(&renames)?;
+        Ok(renames)
+    }
+
+    /// Permanently delete everything in the trash. Returns how many files
+    /// were removed and their total size.
+    pub fn empty_trash() -> Result<(usize, u64)> {
+        Self::remove_from_trash(Self::trash_entries()?)
+    }
+
+    /// Permanently delete what was trashed before `cutoff`. Entries whose
+    /// name has no readable time are kept.
+    pub fn purge_trash(cutoff: DateTime<Local>) -> Result<(usize, u64)> {
+        let expired = Self::trash_entries()?
+            .into_iter()
+            .filter(|entry| entry.deleted.is_some_and(|deleted| deleted < cutoff))
+            .collect();
+        Self::remove_from_trash(expired)
+    }
+
+    fn remove_from_trash(entries: Vec<TrashEntry>) -> Result<(usize, u64)> {
+        let mut removed = (0, 0);
+        for entry in entries {
+            std_fs::remove_file(Path::new(TRASH_DIR).join(&entry.name))?;
+            removed.0 += 1;
+            removed.1 += entry.size;
+        }
+        Ok(removed)
     }
 
     #[allow(dead_code)]
@@ -172,25 +665,365 @@ impl StorageService {
 }
 
 impl FileEntry {
-    #[allow(dead_code)]
+    /// The video ID and what follows it in the name: a translation's language
+    /// or a report's template.
+    fn split_name(&self) -> Option<(&str, Option<&str>)> {
+        match self.file_type {
+            FileType::Transcript => {
+                split_file_name(&self.name, TRANSCRIPT_PREFIX, TRANSCRIPT_SUFFIX)
+            }
+            FileType::Report => split_file_name(&self.name, REPORT_PREFIX, REPORT_SUFFIX),
+        }
+    }
+
     pub fn video_id(&self) -> Option<String> {
-        let name = &self.name;
-        if name.starts_with("transcript_") && name.ends_with(".txt") {
-            Some(
-                name.trim_start_matches("transcript_")
-                    .trim_end_matches(".txt")
-                    .to_string(),
-            )
-        } else if name.starts_with("report_") && name.ends_with(".md") {
-            Some(
-                name.trim_start_matches("report_")
-                    .trim_end_matches(".md")
-                    .to_string(),
-            )
-        } else {
-            None
+        self.split_name().map(|(video_id, _)| video_id.to_string())
+    }
+
+    /// The target language of a translated transcript.
+    pub fn translation(&self) -> Option<String> {
+        match self.file_type {
+            FileType::Transcript => self.variant(),
+            FileType::Report => None,
         }
     }
+
+    /// The template a report was written with.
+    pub fn template(&self) -> Option<ReportTemplate> {
+        match (&self.file_type, self.split_name()?) {
+            (FileType::Report, (_, None)) => Some(ReportTemplate::Detailed),
+            (FileType::Report, (_, Some(name))) => ReportTemplate::parse(name).ok(),
+            (FileType::Transcript, _) => None,
+        }
+    }
+
+    /// The Markdown copy `--format md` saved next to an original transcript,
+    /// if there is one.
+    pub fn markdown_copy(&self) -> Option<PathBuf> {
+        if self.file_type != FileType::Transcript || self.translation().is_some() {
+            return None;
+        }
+        let name = format!("{TRANSCRIPT_PREFIX}{}{MARKDOWN_SUFFIX}", self.video_id()?);
+        Some(self.path.with_file_name(name)).filter(|path| path.is_file())
+    }
+
+    fn variant(&self) -> Option<String> {
+        self.split_name()
+            .and_then(|(_, variant)| variant)
+            .map(str::to_string)
+    }
+
+    /// `Title (id)` when the title is known, otherwise the file name.
+    /// Translations and non-detailed reports add what sets them apart:
+    /// `Title (id) [es]`, `Title (id) [summary]`.
+    pub fn display_name(&self) -> String {
+        match (&self.title, self.video_id(), self.variant()) {
+            (Some(title), Some(video_id), Some(variant)) => {
+                format!("{title} ({video_id}) [{variant}]")
+            }
+            (Some(title), Some(video_id), None) => format!("{title} ({video_id})"),
+            _ => self.name.clone(),
+        }
+    }
+}
+
+/// Everything listed for one video, for the Browser's grouped view.
+#[derive(Debug, Clone)]
+pub struct VideoEntry {
+    pub id: String,
+    pub title: Option<String>,
+    /// The original transcript, not a translation.
+    pub transcript: Option<FileEntry>,
+    /// The detailed report, or another one if there is none.
+    pub report: Option<FileEntry>,
+    /// Translations and the reports from other templates.
+    pub others: Vec<FileEntry>,
+}
+
+impl VideoEntry {
+    /// The video's files, transcript first.
+    pub fn files(&self) -> impl Iterator<Item = &FileEntry> {
+        self.transcript
+            .iter()
+            .chain(self.report.iter())
+            .chain(self.others.iter())
+    }
+}
+
+/// `files` joined by video, in the order each video first appears.
+pub fn group_by_video(files: &[FileEntry]) -> Vec<VideoEntry> {
+    let mut videos: Vec<VideoEntry> = Vec::new();
+    for file in files {
+        let id = file.video_id().unwrap_or_else(|| file.name.clone());
+        let index = match videos.iter().position(|video| video.id == id) {
+            Some(index) => index,
+            None => {
+                videos.push(VideoEntry {
+                    id,
+                    title: None,
+                    transcript: None,
+                    report: None,
+                    others: Vec::new(),
+                });
+                videos.len() - 1
+            }
+        };
+        let video = &mut videos[index];
+        if video.title.is_none() {
+            video.title = file.title.clone();
+        }
+        let file = file.clone();
+        let slot = match file.file_type {
+            FileType::Transcript if file.translation().is_none() => &mut video.transcript,
+            FileType::Report
+                if video.report.as_ref().is_none_or(|report| {
+                    file.template() == Some(ReportTemplate::Detailed)
+                        && report.template() != Some(ReportTemplate::Detailed)
+                }) =>
+            {
+                &mut video.report
+            }
+            _ => {
+                video.others.push(file);
+                continue;
+            }
+        };
+        if let Some(replaced) = slot.replace(file) {
+            video.others.push(replaced);
+        }
+    }
+    videos
+}
+
+/// `report_<id>` or `report_<id>.<template>`, the name of a report without its
+/// extension.
+fn report_stem(video_id: &str, template: ReportTemplate) -> Result<String> {
+    let sanitized = transcript::sanitize_video_id(video_id)?;
+    Ok(match template.file_suffix() {
+        Some(suffix) => format!("{REPORT_PREFIX}{sanitized}.{suffix}"),
+        None => format!("{REPORT_PREFIX}{sanitized}"),
+    })
+}
+
+/// The video ID of a stored file name and whatever follows it before the
+/// extension. Video IDs never contain a dot, so the first one starts that.
+fn split_file_name<'a>(
+    name: &'a str,
+    prefix: &str,
+    suffix: &str,
+) -> Option<(&'a str, Option<&'a str>)> {
+    let stem = name.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    match stem.split_once('.') {
+        Some((video_id, variant)) => Some((video_id, Some(variant))),
+        None => Some((stem, None)),
+    }
+}
+
+/// The video ID of a `transcript_<id>.plain.txt` or `transcript_<id>.md` name.
+fn copy_video_id_of(name: &str) -> Option<&str> {
+    let stem = name.strip_prefix(TRANSCRIPT_PREFIX)?;
+    stem.strip_suffix(PLAIN_SUFFIX)
+        .or_else(|| stem.strip_suffix(MARKDOWN_SUFFIX))
+}
+
+/// The listing entry for a file in `transcripts/` or `reports/`, or `None`
+/// for anything else found there.
+fn stored_file(path: PathBuf, metadata: &std_fs::Metadata) -> Result<Option<FileEntry>> {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return Ok(None);
+    };
+    let name = name.to_string();
+    let in_transcripts = path.parent() == Some(Path::new(TRANSCRIPTS_DIR));
+
+    if in_transcripts {
+        if name.ends_with(PLAIN_SUFFIX) {
+            return Ok(None);
+        }
+        let Some((video_id, translation)) =
+            split_file_name(&name, TRANSCRIPT_PREFIX, TRANSCRIPT_SUFFIX)
+        else {
+            return Ok(None);
+        };
+        let sidecar = TranscriptMetadata::load(video_id).ok().flatten();
+        return Ok(Some(FileEntry {
+            bookmarks: BookmarkList::count(video_id),
+            // The sidecar describes the original, not a translation
+            auto_generated: translation.is_none()
+                && sidecar.as_ref().is_some_and(|m| m.is_generated),
+            title: sidecar.and_then(|m| m.title),
+            file_type: FileType::Transcript,
+            size: metadata.len(),
+            modified: metadata.modified()?,
+            path,
+            name,
+        }));
+    }
+
+    let Some((video_id, _)) = split_file_name(&name, REPORT_PREFIX, REPORT_SUFFIX) else {
+        return Ok(None);
+    };
+    Ok(Some(FileEntry {
+        title: stored_title(video_id),
+        file_type: FileType::Report,
+        size: metadata.len(),
+        modified: metadata.modified()?,
+        bookmarks: 0,
+        auto_generated: false,
+        path,
+        name,
+    }))
+}
+
+/// The files `options` keeps, newest first.
+fn sorted_files(mut files: Vec<FileEntry>, options: &ListOptions) -> Vec<FileEntry> {
+    files.retain(|file| options.matches(file));
+    files.sort_by_key(|file| std::cmp::Reverse(file.modified));
+    files
+}
+
+/// The title recorded for a video, if any. Listing should not fail over an
+/// unreadable sidecar, so errors count as no title.
+fn stored_title(video_id: &str) -> Option<String> {
+    TranscriptMetadata::load(video_id).ok().flatten()?.title
+}
+
+/// `SystemTime` as an RFC 3339 UTC string, so JSON output does not depend on
+/// the platform's time representation.
+mod rfc3339 {
+    use chrono::{DateTime, SecondsFormat, Utc};
+    use serde::{Deserialize, Deserializer, Serializer, de};
+    use std::time::SystemTime;
+
+    pub fn serialize<S: Serializer>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        let time = DateTime::<Utc>::from(*time);
+        serializer.serialize_str(&time.to_rfc3339_opts(SecondsFormat::Secs, true))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        DateTime::parse_from_rfc3339(&value)
+            .map(SystemTime::from)
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Count and total size of one kind of stored file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TypeUsage {
+    pub count: usize,
+    pub size: u64,
+}
+
+/// Disk usage of the stored files, as shown by `vidio stats`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StorageStats {
+    /// Where `transcripts/` and `reports/` live, as an absolute path.
+    pub data_dir: PathBuf,
+    pub transcripts: TypeUsage,
+    pub reports: TypeUsage,
+    /// Biggest files first.
+    pub largest: Vec<FileEntry>,
+    pub oldest: Option<FileEntry>,
+    pub newest: Option<FileEntry>,
+    /// `vidio get` batch manifests, newest first.
+    pub batches: Vec<BatchSummary>,
+}
+
+const LARGEST_FILES: usize = 5;
+
+impl StorageStats {
+    pub fn from_files(files: &[FileEntry]) -> Self {
+        let mut stats = Self::default();
+        for file in files {
+            let usage = match file.file_type {
+                FileType::Transcript => &mut stats.transcripts,
+                FileType::Report => &mut stats.reports,
+            };
+            usage.count += 1;
+            usage.size += file.size;
+        }
+
+        let mut by_size: Vec<&FileEntry> = files.iter().collect();
+        by_size.sort_by_key(|file| std::cmp::Reverse(file.size));
+        stats.largest = by_size.into_iter().take(LARGEST_FILES).cloned().collect();
+        stats.oldest = files.iter().min_by_key(|file| file.modified).cloned();
+        stats.newest = files.iter().max_by_key(|file| file.modified).cloned();
+        stats
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.transcripts.size + self.reports.size
+    }
+}
+
+/// Human-readable size, e.g. `12KB` or `3.4MB`.
+pub fn format_size(bytes: u64) -> String {
+    let size_kb = bytes / 1024;
+    if size_kb < 1024 {
+        format!("{size_kb}KB")
+    } else if size_kb < 1024 * 1024 {
+        format!("{:.1}MB", size_kb as f64 / 1024.0)
+    } else {
+        format!("{:.1}GB", size_kb as f64 / (1024.0 * 1024.0))
+    }
+}
+
+async fn write_transcript(path: &Path, lines: &[String]) -> Result<()> {
+    write_atomic(path, &lines.join("\n")).await?;
+    info!("Transcript saved to: {}", path.display());
+    Ok(())
+}
+
+/// A zero-byte file is what an interrupted write leaves behind, so it counts as
+/// missing and gets fetched or generated again.
+fn has_content(path: &Path) -> bool {
+    std_fs::metadata(path)
+        .map(|metadata| metadata.is_file() && metadata.len() > 0)
+        .unwrap_or(false)
+}
+
+/// Write through a uniquely named temp file in the same directory and rename it
+/// into place, so readers never see a partial file and two jobs saving at the
+/// same time cannot interleave their contents.
+async fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let temp_path = temp_path(path)?;
+    if let Err(e) = fs::write(&temp_path, content).await {
+        let _ = fs::remove_file(&temp_path).await;
+        return Err(e.into());
+    }
+    if let Err(e) = fs::rename(&temp_path, path).await {
+        let _ = fs::remove_file(&temp_path).await;
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// [`write_atomic`] for the small data files saved outside of async code.
+pub(crate) fn write_atomic_sync(path: &Path, content: &str) -> Result<()> {
+    let temp_path = temp_path(path)?;
+    if let Err(e) = std_fs::write(&temp_path, content) {
+        let _ = std_fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+    if let Err(e) = std_fs::rename(&temp_path, path) {
+        let _ = std_fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+fn temp_path(path: &Path) -> Result<PathBuf> {
+    static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| Error::custom(format!("Invalid file path: {}", path.display())))?;
+    Ok(path.with_file_name(format!(
+        ".{file_name}.{}.{}.tmp",
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    )))
 }
 
 fn ensure_directory(path: &Path) -> Result<()> {
@@ -211,22 +1044,124 @@ fn ensure_directory(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// The reports and companion files (video ID, path) in `companions` whose
+/// transcript is missing from `files`.
+fn find_orphans(files: &[FileEntry], companions: Vec<(String, PathBuf)>) -> Vec<PathBuf> {
+    let transcripts: Vec<_> = files
+        .iter()
+        .filter(|file| file.file_type == FileType::Transcript)
+        .collect();
+    let has_transcript = |video_id: &str, original: bool| {
+        transcripts.iter().any(|file| {
+            file.video_id().as_deref() == Some(video_id)
+                && (!original || file.translation().is_none())
+        })
+    };
+
+    let mut orphans: Vec<PathBuf> = files
+        .iter()
+        .filter(|file| file.file_type == FileType::Report)
+        .filter(|file| {
+            file.video_id()
+                .is_some_and(|video_id| !has_transcript(&video_id, false))
+        })
+        .map(|file| file.path.clone())
+        .collect();
+    orphans.extend(
+        companions
+            .into_iter()
+            .filter(|(video_id, _)| !has_transcript(video_id, true))
+            .map(|(_, path)| path),
+    );
+    orphans
+}
+
+/// Where a trashed file came from and when it was deleted, from its name in
+/// the trash. Reports go back to `reports/`, everything else to `transcripts/`.
+fn parse_trash_name(name: &str) -> Option<(PathBuf, Option<DateTime<Local>>)> {
+    let (original, suffix) = name.rsplit_once('.')?;
+    // The timestamp, then a counter for names trashed in the same second
+    let (stamp, counter) = suffix.split_at_checked(TRASH_STAMP_LEN)?;
+    if !counter.is_empty() && counter.strip_prefix('-')?.parse::<u32>().is_err() {
+        return None;
+    }
+    let deleted = chrono::NaiveDateTime::parse_from_str(stamp, TRASH_STAMP_FORMAT)
+        .ok()?
+        .and_local_timezone(Local)
+        .earliest();
+    let dir = if original.starts_with(REPORT_PREFIX) {
+        REPORTS_DIR
+    } else {
+        TRANSCRIPTS_DIR
+    };
+    Some((Path::new(dir).join(original), deleted))
+}
+
+/// `path` with `old_id` in its `<kind>_<id><rest>` file name swapped for
+/// `new_id`.
+fn renamed_path(path: &Path, old_id: &str, new_id: &str) -> Result<PathBuf> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.split_once('_'))
+        .and_then(|(kind, rest)| {
+            let rest = rest.strip_prefix(old_id)?;
+            Some(path.with_file_name(format!("{kind}_{new_id}{rest}")))
+        })
+        .ok_or_else(|| Error::custom(format!("Unexpected file {}", path.display())))
+}
+
+/// Apply every rename, or none if one of the new names is taken.
+fn rename_all(renames: &[(PathBuf, PathBuf)]) -> Result<()> {
+    for (old, new) in renames {
+        if old != new && new.exists() {
+            return Err(Error::custom(format!(
+                "{} already exists; nothing was renamed",
+                new.display()
+            )));
+        }
+    }
+    for (old, new) in renames {
+        if old != new {
+            rename_new(old, new)?;
+        }
+    }
+    Ok(())
+}
+
+/// Rename `old` to `new`, failing rather than replacing a file at `new`.
+fn rename_new(old: &Path, new: &Path) -> Result<()> {
+    if new.exists() {
+        return Err(Error::custom(format!("{} already exists", new.display())));
+    }
+    std_fs::rename(old, new)?;
+    Ok(())
+}
+
+/// `title` as a file name stem: lowercase ASCII letters and digits, with a
+/// dash for each run of anything else.
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.truncate(MAX_SLUG_LEN);
+    slug.trim_end_matches('-').to_string()
+}
+
 fn ensure_managed_path(path: &Path) -> Result<()> {
     let canonical = path
         .canonicalize()
         .map_err(|_| Error::custom("Target file does not exist or cannot be resolved"))?;
 
-    let transcript_base = Path::new(TRANSCRIPTS_DIR).canonicalize().ok();
-    let report_base = Path::new(REPORTS_DIR).canonicalize().ok();
-
-    let allowed = transcript_base
-        .as_ref()
-        .map(|base| canonical.starts_with(base))
-        .unwrap_or(false)
-        || report_base
-            .as_ref()
-            .map(|base| canonical.starts_with(base))
-            .unwrap_or(false);
+    let allowed = [TRANSCRIPTS_DIR, REPORTS_DIR, TRASH_DIR].iter().any(|dir| {
+        Path::new(dir)
+            .canonicalize()
+            .is_ok_and(|base| canonical.starts_with(base))
+    });
 
     if !allowed {
         return Err(Error::custom(
@@ -236,3 +1171,337 @@ fn ensure_managed_path(path: &Path) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        FileEntry, FileType, ListOptions, MAX_SLUG_LEN, StorageService, StorageStats, find_orphans,
+        format_size, group_by_video, has_content, parse_trash_name, rename_new, slugify,
+        write_atomic,
+    };
+    use crate::core::report::ReportTemplate;
+    use chrono::{Local, NaiveDate, NaiveDateTime};
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    fn entry(name: &str, file_type: FileType, size: u64, age_secs: u64) -> FileEntry {
+        FileEntry {
+            path: name.into(),
+            name: name.to_string(),
+            file_type,
+            size,
+            modified: SystemTime::UNIX_EPOCH + Duration::from_secs(1_790_000_000 - age_secs),
+            bookmarks: 0,
+            title: None,
+            auto_generated: false,
+        }
+    }
+
+    #[test]
+    fn files_are_grouped_by_video() {
+        let mut titled = entry("transcript_5_EJwYeQusM.txt", FileType::Transcript, 0, 0);
+        titled.title = Some("Rust in 100 Seconds".to_string());
+        let files = [
+            entry("report_5_EJwYeQusM.summary.md", FileType::Report, 0, 0),
+            entry("report_dQw4w9WgXcQ.md", FileType::Report, 0, 0),
+            titled,
+            entry("transcript_5_EJwYeQusM.es.txt", FileType::Transcript, 0, 0),
+            entry("report_5_EJwYeQusM.md", FileType::Report, 0, 0),
+        ];
+
+        let videos = group_by_video(&files);
+        let ids: Vec<&str> = videos.iter().map(|video| video.id.as_str()).collect();
+        assert_eq!(ids, ["5_EJwYeQusM", "dQw4w9WgXcQ"]);
+
+        let rust = &videos[0];
+        assert_eq!(rust.title.as_deref(), Some("Rust in 100 Seconds"));
+        let names: Vec<&str> = rust.files().map(|file| file.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "transcript_5_EJwYeQusM.txt",
+                "report_5_EJwYeQusM.md",
+                // Pushed aside by the detailed report, after the translation
+                "transcript_5_EJwYeQusM.es.txt",
+                "report_5_EJwYeQusM.summary.md",
+            ]
+        );
+        assert!(videos[1].transcript.is_none());
+        assert!(videos[1].report.is_some());
+    }
+
+    #[test]
+    fn display_name_prefers_the_title() {
+        let mut file = entry("report_5_EJwYeQusM.md", FileType::Report, 0, 0);
+        assert_eq!(file.display_name(), "report_5_EJwYeQusM.md");
+        file.title = Some("Rust in 100 Seconds".to_string());
+        assert_eq!(file.display_name(), "Rust in 100 Seconds (5_EJwYeQusM)");
+    }
+
+    #[test]
+    fn translations_share_the_video_id() {
+        let original = entry("transcript_5_EJwYeQusM.txt", FileType::Transcript, 0, 0);
+        assert_eq!(original.video_id().as_deref(), Some("5_EJwYeQusM"));
+        assert_eq!(original.translation(), None);
+
+        let mut translated = entry(
+            "transcript_5_EJwYeQusM.pt-BR.txt",
+            FileType::Transcript,
+            0,
+            0,
+        );
+        assert_eq!(translated.video_id().as_deref(), Some("5_EJwYeQusM"));
+        assert_eq!(translated.translation().as_deref(), Some("pt-BR"));
+        translated.title = Some("Rust in 100 Seconds".to_string());
+        assert_eq!(
+            translated.display_name(),
+            "Rust in 100 Seconds (5_EJwYeQusM) [pt-BR]"
+        );
+
+        assert_eq!(
+            StorageService::transcript_path("5_EJwYeQusM", Some("es")).unwrap(),
+            Path::new("transcripts/transcript_5_EJwYeQusM.es.txt")
+        );
+        assert!(StorageService::transcript_path("5_EJwYeQusM", Some("../es")).is_err());
+    }
+
+    #[test]
+    fn report_names_encode_the_template() {
+        let detailed = entry("report_5_EJwYeQusM.md", FileType::Report, 0, 0);
+        assert_eq!(detailed.template(), Some(ReportTemplate::Detailed));
+
+        let mut summary = entry("report_5_EJwYeQusM.summary.md", FileType::Report, 0, 0);
+        assert_eq!(summary.video_id().as_deref(), Some("5_EJwYeQusM"));
+        assert_eq!(summary.template(), Some(ReportTemplate::Summary));
+        assert_eq!(summary.translation(), None);
+        summary.title = Some("Rust in 100 Seconds".to_string());
+        assert_eq!(
+            summary.display_name(),
+            "Rust in 100 Seconds (5_EJwYeQusM) [summary]"
+        );
+
+        assert_eq!(
+            StorageService::report_path("5_EJwYeQusM", ReportTemplate::Chapters).unwrap(),
+            Path::new("reports/report_5_EJwYeQusM.chapters.md")
+        );
+        assert_eq!(
+            StorageService::report_path("5_EJwYeQusM", ReportTemplate::Detailed).unwrap(),
+            Path::new("reports/report_5_EJwYeQusM.md")
+        );
+    }
+
+    #[test]
+    fn stats_sum_per_type_and_rank_files() {
+        let mut files: Vec<FileEntry> = (0..6)
+            .map(|i| {
+                entry(
+                    &format!("transcript_{i}.txt"),
+                    FileType::Transcript,
+                    i * 1000,
+                    i,
+                )
+            })
+            .collect();
+        files.push(entry("report_a.md", FileType::Report, 10_000, 100));
+
+        let stats = StorageStats::from_files(&files);
+        assert_eq!(stats.transcripts.count, 6);
+        assert_eq!(stats.transcripts.size, 15_000);
+        assert_eq!(stats.reports.count, 1);
+        assert_eq!(stats.total_size(), 25_000);
+        let largest: Vec<&str> = stats.largest.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            largest,
+            [
+                "report_a.md",
+                "transcript_5.txt",
+                "transcript_4.txt",
+                "transcript_3.txt",
+                "transcript_2.txt"
+            ]
+        );
+        assert_eq!(stats.oldest.unwrap().name, "report_a.md");
+        assert_eq!(stats.newest.unwrap().name, "transcript_0.txt");
+        assert!(StorageStats::from_files(&[]).oldest.is_none());
+    }
+
+    #[test]
+    fn orphans_are_files_whose_transcript_is_gone() {
+        let files = [
+            entry("transcript_5_EJwYeQusM.txt", FileType::Transcript, 0, 0),
+            entry("report_5_EJwYeQusM.md", FileType::Report, 0, 0),
+            entry("transcript_dQw4w9WgXcQ.es.txt", FileType::Transcript, 0, 0),
+            entry("report_dQw4w9WgXcQ.summary.md", FileType::Report, 0, 0),
+            entry("report_jNQXAC9IVRw.md", FileType::Report, 0, 0),
+        ];
+        let companions = vec![
+            (
+                "5_EJwYeQusM".to_string(),
+                "transcripts/meta_5_EJwYeQusM.json".into(),
+            ),
+            (
+                "dQw4w9WgXcQ".to_string(),
+                "transcripts/bookmarks_dQw4w9WgXcQ.json".into(),
+            ),
+        ];
+
+        assert_eq!(
+            find_orphans(&files, companions),
+            [
+                Path::new("report_jNQXAC9IVRw.md"),
+                Path::new("transcripts/bookmarks_dQw4w9WgXcQ.json"),
+            ]
+        );
+    }
+
+    #[test]
+    fn trash_names_lead_back_to_the_original() {
+        let (original, deleted) =
+            parse_trash_name("report_5_EJwYeQusM.md.20261016-093000").unwrap();
+        assert_eq!(original, Path::new("reports/report_5_EJwYeQusM.md"));
+        assert_eq!(
+            deleted.unwrap().naive_local(),
+            NaiveDateTime::parse_from_str("2026-10-16 09:30:00", "%Y-%m-%d %H:%M:%S").unwrap()
+        );
+
+        let (original, _) = parse_trash_name("meta_5_EJwYeQusM.json.20261016-093000-2").unwrap();
+        assert_eq!(original, Path::new("transcripts/meta_5_EJwYeQusM.json"));
+
+        assert!(parse_trash_name("transcript_5_EJwYeQusM.txt").is_none());
+        assert!(parse_trash_name("transcript_5_EJwYeQusM.txt.20261016-093000-x").is_none());
+    }
+
+    #[test]
+    fn sizes_use_the_largest_fitting_unit() {
+        assert_eq!(format_size(512), "0KB");
+        assert_eq!(format_size(12 * 1024), "12KB");
+        assert_eq!(format_size(3 * 1024 * 1024 + 400 * 1024), "3.4MB");
+        assert_eq!(format_size(2 * 1024 * 1024 * 1024), "2.0GB");
+    }
+
+    #[test]
+    fn file_entry_json_uses_rfc3339_times() {
+        let entry = FileEntry {
+            path: "transcripts/transcript_x.txt".into(),
+            name: "transcript_x.txt".to_string(),
+            file_type: FileType::Transcript,
+            size: 42,
+            modified: SystemTime::UNIX_EPOCH + Duration::from_secs(1_790_000_000),
+            bookmarks: 0,
+            title: None,
+            auto_generated: false,
+        };
+
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["modified"], "2026-09-21T14:13:20Z");
+        assert_eq!(json["file_type"], "Transcript");
+
+        let back: FileEntry = serde_json::from_value(json).unwrap();
+        assert_eq!(back.modified, entry.modified);
+    }
+
+    #[test]
+    fn list_options_keep_whole_local_days() {
+        let at = |date: &str, time: &str, file_type: FileType| {
+            let local = NaiveDateTime::parse_from_str(&format!("{date} {time}"), "%Y-%m-%d %H:%M")
+                .unwrap()
+                .and_local_timezone(Local)
+                .earliest()
+                .unwrap();
+            FileEntry {
+                modified: local.into(),
+                ..entry("transcript_x.txt", file_type, 0, 0)
+            }
+        };
+        let day = |date: &str| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok();
+        let week = ListOptions {
+            since: day("2025-03-03"),
+            until: day("2025-03-09"),
+            file_type: None,
+        };
+
+        assert!(week.matches(&at("2025-03-03", "00:00", FileType::Transcript)));
+        assert!(week.matches(&at("2025-03-09", "23:59", FileType::Report)));
+        assert!(!week.matches(&at("2025-03-02", "23:59", FileType::Transcript)));
+        assert!(!week.matches(&at("2025-03-10", "00:00", FileType::Transcript)));
+
+        let reports = ListOptions {
+            file_type: Some(FileType::Report),
+            ..week
+        };
+        assert!(reports.matches(&at("2025-03-05", "12:00", FileType::Report)));
+        assert!(!reports.matches(&at("2025-03-05", "12:00", FileType::Transcript)));
+        assert!(ListOptions::default().matches(&at("1999-12-31", "12:00", FileType::Report)));
+    }
+
+    #[test]
+    fn titles_become_file_name_stems() {
+        assert_eq!(slugify("Rust in 100 Seconds!"), "rust-in-100-seconds");
+        assert_eq!(slugify("  ¿Qué es --- Tokio?  "), "qu-es-tokio");
+        assert_eq!(slugify("日本語"), "");
+        assert_eq!(slugify(&"abc ".repeat(40)).len(), MAX_SLUG_LEN - 1);
+    }
+
+    #[test]
+    fn renames_never_replace_a_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("transcript_a.txt");
+        let taken = dir.path().join("transcript_b.txt");
+        std::fs::write(&old, "a").unwrap();
+        std::fs::write(&taken, "b").unwrap();
+
+        assert!(rename_new(&old, &taken).is_err());
+        assert_eq!(std::fs::read_to_string(&taken).unwrap(), "b");
+
+        let new = dir.path().join("transcript_c.txt");
+        rename_new(&old, &new).unwrap();
+        assert!(!old.exists());
+        assert_eq!(std::fs::read_to_string(&new).unwrap(), "a");
+    }
+
+    #[test]
+    fn markdown_copies_belong_to_the_original_transcript() {
+        let dir = tempfile::tempdir().unwrap();
+        let at = |name: &str| FileEntry {
+            path: dir.path().join(name),
+            ..entry(name, FileType::Transcript, 0, 0)
+        };
+        assert_eq!(at("transcript_a.txt").markdown_copy(), None);
+
+        let copy = dir.path().join("transcript_a.md");
+        std::fs::write(&copy, "# A\n").unwrap();
+        assert_eq!(at("transcript_a.txt").markdown_copy(), Some(copy));
+        assert_eq!(at("transcript_a.es.txt").markdown_copy(), None);
+        assert_eq!(at("transcript_b.txt").markdown_copy(), None);
+    }
+
+    #[test]
+    fn zero_byte_files_count_as_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report_x.md");
+        assert!(!has_content(&path));
+
+        std::fs::write(&path, "").unwrap();
+        assert!(!has_content(&path));
+
+        std::fs::write(&path, "#### 1. Metadata").unwrap();
+        assert!(has_content(&path));
+    }
+
+    #[tokio::test]
+    async fn concurrent_atomic_writes_never_interleave() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("transcript_x.txt");
+        let first = "a".repeat(256 * 1024);
+        let second = "b".repeat(256 * 1024);
+
+        let (a, b) = tokio::join!(write_atomic(&path, &first), write_atomic(&path, &second));
+        a.unwrap();
+        b.unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content == first || content == second);
+        let leftovers = std::fs::read_dir(dir.path()).unwrap().count();
+        assert_eq!(leftovers, 1, "temp files must be renamed away");
+    }
+}
@@ -1,15 +1,275 @@
+use crate::core::chapters::{Chapter, chapter_starts, parse_chapters};
+use crate::core::storage::StorageService;
 use crate::error::{Error, Result};
-use yt_transcript_rs::{FetchedTranscript, api::YouTubeTranscriptApi};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+use yt_transcript_rs::errors::{CouldNotRetrieveTranscript, CouldNotRetrieveTranscriptReason};
+use yt_transcript_rs::{
+    FetchedTranscript, FetchedTranscriptSnippet, MicroformatData, Transcript, VideoDetails,
+    VideoInfos, api::YouTubeTranscriptApi,
+};
+
+/// The browser YouTube expects; the same one the transcript API sends.
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36";
+const MAX_LANGUAGE_CODE_LEN: usize = 16;
+
+pub const DEFAULT_FETCH_CONCURRENCY: usize = 3;
+
+/// Per-video progress reported by `fetch_many`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FetchEvent {
+    Started(String),
+    Skipped(String),
+    Saved(String, PathBuf),
+    Failed(String, String),
+}
+
+/// Which caption artifacts `clean_snippets` removes; all of them by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CleanOptions {
+    /// Sound cues such as `[Music]` or `[Applause]`, and music notes.
+    pub cues: bool,
+    /// A line repeating the one before it, as rolling captions do.
+    pub duplicates: bool,
+    /// HTML entities such as `&amp;` or `&#39;`.
+    pub entities: bool,
+}
+
+impl Default for CleanOptions {
+    fn default() -> Self {
+        Self {
+            cues: true,
+            duplicates: true,
+            entities: true,
+        }
+    }
+}
+
+impl CleanOptions {
+    pub const NONE: Self = Self {
+        cues: false,
+        duplicates: false,
+        entities: false,
+    };
+
+    /// `all`, `none`, or a comma-separated list of `cues`, `duplicates` and
+    /// `entities`.
+    pub fn parse(value: &str) -> Result<Self> {
+        let mut options = Self::NONE;
+        for step in value
+            .split(',')
+            .map(|step| step.trim().to_ascii_lowercase())
+        {
+            match step.as_str() {
+                "all" => options = Self::default(),
+                "none" => {}
+                "cues" => options.cues = true,
+                "duplicates" => options.duplicates = true,
+                "entities" => options.entities = true,
+                _ => {
+                    return Err(Error::custom(format!(
+                        "Unknown cleaning step '{step}'; expected all, none, cues, duplicates or entities"
+                    )));
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Pause, in seconds, that starts a new paragraph in plain-text transcripts.
+pub const DEFAULT_PARAGRAPH_GAP: f64 = 2.0;
+
+/// How a transcript is written out: one `[start - end] text` line per caption,
+/// running text without timestamps, or Markdown with a heading per section.
+/// The timestamped transcript is always saved; the others are copies of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TranscriptFormat {
+    #[default]
+    Timestamped,
+    Plain,
+    Markdown,
+}
+
+impl TranscriptFormat {
+    pub const ALL: [Self; 3] = [Self::Timestamped, Self::Plain, Self::Markdown];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "timestamped" => Ok(Self::Timestamped),
+            "plain" => Ok(Self::Plain),
+            "md" | "markdown" => Ok(Self::Markdown),
+            other => Err(Error::custom(format!(
+                "Unknown transcript format '{other}'; expected timestamped, plain or md"
+            ))),
+        }
+    }
+
+    /// The next format, wrapping around; for the TUI's selector.
+    pub fn next(self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|&format| format == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// Outcome of one video in a batch; `Ok(None)` means it was already saved.
+pub type FetchOutcome = (String, Result<Option<PathBuf>>);
+
+/// What YouTube says about a video besides its captions. Anything it did
+/// not return, or returned empty, is `None`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VideoMetadata {
+    pub title: Option<String>,
+    /// The channel's name.
+    pub author: Option<String>,
+    pub channel_id: Option<String>,
+    pub duration_secs: Option<u64>,
+    /// `YYYY-MM-DD`.
+    pub upload_date: Option<String>,
+}
+
+impl VideoMetadata {
+    fn new(details: &VideoDetails, microformat: &MicroformatData) -> Self {
+        let upload_date = microformat
+            .upload_date
+            .as_deref()
+            .or(microformat.publish_date.as_deref())
+            .and_then(|date| date.split('T').next());
+        Self {
+            title: non_empty(&details.title),
+            author: non_empty(&details.author),
+            channel_id: non_empty(&details.channel_id),
+            duration_secs: Some(u64::from(details.length_seconds)).filter(|secs| *secs > 0),
+            upload_date: upload_date.and_then(non_empty),
+        }
+    }
+
+    /// The duration as `HH:MM:SS`.
+    pub fn length(&self) -> Option<String> {
+        self.duration_secs.map(|secs| format_clock(secs as f64))
+    }
+
+    /// The `---` block saved transcripts open with, so whoever reads one,
+    /// the report model included, knows the video without guessing. Empty
+    /// when nothing is known.
+    pub fn to_front_matter(&self) -> String {
+        let mut block = String::new();
+        let fields = [
+            ("title", self.title.clone()),
+            ("author", self.author.clone()),
+            ("channel_id", self.channel_id.clone()),
+            (
+                "duration_secs",
+                self.duration_secs.map(|secs| secs.to_string()),
+            ),
+            ("upload_date", self.upload_date.clone()),
+        ];
+        for (key, value) in fields {
+            if let Some(value) = value {
+                let _ = writeln!(block, "{key}: {}", value.replace(['\r', '\n'], " "));
+            }
+        }
+        if block.is_empty() {
+            return block;
+        }
+        format!("---\n{block}---\n\n")
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    Some(value.trim())
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// A fetched transcript and whatever YouTube said about the video.
+pub struct FetchedVideo {
+    pub transcript: FetchedTranscript,
+    pub metadata: VideoMetadata,
+    /// The target language when the transcript was machine translated.
+    pub translation: Option<String>,
+    /// Caption lines `clean_snippets` dropped.
+    pub removed_lines: usize,
+    /// Chapters from the video description, for Markdown headings.
+    pub chapters: Vec<Chapter>,
+    /// Also save a copy in this format, unless it is `Timestamped`.
+    pub format: TranscriptFormat,
+    /// Pause, in seconds, that starts a paragraph in that copy.
+    pub paragraph_gap: f64,
+}
+
+impl FetchedVideo {
+    /// The text saved as the transcript, and read by the report: the
+    /// metadata block, then one `[start - end] text` line per snippet.
+    pub fn saved_text(&self) -> String {
+        format!(
+            "{}{}",
+            self.metadata.to_front_matter(),
+            TranscriptService::format_transcript(&self.transcript).join("\n")
+        )
+    }
+}
 
 #[derive(Clone)]
 pub struct TranscriptService {
     api: YouTubeTranscriptApi,
+    /// Shared with `api`; translations are fetched through it directly.
+    http: reqwest::Client,
+    /// Refuse auto-generated captions.
+    manual_only: bool,
+    clean: CleanOptions,
+    format: TranscriptFormat,
+    paragraph_gap: f64,
 }
 
 impl TranscriptService {
     pub fn new() -> Result<Self> {
-        let api = YouTubeTranscriptApi::new(None, None, None)?;
-        Ok(Self { api })
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            reqwest::header::ACCEPT_LANGUAGE,
+            reqwest::header::HeaderValue::from_static("en-US"),
+        );
+        let http = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .default_headers(headers)
+            .build()
+            .map_err(|e| Error::custom(format!("Failed to create HTTP client: {e}")))?;
+        let api = YouTubeTranscriptApi::new(None, None, Some(http.clone()))?;
+        Ok(Self {
+            api,
+            http,
+            manual_only: false,
+            clean: CleanOptions::default(),
+            format: TranscriptFormat::Timestamped,
+            paragraph_gap: DEFAULT_PARAGRAPH_GAP,
+        })
+    }
+
+    /// Which artifacts to strip from fetched transcripts.
+    pub fn clean(mut self, clean: CleanOptions) -> Self {
+        self.clean = clean;
+        self
+    }
+
+    /// Save a copy in `format` next to each original transcript, with
+    /// paragraphs at pauses longer than `paragraph_gap` seconds.
+    pub fn format(mut self, format: TranscriptFormat, paragraph_gap: f64) -> Self {
+        self.format = format;
+        self.paragraph_gap = paragraph_gap;
+        self
+    }
+
+    /// Fail with [`Error::TranscriptUnavailable`] instead of falling back to
+    /// auto-generated captions.
+    pub fn manual_only(mut self, manual_only: bool) -> Self {
+        self.manual_only = manual_only;
+        self
     }
 
     pub async fn fetch_transcript(
@@ -18,18 +278,233 @@ impl TranscriptService {
         languages: &[&str],
         preserve_formatting: bool,
     ) -> Result<FetchedTranscript> {
-        // println!("Fetching transcript for video ID: {}", video_id);
+        if self.manual_only {
+            return self
+                .fetch_manual(video_id, languages, preserve_formatting)
+                .await;
+        }
 
-        match self
-            .api
+        self.api
             .fetch_transcript(video_id, languages, preserve_formatting)
             .await
-        {
-            Ok(transcript) => Ok(transcript),
-            Err(e) => Err(crate::error::Error::custom(format!(
-                "Failed to fetch transcript: {e}"
-            ))),
+            .map_err(fetch_error)
+    }
+
+    /// The first manually created transcript in `languages`. Telling "only
+    /// auto-generated" apart from "nothing at all" takes the list first.
+    async fn fetch_manual(
+        &self,
+        video_id: &str,
+        languages: &[&str],
+        preserve_formatting: bool,
+    ) -> Result<FetchedTranscript> {
+        let list = self
+            .api
+            .list_transcripts(video_id)
+            .await
+            .map_err(fetch_error)?;
+        let transcript = match list.find_manually_created_transcript(languages) {
+            Ok(transcript) => transcript,
+            Err(_) if list.find_generated_transcript(languages).is_ok() => {
+                return Err(Error::TranscriptUnavailable(format!(
+                    "{video_id} only has auto-generated captions in {}; drop --manual-only to use them",
+                    languages.join(", ")
+                )));
+            }
+            Err(e) => return Err(fetch_error(e)),
+        };
+        transcript
+            .fetch(&self.http, preserve_formatting)
+            .await
+            .map_err(fetch_error)
+    }
+
+    /// A transcript machine translated into `target` by YouTube. The source is
+    /// the first of `source_languages` that can be translated, or else any
+    /// translatable transcript. One already in `target` is fetched as is.
+    pub async fn fetch_translated(
+        &self,
+        video_id: &str,
+        source_languages: &[&str],
+        target: &str,
+        preserve_formatting: bool,
+    ) -> Result<FetchedTranscript> {
+        let list = self
+            .api
+            .list_transcripts(video_id)
+            .await
+            .map_err(|e| youtube_error("list transcripts", e))?;
+
+        let usable = |transcript: &Transcript| {
+            transcript.is_translatable() && !(self.manual_only && transcript.is_generated)
+        };
+        let source = list
+            .find_transcript(source_languages)
+            .ok()
+            .filter(usable)
+            .or_else(|| list.transcripts().find(|t| usable(t)).cloned())
+            .ok_or_else(|| {
+                if self.manual_only && list.transcripts().any(|t| t.is_translatable()) {
+                    Error::TranscriptUnavailable(format!(
+                        "{video_id} only has auto-generated captions to translate; drop --manual-only to use them"
+                    ))
+                } else {
+                    Error::TranscriptUnavailable(format!(
+                        "No transcript of {video_id} can be translated"
+                    ))
+                }
+            })?;
+
+        let fetched = if source.language_code == target {
+            source.fetch(&self.http, preserve_formatting).await
+        } else {
+            source
+                .translate_and_fetch(&self.http, target, preserve_formatting)
+                .await
+        };
+        fetched.map_err(|e| youtube_error("translate transcript", e))
+    }
+
+    /// The video's title, channel, length and upload date, e.g. to confirm a
+    /// pasted link before anything is downloaded.
+    pub async fn fetch_metadata(&self, video_id: &str) -> Result<VideoMetadata> {
+        let infos = self.fetch_infos(video_id).await?;
+        Ok(VideoMetadata::new(&infos.video_details, &infos.microformat))
+    }
+
+    /// Details and upload date alike, from a single request for the video's
+    /// page.
+    async fn fetch_infos(&self, video_id: &str) -> Result<VideoInfos> {
+        self.api
+            .fetch_video_infos(video_id)
+            .await
+            .map_err(|e| youtube_error("fetch video details", e))
+    }
+
+    /// Fetch a transcript together with the video's metadata and chapters.
+    /// These are only used for display, so failing to get them does not fail
+    /// the fetch.
+    /// With `translate`, the transcript is translated into that language.
+    pub async fn fetch_video(
+        &self,
+        video_id: &str,
+        languages: &[&str],
+        preserve_formatting: bool,
+        translate: Option<&str>,
+    ) -> Result<FetchedVideo> {
+        let transcript = async {
+            match translate {
+                Some(target) => {
+                    self.fetch_translated(video_id, languages, target, preserve_formatting)
+                        .await
+                }
+                None => {
+                    self.fetch_transcript(video_id, languages, preserve_formatting)
+                        .await
+                }
+            }
+        };
+        let (transcript, infos) = tokio::join!(transcript, self.fetch_infos(video_id));
+        let infos = infos.ok();
+        let (transcript, removed_lines) = Self::clean_snippets(&transcript?, self.clean);
+        Ok(FetchedVideo {
+            transcript,
+            removed_lines,
+            metadata: infos
+                .as_ref()
+                .map(|infos| VideoMetadata::new(&infos.video_details, &infos.microformat))
+                .unwrap_or_default(),
+            chapters: infos
+                .map(|infos| parse_chapters(&infos.video_details.short_description))
+                .unwrap_or_default(),
+            translation: translate.map(str::to_string),
+            format: self.format,
+            paragraph_gap: self.paragraph_gap,
+        })
+    }
+
+    /// Fetch and save several transcripts with at most `concurrency` requests
+    /// in flight. Videos already on disk (in the `translate` language, if
+    /// given) are skipped. Results arrive in completion order, not input order.
+    pub async fn fetch_many(
+        &self,
+        video_ids: &[String],
+        languages: &[&str],
+        preserve_formatting: bool,
+        translate: Option<&str>,
+        concurrency: usize,
+        events: &mpsc::UnboundedSender<FetchEvent>,
+    ) -> Vec<FetchOutcome> {
+        stream::iter(video_ids.iter().cloned())
+            .map(|video_id| async move {
+                if StorageService::transcript_exists(&video_id, translate) {
+                    let _ = events.send(FetchEvent::Skipped(video_id.clone()));
+                    return (video_id, Ok(None));
+                }
+
+                let _ = events.send(FetchEvent::Started(video_id.clone()));
+                let result = match self
+                    .fetch_video(&video_id, languages, preserve_formatting, translate)
+                    .await
+                {
+                    Ok(video) => StorageService::save_transcript(&video).await,
+                    Err(e) => Err(e),
+                };
+
+                let _ = events.send(match &result {
+                    Ok(path) => FetchEvent::Saved(video_id.clone(), path.clone()),
+                    Err(e) => FetchEvent::Failed(video_id.clone(), e.to_string()),
+                });
+                (video_id, result.map(Some))
+            })
+            .buffer_unordered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// The transcript without the artifacts `options` names, and how many
+    /// lines that removed. Timestamps are kept: a repeated line is folded
+    /// into the first one, which is stretched to where the repeat ends.
+    pub fn clean_snippets(
+        transcript: &FetchedTranscript,
+        options: CleanOptions,
+    ) -> (FetchedTranscript, usize) {
+        let mut snippets: Vec<FetchedTranscriptSnippet> = Vec::new();
+        for snippet in &transcript.snippets {
+            let mut text = snippet.text.clone();
+            if options.entities {
+                text = decode_entities(&text);
+            }
+            if options.cues {
+                text = strip_cues(&text);
+                if text.is_empty() && !snippet.text.trim().is_empty() {
+                    continue;
+                }
+            }
+            if options.duplicates
+                && let Some(last) = snippets.last_mut()
+                && comparable(&last.text) == comparable(&text)
+            {
+                let end = (snippet.start + snippet.duration).max(last.start + last.duration);
+                last.duration = end - last.start;
+                continue;
+            }
+            snippets.push(FetchedTranscriptSnippet {
+                text,
+                start: snippet.start,
+                duration: snippet.duration,
+            });
         }
+
+        let removed = transcript.snippets.len() - snippets.len();
+        let cleaned = FetchedTranscript {
+            snippets,
+            video_id: transcript.video_id.clone(),
+            language: transcript.language.clone(),
+            language_code: transcript.language_code.clone(),
+            is_generated: transcript.is_generated,
+        };
+        (cleaned, removed)
     }
 
     pub fn format_transcript(transcript: &FetchedTranscript) -> Vec<String> {
@@ -43,9 +518,245 @@ impl TranscriptService {
             })
             .collect()
     }
+
+    /// The captions as running text without timestamps. A pause longer than
+    /// `paragraph_gap` seconds between two captions starts a new paragraph.
+    pub fn format_transcript_plain(transcript: &FetchedTranscript, paragraph_gap: f64) -> String {
+        let text = paragraphs(&transcript.snippets, paragraph_gap);
+        if text.is_empty() {
+            text
+        } else {
+            format!("{text}\n")
+        }
+    }
+
+    /// The captions as Markdown under the video title, with a `## HH:MM:SS`
+    /// heading for each chapter or, for a video without chapters, at every
+    /// pause longer than `paragraph_gap` seconds.
+    pub fn format_transcript_markdown(
+        transcript: &FetchedTranscript,
+        title: Option<&str>,
+        chapters: &[Chapter],
+        paragraph_gap: f64,
+    ) -> String {
+        let snippets = &transcript.snippets;
+        let headings: Vec<(usize, f64, Option<&str>)> = if chapters.is_empty() {
+            paragraph_starts(snippets, paragraph_gap)
+                .into_iter()
+                .map(|index| (index, snippets[index].start, None))
+                .collect()
+        } else {
+            chapter_starts(snippets, chapters)
+                .into_iter()
+                .map(|(index, chapter)| {
+                    let chapter = &chapters[chapter];
+                    (index, chapter.start, Some(chapter.title.as_str()))
+                })
+                .collect()
+        };
+
+        let mut markdown = format!("# {}\n", title.unwrap_or(&transcript.video_id));
+        let starts: Vec<usize> = headings.iter().map(|&(index, _, _)| index).collect();
+        for ((_, start, heading), section) in headings.iter().zip(split_at(snippets, &starts)) {
+            let heading = heading.map(|title| format!(" {title}")).unwrap_or_default();
+            markdown.push_str(&format!(
+                "\n## {}{heading}\n\n{}\n",
+                format_clock(*start),
+                paragraphs(section, paragraph_gap)
+            ));
+        }
+        markdown
+    }
 }
 
-fn format_timestamp(seconds: f64) -> String {
+/// Tell a video that has no transcript to give, which no retry will change,
+/// apart from requests that failed on the way.
+fn fetch_error(e: CouldNotRetrieveTranscript) -> Error {
+    youtube_error("fetch transcript", e)
+}
+
+/// [`fetch_error`] for any request to YouTube; `action` says what failed,
+/// e.g. "list transcripts".
+fn youtube_error(action: &str, e: CouldNotRetrieveTranscript) -> Error {
+    use CouldNotRetrieveTranscriptReason as Reason;
+    let id = &e.video_id;
+    match &e.reason {
+        Some(Reason::TranscriptsDisabled) => {
+            Error::TranscriptUnavailable(format!("This video ({id}) has transcripts disabled"))
+        }
+        Some(Reason::NoTranscriptFound {
+            requested_language_codes,
+            ..
+        }) => Error::TranscriptUnavailable(format!(
+            "This video ({id}) has no transcript in {}",
+            requested_language_codes.join(", ")
+        )),
+        Some(Reason::VideoUnavailable) => Error::TranscriptUnavailable(format!(
+            "This video ({id}) is unavailable; it may be private or removed"
+        )),
+        Some(Reason::AgeRestricted) => Error::TranscriptUnavailable(format!(
+            "This video ({id}) is age-restricted, so its transcript can't be fetched"
+        )),
+        Some(Reason::VideoUnplayable { .. })
+        | Some(Reason::TranslationUnavailable(_))
+        | Some(Reason::TranslationLanguageUnavailable(_)) => {
+            Error::TranscriptUnavailable(format!("Failed to {action}: {e}"))
+        }
+        Some(Reason::InvalidVideoId) => Error::InvalidVideoId(format!("Invalid video ID: {id}")),
+        Some(Reason::YouTubeRequestFailed(_)) => Error::Network(format!("Failed to {action}: {e}")),
+        Some(Reason::IpBlocked(_))
+        | Some(Reason::RequestBlocked(_))
+        | Some(Reason::YouTubeDataUnparsable(_)) => Error::Api(format!("Failed to {action}: {e}")),
+        _ => Error::custom(format!("Failed to {action}: {e}")),
+    }
+}
+
+/// Indices of the captions that start a paragraph: the first one with text,
+/// and each one after a pause longer than `gap` seconds.
+fn paragraph_starts(snippets: &[FetchedTranscriptSnippet], gap: f64) -> Vec<usize> {
+    let mut starts = Vec::new();
+    let mut previous_end: Option<f64> = None;
+    for (index, snippet) in snippets.iter().enumerate() {
+        if snippet.text.trim().is_empty() {
+            continue;
+        }
+        if previous_end.is_none_or(|end| snippet.start - end > gap) {
+            starts.push(index);
+        }
+        previous_end = Some(snippet.start + snippet.duration);
+    }
+    starts
+}
+
+/// `snippets` cut at each of `starts`; anything before the first is dropped.
+fn split_at<'a>(
+    snippets: &'a [FetchedTranscriptSnippet],
+    starts: &'a [usize],
+) -> impl Iterator<Item = &'a [FetchedTranscriptSnippet]> {
+    starts.iter().enumerate().map(move |(i, &start)| {
+        let end = starts.get(i + 1).copied().unwrap_or(snippets.len());
+        &snippets[start..end]
+    })
+}
+
+/// Captions joined into paragraphs separated by blank lines.
+fn paragraphs(snippets: &[FetchedTranscriptSnippet], gap: f64) -> String {
+    let starts = paragraph_starts(snippets, gap);
+    split_at(snippets, &starts)
+        .map(|paragraph| {
+            paragraph
+                .iter()
+                .flat_map(|snippet| snippet.text.split_whitespace())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// `HH:MM:SS`, for headings and video lengths.
+pub fn format_clock(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    format!(
+        "{:02}:{:02}:{:02}",
+        total / 3600,
+        total % 3600 / 60,
+        total % 60
+    )
+}
+
+/// Longest bracketed text taken for a sound cue rather than speech.
+const MAX_CUE_LEN: usize = 30;
+
+/// `text` without sound cues (`[Music]`, `[Aplausos]`) or music notes, and
+/// with the spaces they leave collapsed. Brackets around anything but words,
+/// like `v[0]`, are kept.
+fn strip_cues(text: &str) -> String {
+    let mut kept = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('[') {
+        kept.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        match after.find(']') {
+            Some(close)
+                if close <= MAX_CUE_LEN
+                    && after[..close].chars().all(|c| {
+                        c.is_alphabetic() || c.is_whitespace() || c == '_' || c == '-'
+                    }) =>
+            {
+                rest = &after[close + 1..];
+            }
+            _ => {
+                kept.push('[');
+                rest = after;
+            }
+        }
+    }
+    kept.push_str(rest);
+    kept.replace(['♪', '♫'], " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `text` with HTML entities such as `&amp;`, `&quot;` or `&#39;` decoded.
+/// An `&` that starts no known entity is left as it is.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| Some((decode_entity(&rest[1..=end])?, end + 2)));
+        match entity {
+            Some((c, len)) => {
+                decoded.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let number = name.strip_prefix('#')?;
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+/// A line as compared for repeats: case and punctuation do not count.
+fn comparable(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn format_timestamp(seconds: f64) -> String {
     let total_millis = (seconds * 1000.0).round() as u64;
     let hours = total_millis / 3_600_000;
     let minutes = (total_millis % 3_600_000) / 60_000;
@@ -59,14 +770,103 @@ fn format_timestamp(seconds: f64) -> String {
     }
 }
 
-pub fn extract_video_id(url: &str) -> Option<String> {
-    // Extract video ID from various YouTube URL formats
-    let raw_id = if let Some(v_param) = url.split("v=").nth(1) {
-        v_param.split('&').next().unwrap_or(v_param)
-    } else if let Some(youtu_be) = url.split("youtu.be/").nth(1) {
-        youtu_be.split('?').next().unwrap_or(youtu_be)
+/// Parse a timestamp as written by `format_timestamp` (`MM:SS.mmm` or
+/// `HH:MM:SS.mmm`). Milliseconds are optional and bare seconds are accepted.
+pub fn parse_timestamp(value: &str) -> Option<f64> {
+    let parts: Vec<&str> = value.trim().split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+
+    let (seconds, larger_units) = parts.split_last()?;
+    let seconds: f64 = seconds.parse().ok()?;
+    if !seconds.is_finite() || seconds < 0.0 || (!larger_units.is_empty() && seconds >= 60.0) {
+        return None;
+    }
+
+    let mut total = 0.0;
+    for unit in larger_units {
+        let unit: u32 = unit.parse().ok()?;
+        total = total * 60.0 + f64::from(unit);
+    }
+
+    Some(total * 60.0 + seconds)
+}
+
+/// Start timestamps of the `[start - end]` prefixes written by
+/// `format_transcript`, with the byte offset where each prefix begins.
+pub fn find_timestamps(text: &str) -> Vec<(usize, f64)> {
+    let mut found = Vec::new();
+
+    for (offset, _) in text.match_indices('[') {
+        let rest = &text[offset + 1..];
+        if let Some((start, _)) = rest.split_once(" - ")
+            && start.len() <= 12
+            && start.contains(':')
+            && let Some(seconds) = parse_timestamp(start)
+        {
+            found.push((offset, seconds));
+        }
+    }
+
+    found
+}
+
+/// Hosts that serve videos, after dropping a `www.` or `m.` prefix.
+const YOUTUBE_HOSTS: &[&str] = &[
+    "youtube.com",
+    "music.youtube.com",
+    "youtube-nocookie.com",
+    "youtu.be",
+];
+
+/// Paths on the YouTube hosts that end in a video ID.
+const VIDEO_ID_PATHS: &[&str] = &["shorts/", "live/", "embed/", "v/", "e/"];
+
+/// The video ID from a YouTube URL or a bare ID. Tracking parameters such as
+/// `si=` and `feature=` are ignored; links to other sites give `None`.
+pub fn extract_video_id(input: &str) -> Option<String> {
+    let input = input.trim();
+    if !input.contains(['/', '?', '.']) {
+        return sanitize_video_id(input).ok();
+    }
+
+    let lower = input.to_ascii_lowercase();
+    let without_scheme = ["https://", "http://"]
+        .iter()
+        .find(|scheme| lower.starts_with(*scheme))
+        .map_or(input, |scheme| &input[scheme.len()..]);
+    let host_end = without_scheme
+        .find(['/', '?', '#'])
+        .unwrap_or(without_scheme.len());
+    let (host, rest) = without_scheme.split_at(host_end);
+
+    let host = host.to_ascii_lowercase();
+    let host = host.split(':').next().unwrap_or_default();
+    let host = ["www.", "m."]
+        .iter()
+        .find_map(|prefix| host.strip_prefix(prefix))
+        .unwrap_or(host);
+    if !YOUTUBE_HOSTS.contains(&host) {
+        return None;
+    }
+
+    let rest = rest.split('#').next().unwrap_or_default();
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let path = path.trim_start_matches('/');
+
+    let raw_id = if host == "youtu.be" {
+        path.split('/').next()?
+    } else if path.trim_end_matches('/') == "watch" {
+        query
+            .split('&')
+            .find_map(|param| param.strip_prefix("v="))?
     } else {
-        url
+        VIDEO_ID_PATHS
+            .iter()
+            .find_map(|prefix| path.strip_prefix(prefix))?
+            .split('/')
+            .next()?
     };
 
     sanitize_video_id(raw_id).ok()
@@ -80,28 +880,319 @@ pub fn sanitize_video_id(raw: &str) -> Result<String> {
     let trimmed = raw.trim();
 
     if trimmed.is_empty() {
-        return Err(Error::custom("Video ID cannot be empty"));
+        return Err(Error::InvalidVideoId(
+            "Video ID cannot be empty".to_string(),
+        ));
     }
 
     if trimmed.len() > MAX_VIDEO_ID_LEN {
-        return Err(Error::custom("Video ID is unexpectedly long"));
+        return Err(Error::InvalidVideoId(
+            "Video ID is unexpectedly long".to_string(),
+        ));
     }
 
     if !trimmed
         .chars()
         .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
     {
-        return Err(Error::custom(
-            "Video ID contains unsupported characters; expected only letters, numbers, '-' or '_'",
+        return Err(Error::InvalidVideoId(
+            "Video ID contains unsupported characters; expected only letters, numbers, '-' or '_'"
+                .to_string(),
         ));
     }
 
     Ok(trimmed.to_string())
 }
 
+/// A language code such as `es` or `pt-BR`, as used in translated transcript
+/// file names.
+pub fn sanitize_language(raw: &str) -> Result<String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty()
+        || trimmed.len() > MAX_LANGUAGE_CODE_LEN
+        || !trimmed
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(Error::custom(format!(
+            "Invalid language code '{trimmed}'; expected something like 'es' or 'pt-BR'"
+        )));
+    }
+    Ok(trimmed.to_string())
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{MAX_VIDEO_ID_LEN, sanitize_video_id};
+    use super::{
+        Chapter, CleanOptions, DEFAULT_PARAGRAPH_GAP, FetchedVideo, MAX_VIDEO_ID_LEN,
+        TranscriptFormat, TranscriptService, VideoMetadata, extract_video_id, fetch_error,
+        find_timestamps, format_timestamp, parse_timestamp, sanitize_language, sanitize_video_id,
+    };
+    use crate::core::report::front_matter;
+    use crate::error::Error;
+    use yt_transcript_rs::errors::{CouldNotRetrieveTranscript, CouldNotRetrieveTranscriptReason};
+    use yt_transcript_rs::{FetchedTranscript, FetchedTranscriptSnippet};
+
+    fn captions(lines: &[(f64, &str)]) -> FetchedTranscript {
+        FetchedTranscript {
+            snippets: lines
+                .iter()
+                .map(|&(start, text)| FetchedTranscriptSnippet {
+                    text: text.to_string(),
+                    start,
+                    duration: 2.0,
+                })
+                .collect(),
+            video_id: "5_EJwYeQusM".to_string(),
+            language: "English (auto-generated)".to_string(),
+            language_code: "en".to_string(),
+            is_generated: true,
+        }
+    }
+
+    fn clean(lines: &[(f64, &str)], options: CleanOptions) -> (Vec<String>, usize) {
+        let (cleaned, removed) = TranscriptService::clean_snippets(&captions(lines), options);
+        (TranscriptService::format_transcript(&cleaned), removed)
+    }
+
+    #[test]
+    fn sound_cues_are_removed() {
+        let cues = CleanOptions {
+            cues: true,
+            ..CleanOptions::NONE
+        };
+        let (lines, removed) = clean(
+            &[
+                (0.0, "[Music]"),
+                (2.0, "♪ la la ♪ welcome [Applause] back"),
+                (4.0, "read v[0] first"),
+            ],
+            cues,
+        );
+        assert_eq!(
+            lines,
+            [
+                "[00:02.000 - 00:04.000] la la welcome back",
+                "[00:04.000 - 00:06.000] read v[0] first",
+            ]
+        );
+        assert_eq!(removed, 1);
+    }
+
+    #[test]
+    fn repeated_lines_fold_into_the_first() {
+        let duplicates = CleanOptions {
+            duplicates: true,
+            ..CleanOptions::NONE
+        };
+        let (lines, removed) = clean(
+            &[
+                (0.0, "so today we"),
+                (1.0, "So today we."),
+                (3.0, "so today we"),
+                (6.0, "talk about Rust"),
+                (8.0, "so today we"),
+            ],
+            duplicates,
+        );
+        assert_eq!(
+            lines,
+            [
+                "[00:00.000 - 00:05.000] so today we",
+                "[00:06.000 - 00:08.000] talk about Rust",
+                "[00:08.000 - 00:10.000] so today we",
+            ]
+        );
+        assert_eq!(removed, 2);
+    }
+
+    #[test]
+    fn html_entities_are_decoded() {
+        let entities = CleanOptions {
+            entities: true,
+            ..CleanOptions::NONE
+        };
+        let (lines, removed) = clean(
+            &[(
+                0.0,
+                "it&#39;s Tom &amp; Jerry&#x21; &quot;a&lt;b&quot; R&D &bogus;",
+            )],
+            entities,
+        );
+        assert_eq!(
+            lines,
+            ["[00:00.000 - 00:02.000] it's Tom & Jerry! \"a<b\" R&D &bogus;"]
+        );
+        assert_eq!(removed, 0);
+    }
+
+    #[test]
+    fn cleaning_is_chosen_step_by_step() {
+        assert_eq!(CleanOptions::parse("all").unwrap(), CleanOptions::default());
+        assert_eq!(CleanOptions::parse("none").unwrap(), CleanOptions::NONE);
+        let some = CleanOptions::parse("cues, entities").unwrap();
+        assert!(some.cues && some.entities && !some.duplicates);
+        assert!(CleanOptions::parse("music").is_err());
+
+        let untouched = [(0.0, "[Music]"), (2.0, "[Music]"), (4.0, "&amp;")];
+        let (lines, removed) = clean(&untouched, CleanOptions::NONE);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(removed, 0);
+    }
+
+    #[test]
+    fn plain_text_breaks_paragraphs_at_long_pauses() {
+        // Each caption lasts two seconds: 4.0 to 7.0 is a three second pause
+        let transcript = captions(&[
+            (0.0, "Hello  there."),
+            (2.0, "How are\nyou?"),
+            (7.0, "New topic."),
+            (9.0, " "),
+            (9.5, "Same one."),
+        ]);
+        assert_eq!(
+            TranscriptService::format_transcript_plain(&transcript, 2.0),
+            "Hello there. How are you?\n\nNew topic. Same one.\n"
+        );
+        assert_eq!(
+            TranscriptService::format_transcript_plain(&transcript, 3.0),
+            "Hello there. How are you? New topic. Same one.\n"
+        );
+        assert_eq!(
+            TranscriptFormat::parse("Plain").unwrap(),
+            TranscriptFormat::Plain
+        );
+        assert!(TranscriptFormat::parse("srt").is_err());
+    }
+
+    #[test]
+    fn saved_text_opens_with_the_known_metadata() {
+        let mut video = FetchedVideo {
+            transcript: captions(&[(0.0, "Welcome.")]),
+            metadata: VideoMetadata {
+                title: Some("Rust: the\nbasics".to_string()),
+                author: Some("Rustacean".to_string()),
+                channel_id: None,
+                duration_secs: Some(754),
+                upload_date: Some("2025-03-01".to_string()),
+            },
+            translation: None,
+            removed_lines: 0,
+            chapters: Vec::new(),
+            format: TranscriptFormat::Timestamped,
+            paragraph_gap: DEFAULT_PARAGRAPH_GAP,
+        };
+        let text = video.saved_text();
+        assert_eq!(
+            text,
+            "---\ntitle: Rust: the basics\nauthor: Rustacean\nduration_secs: 754\nupload_date: 2025-03-01\n---\n\n[00:00.000 - 00:02.000] Welcome."
+        );
+        let (fields, body) = front_matter::split(&text);
+        assert_eq!(fields[0], ("title", "Rust: the basics"));
+        assert_eq!(body, "[00:00.000 - 00:02.000] Welcome.");
+        assert_eq!(video.metadata.length().as_deref(), Some("00:12:34"));
+
+        video.metadata = VideoMetadata::default();
+        assert_eq!(video.saved_text(), "[00:00.000 - 00:02.000] Welcome.");
+    }
+
+    #[test]
+    fn markdown_heads_chapters_or_pauses() {
+        let transcript = captions(&[
+            (0.0, "Welcome."),
+            (2.0, "Let's start."),
+            (65.0, "First step."),
+            (67.0, "Then the second."),
+        ]);
+        assert_eq!(
+            TranscriptService::format_transcript_markdown(&transcript, Some("Demo"), &[], 2.0),
+            "# Demo\n\n## 00:00:00\n\nWelcome. Let's start.\n\n## 00:01:05\n\nFirst step. Then the second.\n"
+        );
+
+        let chapters = [
+            Chapter {
+                start: 0.0,
+                title: "Intro".to_string(),
+            },
+            Chapter {
+                start: 67.0,
+                title: "Steps".to_string(),
+            },
+        ];
+        assert_eq!(
+            TranscriptService::format_transcript_markdown(&transcript, None, &chapters, 2.0),
+            "# 5_EJwYeQusM\n\n## 00:00:00 Intro\n\nWelcome. Let's start.\n\nFirst step.\n\n## 00:01:07 Steps\n\nThen the second.\n"
+        );
+    }
+
+    #[test]
+    fn extracts_ids_from_youtube_url_shapes() {
+        let cases = [
+            ("5_EJwYeQusM", Some("5_EJwYeQusM")),
+            (
+                "https://www.youtube.com/watch?v=5_EJwYeQusM",
+                Some("5_EJwYeQusM"),
+            ),
+            (
+                "https://www.youtube.com/watch?feature=share&v=5_EJwYeQusM&t=42s",
+                Some("5_EJwYeQusM"),
+            ),
+            ("youtube.com/watch?v=5_EJwYeQusM", Some("5_EJwYeQusM")),
+            (
+                "https://m.youtube.com/watch?v=5_EJwYeQusM",
+                Some("5_EJwYeQusM"),
+            ),
+            (
+                "https://music.youtube.com/watch?v=5_EJwYeQusM&si=x1",
+                Some("5_EJwYeQusM"),
+            ),
+            (
+                "https://youtu.be/5_EJwYeQusM?si=AbCdEf",
+                Some("5_EJwYeQusM"),
+            ),
+            (
+                "HTTPS://WWW.YOUTUBE.COM/watch?v=5_EJwYeQusM",
+                Some("5_EJwYeQusM"),
+            ),
+            ("https://YouTu.be/5_EJwYeQusM/", Some("5_EJwYeQusM")),
+            (
+                "https://www.youtube.com/shorts/5_EJwYeQusM",
+                Some("5_EJwYeQusM"),
+            ),
+            (
+                "https://youtube.com/shorts/5_EJwYeQusM/?feature=share",
+                Some("5_EJwYeQusM"),
+            ),
+            (
+                "https://www.youtube.com/live/5_EJwYeQusM?si=tracking",
+                Some("5_EJwYeQusM"),
+            ),
+            (
+                "https://www.youtube.com/embed/5_EJwYeQusM?start=10",
+                Some("5_EJwYeQusM"),
+            ),
+            (
+                "https://www.youtube-nocookie.com/embed/5_EJwYeQusM",
+                Some("5_EJwYeQusM"),
+            ),
+            (
+                "http://www.youtube.com:443/watch/?v=5_EJwYeQusM#t=1",
+                Some("5_EJwYeQusM"),
+            ),
+            ("https://vimeo.com/watch?v=5_EJwYeQusM", None),
+            ("https://example.com/youtu.be/5_EJwYeQusM", None),
+            ("https://www.youtube.com/@rustlang", None),
+            ("https://www.youtube.com/watch?list=PL123", None),
+            ("https://youtu.be/", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                extract_video_id(input).as_deref(),
+                expected,
+                "input: {input}"
+            );
+        }
+    }
 
     #[test]
     fn allows_expected_characters() {
@@ -116,7 +1207,9 @@ mod tests {
 
     #[test]
     fn rejects_invalid_chars() {
-        assert!(sanitize_video_id("abc/../../etc").is_err());
+        let error = sanitize_video_id("abc/../../etc").unwrap_err();
+        assert!(matches!(error, Error::InvalidVideoId(_)), "{error:?}");
+        assert!(error.to_string().starts_with("Video ID contains"));
     }
 
     #[test]
@@ -124,4 +1217,65 @@ mod tests {
         let long = "a".repeat(MAX_VIDEO_ID_LEN + 1);
         assert!(sanitize_video_id(&long).is_err());
     }
+
+    #[test]
+    fn disabled_transcripts_are_not_a_fetch_failure() {
+        let failure = |reason| CouldNotRetrieveTranscript {
+            video_id: "dQw4w9WgXcQ".to_string(),
+            reason: Some(reason),
+        };
+
+        let error = fetch_error(failure(
+            CouldNotRetrieveTranscriptReason::TranscriptsDisabled,
+        ));
+        assert!(
+            matches!(error, Error::TranscriptUnavailable(_)),
+            "{error:?}"
+        );
+        assert_eq!(
+            error.to_string(),
+            "This video (dQw4w9WgXcQ) has transcripts disabled"
+        );
+
+        let error = fetch_error(failure(
+            CouldNotRetrieveTranscriptReason::YouTubeRequestFailed("timed out".to_string()),
+        ));
+        assert!(matches!(error, Error::Network(_)), "{error:?}");
+        assert!(error.is_transient());
+
+        let error = fetch_error(failure(CouldNotRetrieveTranscriptReason::RequestBlocked(
+            None,
+        )));
+        assert!(matches!(error, Error::Api(_)), "{error:?}");
+        assert!(!error.is_transient());
+    }
+
+    #[test]
+    fn language_codes_are_safe_in_file_names() {
+        assert_eq!(sanitize_language(" pt-BR ").unwrap(), "pt-BR");
+        for bad in ["", "es.txt", "../es", "es_ES", "a-very-long-language"] {
+            assert!(sanitize_language(bad).is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn parse_timestamp_round_trips_formatted_values() {
+        for seconds in [0.0, 4.2, 872.5, 3_723.042] {
+            let parsed = parse_timestamp(&format_timestamp(seconds)).expect("parses");
+            assert!((parsed - seconds).abs() < 0.001);
+        }
+        assert_eq!(parse_timestamp("14:32"), Some(872.0));
+        assert_eq!(parse_timestamp("90"), Some(90.0));
+        assert_eq!(parse_timestamp("1:75"), None);
+        assert_eq!(parse_timestamp("a:10"), None);
+    }
+
+    #[test]
+    fn finds_snippet_prefixes_in_text() {
+        let text = "[00:01.000 - 00:02.000] Hola [link] [01:00:00.500 - 01:00:01.000] adiós";
+        let found = find_timestamps(text);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0], (0, 1.0));
+        assert_eq!(found[1].1, 3_600.5);
+    }
 }
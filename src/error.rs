@@ -1,14 +1,27 @@
+use crate::core::report::retry;
 use async_openai::error::OpenAIError;
 use derive_more::{Display, From};
 use yt_transcript_rs::errors::CookieError;
 
 pub type Result<T> = core::result::Result<T, Error>;
 
+/// Every variant displays as its message alone; the variant says what kind
+/// of failure it was, so callers can react to it.
 #[derive(Debug, Display, From)]
-#[display("{self:?}")]
 pub enum Error {
     #[from(String, &String, &str)]
     Custom(String),
+    /// No transcript the user would accept, e.g. only auto-generated captions
+    /// with `--manual-only`.
+    TranscriptUnavailable(String),
+    /// Neither a YouTube URL nor a well-formed video ID.
+    InvalidVideoId(String),
+    /// A server could not be reached at all, or stopped answering midway.
+    Network(String),
+    /// A server answered, but with an error or something unreadable.
+    Api(String),
+    /// Transcripts may only be sent to the report server once the user says so.
+    OptInRequired(String),
 
     // -- Externals
     #[from]
@@ -28,6 +41,16 @@ impl Error {
     pub fn custom(val: impl Into<String>) -> Self {
         Self::Custom(val.into())
     }
+
+    /// Whether trying again later may succeed: the connection failed, or
+    /// the report server had a passing problem.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Network(_) => true,
+            Self::OpenAi(e) => retry::is_transient(e),
+            _ => false,
+        }
+    }
 }
 
 impl From<OpenAIError> for Error {
@@ -3,39 +3,253 @@ mod core;
 mod error;
 mod tui;
 
-use crate::cli::{Cli, Commands};
+use crate::cli::render::{self, Output};
+use crate::cli::{ChannelsCommand, Cli, Commands, TrashCommand};
+use crate::core::batch::{BatchManifest, BatchStatus};
+use crate::core::channels::{
+    ChannelClient, ChannelStore, SYNC_DELAY, SyncCursor, Upload, pending_uploads,
+};
+use crate::core::lint::LintProfile;
+use crate::core::media::{self, SubtitleStream};
+use crate::core::metadata::{AUTO_GENERATED_WARNING, TranscriptMetadata, TranscriptSource};
+use crate::core::pipeline::{
+    self, CANCEL_GRACE, FileStorage, Job, Outcome, Progress, Services, Stage,
+};
+use crate::core::prompt_test::{
+    PromptTestOptions, RESULTS_FILE as PROMPT_TEST_RESULTS_FILE, append_record, run_prompt_test,
+};
+use crate::core::recovery::{self, ConflictSide};
+use crate::core::report::front_matter;
+use crate::core::searches::SavedSearches;
+use crate::core::settings::{ReportConfig, Settings};
+use crate::core::site;
+use crate::core::transcript::format_clock;
 use crate::core::{
-    ReportService, StorageService, TranscriptService, extract_video_id, sanitize_video_id,
+    BookmarkList, CleanOptions, Config, CostEstimate, DEFAULT_MODEL, DEFAULT_PARAGRAPH_GAP, Effort,
+    FetchEvent, FetchedVideo, ListOptions, OpenAiConfig, PromptSource, PromptVideo, ReportService,
+    ReportTemplate, StorageService, StorageStats, TranscriptFormat, TranscriptService, UrlHistory,
+    VideoMetadata, configured_model, extract_video_id, format_size, format_timestamp,
+    parse_timestamp, report_language, sanitize_video_id, slugify,
 };
 use crate::error::Result;
-use crate::tui::{App, EventHandler, init as tui_init, restore as tui_restore, ui};
+use crate::tui::theme::saved_theme;
+use crate::tui::{App, EventHandler, Theme, init as tui_init, restore as tui_restore, ui};
+use chrono::{Local, Utc};
 use clap::Parser;
-use tokio::sync::mpsc;
+use log::{info, warn};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use tokio::sync::{mpsc, oneshot};
+use tokio_util::sync::CancellationToken;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    // The TUI takes log records itself; printing them would garble the screen
+    if !matches!(cli.command, Some(Commands::Tui) | None) || cli.cli {
+        cli::logging::init(cli.quiet, cli.verbose);
+    }
+    // Not while the user is looking at or restoring from the trash, where a
+    // file they just listed could vanish before they restore it
+    if !matches!(cli.command, Some(Commands::Trash { .. })) {
+        purge_expired_trash();
+    }
 
     match cli.command {
         Some(Commands::Get {
-            video_id,
+            mut video_ids,
+            jobs,
             languages,
             preserve_formatting,
+            translate,
+            manual_only,
+            clean,
+            format,
+            paragraph_gap,
             report,
+            template,
+            prompt_file,
+            report_language,
+            model,
+            reasoning_effort,
+            max_output_tokens,
+            yes,
+            force,
+            resume,
+            dry_run,
+        }) => {
+            let fetch = FetchOptions {
+                languages: languages.split(',').map(|s| s.trim().to_string()).collect(),
+                preserve_formatting,
+                translate,
+                manual_only,
+                clean,
+                format,
+                paragraph_gap,
+            };
+            // Report options only matter when a report is generated; a custom
+            // prompt is read before anything is fetched
+            let report = if report || prompt_file.is_some() {
+                let prompt = PromptSource::resolve(template, prompt_file.as_deref())?;
+                Some(ReportOptions::new(
+                    prompt,
+                    report_language,
+                    model,
+                    reasoning_effort,
+                    max_output_tokens,
+                    yes,
+                )?)
+            } else {
+                None
+            };
+            if dry_run {
+                let report = report.ok_or_else(|| {
+                    error::Error::custom("--dry-run shows report prompts; add --report")
+                })?;
+                run_cli_get_dry_run(video_ids, jobs, fetch, report).await?;
+            } else if resume.is_none() && video_ids.len() == 1 {
+                let video_id = video_ids.remove(0);
+                run_cli_get(video_id, fetch, report, force).await?;
+            } else {
+                let manifest = open_batch(&video_ids, resume, report.is_some())?;
+                run_cli_get_many(manifest, jobs, fetch, report, force).await?;
+            }
+        }
+        Some(Commands::Report {
+            video_id,
+            template,
+            prompt_file,
+            report_language,
+            model,
+            reasoning_effort,
+            max_output_tokens,
+            yes,
+            dry_run,
+            force,
+        }) => {
+            let prompt = PromptSource::resolve(template, prompt_file.as_deref())?;
+            let report = ReportOptions::new(
+                prompt,
+                report_language,
+                model,
+                reasoning_effort,
+                max_output_tokens,
+                yes,
+            )?;
+            if dry_run {
+                print_prompts(&sanitize_video_id(&video_id)?, &report, None).await?;
+            } else {
+                run_cli_report(video_id, report, force).await?;
+            }
+        }
+        Some(Commands::List {
+            saved,
+            since,
+            until,
+            file_type,
         }) => {
-            run_cli_get(video_id, languages, preserve_formatting, report).await?;
+            let options = ListOptions {
+                since,
+                until,
+                file_type,
+            };
+            run_cli_list(saved, options, cli.json)?;
         }
-        Some(Commands::Report { video_id }) => {
-            run_cli_report(video_id).await?;
+        Some(Commands::Show {
+            video_id,
+            render,
+            template,
+        }) => {
+            run_cli_show(video_id, render, template, Output::from_env(cli.no_pager)).await?;
         }
-        Some(Commands::List) => {
-            run_cli_list()?;
+        Some(Commands::Diff {
+            video_id,
+            against,
+            template,
+        }) => {
+            run_cli_diff(video_id, against, template, Output::from_env(cli.no_pager)).await?;
+        }
+        Some(Commands::Info { video_id }) => {
+            run_cli_info(video_id, cli.json)?;
+        }
+        Some(Commands::Delete {
+            video_id,
+            transcript_only,
+            report_only,
+            yes,
+        }) => {
+            run_cli_delete(video_id, transcript_only, report_only, yes)?;
+        }
+        Some(Commands::Rename {
+            video_id,
+            new_stem,
+            use_title: _,
+        }) => {
+            run_cli_rename(video_id, new_stem)?;
+        }
+        Some(Commands::Clean {
+            older_than,
+            orphans,
+            dry_run,
+            yes,
+        }) => {
+            run_cli_clean(older_than, orphans, dry_run, yes)?;
+        }
+        Some(Commands::Trash { action }) => {
+            run_cli_trash(action, cli.json)?;
+        }
+        Some(Commands::History { clear }) => {
+            run_cli_history(clear)?;
+        }
+        Some(Commands::Stats) => {
+            run_cli_stats(cli.json)?;
+        }
+        Some(Commands::ExportSite {
+            dest,
+            engine,
+            prune,
+        }) => {
+            run_cli_export_site(dest, engine, prune)?;
+        }
+        Some(Commands::Repair {
+            video_id,
+            keep,
+            template,
+        }) => {
+            run_cli_repair(video_id, keep, template).await?;
+        }
+        Some(Commands::ImportMedia {
+            video_id,
+            path,
+            stream,
+        }) => {
+            run_cli_import_media(video_id, path, stream).await?;
+        }
+        Some(Commands::Channels { action }) => {
+            run_cli_channels(action, cli.json).await?;
+        }
+        Some(Commands::Bookmarks {
+            video_id,
+            add,
+            note,
+            list: _,
+            remove,
+        }) => {
+            run_cli_bookmarks(video_id, add, note, remove)?;
+        }
+        Some(Commands::PromptTest {
+            template,
+            fixture,
+            canned,
+            expect,
+        }) => {
+            run_cli_prompt_test(template, fixture, canned, expect).await?;
         }
         Some(Commands::Tui) | None => {
             if cli.cli {
                 println!("Use 'vidio --help' for available commands");
             } else {
-                run_tui().await?;
+                run_tui(cli.ascii).await?;
             }
         }
     }
@@ -43,90 +257,637 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// How `get` fetches transcripts.
+struct FetchOptions {
+    /// Preferred transcript languages, in order.
+    languages: Vec<String>,
+    preserve_formatting: bool,
+    /// Save a machine translation into this language instead.
+    translate: Option<String>,
+    /// Fail rather than fall back to auto-generated captions.
+    manual_only: bool,
+    clean: CleanOptions,
+    /// Also save a copy in this format, with paragraphs at pauses this long.
+    format: TranscriptFormat,
+    paragraph_gap: f64,
+}
+
+impl FetchOptions {
+    fn service(&self) -> Result<TranscriptService> {
+        Ok(TranscriptService::new()?
+            .manual_only(self.manual_only)
+            .clean(self.clean)
+            .format(self.format, self.paragraph_gap))
+    }
+
+    fn languages(&self) -> Vec<&str> {
+        self.languages.iter().map(String::as_str).collect()
+    }
+}
+
+/// How `get` and `report` generate reports.
+struct ReportOptions {
+    prompt: PromptSource,
+    /// `None` follows each transcript's language.
+    language: Option<String>,
+    model: String,
+    endpoint: OpenAiConfig,
+    settings: ReportConfig,
+    /// Skip the cost confirmation.
+    yes: bool,
+}
+
+impl ReportOptions {
+    /// Fill in what the command line leaves out from Settings and
+    /// `vidio.toml`. A missing language follows each transcript's.
+    fn new(
+        prompt: PromptSource,
+        language: Option<String>,
+        model: Option<String>,
+        reasoning_effort: Option<Effort>,
+        max_output_tokens: Option<u32>,
+        yes: bool,
+    ) -> Result<Self> {
+        let settings = Settings::load()?.report;
+        let config = Config::load()?;
+        let model = configured_model(model, &config);
+        // The command line wins over vidio.toml
+        let mut endpoint = config.openai;
+        endpoint.reasoning_effort = reasoning_effort.or(endpoint.reasoning_effort);
+        endpoint.max_output_tokens = max_output_tokens.or(endpoint.max_output_tokens);
+        Ok(Self {
+            prompt,
+            language: language.or_else(|| settings.language.clone()),
+            model,
+            endpoint,
+            settings,
+            yes,
+        })
+    }
+
+    fn service(&self) -> ReportService {
+        ReportService::new(&self.model, &self.endpoint, &self.settings)
+    }
+}
+
+/// Show a report's estimated cost and ask before paying it, unless `yes`
+/// or it is free. A batch labels it with the video it is for.
+fn approve_cost(estimate: &CostEstimate, yes: bool, video_id: Option<&str>) -> Result<bool> {
+    let free = estimate.is_free();
+    let estimate = match video_id {
+        Some(video_id) => format!("[{video_id}] {estimate}"),
+        None => estimate.to_string(),
+    };
+    if yes || free {
+        info!("{estimate}");
+        return Ok(true);
+    }
+    confirm(&format!("{estimate} — continue?"))
+}
+
 async fn run_cli_get(
     video_input: String,
-    languages: String,
-    preserve_formatting: bool,
-    generate_report: bool,
+    fetch: FetchOptions,
+    report: Option<ReportOptions>,
+    force: bool,
 ) -> Result<()> {
+    let generate_report = report.is_some();
     let video_id = extract_video_id(&video_input)
-        .ok_or_else(|| error::Error::custom("Invalid video URL or ID"))?;
+        .ok_or_else(|| error::Error::InvalidVideoId("Invalid video URL or ID".to_string()))?;
 
-    println!("Processing video: {video_id}");
+    info!("Processing video: {video_id}");
 
-    let transcript_service = TranscriptService::new()?;
-    let report_service = ReportService::new();
+    let transcript_service = fetch.service()?;
+    let (report_service, yes) = match &report {
+        Some(report) => (report.service(), report.yes),
+        None => (
+            ReportService::new(
+                DEFAULT_MODEL,
+                &OpenAiConfig::default(),
+                &ReportConfig::default(),
+            ),
+            false,
+        ),
+    };
+    // A report that is bound to fail is better known before the fetch
+    if generate_report {
+        report_service.check_ready().await?;
+    }
+    let (prompt, report_language) = match report {
+        Some(report) => (report.prompt, report.language),
+        None => (PromptSource::default(), None),
+    };
+    let approve = |estimate: CostEstimate| {
+        let (reply, answer) = oneshot::channel();
+        let _ = reply.send(approve_cost(&estimate, yes, None));
+        answer
+    };
+    let services = Services {
+        transcript: &transcript_service,
+        report: &report_service,
+        approve: &approve,
+        on_chunk: &|progress| info!("{progress}"),
+        on_text: &|_| {},
+    };
+    let job = Job {
+        video_id,
+        languages: fetch.languages,
+        preserve_formatting: fetch.preserve_formatting,
+        generate_report,
+        prompt,
+        report_language,
+        translate: fetch.translate,
+        overwrite_report: force,
+    };
 
-    let languages: Vec<&str> = languages.split(',').map(|s| s.trim()).collect();
+    // First Ctrl-C stops at the next safe point; a second one, or the grace
+    // period running out, exits straight away
+    let token = CancellationToken::new();
+    let interrupt = tokio::spawn({
+        let token = token.clone();
+        async move {
+            if tokio::signal::ctrl_c().await.is_err() {
+                return;
+            }
+            warn!("Cancelling... (press Ctrl-C again to quit now)");
+            token.cancel();
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = tokio::time::sleep(CANCEL_GRACE) => {}
+            }
+            std::process::exit(130);
+        }
+    });
 
-    let transcript_exists = StorageService::transcript_exists(&video_id);
-    let report_exists = StorageService::report_exists(&video_id);
-    let needs_report = generate_report && !report_exists;
+    let mut print_progress = |event: Progress| match event {
+        Progress::Started(Stage::Fetch) => info!("Fetching transcript..."),
+        Progress::Skipped(Stage::Fetch) => info!("Transcript already saved. Skipping download."),
+        // A translation's sidecar describes the original, not it
+        Progress::Finished(Stage::SaveTranscript)
+            if job.translate.is_none() && TranscriptMetadata::is_auto_generated(&job.video_id) =>
+        {
+            warn!("{AUTO_GENERATED_WARNING}; use --manual-only to refuse them.")
+        }
+        Progress::Started(Stage::GenerateReport) => info!("Generating report..."),
+        Progress::Skipped(Stage::GenerateReport) if generate_report => {
+            info!("Report up to date. Skipping generation.")
+        }
+        _ => {}
+    };
+    let result =
+        pipeline::run_job(&FileStorage, &services, &job, &token, &mut print_progress).await;
+    interrupt.abort();
 
-    if transcript_exists && !needs_report {
-        println!("Transcript already exists locally. Skipping processing.");
+    if result? == Outcome::AlreadyProcessed {
+        info!("Transcript already exists locally. Skipping processing.");
         if generate_report {
-            println!("Report already exists as well.");
+            info!("Report up to date as well.");
         }
-        return Ok(());
     }
 
-    let mut fetched_transcript = None;
+    Ok(())
+}
 
-    // Fetch transcript
-    if !transcript_exists {
-        println!("Fetching transcript...");
-        let transcript = transcript_service
-            .fetch_transcript(&video_id, &languages, preserve_formatting)
-            .await?;
+/// The video ids of `video_inputs` in order, each once.
+fn unique_video_ids(video_inputs: &[String]) -> Result<Vec<String>> {
+    let mut video_ids: Vec<String> = Vec::new();
+    for input in video_inputs {
+        let video_id = extract_video_id(input).ok_or_else(|| {
+            error::Error::InvalidVideoId(format!("Invalid video URL or ID: {input}"))
+        })?;
+        if !video_ids.contains(&video_id) {
+            video_ids.push(video_id);
+        }
+    }
+    Ok(video_ids)
+}
 
-        let transcript_path = StorageService::save_transcript(&transcript).await?;
-        println!("Transcript saved to: {transcript_path:?}");
-        fetched_transcript = Some(transcript);
+/// The manifest of a new batch over `video_inputs`, or of the interrupted
+/// one being resumed: the given file, or the newest unfinished one when
+/// `--resume` has no value.
+fn open_batch(
+    video_inputs: &[String],
+    resume: Option<Option<PathBuf>>,
+    report: bool,
+) -> Result<BatchManifest> {
+    let data_dir = StorageService::data_dir()?;
+    let manifest = match resume {
+        None => BatchManifest::new(&data_dir, &unique_video_ids(video_inputs)?, report),
+        Some(None) => BatchManifest::latest_incomplete(&data_dir)?
+            .ok_or_else(|| error::Error::custom("No unfinished batch to resume"))?,
+        Some(Some(path)) => BatchManifest::load(&path)?,
+    };
+    // What is left depends on whether the batch generates reports, so a
+    // resume keeps to what the first run was asked for
+    if manifest.report != report {
+        let flag = if manifest.report { "with" } else { "without" };
+        return Err(error::Error::custom(format!(
+            "{} was started {flag} --report; resume it {flag} --report",
+            manifest.path.display()
+        )));
+    }
+    manifest.save()?;
+    Ok(manifest)
+}
+
+async fn run_cli_get_many(
+    mut manifest: BatchManifest,
+    jobs: usize,
+    fetch: FetchOptions,
+    report: Option<ReportOptions>,
+    force: bool,
+) -> Result<()> {
+    // As for a single video, a report bound to fail is better known before
+    // any of the fetches
+    if let Some(report) = &report {
+        report.service().check_ready().await?;
+    }
+
+    let video_ids = manifest.remaining();
+    let done = manifest.videos.len() - video_ids.len();
+    if done > 0 {
+        info!(
+            "Resuming {}: {done} of {} videos already done",
+            manifest.path.display(),
+            manifest.videos.len()
+        );
+    }
+    info!(
+        "Processing {} videos ({} at a time)",
+        video_ids.len(),
+        jobs.max(1)
+    );
+
+    let transcript_service = fetch.service()?;
+    let translate = fetch.translate.as_deref();
+
+    // The manifest is updated as each download ends, so an interrupted run
+    // knows what it got
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let progress = tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            let update = match event {
+                FetchEvent::Started(id) => {
+                    info!("[{id}] Fetching transcript...");
+                    None
+                }
+                FetchEvent::Skipped(id) => {
+                    info!("[{id}] Transcript already saved. Skipping.");
+                    Some((id, BatchStatus::Fetched))
+                }
+                FetchEvent::Saved(id, path) => {
+                    info!("[{id}] Saved to {path:?}");
+                    Some((id, BatchStatus::Fetched))
+                }
+                FetchEvent::Failed(id, error) => {
+                    warn!("[{id}] Failed: {error}");
+                    Some((id, BatchStatus::Failed { error }))
+                }
+            };
+            if let Some((id, status)) = update {
+                record(&mut manifest, &id, status);
+            }
+        }
+        manifest
+    });
+
+    let outcomes = transcript_service
+        .fetch_many(
+            &video_ids,
+            &fetch.languages(),
+            fetch.preserve_formatting,
+            translate,
+            jobs,
+            &tx,
+        )
+        .await;
+    drop(tx);
+    let mut manifest = progress
+        .await
+        .map_err(|e| error::Error::custom(format!("Batch progress task failed: {e}")))?;
+
+    let failed: Vec<&String> = outcomes
+        .iter()
+        .filter(|(_, result)| result.is_err())
+        .map(|(id, _)| id)
+        .collect();
+
+    // Reports go one at a time: they are long model calls and share a quota
+    if let Some(report) = report {
+        let template = report.prompt.template();
+        let report_service = report.service();
+        for video_id in video_ids.iter().filter(|id| !failed.contains(id)) {
+            let exists = StorageService::report_exists(video_id, template);
+            let (title, language, removed_lines) =
+                saved_video_details(video_id, report.language.as_deref(), translate);
+            let inputs = report_service.inputs(&report.prompt, &language);
+            let stale = exists
+                && StorageService::report_is_stale(video_id, template, translate, &inputs)
+                    .unwrap_or(false);
+            if exists && !stale && !force {
+                info!("[{video_id}] Report up to date. Skipping generation.");
+                record(&mut manifest, video_id, BatchStatus::Reported);
+                continue;
+            }
+            let generated = async {
+                let transcript_content =
+                    StorageService::load_transcript(video_id, translate).await?;
+                let video = PromptVideo {
+                    video_id,
+                    title: title.as_deref(),
+                    language: &language,
+                    removed_lines,
+                };
+                let estimate =
+                    report_service.estimate(&transcript_content, &report.prompt, video)?;
+                if !approve_cost(&estimate, report.yes, Some(video_id))? {
+                    info!("[{video_id}] Skipping the report.");
+                    return Ok(false);
+                }
+                info!("[{video_id}] Generating report...");
+                let report_content = report_service
+                    .generate_report_text(&transcript_content, &report.prompt, video, &|progress| {
+                        info!("[{video_id}] {progress}")
+                    })
+                    .await?;
+                // The replaced report goes to history, and comes back if saving fails
+                let rotated = if exists {
+                    Some(StorageService::rotate_report(video_id, template).await?)
+                } else {
+                    None
+                };
+                if let Err(e) =
+                    StorageService::save_report(video_id, template, &report_content).await
+                {
+                    if let Some(rotated) = rotated {
+                        let _ = StorageService::restore_report(video_id, template, &rotated).await;
+                    }
+                    return Err(e);
+                }
+                Ok(true)
+            }
+            .await;
+            match generated {
+                Ok(true) => record(&mut manifest, video_id, BatchStatus::Reported),
+                Ok(false) => {}
+                Err(e) => {
+                    let error = e.to_string();
+                    record(&mut manifest, video_id, BatchStatus::Failed { error });
+                    warn!(
+                        "Resume with: vidio get --resume {}",
+                        manifest.path.display()
+                    );
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    if !manifest.is_complete() {
+        warn!(
+            "Resume with: vidio get --resume {}",
+            manifest.path.display()
+        );
+    }
+    if failed.is_empty() {
+        Ok(())
     } else {
-        println!("Transcript already saved. Skipping download.");
+        Err(error::Error::custom(format!(
+            "{} of {} videos failed",
+            failed.len(),
+            video_ids.len()
+        )))
     }
+}
 
-    // Generate report if requested
-    if needs_report {
-        println!("Generating report...");
-        let report_content = if let Some(transcript) = fetched_transcript.as_ref() {
-            report_service.generate_report(transcript).await?
-        } else {
-            let transcript_content = StorageService::load_transcript(&video_id).await?;
-            report_service
-                .generate_report_text(&transcript_content)
-                .await?
-        };
+/// Download the transcripts a report would need, then print its prompts.
+/// Progress goes to stderr, so stdout holds only the prompts.
+async fn run_cli_get_dry_run(
+    video_inputs: Vec<String>,
+    jobs: usize,
+    fetch: FetchOptions,
+    report: ReportOptions,
+) -> Result<()> {
+    let video_ids = unique_video_ids(&video_inputs)?;
 
-        let report_path = StorageService::save_report(&video_id, &report_content).await?;
-        println!("Report saved to: {report_path:?}");
-    } else if generate_report {
-        println!("Report already exists. Skipping generation.");
+    let transcript_service = fetch.service()?;
+    let translate = fetch.translate.as_deref();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let progress = tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            match event {
+                FetchEvent::Started(id) => info!("[{id}] Fetching transcript..."),
+                FetchEvent::Failed(id, err) => warn!("[{id}] Failed: {err}"),
+                FetchEvent::Skipped(_) | FetchEvent::Saved(..) => {}
+            }
+        }
+    });
+    let outcomes = transcript_service
+        .fetch_many(
+            &video_ids,
+            &fetch.languages(),
+            fetch.preserve_formatting,
+            translate,
+            jobs,
+            &tx,
+        )
+        .await;
+    drop(tx);
+    let _ = progress.await;
+
+    for (video_id, outcome) in &outcomes {
+        if outcome.is_ok() {
+            print_prompts(video_id, &report, translate).await?;
+        }
     }
+    match outcomes
+        .iter()
+        .filter(|(_, outcome)| outcome.is_err())
+        .count()
+    {
+        0 => Ok(()),
+        failed => Err(error::Error::custom(format!(
+            "{failed} of {} videos failed",
+            video_ids.len()
+        ))),
+    }
+}
+
+/// Print the prompts a report on a saved transcript would send, with the
+/// transcript in place, between headings that say which request each is.
+async fn print_prompts(
+    video_id: &str,
+    report: &ReportOptions,
+    translation: Option<&str>,
+) -> Result<()> {
+    let transcript_content = StorageService::load_transcript(video_id, translation).await?;
+    let (title, language, removed_lines) =
+        saved_video_details(video_id, report.language.as_deref(), translation);
+    let video = PromptVideo {
+        video_id,
+        title: title.as_deref(),
+        language: &language,
+        removed_lines,
+    };
+    let prompts = report
+        .service()
+        .build_prompts(&transcript_content, &report.prompt, video)?;
 
+    let total = prompts.len();
+    for (i, (system_prompt, user_prompt)) in prompts.iter().enumerate() {
+        if total == 1 {
+            println!("===== {video_id}: {} =====", report.model);
+        } else {
+            println!(
+                "===== {video_id}: {}, request {} of {} (part {} of {total}) =====",
+                report.model,
+                i + 1,
+                total + 1,
+                i + 1
+            );
+        }
+        println!("----- system -----");
+        println!("{system_prompt}");
+        println!("----- user -----");
+        println!("{user_prompt}");
+        println!();
+    }
+    if total > 1 {
+        println!(
+            "===== {video_id}: request {} of {} merges the partial reports, so it is built from their answers =====",
+            total + 1,
+            total + 1
+        );
+        println!();
+    }
     Ok(())
 }
 
-async fn run_cli_report(video_id: String) -> Result<()> {
+/// Note how far a video of a batch got. Failing to save the manifest only
+/// costs the ability to resume, so the run carries on.
+fn record(manifest: &mut BatchManifest, video_id: &str, status: BatchStatus) {
+    if let Err(e) = manifest.set(video_id, status) {
+        warn!("Could not update {}: {e}", manifest.path.display());
+    }
+}
+
+/// Title, report language and cleaned-out line count for a saved transcript,
+/// read from its metadata sidecar. A translation is in its own language, not
+/// the original's.
+fn saved_video_details(
+    video_id: &str,
+    requested_language: Option<&str>,
+    translation: Option<&str>,
+) -> (Option<String>, String, usize) {
+    let metadata = TranscriptMetadata::load(video_id).ok().flatten();
+    let transcript_language = match translation {
+        Some(language) => Some(language.to_string()),
+        None => metadata
+            .as_ref()
+            .and_then(|m| m.known_language().map(str::to_string)),
+    };
+    (
+        metadata.as_ref().and_then(|m| m.title.clone()),
+        report_language(requested_language, transcript_language.as_deref()),
+        metadata.map_or(0, |m| m.removed_lines),
+    )
+}
+
+async fn run_cli_report(video_id: String, report: ReportOptions, force: bool) -> Result<()> {
     let video_id = sanitize_video_id(&video_id)?;
-    println!("Generating report for video: {video_id}");
+    let template = report.prompt.template();
+    let exists = StorageService::report_exists(&video_id, template);
+    let (title, language, removed_lines) =
+        saved_video_details(&video_id, report.language.as_deref(), None);
+    let report_service = report.service();
+    let inputs = report_service.inputs(&report.prompt, &language);
+    if exists && !force && !StorageService::report_is_stale(&video_id, template, None, &inputs)? {
+        info!("Report up to date. Use --force to generate it again.");
+        return Ok(());
+    }
+    match &report.prompt {
+        PromptSource::Custom(custom) => info!(
+            "Generating report from {} for video: {video_id}",
+            custom.path().display()
+        ),
+        PromptSource::Template(_) => info!(
+            "Generating {} report for video: {video_id}",
+            template.name()
+        ),
+    }
 
-    let transcript_content = StorageService::load_transcript(&video_id).await?;
+    let transcript_content = StorageService::load_transcript(&video_id, None).await?;
 
-    let report_service = ReportService::new();
+    let video = PromptVideo {
+        video_id: &video_id,
+        title: title.as_deref(),
+        language: &language,
+        removed_lines,
+    };
+    let estimate = report_service.estimate(&transcript_content, &report.prompt, video)?;
+    if !approve_cost(&estimate, report.yes, None)? {
+        return Err(error::Error::custom("Cancelled before generating report"));
+    }
     let report_content = report_service
-        .generate_report_text(&transcript_content)
+        .generate_report_text(&transcript_content, &report.prompt, video, &|progress| {
+            info!("{progress}")
+        })
         .await?;
 
-    let report_path = StorageService::save_report(&video_id, &report_content).await?;
-    println!("Report saved to: {report_path:?}");
+    // The replaced report goes to history, and comes back if saving fails
+    let rotated = if exists {
+        Some(StorageService::rotate_report(&video_id, template).await?)
+    } else {
+        None
+    };
+    if let Err(e) = StorageService::save_report(&video_id, template, &report_content).await {
+        if let Some(rotated) = rotated {
+            let _ = StorageService::restore_report(&video_id, template, &rotated).await;
+        }
+        return Err(e);
+    }
 
     Ok(())
 }
 
-fn run_cli_list() -> Result<()> {
-    let files = StorageService::list_files()?;
+/// Print a value as one pretty JSON document on stdout. A reader that stops
+/// early (e.g. `| head`) is not an error.
+fn print_json(value: &impl serde::Serialize) -> Result<()> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| error::Error::custom(format!("Failed to serialize output: {e}")))?;
+    match writeln!(io::stdout().lock(), "{json}") {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+fn run_cli_list(saved: Option<String>, options: ListOptions, json: bool) -> Result<()> {
+    if let (Some(since), Some(until)) = (options.since, options.until)
+        && since > until
+    {
+        return Err(error::Error::custom(format!(
+            "--since {since} is after --until {until}"
+        )));
+    }
+    let mut files = StorageService::list_files_filtered(&options)?;
+    if let Some(name) = saved {
+        let searches = SavedSearches::load()?;
+        let search = searches.get(&name).ok_or_else(|| {
+            let names: Vec<&str> = searches.searches.iter().map(|s| s.name.as_str()).collect();
+            error::Error::custom(if names.is_empty() {
+                format!("No saved search named '{name}' (none saved yet)")
+            } else {
+                format!(
+                    "No saved search named '{name}' (saved: {})",
+                    names.join(", ")
+                )
+            })
+        })?;
+        files.retain(|file| search.matches(file));
+    }
+    if json {
+        return print_json(&files);
+    }
 
     if files.is_empty() {
         println!("No files found.");
@@ -142,37 +903,1014 @@ fn run_cli_list() -> Result<()> {
             core::storage::FileType::Report => "Report",
         };
 
-        let size_kb = file.size / 1024;
-        let size_str = if size_kb < 1024 {
-            format!("{size_kb}KB")
-        } else {
-            format!("{:.1}MB", size_kb as f64 / 1024.0)
+        println!(
+            "{:<12} {:<30} {}",
+            file_type,
+            file.display_name(),
+            format_size(file.size)
+        );
+    }
+
+    Ok(())
+}
+
+fn run_cli_history(clear: bool) -> Result<()> {
+    let mut history = UrlHistory::load();
+    if clear {
+        let count = history.entries().len();
+        history.clear();
+        history.save()?;
+        println!("Forgot {count} URL(s).");
+        return Ok(());
+    }
+    if history.entries().is_empty() {
+        println!("No URLs in the history.");
+        return Ok(());
+    }
+    for entry in history.entries() {
+        let saved = entry
+            .video_id()
+            .is_some_and(|id| StorageService::transcript_exists(&id, None));
+        let mark = if saved { "✓" } else { " " };
+        match &entry.title {
+            Some(title) => println!("{mark} {}  {title}", entry.url),
+            None => println!("{mark} {}", entry.url),
+        }
+    }
+    println!();
+    println!("✓ transcript already saved");
+    Ok(())
+}
+
+fn run_cli_stats(json: bool) -> Result<()> {
+    let files = StorageService::list_files()?;
+    let data_dir = StorageService::data_dir()?;
+    let batches = BatchManifest::list(&data_dir)?;
+    let stats = StorageStats {
+        batches: batches.iter().map(BatchManifest::summary).collect(),
+        data_dir,
+        ..StorageStats::from_files(&files)
+    };
+    if json {
+        return print_json(&stats);
+    }
+
+    println!("Data directory: {}", stats.data_dir.display());
+    println!();
+    if files.is_empty() {
+        println!("No files found.");
+        return Ok(());
+    }
+
+    let modified = |file: &Option<core::storage::FileEntry>| {
+        file.as_ref()
+            .map(|file| {
+                let time: chrono::DateTime<Local> = file.modified.into();
+                format!("{} ({})", time.format("%Y-%m-%d %H:%M"), file.name)
+            })
+            .unwrap_or_default()
+    };
+
+    println!(
+        "{:<12} {:>5} {:>8}",
+        "Transcripts",
+        stats.transcripts.count,
+        format_size(stats.transcripts.size)
+    );
+    println!(
+        "{:<12} {:>5} {:>8}",
+        "Reports",
+        stats.reports.count,
+        format_size(stats.reports.size)
+    );
+    println!(
+        "{:<12} {:>5} {:>8}",
+        "Total",
+        files.len(),
+        format_size(stats.total_size())
+    );
+    println!();
+    println!("Largest files:");
+    for file in &stats.largest {
+        println!("  {:<30} {:>8}", file.name, format_size(file.size));
+    }
+    println!();
+    println!("Oldest: {}", modified(&stats.oldest));
+    println!("Newest: {}", modified(&stats.newest));
+
+    if !stats.batches.is_empty() {
+        println!();
+        println!("Batches:");
+        for batch in &stats.batches {
+            let mut progress = format!("{}/{} done", batch.done, batch.total);
+            if batch.failed > 0 {
+                progress.push_str(&format!(", {} failed", batch.failed));
+            }
+            if batch.done < batch.total {
+                progress.push_str(" (unfinished)");
+            }
+            println!("  {:<30} {progress}", batch.name);
+        }
+    }
+
+    Ok(())
+}
+
+fn run_cli_export_site(dest: PathBuf, engine: site::Engine, prune: bool) -> Result<()> {
+    let videos = site::load_library()?;
+    let summary = site::export(&dest, engine, &videos, prune)?;
+    println!(
+        "Exported {} videos for {} to {} ({} updated, {} unchanged).",
+        videos.len(),
+        engine.name(),
+        dest.display(),
+        summary.updated,
+        summary.unchanged
+    );
+    if summary.pruned > 0 {
+        println!("Removed pages of {} deleted videos.", summary.pruned);
+    }
+    if summary.stale > 0 {
+        println!(
+            "Kept pages of {} videos no longer in the library; pass --prune to remove them.",
+            summary.stale
+        );
+    }
+    Ok(())
+}
+
+async fn run_cli_show(
+    video_id: String,
+    render_markdown: bool,
+    template: ReportTemplate,
+    output: Output,
+) -> Result<()> {
+    let video_id = sanitize_video_id(&video_id)?;
+    let content = StorageService::load_report(&video_id, template).await?;
+
+    let issues = recovery::detect(&content);
+    for issue in &issues {
+        eprintln!("Warning: report looks corrupt: {issue}");
+    }
+    if !issues.is_empty() {
+        match template {
+            ReportTemplate::Detailed => eprintln!("Run 'vidio repair {video_id}' to fix it."),
+            _ => eprintln!(
+                "Run 'vidio repair {video_id} --template {}' to fix it.",
+                template.name()
+            ),
+        }
+        eprintln!();
+    }
+
+    if render_markdown {
+        let (_, body) = front_matter::split(&content);
+        let config = Config::load()?;
+        let theme = match Theme::named(saved_theme(&Settings::load()?, &config), &config) {
+            Ok(theme) => theme,
+            Err(e) => {
+                eprintln!("Warning: {e}; using the dark theme.");
+                Theme::dark()
+            }
+        };
+        let rendered =
+            render::markdown_to_ansi(body, render::terminal_width(), &theme, output.color);
+        render::print_paged(&rendered, output)
+    } else {
+        render::print_paged(&content, output)
+    }
+}
+
+async fn run_cli_diff(
+    video_id: String,
+    against: Option<PathBuf>,
+    template: ReportTemplate,
+    output: Output,
+) -> Result<()> {
+    let video_id = sanitize_video_id(&video_id)?;
+    let current = StorageService::load_report(&video_id, template).await?;
+
+    let old_path = match against {
+        Some(path) => path,
+        None => StorageService::latest_report_version(&video_id, template)?.ok_or_else(|| {
+            error::Error::custom(format!(
+                "No earlier version of the report for {video_id} in reports/history"
+            ))
+        })?,
+    };
+    let old = std::fs::read_to_string(&old_path)?;
+
+    let diff = render::unified_diff(
+        &old,
+        &current,
+        &old_path.display().to_string(),
+        &StorageService::report_path(&video_id, template)?
+            .display()
+            .to_string(),
+    );
+    if diff.is_empty() {
+        println!("No differences.");
+        return Ok(());
+    }
+    render::print_paged(&render::colorize_diff(&diff, output.color), output)
+}
+
+fn run_cli_info(video_input: String, json: bool) -> Result<()> {
+    let video_id = extract_video_id(&video_input)
+        .ok_or_else(|| error::Error::InvalidVideoId("Invalid video URL or ID".to_string()))?;
+    let info = StorageService::video_info(&video_id)?;
+    if json {
+        return print_json(&info);
+    }
+
+    let describe = |file: &core::storage::StoredFile| {
+        let path = file.path.display();
+        match (file.exists, file.size, file.modified) {
+            (true, Some(size), Some(modified)) => format!(
+                "{path} ({}, {})",
+                format_size(size),
+                modified.format("%Y-%m-%d %H:%M")
+            ),
+            (false, Some(0), _) => format!("{path} (empty, will be fetched again)"),
+            _ => format!("{path} (missing)"),
+        }
+    };
+
+    println!("{:<12} {video_id}", "Video:");
+    if let Some(metadata) = &info.metadata {
+        if let Some(title) = &metadata.title {
+            println!("{:<12} {title}", "Title:");
+        }
+        if let Some(channel) = &metadata.channel {
+            match &metadata.channel_id {
+                Some(id) => println!("{:<12} {channel} ({id})", "Channel:"),
+                None => println!("{:<12} {channel}", "Channel:"),
+            }
+        }
+        if let Some(secs) = metadata.duration_secs {
+            println!("{:<12} {}", "Duration:", format_clock(secs as f64));
+        }
+        if let Some(date) = &metadata.upload_date {
+            println!("{:<12} {date}", "Uploaded:");
+        }
+    }
+    println!("{:<12} {}", "Transcript:", describe(&info.transcript));
+    if let Some(metadata) = &info.metadata {
+        println!(
+            "{:<12} {} ({}), {}",
+            "Language:",
+            metadata.language,
+            metadata.language_code,
+            match metadata.source {
+                TranscriptSource::Embedded => "embedded subtitles",
+                TranscriptSource::YouTube if metadata.is_generated => "auto-generated",
+                TranscriptSource::YouTube => "manual captions",
+            }
+        );
+        println!(
+            "{:<12} {}",
+            "Fetched:",
+            metadata
+                .fetched_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+        );
+        if metadata.removed_lines > 0 {
+            println!(
+                "{:<12} {} caption lines removed as artifacts",
+                "Cleaned:", metadata.removed_lines
+            );
+        }
+    }
+    println!("{:<12} {}", "Report:", describe(&info.report));
+    if let Some(metadata) = &info.report_metadata {
+        println!("{:<12} {}", "Generated:", metadata.summary());
+    }
+    println!("{:<12} {}", "Bookmarks:", info.bookmarks);
+
+    Ok(())
+}
+
+fn run_cli_delete(
+    video_input: String,
+    transcript_only: bool,
+    report_only: bool,
+    yes: bool,
+) -> Result<()> {
+    let video_id = extract_video_id(&video_input)
+        .ok_or_else(|| error::Error::InvalidVideoId("Invalid video URL or ID".to_string()))?;
+    let paths = StorageService::video_files(&video_id, !report_only, !transcript_only)?;
+    if paths.is_empty() {
+        println!("Nothing stored for {video_id}.");
+        return Ok(());
+    }
+
+    for path in &paths {
+        println!("  {}", path.display());
+    }
+    delete_files(&paths, yes)
+}
+
+/// Without a `new_stem`, clap has made sure `--use-title` was given.
+fn run_cli_rename(video_input: String, new_stem: Option<String>) -> Result<()> {
+    let video_id = extract_video_id(&video_input)
+        .ok_or_else(|| error::Error::InvalidVideoId("Invalid video URL or ID".to_string()))?;
+    let new_stem = match new_stem {
+        Some(new_stem) => new_stem,
+        None => {
+            let title = TranscriptMetadata::load(&video_id)?
+                .and_then(|metadata| metadata.title)
+                .ok_or_else(|| {
+                    error::Error::custom(format!(
+                        "No title recorded for {video_id}; give a new name instead"
+                    ))
+                })?;
+            let slug = slugify(&title);
+            if slug.is_empty() {
+                return Err(error::Error::custom(format!(
+                    "'{title}' has no letters or numbers to name files after; give a new name instead"
+                )));
+            }
+            slug
+        }
+    };
+
+    let renames = StorageService::rename_video(&video_id, &new_stem)?;
+    if renames.is_empty() {
+        println!("Nothing stored for {video_id}.");
+    }
+    for (old, new) in &renames {
+        println!("Renamed {} -> {}", old.display(), new.display());
+    }
+    Ok(())
+}
+
+fn run_cli_clean(
+    older_than: Option<std::time::Duration>,
+    orphans: bool,
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    let mut paths = Vec::new();
+    if let Some(older_than) = older_than {
+        let cutoff = std::time::SystemTime::now()
+            .checked_sub(older_than)
+            .unwrap_or(std::time::UNIX_EPOCH);
+        paths.extend(StorageService::files_older_than(cutoff)?);
+    }
+    if orphans {
+        for path in StorageService::orphaned_files()? {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    if paths.is_empty() {
+        println!("Nothing to clean.");
+        return Ok(());
+    }
+
+    let total: u64 = paths
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum();
+    for path in &paths {
+        println!("  {}", path.display());
+    }
+
+    if dry_run {
+        println!(
+            "Would delete {} files ({}).",
+            paths.len(),
+            format_size(total)
+        );
+        return Ok(());
+    }
+    delete_files(&paths, yes)
+}
+
+/// Delete managed files after confirmation, reporting the space reclaimed.
+/// With `safe_delete` on they go to the trash and nothing is reclaimed yet.
+fn delete_files(paths: &[PathBuf], yes: bool) -> Result<()> {
+    let safe_delete = Config::load()?.safe_delete;
+    let question = if safe_delete {
+        format!("Move {} files to the trash?", paths.len())
+    } else {
+        format!("Delete {} files?", paths.len())
+    };
+    if !yes && !confirm(&question)? {
+        println!("Nothing deleted.");
+        return Ok(());
+    }
+
+    let mut total = 0;
+    for path in paths {
+        let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        StorageService::delete_file(path, safe_delete)?;
+        total += size;
+    }
+    if safe_delete {
+        println!(
+            "Moved {} files ({}) to the trash; 'vidio trash empty' reclaims the space.",
+            paths.len(),
+            format_size(total)
+        );
+    } else {
+        println!(
+            "Deleted {} files ({} reclaimed).",
+            paths.len(),
+            format_size(total)
+        );
+    }
+    Ok(())
+}
+
+/// Drop trash entries older than `trash_retention_days`. Runs before every
+/// command, so a broken config or trash never stops one.
+fn purge_expired_trash() {
+    let Ok(config) = Config::load() else {
+        return;
+    };
+    if config.trash_retention_days == 0 {
+        return;
+    }
+    let cutoff = Local::now() - chrono::Duration::days(config.trash_retention_days as i64);
+    match StorageService::purge_trash(cutoff) {
+        Ok((0, _)) => {}
+        Ok((removed, size)) => info!(
+            "Removed {removed} files older than {} days from the trash ({})",
+            config.trash_retention_days,
+            format_size(size)
+        ),
+        Err(e) => warn!("Could not clean up the trash: {e}"),
+    }
+}
+
+fn run_cli_trash(action: TrashCommand, json: bool) -> Result<()> {
+    match action {
+        TrashCommand::List => {
+            let entries = StorageService::trash_entries()?;
+            if json {
+                return print_json(&entries);
+            }
+            if entries.is_empty() {
+                println!("The trash is empty.");
+                return Ok(());
+            }
+            println!("{:>3}  {:<17} {:>9}  Name", "#", "Deleted", "Size");
+            for (n, entry) in entries.iter().enumerate() {
+                let deleted = entry.deleted.map_or("unknown".to_string(), |at| {
+                    at.format("%Y-%m-%d %H:%M").to_string()
+                });
+                println!(
+                    "{:>3}  {deleted:<17} {:>9}  {}",
+                    n + 1,
+                    format_size(entry.size),
+                    entry.name
+                );
+            }
+        }
+        TrashCommand::Restore { name } => {
+            let name = name.trim();
+            let name = match name.parse::<usize>() {
+                Ok(n) => StorageService::trash_entries()?
+                    .into_iter()
+                    .nth(n.wrapping_sub(1))
+                    .map(|entry| entry.name)
+                    .ok_or_else(|| {
+                        error::Error::custom(format!("No entry {n} in `vidio trash list`"))
+                    })?,
+                Err(_) => name.to_string(),
+            };
+            let path = StorageService::restore_from_trash(&name)?;
+            println!("Restored {}", path.display());
+        }
+        TrashCommand::Empty { yes } => {
+            let count = StorageService::trash_entries()?.len();
+            if count == 0 {
+                println!("The trash is empty.");
+                return Ok(());
+            }
+            if !yes && !confirm(&format!("Permanently delete {count} files in the trash?"))? {
+                println!("Nothing deleted.");
+                return Ok(());
+            }
+            let (removed, size) = StorageService::empty_trash()?;
+            println!("Deleted {removed} files ({} reclaimed).", format_size(size));
+        }
+    }
+    Ok(())
+}
+
+/// Ask a yes/no question on the terminal. Without one there is nobody to
+/// answer, so destructive commands must be given `--yes` instead.
+fn confirm(question: &str) -> Result<bool> {
+    if !io::stdin().is_terminal() {
+        return Err(error::Error::custom(
+            "Not asking for confirmation without a terminal; pass --yes",
+        ));
+    }
+    print!("{question} [y/N] ");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+async fn run_cli_repair(video_id: String, keep: String, template: ReportTemplate) -> Result<()> {
+    let video_id = sanitize_video_id(&video_id)?;
+    let side = ConflictSide::parse(&keep)?;
+    let content = StorageService::load_report(&video_id, template).await?;
+
+    if recovery::detect(&content).is_empty() {
+        println!("No corruption detected in report for {video_id}.");
+        return Ok(());
+    }
+
+    let (repaired, fixes) = recovery::repair(&content, side, template);
+    for fix in &fixes {
+        println!("  - {fix}");
+    }
+
+    let original = StorageService::rotate_report(&video_id, template).await?;
+    println!("Original kept at: {original:?}");
+    let report_path = StorageService::save_report_text(&video_id, template, &repaired).await?;
+    println!("Repaired report saved to: {report_path:?}");
+
+    Ok(())
+}
+
+async fn run_cli_import_media(video_id: String, path: PathBuf, stream: Option<u32>) -> Result<()> {
+    let video_id = sanitize_video_id(&video_id)?;
+    if !path.is_file() {
+        return Err(error::Error::custom(format!(
+            "Media file not found: {}",
+            path.display()
+        )));
+    }
+    let tools = Config::load()?.tools;
+
+    let probe = media::run_tool(media::probe_command(&tools.ffprobe, &path), "tools.ffprobe")?;
+    let streams = media::text_streams(&media::parse_probe_output(&probe)?)?;
+    let selected = match stream {
+        Some(index) => streams
+            .iter()
+            .find(|s| s.index == index)
+            .cloned()
+            .ok_or_else(|| {
+                error::Error::custom(format!("No text subtitle stream with index {index}"))
+            })?,
+        None if streams.len() == 1 => streams[0].clone(),
+        None => prompt_for_stream(&streams)?,
+    };
+    println!("Importing subtitle stream {}", selected.label());
+
+    let srt_path =
+        std::env::temp_dir().join(format!("vidio-{video_id}-{}.srt", std::process::id()));
+    let extracted = media::run_tool(
+        media::extract_command(&tools.ffmpeg, &path, selected.index, &srt_path),
+        "tools.ffmpeg",
+    )
+    .and_then(|_| Ok(std::fs::read_to_string(&srt_path)?));
+    let _ = std::fs::remove_file(&srt_path);
+
+    let lines = media::srt_to_transcript(&extracted?)?;
+    let transcript_path = StorageService::save_transcript_lines(&video_id, &lines).await?;
+    TranscriptMetadata::embedded(selected.language.as_deref()).save(&video_id)?;
+    println!("Imported {} snippets to: {transcript_path:?}", lines.len());
+
+    Ok(())
+}
+
+fn prompt_for_stream(streams: &[SubtitleStream]) -> Result<SubtitleStream> {
+    println!("Subtitle streams:");
+    for (i, stream) in streams.iter().enumerate() {
+        println!("{:>3}. {}", i + 1, stream.label());
+    }
+    print!("Pick a stream [1-{}]: ", streams.len());
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    answer
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .and_then(|choice| streams.get(choice.checked_sub(1)?))
+        .cloned()
+        .ok_or_else(|| error::Error::custom(format!("Invalid choice '{}'", answer.trim())))
+}
+
+async fn run_cli_channels(action: ChannelsCommand, json: bool) -> Result<()> {
+    match action {
+        ChannelsCommand::Add { channel, report } => {
+            let mut store = ChannelStore::load()?;
+            if store.add(&channel, report) {
+                store.save()?;
+                println!("Watching {}", channel.trim());
+            } else {
+                println!("Already watching {}", channel.trim());
+            }
+        }
+        ChannelsCommand::List => {
+            let store = ChannelStore::load()?;
+            if json {
+                return print_json(&store.channels);
+            }
+            if store.channels.is_empty() {
+                println!("No channels watched. Add one with 'vidio channels add <channel>'.");
+                return Ok(());
+            }
+            println!("{:<32} {:<17} {:>4}  Status", "Channel", "Last sync", "New");
+            for channel in &store.channels {
+                let last_sync = channel.last_sync.map_or("never".to_string(), |at| {
+                    at.with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M")
+                        .to_string()
+                });
+                let status = match (&channel.error, channel.report) {
+                    (Some(error), _) => format!("error: {error}"),
+                    (None, true) => "ok (+report)".to_string(),
+                    (None, false) => "ok".to_string(),
+                };
+                println!(
+                    "{:<32} {:<17} {:>4}  {status}",
+                    channel.name(),
+                    last_sync,
+                    channel.last_new
+                );
+            }
+        }
+        ChannelsCommand::Sync { since, languages } => {
+            run_cli_channels_sync(since, languages).await?;
+        }
+        ChannelsCommand::Ignore { video_id } => {
+            let video_id = extract_video_id(&video_id).ok_or_else(|| {
+                error::Error::InvalidVideoId("Invalid video URL or ID".to_string())
+            })?;
+            let mut store = ChannelStore::load()?;
+            if store.ignore(&video_id) {
+                store.save()?;
+            }
+            println!("Sync will skip {video_id}");
+        }
+    }
+    Ok(())
+}
+
+/// Sync every watched channel. Safe to run repeatedly: uploads already stored
+/// are skipped and the store is saved after each one, so an interrupted run
+/// resumes where it stopped. A channel that cannot be read is marked as
+/// erroring without stopping the others.
+async fn run_cli_channels_sync(since_days: Option<u32>, languages: String) -> Result<()> {
+    let mut store = ChannelStore::load()?;
+    if store.channels.is_empty() {
+        println!("No channels watched. Add one with 'vidio channels add <channel>'.");
+        return Ok(());
+    }
+
+    let since = since_days.map(|days| Utc::now() - chrono::Duration::days(days.into()));
+    let client = ChannelClient::new()?;
+    let transcript_service = TranscriptService::new()?;
+    let settings = Settings::load()?.report;
+    let config = Config::load()?;
+    let report_service =
+        ReportService::new(&configured_model(None, &config), &config.openai, &settings);
+    let languages: Vec<&str> = languages.split(',').map(|s| s.trim()).collect();
+    let report_language = settings.language;
+
+    let mut summary = Vec::new();
+    let mut channel_errors = 0;
+    let mut upload_failures = 0;
+
+    for index in 0..store.channels.len() {
+        if index > 0 {
+            tokio::time::sleep(SYNC_DELAY).await;
+        }
+        let channel = store.channels[index].clone();
+        println!("Syncing {}", channel.name());
+
+        let feed = match client.resolve(&channel).await {
+            Ok(channel_id) => client
+                .uploads(&channel_id)
+                .await
+                .map(|feed| (channel_id, feed)),
+            Err(e) => Err(e),
+        };
+        let (channel_id, (title, uploads)) = match feed {
+            Ok(feed) => feed,
+            Err(e) => {
+                println!("  Failed: {e}");
+                let entry = &mut store.channels[index];
+                entry.error = Some(e.to_string());
+                entry.last_sync = Some(Utc::now());
+                entry.last_new = 0;
+                store.save()?;
+                channel_errors += 1;
+                summary.push(format!("{:<32} error: {e}", channel.name()));
+                continue;
+            }
+        };
+
+        let entry = &mut store.channels[index];
+        entry.channel_id = Some(channel_id);
+        entry.title = title.or(entry.title.take());
+        entry.error = None;
+
+        let is_stored = |video_id: &str| {
+            StorageService::transcript_exists(video_id, None)
+                && (!channel.report
+                    || StorageService::report_exists(video_id, ReportTemplate::Detailed))
         };
+        let pending = pending_uploads(entry, &uploads, since, &store.ignored, is_stored);
+        let channel_name = entry.name().to_string();
+        println!("  {} new upload(s)", pending.len());
 
-        println!("{:<12} {:<30} {}", file_type, file.name, size_str);
+        let mut saved = 0;
+        let mut failed = 0;
+        for upload in pending {
+            tokio::time::sleep(SYNC_DELAY).await;
+            println!("  [{}] {}", upload.video_id, upload.title);
+            match sync_upload(
+                &transcript_service,
+                &report_service,
+                &upload,
+                &channel_name,
+                &languages,
+                channel.report,
+                report_language.as_deref(),
+            )
+            .await
+            {
+                Ok(()) => {
+                    saved += 1;
+                    // A failed upload keeps the cursor behind it so the next
+                    // run retries it
+                    if failed == 0 {
+                        store.channels[index].cursor = Some(SyncCursor {
+                            video_id: upload.video_id.clone(),
+                            published: upload.published,
+                        });
+                    }
+                }
+                Err(e) => {
+                    failed += 1;
+                    println!("  [{}] Failed: {e}", upload.video_id);
+                    println!(
+                        "  (skip it for good with 'vidio channels ignore {}')",
+                        upload.video_id
+                    );
+                }
+            }
+            store.channels[index].last_new = saved;
+            store.save()?;
+        }
+
+        let entry = &mut store.channels[index];
+        entry.last_sync = Some(Utc::now());
+        entry.last_new = saved;
+        summary.push(format!(
+            "{:<32} {saved} saved, {failed} failed",
+            entry.name()
+        ));
+        store.save()?;
+        upload_failures += failed;
+    }
+
+    println!();
+    println!("Sync summary:");
+    for line in &summary {
+        println!("  {line}");
+    }
+
+    if channel_errors == 0 && upload_failures == 0 {
+        Ok(())
+    } else {
+        Err(error::Error::custom(format!(
+            "{channel_errors} channel(s) could not be read, {upload_failures} upload(s) failed"
+        )))
+    }
+}
+
+async fn sync_upload(
+    transcript_service: &TranscriptService,
+    report_service: &ReportService,
+    upload: &Upload,
+    channel_name: &str,
+    languages: &[&str],
+    generate_report: bool,
+    report_language: Option<&str>,
+) -> Result<()> {
+    let video_id = &upload.video_id;
+    if !StorageService::transcript_exists(video_id, None) {
+        // The feed already gave us the title, no need to ask YouTube again
+        let transcript = transcript_service
+            .fetch_transcript(video_id, languages, false)
+            .await?;
+        let (transcript, removed_lines) =
+            TranscriptService::clean_snippets(&transcript, CleanOptions::default());
+        let video = FetchedVideo {
+            transcript,
+            metadata: VideoMetadata {
+                title: Some(upload.title.clone()),
+                author: Some(channel_name.to_string()),
+                upload_date: Some(upload.published.format("%Y-%m-%d").to_string()),
+                ..VideoMetadata::default()
+            },
+            translation: None,
+            removed_lines,
+            chapters: Vec::new(),
+            format: TranscriptFormat::Timestamped,
+            paragraph_gap: DEFAULT_PARAGRAPH_GAP,
+        };
+        let path = StorageService::save_transcript(&video).await?;
+        println!("  [{video_id}] Transcript saved to {path:?}");
+    }
+
+    let template = ReportTemplate::Detailed;
+    if generate_report && !StorageService::report_exists(video_id, template) {
+        println!("  [{video_id}] Generating report...");
+        let transcript_content = StorageService::load_transcript(video_id, None).await?;
+        let (_, language, removed_lines) = saved_video_details(video_id, report_language, None);
+        let video = PromptVideo {
+            video_id,
+            title: Some(&upload.title),
+            language: &language,
+            removed_lines,
+        };
+        // Watching a channel with reports on is the go-ahead to pay for
+        // them, but an upload too long for the model is still refused
+        let prompt = PromptSource::Template(template);
+        let estimate = report_service.estimate(&transcript_content, &prompt, video)?;
+        println!("  [{video_id}] {estimate}");
+        let report_content = report_service
+            .generate_report_text(&transcript_content, &prompt, video, &|progress| {
+                println!("  [{video_id}] {progress}")
+            })
+            .await?;
+        let path = StorageService::save_report(video_id, template, &report_content).await?;
+        println!("  [{video_id}] Report saved to {path:?}");
     }
 
     Ok(())
 }
 
-async fn run_tui() -> Result<()> {
+fn run_cli_bookmarks(
+    video_input: String,
+    add: Option<String>,
+    note: Option<String>,
+    remove: Option<usize>,
+) -> Result<()> {
+    let video_id = extract_video_id(&video_input)
+        .ok_or_else(|| error::Error::InvalidVideoId("Invalid video URL or ID".to_string()))?;
+    let mut bookmarks = BookmarkList::load(&video_id)?;
+
+    if let Some(timestamp) = add {
+        let seconds = parse_timestamp(&timestamp).ok_or_else(|| {
+            error::Error::custom(format!(
+                "Invalid timestamp '{timestamp}'; expected MM:SS or HH:MM:SS"
+            ))
+        })?;
+        let index = bookmarks.add(seconds, note);
+        bookmarks.save()?;
+        println!(
+            "Added bookmark #{} at {}",
+            index + 1,
+            format_timestamp(seconds)
+        );
+        return Ok(());
+    }
+
+    if let Some(position) = remove {
+        let removed = bookmarks.remove(position)?;
+        bookmarks.save()?;
+        println!(
+            "Removed bookmark #{position} at {}",
+            format_timestamp(removed.timestamp)
+        );
+        return Ok(());
+    }
+
+    if bookmarks.entries.is_empty() {
+        println!("No bookmarks for {video_id}.");
+        return Ok(());
+    }
+
+    println!("Bookmarks for {video_id}:");
+    for (i, bookmark) in bookmarks.entries.iter().enumerate() {
+        println!(
+            "{:>3}. {:<12} {}",
+            i + 1,
+            format_timestamp(bookmark.timestamp),
+            bookmark.note.as_deref().unwrap_or("")
+        );
+    }
+
+    Ok(())
+}
+
+async fn run_cli_prompt_test(
+    template: String,
+    fixture: String,
+    canned: Option<String>,
+    expect: String,
+) -> Result<()> {
+    let options = PromptTestOptions {
+        template,
+        fixture,
+        canned,
+        profile: LintProfile::parse(&expect)?,
+    };
+
+    println!(
+        "Prompt test: template={} fixture={}{}",
+        options.template,
+        options.fixture,
+        if options.canned.is_some() {
+            " (canned response)"
+        } else {
+            ""
+        }
+    );
+
+    let config = Config::load()?;
+    let report_service = ReportService::new(
+        &configured_model(None, &config),
+        &config.openai,
+        &Settings::load()?.report,
+    );
+    let record = run_prompt_test(&report_service, options).await?;
+    append_record(Path::new(PROMPT_TEST_RESULTS_FILE), &record)?;
+
+    for check in &record.checks {
+        let mark = if check.passed { "PASS" } else { "FAIL" };
+        println!("  [{mark}] {:<28} {}", check.name, check.detail);
+    }
+
+    let passed = record.checks.iter().filter(|c| c.passed).count();
+    println!();
+    println!(
+        "Result: {} ({passed}/{} checks) — appended to {PROMPT_TEST_RESULTS_FILE}",
+        if record.passed { "PASS" } else { "FAIL" },
+        record.checks.len()
+    );
+
+    if record.passed {
+        Ok(())
+    } else {
+        Err(error::Error::custom("Prompt test failed"))
+    }
+}
+
+async fn run_tui(ascii: bool) -> Result<()> {
+    // Create app
+    let app = App::new()?;
+    tui::symbols::init(tui::symbols::wants_ascii(ascii, &app.config));
+
     // Initialize terminal
     let mut terminal = tui_init()?;
 
-    // Create app
-    let mut app = App::new()?;
-    let event_handler = EventHandler::new();
+    // Run the app, restoring the terminal even when it exits with an error
+    let result = run_tui_loop(&mut terminal, app).await;
+
+    // Restore terminal
+    tui_restore()?;
+    result
+}
+
+async fn run_tui_loop(terminal: &mut tui::Tui, mut app: App) -> Result<()> {
+    let event_handler =
+        EventHandler::new(std::time::Duration::from_millis(app.config.tick_rate_ms));
 
     // Setup async communication channel for background tasks
     let (tx, rx) = mpsc::unbounded_channel();
     app.processing_tx = Some(tx.clone());
     app.processing_rx = Some(rx);
+    app.log_rx = Some(tui::logging::init());
+    // Paging in the Viewer needs the height before the first frame is drawn
+    let size = terminal.size()?;
+    app.handle_resize(size.width, size.height);
 
     // Main event loop
     loop {
         // Handle events
         let event = event_handler.next_event()?;
-        app.handle_event(event)?;
+        app.handle_event(event);
+
+        // The editor gets the terminal, keys included, until it exits
+        if let Some(path) = app.pending_editor.take() {
+            event_handler.pause();
+            tui_restore()?;
+            let result = tui::editor::open(&path);
+            tui::resume(terminal)?;
+            event_handler.resume();
+            // The terminal may have been resized while the editor had it
+            let size = terminal.size()?;
+            app.handle_resize(size.width, size.height);
+            app.editor_closed(&path, result);
+        }
 
         // Draw UI
         terminal.draw(|f| {
@@ -181,6 +1919,10 @@ async fn run_tui() -> Result<()> {
 
         // Check if we should quit
         if app.should_quit {
+            // Let a cancelled job reach a safe point before the runtime goes away
+            if let Some(task) = app.stopping_task.take() {
+                let _ = task.await;
+            }
             break;
         }
 
@@ -188,7 +1930,5 @@ async fn run_tui() -> Result<()> {
         // No additional processing needed here
     }
 
-    // Restore terminal
-    tui_restore()?;
     Ok(())
 }
@@ -1,11 +1,37 @@
-use crate::core::{FileType, ReportService, StorageService, TranscriptService, storage::FileEntry};
-use crate::error::Result;
-use crate::tui::components::{FileList, InputField, ProgressBar, Viewer};
+use crate::core::pipeline::{self, FileStorage, Job, JobError, Outcome, Progress, Services, Stage};
+use crate::core::{
+    BookmarkList, Config, CostEstimate, CustomPrompt, DEFAULT_PARAGRAPH_GAP, FileType,
+    HistoryEntry, PromptSource, ReportService, ReportTemplate, ReportText, StorageService,
+    TranscriptFormat, TranscriptService, UrlHistory, VideoMetadata,
+    channels::{Channel, ChannelStore},
+    configured_model,
+    log_file::{self, LOG_DIR, LogFile},
+    metadata::{AUTO_GENERATED_WARNING, TranscriptMetadata},
+    recovery,
+    searches::{FileFilter, SavedSearch, SavedSearches},
+    settings::{ReportConfig, Settings},
+    storage::FileEntry,
+    transcript::{extract_video_id, sanitize_language},
+};
+use crate::error::{Error, Result};
+use crate::tui::browser::{self, BrowserQuery, ViewRow};
+use crate::tui::components::{
+    BYTES_PER_TOKEN, FileList, InputField, MenuGrid, Preview, ProgressBar, TYPICAL_REPORT_BYTES,
+    Viewer, clock,
+};
 use crate::tui::events::AppEvent;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use tokio::sync::mpsc;
+use crate::tui::home::{self, HomeAction, HomeEntry};
+use crate::tui::theme::saved_theme;
+use crate::tui::{Theme, notify, ui};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+use ratatui::widgets::ListState;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppState {
@@ -17,21 +43,149 @@ pub enum AppState {
         status: String,
         logs: Vec<String>,
     },
-    Browser {
-        filter: FileFilter,
-        search: String,
-    },
+    Browser,
     Viewer {
         file_path: PathBuf,
     },
+    Channels,
     Settings,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub enum FileFilter {
-    All,
-    Transcripts,
-    Reports,
+/// The saved-search manager opened with `m` in the Browser.
+#[derive(Debug, Clone)]
+pub struct ChipsOverlay {
+    pub selected: usize,
+    /// The new name while a chip is being renamed.
+    pub rename: Option<InputField>,
+}
+
+/// How long typing in the Browser's search must pause before the list is
+/// filtered again.
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// How long the URL must stay on one video before its title is looked up.
+const TITLE_LOOKUP_DELAY: Duration = Duration::from_millis(500);
+
+/// The video the New Transcript URL points at, shown under the field so a
+/// wrong link is caught before the job starts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedVideo {
+    pub id: String,
+    pub title: TitleLookup,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TitleLookup {
+    /// Typing may still change the ID; looked up once it has rested.
+    Waiting(Instant),
+    Loading,
+    /// Only metadata with a title counts as found.
+    Found(VideoMetadata),
+    Failed,
+}
+
+/// A submitted job whose report needs a key, consent and a reachable server,
+/// held on the New Transcript form until that is known.
+#[derive(Debug)]
+pub enum ReportCheck {
+    Checking {
+        id: u64,
+        video_id: String,
+        request: TranscriptRequest,
+    },
+    /// Shown in a popup offering to go on without the report.
+    Failed {
+        video_id: String,
+        request: TranscriptRequest,
+        problem: Error,
+    },
+    /// A report batch from the Browser; a failure goes to its status line,
+    /// since the transcripts are there already.
+    CheckingBatch {
+        id: u64,
+        video_ids: Vec<String>,
+        overwrite: bool,
+    },
+}
+
+/// The Ctrl+R popup on the New Transcript form, filtering the URL history.
+#[derive(Debug, Clone)]
+pub struct HistorySearch {
+    pub query: InputField,
+    /// The entries matching the query, each with whether its video's
+    /// transcript is saved. Worked out as the query changes, not per frame.
+    pub matches: Vec<(HistoryEntry, bool)>,
+    /// The highlighted match, kept in view as the list scrolls.
+    pub state: ListState,
+}
+
+impl HistorySearch {
+    fn new(history: &UrlHistory) -> Self {
+        let mut query = InputField::new("Search history", "part of a URL or title");
+        query.focused = true;
+        let mut search = Self {
+            query,
+            matches: Vec::new(),
+            state: ListState::default(),
+        };
+        search.refresh(history);
+        search
+    }
+
+    /// Match the query again, highlighting the newest match.
+    fn refresh(&mut self, history: &UrlHistory) {
+        self.matches = history
+            .search(&self.query.value)
+            .into_iter()
+            .map(|entry| {
+                let saved = entry
+                    .video_id()
+                    .is_some_and(|id| StorageService::transcript_exists(&id, None));
+                (entry.clone(), saved)
+            })
+            .collect();
+        self.state.select((!self.matches.is_empty()).then_some(0));
+    }
+}
+
+/// Where the Processing screen goes once its queue finishes: Home after a
+/// single video, back to the Browser after a report batch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProcessingOrigin {
+    NewTranscript,
+    Browser,
+}
+
+/// What the background worker tells the Processing screen.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProcessingMsg {
+    JobStart(String),
+    Progress(f64),
+    Status(String),
+    Log(String),
+    /// A piece of the report as it streams in.
+    Content(String),
+    /// The report starts over, e.g. after a retry.
+    ContentReset,
+    Stage(Stage),
+    StageDone,
+    Done(std::result::Result<(), JobFailure>),
+}
+
+/// Why a job did not finish.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JobFailure {
+    Cancelled,
+    Failed,
+    /// The video has no transcript to fetch, so trying again won't help.
+    NoTranscript,
+}
+
+/// A report waiting on the Processing screen for a yes to its estimated cost.
+#[derive(Debug)]
+pub struct CostQuestion {
+    pub estimate: CostEstimate,
+    pub reply: oneshot::Sender<Result<bool>>,
 }
 
 #[derive(Debug, Clone)]
@@ -40,113 +194,492 @@ pub struct TranscriptRequest {
     pub languages: Vec<String>,
     pub preserve_formatting: bool,
     pub generate_report: bool,
+    pub template: ReportTemplate,
+    /// Regenerate the report even if one already exists.
+    pub overwrite_report: bool,
+    /// Save a machine translation into this language.
+    pub translate: Option<String>,
+    /// Fail rather than fall back to auto-generated captions.
+    pub manual_only: bool,
+    /// Also save a copy of the transcript in this format.
+    pub format: TranscriptFormat,
+    /// Language to write the report in; `None` follows the transcript.
+    pub report_language: Option<String>,
 }
 
 pub struct App {
     pub state: AppState,
+    /// Screens to go back to with Esc, most recent last. Home is never on it;
+    /// an empty stack goes back to Home.
+    pub back_stack: Vec<AppState>,
     pub should_quit: bool,
+    pub show_help: bool,
+    /// An error shown in a popup until any key is pressed.
+    pub error_popup: Option<String>,
+    /// File to open in the external editor, which the main loop does since it
+    /// owns the terminal.
+    pub pending_editor: Option<PathBuf>,
+    pub config: Config,
+    pub theme: Theme,
+    /// Name of `theme`, which the Settings screen may be previewing unsaved.
+    pub theme_name: String,
 
     // Home screen
-    pub selected_option: usize,
+    pub home_menu: MenuGrid,
 
     // New transcript screen
     pub url_input: InputField,
     pub languages_input: InputField,
+    pub translate_input: InputField,
     pub preserve_formatting: bool,
+    pub manual_only: bool,
+    pub transcript_format: TranscriptFormat,
     pub generate_report: bool,
+    pub report_template: ReportTemplate,
     pub input_focus: usize,
+    pub url_history: UrlHistory,
+    pub history_index: Option<usize>,
+    pub history_search: Option<HistorySearch>,
 
     // Browser screen
     pub file_list: FileList,
     pub search_input: InputField,
-    pub filter: FileFilter,
+    /// When the search was last typed, while the list still waits to be
+    /// filtered by it.
+    pub search_typed: Option<Instant>,
+    /// What the file list shows; change it through `set_query`.
+    pub query: BrowserQuery,
     pub file_cache: Vec<FileEntry>,
+    /// Whether a listing asked for by `refresh_file_list` is still on its way.
+    pub listing_files: bool,
+    /// Bumped by every listing request, so an older listing arriving late is
+    /// dropped.
+    pub listing_generation: u64,
+    pub listing_tx: mpsc::UnboundedSender<(u64, Result<Vec<FileEntry>>)>,
+    pub listing_rx: mpsc::UnboundedReceiver<(u64, Result<Vec<FileEntry>>)>,
+    /// Video IDs waiting on the overwrite prompt before a report batch starts.
+    pub pending_report_batch: Option<Vec<String>>,
+    pub saved_searches: SavedSearches,
+    /// Name prompt for saving the current search (Ctrl+s).
+    pub chip_prompt: Option<InputField>,
+    /// The start of the highlighted file, below the list; `p` hides it.
+    pub preview: Preview,
+    pub show_preview: bool,
+    /// New name prompt for the highlighted file (F2), with the file it renames.
+    pub rename_prompt: Option<(PathBuf, InputField)>,
+    pub chips_overlay: Option<ChipsOverlay>,
+    /// Where each chip was drawn, for mouse clicks.
+    pub chip_areas: Vec<Rect>,
+    /// Trash names of the files the last Browser delete moved away, for `u`.
+    pub last_deleted: Vec<String>,
+    /// One-off message shown in place of the Browser help line.
+    pub browser_status: Option<String>,
 
     // Viewer screen
     pub content_viewer: Option<Viewer>,
     pub viewer_height: u16,
+    /// Where each file was left in the Viewer, restored when it is reopened.
+    /// Entries for files that no longer exist are dropped on refresh.
+    pub scroll_positions: HashMap<PathBuf, usize>,
+
+    // Channels screen (read-only; `vidio channels sync` updates the store)
+    pub channels: Vec<Channel>,
+    pub channels_error: Option<String>,
+
+    // Settings screen
+    pub settings: Settings,
+    pub prompt_file_input: InputField,
+    pub report_language_input: InputField,
+    pub file_log_input: bool,
+    pub file_log_focused: bool,
+    pub url_history_input: bool,
+    pub url_history_focused: bool,
+    pub theme_focused: bool,
+    /// Outcome of the last save, shown in place of the help line.
+    pub settings_status: Option<String>,
+
+    // New Transcript screen
+    /// Why the job was not started, shown in place of the help line.
+    pub new_transcript_status: Option<String>,
+    /// The video the URL names, if it names one.
+    pub detected_video: Option<DetectedVideo>,
+    pub report_check: Option<ReportCheck>,
+    /// Numbers the checks, so the answer to a cancelled one is dropped.
+    pub report_checks: u64,
+    pub check_tx: mpsc::UnboundedSender<(u64, Result<()>)>,
+    pub check_rx: mpsc::UnboundedReceiver<(u64, Result<()>)>,
+    pub title_tx: mpsc::UnboundedSender<(String, Option<VideoMetadata>)>,
+    pub title_rx: mpsc::UnboundedReceiver<(String, Option<VideoMetadata>)>,
 
     // Processing screen
     pub progress_bar: ProgressBar,
+    /// Where the processing log is kept when `settings.file_log` is on.
+    pub log_file: LogFile,
+    /// Why the log file was turned off for this session, shown in the
+    /// status bar until Settings turns it back on.
+    pub log_file_error: Option<String>,
+    pub processing_origin: ProcessingOrigin,
+    /// Jobs sent to the worker that have not started yet, oldest first.
+    pub job_queue: VecDeque<TranscriptRequest>,
+    pub current_job: Option<String>,
+    /// Jobs of this run that failed. With any, the processing screen stays
+    /// up once the queue is done, so the error can be read.
+    pub failed_jobs: usize,
+    /// Videos of this run without a transcript to fetch. Counted apart from
+    /// failures, since trying them again won't help; they keep the screen up
+    /// too.
+    pub no_transcript_jobs: usize,
+    /// The pre-flight check: the running job waits here until its report's
+    /// cost is accepted or refused.
+    pub preflight: Option<CostQuestion>,
 
     // Services
     pub transcript_service: TranscriptService,
     pub report_service: ReportService,
 
     // Async communication
-    pub processing_tx: Option<mpsc::UnboundedSender<String>>,
-    pub processing_rx: Option<mpsc::UnboundedReceiver<String>>,
+    pub processing_tx: Option<mpsc::UnboundedSender<ProcessingMsg>>,
+    pub processing_rx: Option<mpsc::UnboundedReceiver<ProcessingMsg>>,
+    /// Log records from the core, shown in the processing log.
+    pub log_rx: Option<mpsc::UnboundedReceiver<String>>,
+    pub processing_task: Option<JoinHandle<()>>,
+    pub processing_token: Option<CancellationToken>,
+    /// A cancelled worker winding down; waited for before the app exits.
+    pub stopping_task: Option<JoinHandle<()>>,
+    pub job_tx: Option<mpsc::UnboundedSender<TranscriptRequest>>,
+    pub cost_tx: mpsc::UnboundedSender<CostQuestion>,
+    pub cost_rx: mpsc::UnboundedReceiver<CostQuestion>,
 }
 
 impl App {
     pub fn new() -> Result<Self> {
+        let config = Config::load()?;
         let transcript_service = TranscriptService::new()?;
-        let report_service = ReportService::new();
-        let file_cache = StorageService::list_files().unwrap_or_default();
-        let file_list = FileList::new(file_cache.clone());
+        let settings = Settings::load()?;
+        let report_service = ReportService::new(
+            &configured_model(None, &config),
+            &config.openai,
+            &settings.report,
+        );
+        // A theme that no longer exists shouldn't keep the TUI from starting
+        let (theme_name, theme, theme_error) =
+            match Theme::named(saved_theme(&settings, &config), &config) {
+                Ok(theme) => (saved_theme(&settings, &config).to_string(), theme, None),
+                Err(e) => ("dark".to_string(), Theme::dark(), Some(e.to_string())),
+            };
+        let (cost_tx, cost_rx) = mpsc::unbounded_channel();
+        let (title_tx, title_rx) = mpsc::unbounded_channel();
+        let (listing_tx, listing_rx) = mpsc::unbounded_channel();
+        let (check_tx, check_rx) = mpsc::unbounded_channel();
 
-        Ok(Self {
+        let mut app = Self {
             state: AppState::Home,
+            back_stack: Vec::new(),
             should_quit: false,
+            show_help: false,
+            error_popup: theme_error,
+            pending_editor: None,
+            config,
+            theme,
+            theme_name,
 
-            selected_option: 0,
+            home_menu: MenuGrid::new(),
 
             url_input: InputField::new("Video URL", "https://youtu.be/..."),
             languages_input: InputField::new("Languages", "en,es"),
+            translate_input: InputField::new("Traducir a", "optional, e.g. es"),
             preserve_formatting: true,
+            manual_only: false,
+            transcript_format: TranscriptFormat::Timestamped,
             generate_report: true,
+            report_template: ReportTemplate::Detailed,
             input_focus: 0,
+            url_history: UrlHistory::load(),
+            history_index: None,
+            history_search: None,
 
-            file_list,
+            file_list: FileList::new(Vec::new()),
             search_input: InputField::new("Search", "Filter files..."),
-            filter: FileFilter::All,
-            file_cache,
+            search_typed: None,
+            query: BrowserQuery::default(),
+            file_cache: Vec::new(),
+            listing_files: false,
+            listing_generation: 0,
+            listing_tx,
+            listing_rx,
+            pending_report_batch: None,
+            saved_searches: SavedSearches::load()?,
+            chip_prompt: None,
+            rename_prompt: None,
+            preview: Preview::default(),
+            show_preview: true,
+            last_deleted: Vec::new(),
+            chips_overlay: None,
+            chip_areas: Vec::new(),
+            browser_status: None,
 
             content_viewer: None,
             viewer_height: 0,
+            scroll_positions: HashMap::new(),
+            channels: Vec::new(),
+            channels_error: None,
+            settings,
+            prompt_file_input: InputField::new("Default prompt file", "e.g. prompts/my_prompt.md"),
+            report_language_input: InputField::new("Report language", "the transcript's, e.g. en"),
+            file_log_input: true,
+            file_log_focused: false,
+            url_history_input: true,
+            url_history_focused: false,
+            theme_focused: false,
+            settings_status: None,
+            new_transcript_status: None,
+            detected_video: None,
+            report_check: None,
+            report_checks: 0,
+            check_tx,
+            check_rx,
+            title_tx,
+            title_rx,
             progress_bar: ProgressBar::new(),
+            log_file: LogFile::new(&StorageService::data_dir()?.join(LOG_DIR)),
+            log_file_error: None,
+            processing_origin: ProcessingOrigin::NewTranscript,
+            job_queue: VecDeque::new(),
+            current_job: None,
+            failed_jobs: 0,
+            no_transcript_jobs: 0,
+            preflight: None,
 
             transcript_service,
             report_service,
 
             processing_tx: None,
+            log_rx: None,
             processing_rx: None,
-        })
+            processing_task: None,
+            processing_token: None,
+            stopping_task: None,
+            job_tx: None,
+            cost_tx,
+            cost_rx,
+        };
+        app.refresh_file_list();
+        Ok(app)
     }
 
-    pub fn handle_event(&mut self, event: AppEvent) -> Result<()> {
-        match event {
+    /// Handle one event. A failure, such as an unreadable file or a missing
+    /// directory, is shown in the error popup rather than ending the TUI.
+    pub fn handle_event(&mut self, event: AppEvent) {
+        let result = match event {
             AppEvent::Quit => {
-                self.should_quit = true;
+                self.quit();
+                Ok(())
             }
-            AppEvent::Key(key) => {
-                self.handle_key(key)?;
+            AppEvent::Key(key) => self.handle_key(key),
+            AppEvent::Mouse(mouse) => self.handle_mouse(mouse),
+            AppEvent::Paste(text) => {
+                self.handle_paste(&text);
+                Ok(())
             }
-            AppEvent::Mouse(mouse) => {
-                self.handle_mouse(mouse)?;
+            AppEvent::Resize(width, height) => {
+                self.handle_resize(width, height);
+                Ok(())
             }
-            AppEvent::Tick => {
-                // Handle any periodic updates
-                self.handle_tick()?;
+            // Handle any periodic updates
+            AppEvent::Tick => self.handle_tick(),
+        };
+        if let Err(e) = result {
+            self.error_popup = Some(e.to_string());
+        }
+        self.follow_search();
+        self.follow_preview();
+        self.follow_url();
+    }
+
+    /// Mark the URL field valid or not and note which video it names; the
+    /// title is looked up on a later tick.
+    fn follow_url(&mut self) {
+        if self.state != AppState::NewTranscript {
+            return;
+        }
+        let url = self.url_input.value.trim();
+        let video_id = extract_video_id(url);
+        self.url_input.border_color = match &video_id {
+            _ if url.is_empty() => None,
+            Some(_) => Some(self.theme.success),
+            None => Some(self.theme.error),
+        };
+        if self.detected_video.as_ref().map(|video| &video.id) != video_id.as_ref() {
+            self.detected_video = video_id.map(|id| DetectedVideo {
+                id,
+                title: TitleLookup::Waiting(Instant::now()),
+            });
+        }
+    }
+
+    /// Fetch the detected video's title once the URL has stopped changing,
+    /// and take in any title that arrived.
+    fn look_up_title(&mut self) {
+        if let Some(video) = &mut self.detected_video
+            && matches!(video.title, TitleLookup::Waiting(since) if since.elapsed() >= TITLE_LOOKUP_DELAY)
+        {
+            video.title = TitleLookup::Loading;
+            let video_id = video.id.clone();
+            let transcript_service = self.transcript_service.clone();
+            let tx = self.title_tx.clone();
+            tokio::spawn(async move {
+                let metadata = transcript_service
+                    .fetch_metadata(&video_id)
+                    .await
+                    .ok()
+                    .filter(|metadata| metadata.title.is_some());
+                let _ = tx.send((video_id, metadata));
+            });
+        }
+        while let Ok((video_id, metadata)) = self.title_rx.try_recv() {
+            // A title for a URL typed over since is dropped
+            if let Some(video) = &mut self.detected_video
+                && video.id == video_id
+            {
+                video.title = metadata.map_or(TitleLookup::Failed, TitleLookup::Found);
             }
         }
-        Ok(())
+    }
+
+    /// Show or hide a video's files in the grouped Browser.
+    fn set_expanded(&mut self, video_id: String, expand: bool) {
+        let mut expanded = self.query.expanded.clone();
+        if expand {
+            expanded.insert(video_id.clone());
+        } else {
+            expanded.remove(&video_id);
+        }
+        self.set_query(BrowserQuery {
+            expanded,
+            ..self.query.clone()
+        });
+        // Folding from a file row moves the highlight up to its video
+        if !expand
+            && let Some(index) =
+                self.file_list.items.iter().position(
+                    |row| matches!(row, ViewRow::Video { video, .. } if video.id == video_id),
+                )
+        {
+            self.file_list.state.select(Some(index));
+        }
+    }
+
+    /// Filter by the typed search once typing has paused.
+    fn follow_search(&mut self) {
+        if self
+            .search_typed
+            .is_some_and(|since| since.elapsed() >= SEARCH_DEBOUNCE)
+        {
+            self.apply_search();
+        }
+    }
+
+    fn apply_search(&mut self) {
+        self.set_query(BrowserQuery {
+            search: self.search_input.value.clone(),
+            chip: None,
+            ..self.query.clone()
+        });
+    }
+
+    /// Point the preview at the highlighted file; it reads it on a later tick.
+    fn follow_preview(&mut self) {
+        if self.state == AppState::Browser && self.show_preview {
+            // A video row previews its first file
+            let file = self
+                .file_list
+                .get_selected_row()
+                .and_then(|row| row.files().first().copied());
+            self.preview
+                .request(file.map(|file| (file.path.as_path(), &file.file_type)));
+        }
     }
 
     fn handle_key(&mut self, key: KeyEvent) -> Result<()> {
+        // The overlay swallows the key that closes it so nothing underneath moves
+        if self.show_help {
+            self.show_help = false;
+            return Ok(());
+        }
+        if self.error_popup.take().is_some() {
+            return Ok(());
+        }
+
+        if key.code == KeyCode::F(1) || (key.code == KeyCode::Char('?') && !self.is_typing()) {
+            self.show_help = true;
+            return Ok(());
+        }
+
         match &self.state {
             AppState::Home => self.handle_home_key(key),
             AppState::NewTranscript => self.handle_new_transcript_key(key),
-            AppState::Browser { .. } => self.handle_browser_key(key),
+            AppState::Browser => self.handle_browser_key(key),
             AppState::Viewer { .. } => self.handle_viewer_key(key),
             AppState::Processing { .. } => self.handle_processing_key(key),
+            AppState::Channels => self.handle_channels_key(key),
             AppState::Settings => self.handle_settings_key(key),
         }
     }
 
+    /// Put pasted text into whichever field is taking typed keys; elsewhere
+    /// it is ignored rather than replayed as shortcuts.
+    fn handle_paste(&mut self, text: &str) {
+        match &self.state {
+            AppState::NewTranscript => match self.input_focus {
+                _ if self.history_search.is_some() => {
+                    if let Some(search) = &mut self.history_search {
+                        search.query.paste(text);
+                        search.refresh(&self.url_history);
+                    }
+                }
+                0 => {
+                    self.url_input.paste(text);
+                    self.history_index = None;
+                }
+                1 => self.languages_input.paste(text),
+                2 => self.translate_input.paste(text),
+                _ => {}
+            },
+            AppState::Settings => {
+                if self.report_language_input.focused {
+                    self.report_language_input.paste(text);
+                } else if self.prompt_file_input.focused {
+                    self.prompt_file_input.paste(text);
+                }
+            }
+            AppState::Browser => {
+                if let Some(prompt) = &mut self.chip_prompt {
+                    prompt.paste(text);
+                } else if let Some((_, prompt)) = &mut self.rename_prompt {
+                    prompt.paste(text);
+                } else if let Some(rename) = self
+                    .chips_overlay
+                    .as_mut()
+                    .and_then(|overlay| overlay.rename.as_mut())
+                {
+                    rename.paste(text);
+                } else if self.search_input.focused {
+                    self.search_input.paste(text);
+                    self.apply_search();
+                }
+            }
+            AppState::Viewer { .. } => {
+                if let Some(viewer) = &mut self.content_viewer {
+                    viewer.paste(text);
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn handle_mouse(&mut self, mouse: MouseEvent) -> Result<()> {
         match &self.state {
-            AppState::Browser { .. } => {
+            AppState::Browser => {
                 self.handle_browser_mouse(mouse);
             }
             AppState::Viewer { .. } => {
@@ -160,91 +693,146 @@ impl App {
     fn handle_home_key(&mut self, key: KeyEvent) -> Result<()> {
         match key.code {
             KeyCode::Char('q') => {
-                self.should_quit = true;
+                self.quit();
             }
-            KeyCode::Up => {
-                if self.selected_option > 0 {
-                    self.selected_option -= 1;
+            KeyCode::Char(c) => {
+                if let Some(index) = self.home_entries().iter().position(|e| e.shortcut == c) {
+                    self.home_menu.selected = index;
                 }
             }
-            KeyCode::Down => {
-                if self.selected_option < 3 {
-                    self.selected_option += 1;
+            KeyCode::Enter => {
+                let entries = self.home_entries();
+                if let Some(entry) = entries.get(self.home_menu.selected)
+                    && (entry.disabled)(&self.config).is_none()
+                {
+                    self.open_home_entry(entry.action);
                 }
             }
-            KeyCode::Char('1') => self.selected_option = 0,
-            KeyCode::Char('2') => self.selected_option = 1,
-            KeyCode::Char('3') => self.selected_option = 2,
-            KeyCode::Char('4') => self.selected_option = 3,
-            KeyCode::Enter => match self.selected_option {
-                0 => {
-                    self.state = AppState::NewTranscript;
-                    self.url_input.clear();
-                    self.languages_input.value = "en,es".to_string();
-                    self.url_input.focused = true;
-                    self.input_focus = 0;
-                }
-                1 => {
-                    // Reset search input when entering Browser
-                    self.search_input.clear();
-                    self.search_input.focused = false;
-                    self.filter = FileFilter::Transcripts;
-                    self.apply_filter();
-                    self.state = AppState::Browser {
-                        filter: FileFilter::Transcripts,
-                        search: String::new(),
-                    };
-                }
-                2 => {
-                    // Reset search input when entering Browser
-                    self.search_input.clear();
-                    self.search_input.focused = false;
-                    self.filter = FileFilter::Reports;
-                    self.apply_filter();
-                    self.state = AppState::Browser {
-                        filter: FileFilter::Reports,
-                        search: String::new(),
-                    };
-                }
-                3 => {
-                    self.state = AppState::Settings;
-                }
-                _ => {}
-            },
-            _ => {}
+            _ => {
+                let len = self.home_entries().len();
+                self.home_menu.handle_key(key, len);
+            }
         }
         Ok(())
     }
 
+    /// Home entries in display order, pinned ones first.
+    pub fn home_entries(&self) -> Vec<&'static HomeEntry> {
+        home::ordered_entries(&self.config.home.pinned)
+    }
+
+    fn open_home_entry(&mut self, action: HomeAction) {
+        match action {
+            HomeAction::NewTranscript => {
+                self.push_state(AppState::NewTranscript);
+                self.url_input.clear();
+                self.history_index = None;
+                self.languages_input.value = "en,es".to_string();
+                self.translate_input.clear();
+                self.url_input.focused = true;
+                self.input_focus = 0;
+            }
+            HomeAction::Transcripts => self.open_browser(FileFilter::Transcripts),
+            HomeAction::Reports => self.open_browser(FileFilter::Reports),
+            HomeAction::Channels => {
+                match ChannelStore::load() {
+                    Ok(store) => {
+                        self.channels = store.channels;
+                        self.channels_error = None;
+                    }
+                    Err(e) => {
+                        self.channels.clear();
+                        self.channels_error = Some(e.to_string());
+                    }
+                }
+                self.push_state(AppState::Channels);
+            }
+            HomeAction::Settings => {
+                let path = self
+                    .settings
+                    .prompt_file
+                    .as_deref()
+                    .unwrap_or(Path::new(""));
+                self.prompt_file_input
+                    .set_value(&path.display().to_string());
+                self.prompt_file_input.focused = true;
+                let language = self.settings.report.language.as_deref().unwrap_or("");
+                self.report_language_input.set_value(language);
+                self.report_language_input.focused = false;
+                self.file_log_input = self.settings.file_log;
+                self.file_log_focused = false;
+                self.url_history_input = self.settings.url_history;
+                self.url_history_focused = false;
+                self.theme_focused = false;
+                self.settings_status = None;
+                self.push_state(AppState::Settings);
+            }
+        }
+    }
+
     fn handle_new_transcript_key(&mut self, key: KeyEvent) -> Result<()> {
+        self.new_transcript_status = None;
+        if self.history_search.is_some() {
+            self.handle_history_search_key(key);
+            return Ok(());
+        }
+        if self.report_check.is_some() {
+            self.handle_report_check_key(key);
+            return Ok(());
+        }
         match key.code {
-            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.should_quit = true;
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if !self.settings.url_history {
+                    self.new_transcript_status =
+                        Some("URL history is turned off in Settings".to_string());
+                } else if self.url_history.entries().is_empty() {
+                    self.new_transcript_status = Some("No URLs in the history yet".to_string());
+                } else {
+                    self.history_search = Some(HistorySearch::new(&self.url_history));
+                }
             }
             KeyCode::Esc => {
-                self.state = AppState::Home;
+                self.pop_state();
             }
             KeyCode::Tab => {
                 self.cycle_input_focus();
             }
             KeyCode::Enter => {
-                if self.input_focus < 2 {
+                if self.input_focus < 3 {
                     self.cycle_input_focus();
                 } else {
                     self.start_processing()?;
                 }
             }
-            KeyCode::Char(' ') if self.input_focus == 2 => {
+            KeyCode::Char(' ') if self.input_focus == 3 => {
                 self.preserve_formatting = !self.preserve_formatting;
             }
-            KeyCode::Char(' ') if self.input_focus == 3 => {
+            KeyCode::Char(' ') if self.input_focus == 4 => {
+                self.manual_only = !self.manual_only;
+            }
+            KeyCode::Char(' ') if self.input_focus == 5 => {
+                self.transcript_format = self.transcript_format.next();
+            }
+            KeyCode::Char(' ') if self.input_focus == 6 => {
                 self.generate_report = !self.generate_report;
             }
+            KeyCode::Char(' ') if self.input_focus == 7 => {
+                self.report_template = self.report_template.next();
+            }
+            KeyCode::Up if self.input_focus == 0 && self.can_browse_history() => {
+                self.recall_older_url();
+            }
+            KeyCode::Down if self.input_focus == 0 && self.history_index.is_some() => {
+                self.recall_newer_url();
+            }
             _ => {
                 if self.input_focus == 0 {
                     self.url_input.handle_key(key);
+                    self.history_index = None;
                 } else if self.input_focus == 1 {
                     self.languages_input.handle_key(key);
+                } else if self.input_focus == 2 {
+                    self.translate_input.handle_key(key);
                 }
             }
         }
@@ -252,48 +840,180 @@ impl App {
     }
 
     fn handle_browser_key(&mut self, key: KeyEvent) -> Result<()> {
+        if self.report_check.is_some() {
+            self.handle_report_check_key(key);
+            return Ok(());
+        }
+        if let Some(video_ids) = self.pending_report_batch.take() {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => self.start_report_batch(video_ids, true),
+                KeyCode::Char('n') | KeyCode::Char('N') => {
+                    self.start_report_batch(video_ids, false)
+                }
+                KeyCode::Esc => {}
+                _ => self.pending_report_batch = Some(video_ids),
+            }
+            return Ok(());
+        }
+
+        self.browser_status = None;
+        if self.chip_prompt.is_some() {
+            self.handle_chip_prompt_key(key);
+            return Ok(());
+        }
+        if self.rename_prompt.is_some() {
+            return self.handle_rename_prompt_key(key);
+        }
+        if self.chips_overlay.is_some() {
+            self.handle_chips_overlay_key(key);
+            return Ok(());
+        }
+
+        if key.code == KeyCode::Char('s') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            if self.search_typed.is_some() {
+                self.apply_search();
+            }
+            let mut prompt = InputField::new("Save search as", "e.g. rust talks");
+            if let Some(search) = self
+                .query
+                .chip
+                .and_then(|i| self.saved_searches.searches.get(i))
+            {
+                prompt.set_value(&search.name);
+            }
+            prompt.focused = true;
+            self.chip_prompt = Some(prompt);
+            return Ok(());
+        }
+        if key.modifiers.contains(KeyModifiers::ALT)
+            && let KeyCode::Char(c @ '1'..='9') = key.code
+        {
+            self.activate_chip(c as usize - '1' as usize);
+            return Ok(());
+        }
+
         if self.search_input.focused {
             match key.code {
                 KeyCode::Enter => {
                     self.search_input.focused = false;
+                    if self.search_typed.is_some() {
+                        self.apply_search();
+                    }
                 }
                 KeyCode::Esc => {
                     self.search_input.focused = false;
                     self.search_input.clear();
-                    self.apply_filter();
+                    self.set_query(BrowserQuery {
+                        search: String::new(),
+                        chip: None,
+                        ..self.query.clone()
+                    });
                 }
                 _ => {
-                    self.search_input.handle_key(key);
-                    self.apply_search_filter();
+                    if self.search_input.handle_key(key) {
+                        self.search_typed = Some(Instant::now());
+                    }
                 }
             }
         } else {
             match key.code {
                 KeyCode::Char('q') | KeyCode::Esc => {
-                    self.state = AppState::Home;
+                    self.pop_state();
                 }
-                KeyCode::Enter => {
-                    if let Some(file) = self.file_list.get_selected() {
-                        self.open_file(file.clone())?;
+                KeyCode::Enter => match self.file_list.get_selected_row() {
+                    Some(ViewRow::File(file)) => self.open_file(file.clone())?,
+                    Some(ViewRow::Video {
+                        video, expanded, ..
+                    }) => {
+                        let (id, expand) = (video.id.clone(), !expanded);
+                        self.set_expanded(id, expand);
+                    }
+                    None => {}
+                },
+                KeyCode::Right | KeyCode::Left if self.query.grouped => {
+                    // Left also folds the video a file row belongs to
+                    let id = match self.file_list.get_selected_row() {
+                        Some(ViewRow::Video { video, .. }) => Some(video.id.clone()),
+                        Some(ViewRow::File(file)) if key.code == KeyCode::Left => file.video_id(),
+                        _ => None,
+                    };
+                    if let Some(id) = id {
+                        self.set_expanded(id, key.code == KeyCode::Right);
                     }
                 }
+                KeyCode::Char('g') => {
+                    self.set_query(BrowserQuery {
+                        grouped: !self.query.grouped,
+                        ..self.query.clone()
+                    });
+                }
                 KeyCode::Delete => {
                     self.delete_selected_files()?;
                 }
+                KeyCode::Char('u') => {
+                    self.undo_delete()?;
+                }
+                KeyCode::Char('e') => {
+                    if let Some(file) = self.file_list.get_selected() {
+                        self.pending_editor = Some(file.path.clone());
+                    }
+                }
+                KeyCode::Char('p') => {
+                    self.show_preview = !self.show_preview;
+                    self.preview.reset();
+                }
+                KeyCode::F(2) => {
+                    if let Some(file) = self.file_list.get_selected() {
+                        let mut prompt = InputField::new("Rename to", "");
+                        prompt.set_value(&file.name);
+                        prompt.focused = true;
+                        self.rename_prompt = Some((file.path.clone(), prompt));
+                    }
+                }
                 KeyCode::Char('/') => {
                     self.search_input.focused = true;
                 }
-                KeyCode::Char('1') => {
-                    self.filter = FileFilter::All;
-                    self.apply_filter();
+                KeyCode::Char('r') => {
+                    self.request_report_batch();
+                }
+                KeyCode::Char('R') => {
+                    self.regenerate_report();
+                }
+                KeyCode::Char('m') => {
+                    if self.saved_searches.searches.is_empty() {
+                        self.browser_status =
+                            Some("No saved searches yet: press Ctrl+s to save one".to_string());
+                    } else {
+                        self.chips_overlay = Some(ChipsOverlay {
+                            selected: self.query.chip.unwrap_or(0),
+                            rename: None,
+                        });
+                    }
+                }
+                KeyCode::Char('s') => {
+                    self.set_query(BrowserQuery {
+                        sort: self.query.sort.next(),
+                        reverse: false,
+                        ..self.query.clone()
+                    });
                 }
-                KeyCode::Char('2') => {
-                    self.filter = FileFilter::Transcripts;
-                    self.apply_filter();
+                KeyCode::Char('S') => {
+                    self.set_query(BrowserQuery {
+                        reverse: !self.query.reverse,
+                        ..self.query.clone()
+                    });
                 }
-                KeyCode::Char('3') => {
-                    self.filter = FileFilter::Reports;
-                    self.apply_filter();
+                KeyCode::Char(c @ '1'..='3') => {
+                    let filter = match c {
+                        '1' => FileFilter::All,
+                        '2' => FileFilter::Transcripts,
+                        _ => FileFilter::Reports,
+                    };
+                    self.set_query(BrowserQuery {
+                        filter,
+                        chip: None,
+                        ..self.query.clone()
+                    });
                 }
                 _ => {
                     self.file_list.handle_key(key);
@@ -303,61 +1023,493 @@ impl App {
         Ok(())
     }
 
-    fn handle_viewer_key(&mut self, key: KeyEvent) -> Result<()> {
+    fn handle_chip_prompt_key(&mut self, key: KeyEvent) {
+        let Some(prompt) = &mut self.chip_prompt else {
+            return;
+        };
         match key.code {
-            KeyCode::Esc | KeyCode::Char('q') => {
-                // Returning to Browser: reset search state
-                self.search_input.clear();
-                self.search_input.focused = false;
-                self.apply_filter();
-                self.state = AppState::Browser {
-                    filter: self.filter.clone(),
-                    search: String::new(),
-                };
+            KeyCode::Esc => self.chip_prompt = None,
+            KeyCode::Enter => {
+                let search =
+                    SavedSearch::new(&prompt.value, &self.query.search, &self.query.filter);
+                let name = search.name.clone();
+                match self.saved_searches.save_search(search) {
+                    Ok(index) => {
+                        self.chip_prompt = None;
+                        self.query.chip = Some(index);
+                        self.browser_status = Some(match self.saved_searches.save() {
+                            Ok(()) => format!("Saved search '{name}' (Alt+{})", index + 1),
+                            Err(e) => format!("Could not save searches: {e}"),
+                        });
+                    }
+                    Err(e) => self.browser_status = Some(e.to_string()),
+                }
             }
             _ => {
-                if let Some(viewer) = &mut self.content_viewer {
-                    viewer.handle_key(key, self.viewer_height);
+                prompt.handle_key(key);
+            }
+        }
+    }
+
+    fn handle_rename_prompt_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some((path, prompt)) = &mut self.rename_prompt else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Esc => self.rename_prompt = None,
+            KeyCode::Enter => match StorageService::rename_file(path, &prompt.value) {
+                Ok(new) => {
+                    self.rename_prompt = None;
+                    self.browser_status = Some(format!("Renamed to {}", new.display()));
+                    self.refresh_file_list();
                 }
+                Err(e) => self.browser_status = Some(e.to_string()),
+            },
+            _ => {
+                prompt.handle_key(key);
             }
         }
         Ok(())
     }
 
-    fn handle_browser_mouse(&mut self, mouse: MouseEvent) {
-        if self.file_list.handle_mouse(mouse) {
-            // ensure filter state stays consistent; selection already updated inside handle_mouse
+    fn handle_chips_overlay_key(&mut self, key: KeyEvent) {
+        let Some(overlay) = &mut self.chips_overlay else {
+            return;
+        };
+        let count = self.saved_searches.searches.len();
+
+        if let Some(rename) = &mut overlay.rename {
+            match key.code {
+                KeyCode::Esc => overlay.rename = None,
+                KeyCode::Enter => {
+                    match self.saved_searches.rename(overlay.selected, &rename.value) {
+                        Ok(()) => {
+                            overlay.rename = None;
+                            self.persist_saved_searches();
+                        }
+                        Err(e) => self.browser_status = Some(e.to_string()),
+                    }
+                }
+                _ => {
+                    rename.handle_key(key);
+                }
+            }
+            return;
         }
-    }
 
-    fn handle_viewer_mouse(&mut self, mouse: MouseEvent) {
-        if let Some(viewer) = &mut self.content_viewer {
-            let height = if self.viewer_height == 0 {
-                1
-            } else {
-                self.viewer_height
-            };
-            viewer.handle_mouse(mouse, height);
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('m') => self.chips_overlay = None,
+            KeyCode::Up | KeyCode::Char('k') => {
+                overlay.selected = overlay.selected.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                overlay.selected = (overlay.selected + 1).min(count.saturating_sub(1));
+            }
+            KeyCode::Enter => {
+                let index = overlay.selected;
+                self.chips_overlay = None;
+                self.activate_chip(index);
+            }
+            KeyCode::Char('r') | KeyCode::F(2) => {
+                if let Some(search) = self.saved_searches.searches.get(overlay.selected) {
+                    let mut rename = InputField::new("Rename", "");
+                    rename.set_value(&search.name);
+                    rename.focused = true;
+                    overlay.rename = Some(rename);
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                let index = overlay.selected;
+                if self.saved_searches.remove(index).is_some() {
+                    self.query.chip = match self.query.chip {
+                        Some(active) if active == index => None,
+                        Some(active) if active > index => Some(active - 1),
+                        active => active,
+                    };
+                    let remaining = self.saved_searches.searches.len();
+                    if remaining == 0 {
+                        self.chips_overlay = None;
+                    } else {
+                        overlay.selected = index.min(remaining - 1);
+                    }
+                    self.persist_saved_searches();
+                }
+            }
+            _ => {}
         }
     }
 
-    fn handle_processing_key(&mut self, key: KeyEvent) -> Result<()> {
-        if key.code == KeyCode::Esc || key.code == KeyCode::Char('q') {
-            // Cancel processing
-            self.state = AppState::NewTranscript;
-            self.progress_bar.reset();
+    fn persist_saved_searches(&mut self) {
+        if let Err(e) = self.saved_searches.save() {
+            self.browser_status = Some(format!("Could not save searches: {e}"));
         }
-        Ok(())
     }
 
-    fn handle_settings_key(&mut self, key: KeyEvent) -> Result<()> {
-        if key.code == KeyCode::Esc || key.code == KeyCode::Char('q') {
-            self.state = AppState::Home;
+    /// Apply a chip's filter and query together, as one Browser view.
+    fn activate_chip(&mut self, index: usize) {
+        let Some(search) = self.saved_searches.searches.get(index) else {
+            return;
+        };
+        // The sort is a view preference, not part of a saved search
+        let query = BrowserQuery {
+            filter: search.filter(),
+            search: search.query.clone(),
+            chip: Some(index),
+            ..self.query.clone()
+        };
+        self.search_input.set_value(&query.search);
+        self.search_input.focused = false;
+        self.set_query(query);
+        self.push_state(AppState::Browser);
+    }
+
+    /// Enter the Browser on `filter` with no search.
+    fn open_browser(&mut self, filter: FileFilter) {
+        self.search_input.clear();
+        self.search_input.focused = false;
+        self.set_query(BrowserQuery {
+            filter,
+            search: String::new(),
+            chip: None,
+            ..self.query.clone()
+        });
+        self.push_state(AppState::Browser);
+    }
+
+    /// Go to `state`, remembering the current screen for `pop_state`. A
+    /// screen already on the stack is gone back to rather than stacked twice,
+    /// so moving between Processing and New transcript never piles up.
+    fn push_state(&mut self, state: AppState) {
+        let screen = std::mem::discriminant(&state);
+        if state == AppState::Home {
+            self.back_stack.clear();
+        } else if std::mem::discriminant(&self.state) != screen {
+            let previous = std::mem::replace(&mut self.state, AppState::Home);
+            match self
+                .back_stack
+                .iter()
+                .position(|s| std::mem::discriminant(s) == screen)
+            {
+                Some(index) => self.back_stack.truncate(index),
+                None if previous != AppState::Home => self.back_stack.push(previous),
+                None => {}
+            }
         }
-        Ok(())
+        self.state = state;
+    }
+
+    /// Go back to the previous screen, as it was left. A Processing screen
+    /// with nothing left to show is skipped.
+    fn pop_state(&mut self) {
+        while let Some(state) = self.back_stack.pop() {
+            if let AppState::Processing { video_id, .. } = state {
+                if self.current_job.is_none() && !self.awaiting_dismissal() {
+                    continue;
+                }
+                self.state = AppState::Processing {
+                    video_id: self.current_job.clone().unwrap_or(video_id),
+                    progress: 0.0,
+                    status: String::new(),
+                    logs: Vec::new(),
+                };
+            } else {
+                self.state = state;
+            }
+            return;
+        }
+        self.state = AppState::Home;
+    }
+
+    /// Keep Viewer paging in step with the terminal: the page size is known
+    /// before the next frame and the content is wrapped again for it.
+    pub fn handle_resize(&mut self, width: u16, height: u16) {
+        let [screen, _] = ui::screen_layout(Rect::new(0, 0, width, height));
+        let [content, _] = ui::viewer_layout(screen);
+        self.viewer_height = content.height;
+        if let Some(viewer) = &mut self.content_viewer {
+            viewer.relayout();
+        }
+    }
+
+    fn handle_viewer_key(&mut self, key: KeyEvent) -> Result<()> {
+        if let Some(viewer) = &mut self.content_viewer
+            && viewer.handle_key(key, self.viewer_height)
+        {
+            return Ok(());
+        }
+
+        match key.code {
+            KeyCode::Char('r') => {
+                if let Some(viewer) = &mut self.content_viewer {
+                    viewer.toggle_raw();
+                }
+            }
+            KeyCode::Char('t') => {
+                if let Some(viewer) = &mut self.content_viewer {
+                    viewer.toggle_wide_tables();
+                }
+            }
+            KeyCode::Char('e') => {
+                if let AppState::Viewer { file_path } = &self.state {
+                    self.pending_editor = Some(file_path.clone());
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                if let (AppState::Viewer { file_path }, Some(viewer)) =
+                    (&self.state, &self.content_viewer)
+                {
+                    self.scroll_positions
+                        .insert(file_path.clone(), viewer.scroll);
+                }
+                // The Browser's filter and search are untouched by the Viewer
+                self.pop_state();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_browser_mouse(&mut self, mouse: MouseEvent) {
+        if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+            let position = Position::new(mouse.column, mouse.row);
+            if let Some(index) = self
+                .chip_areas
+                .iter()
+                .position(|area| area.contains(position))
+            {
+                self.activate_chip(index);
+                return;
+            }
+        }
+        if self.file_list.handle_mouse(mouse) {
+            // ensure filter state stays consistent; selection already updated inside handle_mouse
+        }
+    }
+
+    fn handle_viewer_mouse(&mut self, mouse: MouseEvent) {
+        if let Some(viewer) = &mut self.content_viewer {
+            let height = if self.viewer_height == 0 {
+                1
+            } else {
+                self.viewer_height
+            };
+            viewer.handle_mouse(mouse, height);
+        }
+    }
+
+    fn handle_processing_key(&mut self, key: KeyEvent) -> Result<()> {
+        if self.preflight.is_some() {
+            match key.code {
+                KeyCode::Char(answer @ ('y' | 'n')) => {
+                    self.answer_preflight(answer == 'y');
+                    return Ok(());
+                }
+                // Spending needs a deliberate yes, not an Enter meant for the
+                // form that started the job
+                KeyCode::Enter => return Ok(()),
+                _ => {}
+            }
+        }
+        match key.code {
+            KeyCode::Enter if self.awaiting_dismissal() => {
+                self.failed_jobs = 0;
+                self.no_transcript_jobs = 0;
+                self.pop_state();
+                self.progress_bar.reset();
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                // Cancel the running job and everything queued behind it
+                self.abort_processing();
+                self.failed_jobs = 0;
+                self.no_transcript_jobs = 0;
+                self.pop_state();
+                self.progress_bar.reset();
+            }
+            KeyCode::Char('n') => {
+                // Queue another video while this one keeps running
+                self.url_input.clear();
+                self.history_index = None;
+                self.push_state(AppState::NewTranscript);
+            }
+            KeyCode::Up => self.progress_bar.scroll_log(1),
+            KeyCode::Down => self.progress_bar.scroll_log(-1),
+            KeyCode::PageUp => self.progress_bar.scroll_log(self.progress_bar.log_page()),
+            KeyCode::PageDown => self.progress_bar.scroll_log(-self.progress_bar.log_page()),
+            KeyCode::Home => self.progress_bar.scroll_log(isize::MAX),
+            KeyCode::End => self.progress_bar.log_scroll = 0,
+            KeyCode::Char('s') => {
+                let message =
+                    match log_file::save_snapshot(Path::new(LOG_DIR), &self.progress_bar.logs) {
+                        Ok(path) => format!("Log saved to {}", path.display()),
+                        Err(e) => format!("Error saving the log: {e}"),
+                    };
+                self.log(message);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn answer_preflight(&mut self, approved: bool) {
+        if let Some(question) = self.preflight.take() {
+            self.log(if approved {
+                "Cost accepted.".to_string()
+            } else {
+                "Cost refused; skipping the report.".to_string()
+            });
+            let _ = question.reply.send(Ok(approved));
+        }
+    }
+
+    fn handle_channels_key(&mut self, key: KeyEvent) -> Result<()> {
+        if key.code == KeyCode::Esc || key.code == KeyCode::Char('q') {
+            self.pop_state();
+        }
+        Ok(())
+    }
+
+    fn handle_settings_key(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Esc => {
+                // Drop a theme that was only being previewed
+                let saved = saved_theme(&self.settings, &self.config).to_string();
+                if saved != self.theme_name && self.apply_theme(&saved).is_err() {
+                    self.apply_theme("dark")?;
+                }
+                self.pop_state();
+            }
+            KeyCode::Enter => self.save_settings(),
+            KeyCode::Tab | KeyCode::BackTab => {
+                let focus = if self.prompt_file_input.focused {
+                    0
+                } else if self.report_language_input.focused {
+                    1
+                } else if self.file_log_focused {
+                    2
+                } else if self.url_history_focused {
+                    3
+                } else {
+                    4
+                };
+                let focus = match key.code {
+                    KeyCode::Tab => (focus + 1) % 5,
+                    _ => (focus + 4) % 5,
+                };
+                self.prompt_file_input.focused = focus == 0;
+                self.report_language_input.focused = focus == 1;
+                self.file_log_focused = focus == 2;
+                self.url_history_focused = focus == 3;
+                self.theme_focused = focus == 4;
+            }
+            KeyCode::Left | KeyCode::Right if self.theme_focused => {
+                let names = Theme::names(&self.config);
+                let current = names
+                    .iter()
+                    .position(|name| *name == self.theme_name)
+                    .unwrap_or(0);
+                let next = match key.code {
+                    KeyCode::Right => (current + 1) % names.len(),
+                    _ => (current + names.len() - 1) % names.len(),
+                };
+                if let Err(e) = self.apply_theme(&names[next]) {
+                    self.settings_status = Some(e.to_string());
+                }
+            }
+            _ if self.file_log_focused => {
+                if key.code == KeyCode::Char(' ') {
+                    self.file_log_input = !self.file_log_input;
+                }
+            }
+            _ if self.url_history_focused => {
+                if key.code == KeyCode::Char(' ') {
+                    self.url_history_input = !self.url_history_input;
+                }
+            }
+            _ if self.report_language_input.focused => {
+                self.report_language_input.handle_key(key);
+            }
+            _ => {
+                self.prompt_file_input.handle_key(key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Save the typed settings, refusing a prompt file the custom template
+    /// could not use or an invalid language. An empty field clears it.
+    fn save_settings(&mut self) {
+        let prompt_file = match self.prompt_file_input.value.trim() {
+            "" => None,
+            path => match CustomPrompt::load(Path::new(path)) {
+                Ok(prompt) => Some(prompt.path().to_path_buf()),
+                Err(e) => {
+                    self.settings_status = Some(e.to_string());
+                    return;
+                }
+            },
+        };
+        let language = match self.report_language_input.value.trim() {
+            "" => None,
+            language => match sanitize_language(language) {
+                Ok(language) => Some(language),
+                Err(e) => {
+                    self.settings_status = Some(e.to_string());
+                    return;
+                }
+            },
+        };
+        let settings = Settings {
+            prompt_file,
+            report: ReportConfig {
+                language,
+                ..self.settings.report.clone()
+            },
+            file_log: self.file_log_input,
+            url_history: self.url_history_input,
+            // Left unset unless picked here, so `vidio.toml` keeps deciding
+            theme: if self.theme_name == saved_theme(&self.settings, &self.config) {
+                self.settings.theme.clone()
+            } else {
+                Some(self.theme_name.clone())
+            },
+        };
+        self.settings_status = Some(match settings.save() {
+            Ok(()) => {
+                if settings.file_log {
+                    self.log_file_error = None;
+                }
+                self.settings = settings;
+                "Settings saved".to_string()
+            }
+            Err(e) => format!("Failed to save settings: {e}"),
+        });
+    }
+
+    /// Switch the TUI to another theme right away; saving keeps it.
+    fn apply_theme(&mut self, name: &str) -> Result<()> {
+        self.theme = Theme::named(name, &self.config)?;
+        self.theme_name = name.to_string();
+        Ok(())
+    }
+
+    /// Add a line to the processing log, and to the log file unless that is
+    /// turned off in Settings.
+    fn log(&mut self, line: String) {
+        if self.settings.file_log
+            && let Err(e) = self.log_file.append(self.current_job.as_deref(), &line)
+        {
+            // Said once; the in-memory log carries on regardless
+            self.settings.file_log = false;
+            let error = format!("Could not write {}: {e}", self.log_file.path().display());
+            self.progress_bar.add_log(error.clone());
+            self.log_file_error = Some(format!("Log file off. {error}"));
+        }
+        self.progress_bar.add_log(line);
     }
 
     fn handle_tick(&mut self) -> Result<()> {
+        self.progress_bar.tick();
+        self.preview.tick();
         // Handle any async messages
         let mut messages = Vec::new();
         if let Some(rx) = &mut self.processing_rx {
@@ -365,44 +1517,281 @@ impl App {
                 messages.push(message);
             }
         }
+        let mut lines = Vec::new();
+        if let Some(rx) = &mut self.log_rx {
+            while let Ok(line) = rx.try_recv() {
+                lines.push(line);
+            }
+        }
+        for line in lines {
+            self.log(line);
+        }
 
         for message in messages {
-            if message.starts_with("PROGRESS:") {
-                if let Ok(progress) = message.trim_start_matches("PROGRESS:").parse::<f64>() {
-                    self.progress_bar.set_progress(progress);
-                }
-            } else if message.starts_with("STATUS:") {
-                let status = message.trim_start_matches("STATUS:").to_string();
-                self.progress_bar.set_message(status);
-            } else if message.starts_with("LOG:") {
-                let log = message.trim_start_matches("LOG:").to_string();
-                self.progress_bar.add_log(log);
-            } else if message == "COMPLETE" {
-                self.refresh_file_list()?;
-                self.state = AppState::Home;
-                self.progress_bar.reset();
+            match message {
+                ProcessingMsg::Progress(progress) => self.progress_bar.set_progress(progress),
+                ProcessingMsg::Status(status) => self.progress_bar.set_message(status),
+                ProcessingMsg::Log(line) => self.log(line),
+                ProcessingMsg::Content(text) => {
+                    self.progress_bar.append_live(&text);
+                    let expected = self
+                        .progress_bar
+                        .expected_report_bytes
+                        .unwrap_or(TYPICAL_REPORT_BYTES);
+                    let progress = streaming_progress(self.progress_bar.streamed, expected);
+                    if progress > self.progress_bar.progress {
+                        self.progress_bar.set_progress(progress);
+                    }
+                }
+                ProcessingMsg::ContentReset => self.progress_bar.restart_live(),
+                ProcessingMsg::Stage(stage) => self.progress_bar.start_stage(stage),
+                ProcessingMsg::StageDone => self.progress_bar.stage = None,
+                ProcessingMsg::JobStart(video_id) => {
+                    self.job_queue.pop_front();
+                    self.current_job = Some(video_id.clone());
+                    // Cleared first, so the job's clock starts now
+                    self.progress_bar.clear_live();
+                    self.progress_bar.set_progress(0.0);
+                    if let AppState::Processing {
+                        video_id: shown, ..
+                    } = &mut self.state
+                    {
+                        *shown = video_id;
+                    }
+                }
+                ProcessingMsg::Done(result) => self.finish_job(result)?,
+            }
+        }
+
+        self.look_up_title();
+        self.take_listing();
+        self.take_report_check();
+
+        if let Ok(question) = self.cost_rx.try_recv() {
+            self.log(question.estimate.to_string());
+            let bytes = question.estimate.output_tokens * BYTES_PER_TOKEN;
+            self.progress_bar.expected_report_bytes = Some(bytes as usize);
+            self.progress_bar
+                .set_message("Waiting for the cost to be accepted".to_string());
+            self.preflight = Some(question);
+        }
+        Ok(())
+    }
+
+    fn finish_job(&mut self, result: std::result::Result<(), JobFailure>) -> Result<()> {
+        let finished = self.current_job.clone().unwrap_or_default();
+        let summary = format!("Finished {finished}: {}", self.progress_bar.message);
+        self.log(summary);
+        if self.config.notify_on_complete {
+            let outcome = match result {
+                Ok(()) => "done",
+                Err(JobFailure::Cancelled) => "cancelled",
+                Err(JobFailure::Failed) => "failed",
+                Err(JobFailure::NoTranscript) => "has no transcript",
+            };
+            notify::send(
+                &format!("vidio: {finished} {outcome}"),
+                &self.progress_bar.message,
+            );
+        }
+        // A cancelled job, a declined cost estimate included, did not fail
+        match result {
+            Err(JobFailure::Failed) => {
+                self.failed_jobs += 1;
+                self.progress_bar.failed = true;
+            }
+            Err(JobFailure::NoTranscript) => self.no_transcript_jobs += 1,
+            Ok(()) | Err(JobFailure::Cancelled) => {}
+        }
+        self.current_job = None;
+        // A failed report may still have saved its transcript
+        self.refresh_file_list();
+
+        if self.job_queue.is_empty() && self.failed_jobs + self.no_transcript_jobs > 0 {
+            let outcome = match (self.failed_jobs, self.no_transcript_jobs) {
+                (failed, 0) => format!("{failed} job(s) failed"),
+                (0, missing) => {
+                    format!("{missing} video(s) have no transcript; trying again won't help")
+                }
+                (failed, missing) => {
+                    format!("{failed} job(s) failed and {missing} video(s) have no transcript")
+                }
+            };
+            self.log(format!("{outcome}. Press Enter to go back."));
+        } else if self.job_queue.is_empty() {
+            if matches!(self.state, AppState::Processing { .. }) {
+                match self.processing_origin {
+                    ProcessingOrigin::NewTranscript => self.push_state(AppState::Home),
+                    ProcessingOrigin::Browser => self.pop_state(),
+                }
             }
+            self.progress_bar.reset();
         }
         Ok(())
     }
 
+    /// Whether a text field currently owns character input, in which case `?`
+    /// must be typed rather than open the help overlay.
+    fn is_typing(&self) -> bool {
+        match self.state {
+            AppState::NewTranscript => self.input_focus < 3 || self.history_search.is_some(),
+            AppState::Settings => true,
+            AppState::Browser => {
+                self.search_input.focused
+                    || self.chip_prompt.is_some()
+                    || self.rename_prompt.is_some()
+                    || self
+                        .chips_overlay
+                        .as_ref()
+                        .is_some_and(|overlay| overlay.rename.is_some())
+            }
+            AppState::Viewer { .. } => self
+                .content_viewer
+                .as_ref()
+                .is_some_and(|viewer| viewer.is_capturing_input()),
+            _ => false,
+        }
+    }
+
+    /// Stop any background task before leaving so it cannot keep writing
+    /// files after the terminal has been restored.
+    fn quit(&mut self) {
+        self.abort_processing();
+        self.should_quit = true;
+    }
+
+    /// The queue is done and some job failed or found no transcript: the
+    /// processing screen waits for Enter or Esc so the reason can be read.
+    pub fn awaiting_dismissal(&self) -> bool {
+        self.failed_jobs + self.no_transcript_jobs > 0
+            && self.current_job.is_none()
+            && self.job_queue.is_empty()
+    }
+
+    fn abort_processing(&mut self) {
+        if let Some(task) = self.processing_task.take() {
+            let token = self.processing_token.take().unwrap_or_default();
+            self.stopping_task = Some(tokio::spawn(async move {
+                pipeline::stop(&token, task).await;
+            }));
+        }
+        self.job_tx = None;
+        self.job_queue.clear();
+        self.current_job = None;
+        // Dropping the reply refuses the cost, and a question still in the
+        // channel belongs to the cancelled job
+        self.preflight = None;
+        (self.cost_tx, self.cost_rx) = mpsc::unbounded_channel();
+        // The cancelled worker may keep reporting while it reaches a safe point;
+        // move to a fresh channel so none of that is applied to the next job
+        if self.processing_tx.is_some() {
+            let (tx, rx) = mpsc::unbounded_channel();
+            self.processing_tx = Some(tx);
+            self.processing_rx = Some(rx);
+        }
+    }
+
+    /// Filter the history in the Ctrl+R popup; Enter puts the highlighted URL
+    /// in the field.
+    fn handle_history_search_key(&mut self, key: KeyEvent) {
+        let Some(search) = &mut self.history_search else {
+            return;
+        };
+        let count = search.matches.len();
+        match key.code {
+            KeyCode::Esc => self.history_search = None,
+            KeyCode::Enter => {
+                let url = search
+                    .state
+                    .selected()
+                    .and_then(|index| search.matches.get(index))
+                    .map(|(entry, _)| entry.url.clone());
+                self.history_search = None;
+                if let Some(url) = url {
+                    self.url_input.set_value(&url);
+                    self.history_index = None;
+                }
+            }
+            KeyCode::Up if count > 0 => search.state.select_previous(),
+            KeyCode::Down if count > 0 => {
+                let next = search.state.selected().map_or(0, |index| index + 1);
+                search.state.select(Some(next.min(count - 1)));
+            }
+            _ => {
+                if search.query.handle_key(key) {
+                    search.refresh(&self.url_history);
+                }
+            }
+        }
+    }
+
+    /// History recall only kicks in on an empty field or while already
+    /// browsing, so Up never clobbers a URL being typed.
+    fn can_browse_history(&self) -> bool {
+        self.settings.url_history
+            && (self.url_input.value.trim().is_empty() || self.history_index.is_some())
+    }
+
+    fn recall_older_url(&mut self) {
+        let next = self.history_index.map_or(0, |i| i + 1);
+        if let Some(url) = self.url_history.get(next) {
+            self.url_input.set_value(url);
+            self.history_index = Some(next);
+        }
+    }
+
+    fn recall_newer_url(&mut self) {
+        match self.history_index {
+            Some(0) | None => {
+                self.url_input.clear();
+                self.history_index = None;
+            }
+            Some(i) => {
+                if let Some(url) = self.url_history.get(i - 1) {
+                    self.url_input.set_value(url);
+                }
+                self.history_index = Some(i - 1);
+            }
+        }
+    }
+
     fn cycle_input_focus(&mut self) {
         self.url_input.focused = false;
         self.languages_input.focused = false;
+        self.translate_input.focused = false;
 
-        self.input_focus = (self.input_focus + 1) % 4;
+        self.input_focus = (self.input_focus + 1) % 8;
 
         match self.input_focus {
             0 => self.url_input.focused = true,
             1 => self.languages_input.focused = true,
+            2 => self.translate_input.focused = true,
             _ => {}
         }
     }
 
     fn start_processing(&mut self) -> Result<()> {
         if !self.url_input.is_valid() {
+            self.new_transcript_status = Some("Enter a YouTube URL or video ID first".to_string());
             return Ok(());
         }
+        let Some(video_id) = extract_video_id(&self.url_input.value) else {
+            self.new_transcript_status = Some(format!(
+                "Not a YouTube URL or video ID: {}",
+                self.url_input.value.trim()
+            ));
+            return Ok(());
+        };
+        let translate = match self.translate_input.value.trim() {
+            "" => None,
+            language => match sanitize_language(language) {
+                Ok(language) => Some(language),
+                Err(e) => {
+                    self.new_transcript_status = Some(e.to_string());
+                    return Ok(());
+                }
+            },
+        };
 
         let request = TranscriptRequest {
             video_url: self.url_input.value.clone(),
@@ -414,222 +1803,619 @@ impl App {
                 .collect(),
             preserve_formatting: self.preserve_formatting,
             generate_report: self.generate_report,
+            template: self.report_template,
+            overwrite_report: false,
+            translate,
+            manual_only: self.manual_only,
+            format: self.transcript_format,
+            report_language: self.settings.report.language.clone(),
         };
 
-        if let Some(video_id) = crate::core::transcript::extract_video_id(&request.video_url) {
-            self.state = AppState::Processing {
-                video_id: video_id.clone(),
-                progress: 0.0,
-                status: "Starting...".to_string(),
-                logs: Vec::new(),
+        // Better to hear about a missing key or a server that is down now
+        // than after the download
+        if request.generate_report {
+            let id = self.spawn_report_check();
+            self.report_check = Some(ReportCheck::Checking {
+                id,
+                video_id,
+                request,
+            });
+            return Ok(());
+        }
+
+        self.launch_job(video_id, request);
+        Ok(())
+    }
+
+    /// Check the key, opt-in and server off the event loop; the answer comes
+    /// back on `check_rx` under the returned id.
+    fn spawn_report_check(&mut self) -> u64 {
+        self.report_checks += 1;
+        let id = self.report_checks;
+        let report_service = self.report_service.clone();
+        let tx = self.check_tx.clone();
+        tokio::spawn(async move {
+            let _ = tx.send((id, report_service.check_ready().await));
+        });
+        id
+    }
+
+    /// Take in the answer to the running report check: the job or batch
+    /// starts, or the popup or status line says what is missing.
+    fn take_report_check(&mut self) {
+        while let Ok((answered, result)) = self.check_rx.try_recv() {
+            match self.report_check.take() {
+                Some(ReportCheck::Checking {
+                    id,
+                    video_id,
+                    request,
+                }) if id == answered => match result {
+                    Ok(()) => self.launch_job(video_id, request),
+                    Err(e) => {
+                        self.report_check = Some(ReportCheck::Failed {
+                            video_id,
+                            request,
+                            problem: e,
+                        })
+                    }
+                },
+                Some(ReportCheck::CheckingBatch {
+                    id,
+                    video_ids,
+                    overwrite,
+                }) if id == answered => match result {
+                    Ok(()) => self.launch_report_batch(video_ids, overwrite),
+                    Err(e) => self.browser_status = Some(e.to_string()),
+                },
+                check => self.report_check = check,
+            }
+        }
+    }
+
+    /// Esc drops the job (or the check) and goes back to the form; Enter on
+    /// a failed check starts the job without its report.
+    fn handle_report_check_key(&mut self, key: KeyEvent) {
+        match (key.code, self.report_check.take()) {
+            (KeyCode::Esc, _) => {}
+            (
+                KeyCode::Enter,
+                Some(ReportCheck::Failed {
+                    video_id,
+                    mut request,
+                    ..
+                }),
+            ) => {
+                request.generate_report = false;
+                self.launch_job(video_id, request);
+            }
+            (_, check) => self.report_check = check,
+        }
+    }
+
+    /// Remember the URL and hand the job to the worker.
+    fn launch_job(&mut self, video_id: String, request: TranscriptRequest) {
+        self.history_index = None;
+        if self.settings.url_history {
+            let title = match &self.detected_video {
+                Some(DetectedVideo {
+                    id,
+                    title: TitleLookup::Found(metadata),
+                }) if *id == video_id => metadata.title.as_deref(),
+                _ => None,
             };
+            self.url_history.push(&request.video_url, title);
+            // History is a convenience; failing to persist it must not block the job
+            let _ = self.url_history.save();
+        }
+
+        self.processing_origin = ProcessingOrigin::NewTranscript;
+        self.show_processing(video_id);
+        self.enqueue_job(request);
+    }
 
+    /// Switch to the Processing screen, which keeps showing the running job
+    /// if there is one.
+    fn show_processing(&mut self, video_id: String) {
+        if self.current_job.is_none() && self.job_queue.is_empty() {
+            self.failed_jobs = 0;
+            self.no_transcript_jobs = 0;
             self.progress_bar.reset();
             self.progress_bar.set_message("Starting...".to_string());
+        }
 
-            // Start real async processing
-            if let Some(tx) = &self.processing_tx {
-                self.start_real_processing(video_id, request, tx.clone());
-            }
+        self.push_state(AppState::Processing {
+            video_id: self.current_job.clone().unwrap_or(video_id),
+            progress: 0.0,
+            status: "Starting...".to_string(),
+            logs: Vec::new(),
+        });
+    }
+
+    /// Hand a job to the background worker, starting it on first use.
+    fn enqueue_job(&mut self, request: TranscriptRequest) {
+        let Some(tx) = self.processing_tx.clone() else {
+            return;
+        };
+
+        if self.job_tx.as_ref().is_none_or(|jobs| jobs.is_closed()) {
+            let (job_tx, job_rx) = mpsc::unbounded_channel();
+            let token = CancellationToken::new();
+            self.processing_task = Some(self.spawn_worker(job_rx, tx, token.clone()));
+            self.processing_token = Some(token);
+            self.job_tx = Some(job_tx);
         }
 
-        Ok(())
+        if let Some(job_tx) = &self.job_tx
+            && job_tx.send(request.clone()).is_ok()
+        {
+            self.job_queue.push_back(request);
+        }
     }
 
-    fn start_real_processing(
+    /// Single worker that runs queued jobs one after another.
+    fn spawn_worker(
         &self,
-        video_id: String,
-        request: TranscriptRequest,
-        tx: mpsc::UnboundedSender<String>,
-    ) {
+        mut jobs: mpsc::UnboundedReceiver<TranscriptRequest>,
+        tx: mpsc::UnboundedSender<ProcessingMsg>,
+        token: CancellationToken,
+    ) -> JoinHandle<()> {
         // Clone the services for the async task
         let transcript_service = self.transcript_service.clone();
         let report_service = self.report_service.clone();
+        let questions = self.cost_tx.clone();
 
         tokio::spawn(async move {
-            let _ = tx.send("STATUS:Starting processing...".to_string());
-            let _ = tx.send("PROGRESS:0.1".to_string());
-            let _ = tx.send("LOG:Extracting video ID...".to_string());
-
-            // Convert languages to the correct format
-            let languages: Vec<&str> = request.languages.iter().map(|s| s.as_str()).collect();
-
-            let transcript_exists = StorageService::transcript_exists(&video_id);
-            let report_exists = StorageService::report_exists(&video_id);
-            let needs_report = request.generate_report && !report_exists;
-
-            if transcript_exists && !needs_report {
-                let _ = tx.send("STATUS:Already processed".to_string());
-                let _ = tx.send("PROGRESS:1.0".to_string());
-                let _ = tx.send(
-                    "LOG:Transcript (and report if requested) already exist. Skipping.".to_string(),
-                );
-                let _ = tx.send("COMPLETE".to_string());
-                return;
+            while let Some(request) = jobs.recv().await {
+                if token.is_cancelled() {
+                    break;
+                }
+                let video_id = crate::core::transcript::extract_video_id(&request.video_url)
+                    .unwrap_or_else(|| request.video_url.clone());
+                let _ = tx.send(ProcessingMsg::JobStart(video_id.clone()));
+                let result = run_job(
+                    &transcript_service,
+                    &report_service,
+                    video_id,
+                    request,
+                    &token,
+                    &tx,
+                    &questions,
+                )
+                .await;
+                let _ = tx.send(ProcessingMsg::Done(result));
             }
+        })
+    }
 
-            let mut fetched_transcript = None;
-
-            // Fetch transcript
-            if !transcript_exists {
-                let _ = tx.send("STATUS:Downloading transcript...".to_string());
-                let _ = tx.send("PROGRESS:0.25".to_string());
-                let _ = tx.send("LOG:Fetching transcript...".to_string());
+    /// Collect the checked transcripts, or the highlighted one if nothing is
+    /// checked, and either queue their reports or ask first when some of them
+    /// already have one.
+    fn request_report_batch(&mut self) {
+        let checked = self.file_list.get_selected_items();
+        let candidates: Vec<&FileEntry> = if checked.is_empty() {
+            self.file_list
+                .get_selected_row()
+                .map(ViewRow::files)
+                .unwrap_or_default()
+        } else {
+            checked
+        };
 
-                match transcript_service
-                    .fetch_transcript(&video_id, &languages, request.preserve_formatting)
-                    .await
-                {
-                    Ok(transcript) => {
-                        let _ = tx.send("PROGRESS:0.5".to_string());
-                        let _ = tx.send("LOG:Successfully fetched transcript!".to_string());
-                        let _ = tx.send("LOG:Saving transcript to file...".to_string());
-
-                        match StorageService::save_transcript(&transcript).await {
-                            Ok(_) => {
-                                let _ = tx.send("PROGRESS:0.6".to_string());
-                                let _ = tx.send("LOG:Transcript saved successfully!".to_string());
-                                fetched_transcript = Some(transcript);
-                            }
-                            Err(e) => {
-                                let _ = tx.send(format!("LOG:Error saving transcript: {e}"));
-                                let _ = tx.send("STATUS:Error saving transcript".to_string());
-                                let _ = tx.send("COMPLETE".to_string());
-                                return;
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        let _ = tx.send(format!("LOG:Error fetching transcript: {e}"));
-                        let _ = tx.send("STATUS:Error downloading transcript".to_string());
-                        let _ = tx.send("COMPLETE".to_string());
-                        return;
-                    }
-                }
-            } else {
-                let _ = tx.send("PROGRESS:0.5".to_string());
-                let _ = tx
-                    .send("LOG:Transcript already exists locally. Skipping download.".to_string());
+        let mut video_ids: Vec<String> = Vec::new();
+        for file in candidates {
+            if file.file_type == FileType::Transcript
+                && let Some(video_id) = file.video_id()
+                && !video_ids.contains(&video_id)
+            {
+                video_ids.push(video_id);
             }
+        }
 
-            if needs_report {
-                let _ = tx.send("STATUS:Generating report...".to_string());
-                let _ = tx.send("PROGRESS:0.7".to_string());
-                let _ = tx.send("LOG:Generating report...".to_string());
+        if video_ids.is_empty() {
+            return;
+        }
 
-                let result = if let Some(transcript) = fetched_transcript.as_ref() {
-                    report_service.generate_report(transcript).await
-                } else {
-                    match StorageService::load_transcript(&video_id).await {
-                        Ok(content) => report_service.generate_report_text(&content).await,
-                        Err(e) => Err(e),
-                    }
-                };
+        if video_ids
+            .iter()
+            .any(|id| StorageService::report_exists(id, self.report_template))
+        {
+            self.pending_report_batch = Some(video_ids);
+        } else {
+            self.start_report_batch(video_ids, false);
+        }
+    }
 
-                match result {
-                    Ok(report_content) => {
-                        let _ = tx.send("PROGRESS:0.9".to_string());
-                        let _ = tx.send("LOG:Report generated successfully!".to_string());
-                        let _ = tx.send("LOG:Saving report to file...".to_string());
-
-                        match StorageService::save_report(&video_id, &report_content).await {
-                            Ok(_) => {
-                                let _ = tx.send("PROGRESS:1.0".to_string());
-                                let _ = tx.send("LOG:Report saved successfully!".to_string());
-                                let _ = tx.send("STATUS:Completed".to_string());
-                                let _ = tx.send("COMPLETE".to_string());
-                            }
-                            Err(e) => {
-                                let _ = tx.send(format!("LOG:Error saving report: {e}"));
-                                let _ = tx.send("STATUS:Error saving report".to_string());
-                                let _ = tx.send("COMPLETE".to_string());
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        let _ = tx.send(format!("LOG:Error generating report: {e}"));
-                        let _ = tx.send("STATUS:Error generating report".to_string());
-                        let _ = tx.send("COMPLETE".to_string());
-                    }
+    /// Write the highlighted transcript's report again, replacing the saved
+    /// one without asking.
+    fn regenerate_report(&mut self) {
+        let transcript = match self.file_list.get_selected_row() {
+            Some(ViewRow::File(file)) => Some(file),
+            Some(ViewRow::Video { video, .. }) => video.transcript.as_ref(),
+            None => None,
+        };
+        match transcript.filter(|file| file.file_type == FileType::Transcript) {
+            Some(file) => {
+                if let Some(video_id) = file.video_id() {
+                    self.start_report_batch(vec![video_id], true);
                 }
-            } else {
-                let _ = tx.send("PROGRESS:1.0".to_string());
-                let _ = tx.send("STATUS:Completed".to_string());
-                let _ = tx.send("COMPLETE".to_string());
             }
-        });
+            None => {
+                self.browser_status =
+                    Some("R regenerates a report from a transcript; highlight one".to_string())
+            }
+        }
     }
 
-    fn refresh_file_list(&mut self) -> Result<()> {
-        let files = StorageService::list_files()?;
-        self.file_cache = files;
+    /// Queue the batch once the report check passes, so a missing key or a
+    /// server that is down shows before any job starts.
+    fn start_report_batch(&mut self, video_ids: Vec<String>, overwrite: bool) {
+        let id = self.spawn_report_check();
+        self.report_check = Some(ReportCheck::CheckingBatch {
+            id,
+            video_ids,
+            overwrite,
+        });
+    }
 
-        if self.search_input.value.trim().is_empty() {
-            self.apply_filter();
-        } else {
-            self.apply_search_filter();
+    /// Queue one report-only job per transcript; the transcripts are already
+    /// on disk so the worker skips the fetch stage.
+    fn launch_report_batch(&mut self, video_ids: Vec<String>, overwrite: bool) {
+        self.processing_origin = ProcessingOrigin::Browser;
+        self.show_processing(video_ids[0].clone());
+
+        for video_id in video_ids {
+            self.enqueue_job(TranscriptRequest {
+                video_url: video_id,
+                languages: Vec::new(),
+                preserve_formatting: self.preserve_formatting,
+                generate_report: true,
+                // The template picked on the New Transcript form
+                template: self.report_template,
+                overwrite_report: overwrite,
+                translate: None,
+                manual_only: false,
+                format: TranscriptFormat::Timestamped,
+                report_language: self.settings.report.language.clone(),
+            });
         }
-        Ok(())
     }
 
-    fn apply_filter(&mut self) {
-        let filtered_files: Vec<FileEntry> = self
-            .file_cache
-            .iter()
-            .filter(|file| match self.filter {
-                FileFilter::All => true,
-                FileFilter::Transcripts => file.file_type == FileType::Transcript,
-                FileFilter::Reports => file.file_type == FileType::Report,
-            })
-            .cloned()
-            .collect();
-
-        self.file_list.update_items(filtered_files);
+    /// List the stored files again off the event loop; the listing replaces
+    /// the cached one on a later tick.
+    fn refresh_file_list(&mut self) {
+        self.listing_generation += 1;
+        self.listing_files = true;
+        let generation = self.listing_generation;
+        let tx = self.listing_tx.clone();
+        tokio::spawn(async move {
+            let _ = tx.send((generation, StorageService::list_files_async().await));
+        });
     }
 
-    fn apply_search_filter(&mut self) {
-        let search_term = self.search_input.value.to_lowercase();
-        if search_term.is_empty() {
-            self.apply_filter();
-            return;
+    /// Take in the listing asked for last, if it arrived.
+    fn take_listing(&mut self) {
+        while let Ok((generation, listing)) = self.listing_rx.try_recv() {
+            // Files changed again since this listing was asked for
+            if generation != self.listing_generation {
+                continue;
+            }
+            self.listing_files = false;
+            match listing {
+                Ok(files) => self.set_files(files),
+                Err(e) => self.error_popup = Some(format!("Could not list stored files: {e}")),
+            }
         }
+    }
 
-        let filtered_files: Vec<FileEntry> = self
-            .file_cache
-            .iter()
-            .filter(|file| {
-                let matches_filter = match self.filter {
-                    FileFilter::All => true,
-                    FileFilter::Transcripts => file.file_type == FileType::Transcript,
-                    FileFilter::Reports => file.file_type == FileType::Report,
-                };
+    fn set_files(&mut self, files: Vec<FileEntry>) {
+        self.file_cache = files;
+        self.scroll_positions
+            .retain(|path, _| self.file_cache.iter().any(|file| &file.path == path));
+        let cache = &self.file_cache;
+        self.file_list
+            .retain_checked(|path| cache.iter().any(|file| &file.path == path));
 
-                let matches_search = file.name.to_lowercase().contains(&search_term);
+        self.refresh_view();
+    }
 
-                matches_filter && matches_search
-            })
-            .cloned()
-            .collect();
+    /// Show `query`, recomputing the rows only if it selects different ones.
+    fn set_query(&mut self, query: BrowserQuery) {
+        // Whatever set the query replaces a search still waiting to apply
+        self.search_typed = None;
+        let changed = !query.same_rows(&self.query);
+        self.query = query;
+        if changed {
+            self.refresh_view();
+        }
+    }
 
-        self.file_list.update_items(filtered_files);
+    /// Recompute the rows from the cached listing, e.g. after it was reloaded.
+    fn refresh_view(&mut self) {
+        self.file_list
+            .update_items(browser::apply(&self.query, &self.file_cache));
     }
 
     fn open_file(&mut self, file: FileEntry) -> Result<()> {
-        let content = std::fs::read_to_string(&file.path)?;
-        let viewer = Viewer::new(content, file.path.to_string_lossy().to_string());
+        // A transcript saved with --format md reads better as its Markdown copy
+        let shown = file.markdown_copy().unwrap_or_else(|| file.path.clone());
+        let content = std::fs::read_to_string(&shown).map_err(|e| {
+            std::io::Error::new(e.kind(), format!("Could not read {}: {e}", shown.display()))
+        })?;
+        let mut viewer = Viewer::new(content.clone(), shown.to_string_lossy().to_string());
+        // Bookmarks belong to the original transcript, not its translations
+        if file.file_type == FileType::Transcript
+            && file.translation().is_none()
+            && let Some(video_id) = file.video_id()
+        {
+            let bookmarks = BookmarkList::load(&video_id).unwrap_or_else(|_| BookmarkList {
+                video_id,
+                entries: Vec::new(),
+            });
+            viewer.enable_bookmarks(bookmarks);
+        }
+        // Clamped to the content on the first render
+        viewer.scroll = self.scroll_positions.get(&file.path).copied().unwrap_or(0);
+        if file.file_type == FileType::Report {
+            let repair_hint = file
+                .video_id()
+                .map(|id| format!(" (vidio repair {id})"))
+                .unwrap_or_default();
+            viewer.set_warnings(
+                recovery::detect(&content)
+                    .iter()
+                    .map(|issue| format!("Report looks corrupt: {issue}{repair_hint}"))
+                    .collect(),
+            );
+        }
         self.content_viewer = Some(viewer);
-        self.state = AppState::Viewer {
+        self.push_state(AppState::Viewer {
             file_path: file.path,
-        };
+        });
+        Ok(())
+    }
+
+    /// Pick up what the editor changed: the file list, and the Viewer's
+    /// content if it shows the edited file.
+    pub fn editor_closed(&mut self, path: &Path, result: Result<()>) {
+        let reloaded = self.reload_edited(path);
+        if let Err(e) = result.and(reloaded) {
+            self.error_popup = Some(e.to_string());
+        }
+    }
+
+    fn reload_edited(&mut self, path: &Path) -> Result<()> {
+        self.preview.reset();
+        self.refresh_file_list();
+        if let (AppState::Viewer { file_path }, Some(viewer)) = (&self.state, &self.content_viewer)
+            && file_path == path
+        {
+            self.scroll_positions
+                .insert(file_path.clone(), viewer.scroll);
+            // The new listing is not in yet, but only the content matters here
+            let file = self.file_cache.iter().find(|file| file.path == path);
+            match file.filter(|_| path.is_file()) {
+                Some(file) => self.open_file(file.clone())?,
+                None => {
+                    self.error_popup = Some(format!("{} is gone", path.display()));
+                    self.pop_state();
+                }
+            }
+        }
         Ok(())
     }
 
     fn delete_selected_files(&mut self) -> Result<()> {
         let selected_files = self.file_list.get_selected_items();
-        for file in selected_files {
-            StorageService::delete_file(&file.path)?;
+        if selected_files.is_empty() {
+            return Ok(());
         }
-        self.refresh_file_list()?;
+        self.last_deleted.clear();
+        for file in &selected_files {
+            if let Some(trashed) = StorageService::delete_file(&file.path, self.config.safe_delete)?
+            {
+                self.last_deleted.push(trashed);
+            }
+        }
+        if !self.last_deleted.is_empty() {
+            self.browser_status = Some(format!(
+                "Moved {} file(s) to the trash. Press u to undo.",
+                selected_files.len()
+            ));
+        }
+        self.refresh_file_list();
         Ok(())
     }
+
+    /// Put back what the last delete moved to the trash.
+    fn undo_delete(&mut self) -> Result<()> {
+        if self.last_deleted.is_empty() {
+            self.browser_status = Some("Nothing to undo".to_string());
+            return Ok(());
+        }
+        let mut restored = 0;
+        let mut failed = Vec::new();
+        for name in std::mem::take(&mut self.last_deleted) {
+            match StorageService::restore_from_trash(&name) {
+                Ok(_) => restored += 1,
+                Err(e) => failed.push(e.to_string()),
+            }
+        }
+        self.browser_status = Some(match failed.first() {
+            None => format!("Restored {restored} file(s)"),
+            Some(e) => format!("Restored {restored} file(s); {} failed: {e}", failed.len()),
+        });
+        self.refresh_file_list();
+        Ok(())
+    }
+}
+
+/// Where the bar stands once `streamed` bytes of a report expected to run
+/// about `expected` bytes have arrived: from 0.7, where generation starts,
+/// towards 0.9 without reaching it, since the final length is unknown.
+fn streaming_progress(streamed: usize, expected: usize) -> f64 {
+    0.7 + 0.2 * (1.0 - (-(streamed as f64) / expected.max(1) as f64).exp())
+}
+
+async fn run_job(
+    transcript_service: &TranscriptService,
+    report_service: &ReportService,
+    video_id: String,
+    request: TranscriptRequest,
+    token: &CancellationToken,
+    tx: &mpsc::UnboundedSender<ProcessingMsg>,
+    questions: &mpsc::UnboundedSender<CostQuestion>,
+) -> std::result::Result<(), JobFailure> {
+    let transcript_service = &transcript_service
+        .clone()
+        .manual_only(request.manual_only)
+        .format(request.format, DEFAULT_PARAGRAPH_GAP);
+    let send = |message| {
+        let _ = tx.send(message);
+    };
+    let status = |status: &str| send(ProcessingMsg::Status(status.to_string()));
+    let log = |line: String| send(ProcessingMsg::Log(line));
+    status("Starting processing...");
+    send(ProcessingMsg::Progress(0.1));
+    log("Extracting video ID...".to_string());
+
+    // Read a custom prompt now, so a bad file fails before anything is fetched
+    let prompt = if request.generate_report {
+        match PromptSource::resolve(request.template, None) {
+            Ok(prompt) => prompt,
+            Err(e) => {
+                log(e.to_string());
+                status("Error reading the prompt");
+                return Err(JobFailure::Failed);
+            }
+        }
+    } else {
+        PromptSource::Template(request.template)
+    };
+    let job = Job {
+        video_id,
+        languages: request.languages,
+        preserve_formatting: request.preserve_formatting,
+        generate_report: request.generate_report,
+        prompt,
+        report_language: request.report_language,
+        translate: request.translate,
+        overwrite_report: request.overwrite_report,
+    };
+    let approve = |estimate: CostEstimate| {
+        let (reply, answer) = oneshot::channel();
+        let _ = questions.send(CostQuestion { estimate, reply });
+        answer
+    };
+    let services = Services {
+        transcript: transcript_service,
+        report: report_service,
+        approve: &approve,
+        on_chunk: &|progress| log(progress.to_string()),
+        on_text: &|text| {
+            send(match text {
+                ReportText::Restart => ProcessingMsg::ContentReset,
+                ReportText::Delta(delta) => ProcessingMsg::Content(delta.to_string()),
+            })
+        },
+    };
+    // Each stage is timed here, where it runs, and the app shows the clock
+    let mut started = Instant::now();
+    let mut report_progress = |event: Progress| {
+        let (message, progress, line) = match event {
+            Progress::Started(Stage::Fetch) => (
+                Some("Downloading transcript..."),
+                Some(0.25),
+                Some("Fetching transcript..."),
+            ),
+            Progress::Finished(Stage::Fetch) => (None, Some(0.5), None),
+            Progress::Skipped(Stage::Fetch) => (
+                None,
+                Some(0.5),
+                Some("Transcript already exists locally. Skipping download."),
+            ),
+            Progress::Started(Stage::SaveTranscript) => {
+                (None, None, Some("Saving transcript to file..."))
+            }
+            Progress::Finished(Stage::SaveTranscript) => (None, Some(0.6), None),
+            Progress::Started(Stage::EstimateCost) => {
+                (Some("Estimating the report's cost..."), Some(0.65), None)
+            }
+            Progress::Finished(Stage::EstimateCost) => (None, None, None),
+            Progress::Started(Stage::GenerateReport) => (
+                Some("Generating report..."),
+                Some(0.7),
+                Some("Generating report..."),
+            ),
+            Progress::Finished(Stage::GenerateReport) => (None, Some(0.9), None),
+            Progress::Started(Stage::SaveReport) => (None, None, Some("Saving report to file...")),
+            Progress::Finished(Stage::SaveReport) => (None, Some(1.0), None),
+            Progress::Skipped(_) => (None, None, None),
+        };
+        if let Some(message) = message {
+            status(message);
+        }
+        if let Some(progress) = progress {
+            send(ProcessingMsg::Progress(progress));
+        }
+        if let Some(line) = line {
+            log(line.to_string());
+        }
+        match event {
+            Progress::Started(stage) => {
+                started = Instant::now();
+                send(ProcessingMsg::Stage(stage));
+            }
+            Progress::Finished(stage) => {
+                send(ProcessingMsg::StageDone);
+                let done = match stage {
+                    Stage::Fetch => "Successfully fetched transcript!",
+                    Stage::SaveTranscript => "Transcript saved successfully!",
+                    Stage::EstimateCost => return,
+                    Stage::GenerateReport => "Report generated successfully!",
+                    Stage::SaveReport => "Report saved successfully!",
+                };
+                log(format!("{done} ({})", clock(started.elapsed())));
+                // A translation's sidecar describes the original, not it
+                if stage == Stage::SaveTranscript
+                    && job.translate.is_none()
+                    && TranscriptMetadata::is_auto_generated(&job.video_id)
+                {
+                    log(format!(
+                        "{AUTO_GENERATED_WARNING}. Tick \"Manual captions only\" to refuse them."
+                    ));
+                }
+            }
+            Progress::Skipped(_) => {}
+        }
+    };
+
+    match pipeline::run_job(&FileStorage, &services, &job, token, &mut report_progress).await {
+        Ok(Outcome::AlreadyProcessed) => {
+            status("Already processed");
+            send(ProcessingMsg::Progress(1.0));
+            log("Transcript (and report if requested) already exist. Skipping.".to_string());
+            Ok(())
+        }
+        Ok(Outcome::Completed) => {
+            send(ProcessingMsg::Progress(1.0));
+            status("Completed");
+            Ok(())
+        }
+        Err(e @ JobError::Cancelled(_)) => {
+            log(e.to_string());
+            status("Cancelled");
+            Err(JobFailure::Cancelled)
+        }
+        Err(JobError::Failed(_, Error::TranscriptUnavailable(reason))) => {
+            log(reason);
+            log("There is nothing to download; trying again won't change that.".to_string());
+            status("No transcript available");
+            Err(JobFailure::NoTranscript)
+        }
+        Err(e @ JobError::Failed(stage, _)) => {
+            log(e.to_string());
+            status(&format!("Error {}", stage.describe()));
+            Err(JobFailure::Failed)
+        }
+    }
 }
@@ -0,0 +1,371 @@
+// What the Browser shows. Every criterion lives in one `BrowserQuery` and the
+// visible rows come from `apply` over the cached listing, so key handlers only
+// ever change the query and never filter the list themselves.
+use crate::core::searches::{FileFilter, SortMode, matches_query};
+use crate::core::storage::{FileEntry, VideoEntry, group_by_video};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// One row of the Browser's file list.
+#[derive(Debug, Clone)]
+pub enum ViewRow {
+    File(FileEntry),
+    /// A video in the grouped view; while expanded its files follow it as
+    /// `File` rows.
+    Video {
+        video: VideoEntry,
+        expanded: bool,
+        /// Whether the video has a transcript and a report stored, including
+        /// ones the filter or search hides.
+        has_transcript: bool,
+        has_report: bool,
+    },
+}
+
+/// What keeps a row highlighted when the rows change.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowId {
+    File(PathBuf),
+    Video(String),
+}
+
+impl ViewRow {
+    pub fn id(&self) -> RowId {
+        match self {
+            ViewRow::File(file) => RowId::File(file.path.clone()),
+            ViewRow::Video { video, .. } => RowId::Video(video.id.clone()),
+        }
+    }
+
+    /// The file on a file row.
+    pub fn file(&self) -> Option<&FileEntry> {
+        match self {
+            ViewRow::File(file) => Some(file),
+            ViewRow::Video { .. } => None,
+        }
+    }
+
+    /// The files a row stands for: its own, or all of a video's.
+    pub fn files(&self) -> Vec<&FileEntry> {
+        match self {
+            ViewRow::File(file) => vec![file],
+            ViewRow::Video { video, .. } => video.files().collect(),
+        }
+    }
+}
+
+/// The files behind `rows`, each once, in row order.
+pub fn unique_files(rows: &[ViewRow]) -> Vec<&FileEntry> {
+    let mut seen: HashSet<&Path> = HashSet::new();
+    rows.iter()
+        .flat_map(ViewRow::files)
+        .filter(|file| seen.insert(&file.path))
+        .collect()
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BrowserQuery {
+    pub filter: FileFilter,
+    pub search: String,
+    pub sort: SortMode,
+    /// Flip the sort's natural direction.
+    pub reverse: bool,
+    /// The saved search this query was loaded from, while it is unchanged.
+    pub chip: Option<usize>,
+    /// One row per video instead of one per file.
+    pub grouped: bool,
+    /// Videos whose files are listed below them in the grouped view.
+    pub expanded: BTreeSet<String>,
+}
+
+impl BrowserQuery {
+    /// Whether `other` would show the same rows in the same order; the chip is
+    /// only a label.
+    pub fn same_rows(&self, other: &BrowserQuery) -> bool {
+        self.filter == other.filter
+            && self.search.trim() == other.search.trim()
+            && self.sort == other.sort
+            && self.reverse == other.reverse
+            && self.grouped == other.grouped
+            && (!self.grouped || self.expanded == other.expanded)
+    }
+
+    /// E.g. `date ↓` for newest first.
+    pub fn sort_label(&self) -> String {
+        let arrow = if self.reverse { '↑' } else { '↓' };
+        format!("{} {arrow}", self.sort.name())
+    }
+}
+
+/// The rows `query` selects from `files`, in its sort order. Grouped, each
+/// video comes where its first file would.
+pub fn apply(query: &BrowserQuery, files: &[FileEntry]) -> Vec<ViewRow> {
+    let all = files;
+    let mut files: Vec<FileEntry> = all
+        .iter()
+        .filter(|file| query.filter.matches(file) && matches_query(file, &query.search))
+        .cloned()
+        .collect();
+    query.sort.sort(&mut files, query.reverse);
+    if !query.grouped {
+        return files.into_iter().map(ViewRow::File).collect();
+    }
+
+    let stored: HashMap<String, (bool, bool)> = group_by_video(all)
+        .into_iter()
+        .map(|video| {
+            (
+                video.id,
+                (video.transcript.is_some(), video.report.is_some()),
+            )
+        })
+        .collect();
+    let mut rows = Vec::new();
+    for video in group_by_video(&files) {
+        let (has_transcript, has_report) = stored.get(&video.id).copied().unwrap_or_default();
+        let expanded = query.expanded.contains(&video.id);
+        let children: Vec<ViewRow> = if expanded {
+            video.files().cloned().map(ViewRow::File).collect()
+        } else {
+            Vec::new()
+        };
+        rows.push(ViewRow::Video {
+            has_transcript,
+            has_report,
+            video,
+            expanded,
+        });
+        rows.extend(children);
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BrowserQuery, ViewRow, apply};
+    use crate::core::searches::{FileFilter, SortMode};
+    use crate::core::storage::{FileEntry, FileType};
+    use std::time::{Duration, SystemTime};
+
+    /// Listed newest first, like `list_files`, with sizes in reverse.
+    fn entry(name: &str, file_type: FileType, title: Option<&str>) -> FileEntry {
+        let age = match name {
+            "transcript_rustconf001.txt" => 0,
+            "report_rustconf001.md" => 1,
+            "transcript_gophercon1.txt" => 2,
+            _ => 3,
+        };
+        FileEntry {
+            path: name.into(),
+            name: name.to_string(),
+            file_type,
+            size: age * 100,
+            modified: SystemTime::UNIX_EPOCH + Duration::from_secs(100 - age),
+            bookmarks: 0,
+            title: title.map(str::to_string),
+            auto_generated: false,
+        }
+    }
+
+    fn files() -> Vec<FileEntry> {
+        vec![
+            entry(
+                "transcript_rustconf001.txt",
+                FileType::Transcript,
+                Some("Async Rust"),
+            ),
+            entry(
+                "report_rustconf001.md",
+                FileType::Report,
+                Some("Async Rust"),
+            ),
+            entry("transcript_gophercon1.txt", FileType::Transcript, None),
+            entry("report_gophercon1.md", FileType::Report, None),
+        ]
+    }
+
+    fn names(query: &BrowserQuery) -> Vec<String> {
+        apply(query, &files())
+            .iter()
+            .map(|row| match row {
+                ViewRow::File(file) => file.name.clone(),
+                ViewRow::Video {
+                    video, expanded, ..
+                } => {
+                    format!("{}{}", if *expanded { "-" } else { "+" }, video.id)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn combines_filter_and_search() {
+        let cases: [(FileFilter, &str, &[&str]); 9] = [
+            (
+                FileFilter::All,
+                "",
+                &[
+                    "transcript_rustconf001.txt",
+                    "report_rustconf001.md",
+                    "transcript_gophercon1.txt",
+                    "report_gophercon1.md",
+                ],
+            ),
+            (
+                FileFilter::Transcripts,
+                "",
+                &["transcript_rustconf001.txt", "transcript_gophercon1.txt"],
+            ),
+            (
+                FileFilter::Reports,
+                "",
+                &["report_rustconf001.md", "report_gophercon1.md"],
+            ),
+            // by file name, trimmed and case-insensitive
+            (
+                FileFilter::All,
+                " GOPHER ",
+                &["transcript_gophercon1.txt", "report_gophercon1.md"],
+            ),
+            (FileFilter::Reports, "gopher", &["report_gophercon1.md"]),
+            // by title; entries without one only match on their name
+            (
+                FileFilter::Transcripts,
+                "async",
+                &["transcript_rustconf001.txt"],
+            ),
+            (
+                FileFilter::All,
+                "async",
+                &["transcript_rustconf001.txt", "report_rustconf001.md"],
+            ),
+            // nothing left
+            (FileFilter::Reports, "zig", &[]),
+            (FileFilter::Transcripts, "report_", &[]),
+        ];
+        for (filter, search, expected) in cases {
+            let query = BrowserQuery {
+                filter: filter.clone(),
+                search: search.to_string(),
+                ..BrowserQuery::default()
+            };
+            assert_eq!(names(&query), expected, "{filter:?} + {search:?}");
+        }
+    }
+
+    #[test]
+    fn sorts_the_filtered_rows() {
+        let query = |sort, reverse, filter| BrowserQuery {
+            filter,
+            search: "con".to_string(),
+            sort,
+            reverse,
+            ..BrowserQuery::default()
+        };
+        let cases: [(SortMode, bool, FileFilter, &[&str]); 5] = [
+            (
+                SortMode::Name,
+                false,
+                FileFilter::Transcripts,
+                // titled entries sort by title
+                &["transcript_rustconf001.txt", "transcript_gophercon1.txt"],
+            ),
+            (
+                SortMode::Name,
+                true,
+                FileFilter::Reports,
+                &["report_gophercon1.md", "report_rustconf001.md"],
+            ),
+            (
+                SortMode::Size,
+                false,
+                FileFilter::All,
+                &[
+                    "report_gophercon1.md",
+                    "transcript_gophercon1.txt",
+                    "report_rustconf001.md",
+                    "transcript_rustconf001.txt",
+                ],
+            ),
+            (
+                SortMode::Date,
+                true,
+                FileFilter::Reports,
+                &["report_gophercon1.md", "report_rustconf001.md"],
+            ),
+            (
+                SortMode::Size,
+                true,
+                FileFilter::Reports,
+                &["report_rustconf001.md", "report_gophercon1.md"],
+            ),
+        ];
+        for (sort, reverse, filter, expected) in cases {
+            let query = query(sort, reverse, filter);
+            assert_eq!(names(&query), expected, "{}", query.sort_label());
+        }
+    }
+
+    #[test]
+    fn grouped_rows_list_expanded_videos_files() {
+        let mut query = BrowserQuery {
+            grouped: true,
+            ..BrowserQuery::default()
+        };
+        assert_eq!(names(&query), ["+rustconf001", "+gophercon1"]);
+
+        let collapsed = query.clone();
+        query.expanded.insert("gophercon1".to_string());
+        assert!(!query.same_rows(&collapsed));
+        assert_eq!(
+            names(&query),
+            [
+                "+rustconf001",
+                "-gophercon1",
+                "transcript_gophercon1.txt",
+                "report_gophercon1.md",
+            ]
+        );
+
+        query.filter = FileFilter::Reports;
+        query.search = "async".to_string();
+        assert_eq!(names(&query), ["+rustconf001"]);
+        // The filtered-out transcript still counts as there
+        let rows = apply(&query, &files());
+        assert!(matches!(
+            rows[0],
+            ViewRow::Video {
+                has_transcript: true,
+                has_report: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn empty_listing_gives_no_rows() {
+        assert!(apply(&BrowserQuery::default(), &[]).is_empty());
+    }
+
+    #[test]
+    fn chip_does_not_change_the_rows() {
+        let query = BrowserQuery {
+            filter: FileFilter::Reports,
+            search: "rust".to_string(),
+            chip: Some(2),
+            ..BrowserQuery::default()
+        };
+        let unlabelled = BrowserQuery {
+            chip: None,
+            search: "rust ".to_string(),
+            ..query.clone()
+        };
+        assert!(query.same_rows(&unlabelled));
+        assert_eq!(names(&query), names(&unlabelled));
+        assert!(!query.same_rows(&BrowserQuery::default()));
+        assert!(!query.same_rows(&BrowserQuery {
+            reverse: true,
+            ..query.clone()
+        }));
+    }
+}
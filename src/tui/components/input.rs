@@ -1,4 +1,5 @@
-use crossterm::event::{KeyCode, KeyEvent};
+use crate::tui::{Theme, symbols};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     Frame,
     layout::Rect,
@@ -10,10 +11,15 @@ use ratatui::{
 #[derive(Debug, Clone)]
 pub struct InputField {
     pub value: String,
+    /// Position in characters, not bytes, so accented letters and emoji are
+    /// edited whole.
     pub cursor: usize,
     pub placeholder: String,
     pub label: String,
     pub focused: bool,
+    /// Border colour in place of the focus colour, e.g. to mark the value
+    /// valid or not.
+    pub border_color: Option<Color>,
 }
 
 impl InputField {
@@ -24,26 +30,53 @@ impl InputField {
             placeholder: placeholder.to_string(),
             label: label.to_string(),
             focused: false,
+            border_color: None,
         }
     }
 
     pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
         match key.code {
+            KeyCode::Left if ctrl => {
+                self.cursor = self.previous_word_start();
+                true
+            }
+            KeyCode::Right if ctrl => {
+                self.cursor = self.next_word_end();
+                true
+            }
+            KeyCode::Char('u') if ctrl => {
+                self.value.drain(..self.byte_index(self.cursor));
+                self.cursor = 0;
+                true
+            }
+            KeyCode::Char('k') if ctrl => {
+                self.value.truncate(self.byte_index(self.cursor));
+                true
+            }
+            KeyCode::Char('w') if ctrl => {
+                let start = self.previous_word_start();
+                self.value
+                    .drain(self.byte_index(start)..self.byte_index(self.cursor));
+                self.cursor = start;
+                true
+            }
             KeyCode::Char(c) => {
-                self.value.insert(self.cursor, c);
+                self.value.insert(self.byte_index(self.cursor), c);
                 self.cursor += 1;
                 true
             }
             KeyCode::Backspace => {
                 if self.cursor > 0 {
                     self.cursor -= 1;
-                    self.value.remove(self.cursor);
+                    self.value.remove(self.byte_index(self.cursor));
                 }
                 true
             }
             KeyCode::Delete => {
-                if self.cursor < self.value.len() {
-                    self.value.remove(self.cursor);
+                if self.cursor < self.len() {
+                    self.value.remove(self.byte_index(self.cursor));
                 }
                 true
             }
@@ -54,7 +87,7 @@ impl InputField {
                 true
             }
             KeyCode::Right => {
-                if self.cursor < self.value.len() {
+                if self.cursor < self.len() {
                     self.cursor += 1;
                 }
                 true
@@ -64,35 +97,39 @@ impl InputField {
                 true
             }
             KeyCode::End => {
-                self.cursor = self.value.len();
+                self.cursor = self.len();
                 true
             }
             _ => false,
         }
     }
 
-    pub fn render(&self, f: &mut Frame, area: Rect) {
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         let block = Block::default()
             .borders(Borders::ALL)
+            .border_set(symbols().frame)
             .title(self.label.as_str())
-            .border_style(if self.focused {
-                Style::default().fg(Color::Yellow)
-            } else {
-                Style::default().fg(Color::Gray)
-            });
+            .border_style(Style::default().fg(match self.border_color {
+                Some(color) => color,
+                None if self.focused => theme.accent,
+                None => theme.border,
+            }));
 
         let text = if self.value.is_empty() && !self.focused {
             Line::from(Span::styled(
                 &self.placeholder,
-                Style::default().fg(Color::DarkGray),
+                Style::default().fg(theme.dim),
             ))
         } else {
             let mut spans = vec![];
 
-            if self.focused && self.cursor <= self.value.len() {
-                let (before, after) = self.value.split_at(self.cursor);
+            if self.focused && self.cursor <= self.len() {
+                let (before, after) = self.value.split_at(self.byte_index(self.cursor));
                 spans.push(Span::raw(before));
-                spans.push(Span::styled("│", Style::default().fg(Color::Yellow)));
+                spans.push(Span::styled(
+                    symbols().cursor,
+                    Style::default().fg(theme.accent),
+                ));
                 spans.push(Span::raw(after));
             } else {
                 spans.push(Span::raw(&self.value));
@@ -109,8 +146,143 @@ impl InputField {
         !self.value.trim().is_empty()
     }
 
+    /// Replace the value and move the cursor to its end.
+    pub fn set_value(&mut self, value: &str) {
+        self.value = value.to_string();
+        self.cursor = self.len();
+    }
+
+    /// Insert pasted text at the cursor. The field holds one line, so a
+    /// trailing newline is dropped and any other line breaks or tabs become
+    /// spaces.
+    pub fn paste(&mut self, text: &str) {
+        let text: String = text
+            .trim_end_matches(['\r', '\n'])
+            .chars()
+            .filter(|&c| c != '\r')
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        self.value.insert_str(self.byte_index(self.cursor), &text);
+        self.cursor += text.chars().count();
+    }
+
     pub fn clear(&mut self) {
         self.value.clear();
         self.cursor = 0;
     }
+
+    /// Length of the value in characters.
+    fn len(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    /// Byte offset of the character at `pos`, or the end of the value.
+    fn byte_index(&self, pos: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(pos)
+            .map_or(self.value.len(), |(index, _)| index)
+    }
+
+    /// Cursor position at the start of the word before the cursor, skipping any
+    /// separators directly to its left first.
+    fn previous_word_start(&self) -> usize {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut pos = self.cursor.min(chars.len());
+        while pos > 0 && is_word_separator(chars[pos - 1]) {
+            pos -= 1;
+        }
+        while pos > 0 && !is_word_separator(chars[pos - 1]) {
+            pos -= 1;
+        }
+        pos
+    }
+
+    /// Cursor position at the end of the word after the cursor, skipping any
+    /// separators directly to its right first.
+    fn next_word_end(&self) -> usize {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut pos = self.cursor;
+        while pos < chars.len() && is_word_separator(chars[pos]) {
+            pos += 1;
+        }
+        while pos < chars.len() && !is_word_separator(chars[pos]) {
+            pos += 1;
+        }
+        pos
+    }
+}
+
+/// Word boundaries split URL segments as well as whitespace so that query
+/// parameters can be navigated one piece at a time.
+fn is_word_separator(c: char) -> bool {
+    matches!(c, '/' | '?' | '&' | '=') || c.is_whitespace()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InputField;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    fn press(field: &mut InputField, code: KeyCode) {
+        field.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn ctrl(field: &mut InputField, code: KeyCode) {
+        field.handle_key(KeyEvent::new(code, KeyModifiers::CONTROL));
+    }
+
+    #[test]
+    fn multi_byte_characters_are_edited_whole() {
+        let mut field = InputField::new("Title", "");
+        for c in "canción 🎵".chars() {
+            press(&mut field, KeyCode::Char(c));
+        }
+        assert_eq!(field.cursor, 9);
+        press(&mut field, KeyCode::Backspace);
+        press(&mut field, KeyCode::Backspace);
+        assert_eq!(field.value, "canción");
+
+        press(&mut field, KeyCode::Left);
+        press(&mut field, KeyCode::Left);
+        press(&mut field, KeyCode::Delete);
+        press(&mut field, KeyCode::Char('o'));
+        assert_eq!(field.value, "cancion");
+
+        press(&mut field, KeyCode::Home);
+        press(&mut field, KeyCode::Char('¡'));
+        press(&mut field, KeyCode::End);
+        press(&mut field, KeyCode::Char('!'));
+        assert_eq!(field.value, "¡cancion!");
+    }
+
+    #[test]
+    fn words_are_jumped_and_killed_across_accents() {
+        let mut field = InputField::new("Search", "");
+        field.set_value("año pasado/niño");
+        assert_eq!(field.cursor, 15);
+        ctrl(&mut field, KeyCode::Left);
+        assert_eq!(field.cursor, 11);
+        ctrl(&mut field, KeyCode::Char('w'));
+        assert_eq!((field.value.as_str(), field.cursor), ("año niño", 4));
+        ctrl(&mut field, KeyCode::Left);
+        ctrl(&mut field, KeyCode::Right);
+        assert_eq!(field.cursor, 3);
+        ctrl(&mut field, KeyCode::Char('k'));
+        assert_eq!(field.value, "año");
+        ctrl(&mut field, KeyCode::Char('u'));
+        assert_eq!((field.value.as_str(), field.cursor), ("", 0));
+    }
+
+    #[test]
+    fn pasted_text_lands_at_the_cursor_on_one_line() {
+        let mut field = InputField::new("Video URL", "");
+        field.set_value("é");
+        press(&mut field, KeyCode::Home);
+        field.paste("https://youtu.be/x\tv\r\n");
+        assert_eq!(field.value, "https://youtu.be/x vé");
+        assert_eq!(field.cursor, 20);
+        field.paste("a\nb");
+        assert_eq!(field.value, "https://youtu.be/x va bé");
+    }
 }
@@ -1,23 +1,30 @@
-use crate::core::storage::FileEntry;
+use crate::core::storage::{FileEntry, FileType, format_size};
+use crate::tui::browser::{ViewRow, unique_files};
+use crate::tui::components::ScrollTrack;
+use crate::tui::{Theme, symbols};
 use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
 use ratatui::{
     Frame,
     layout::Rect,
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, ListState},
 };
+use std::collections::HashSet;
+use std::path::PathBuf;
 
 pub struct FileList {
-    pub items: Vec<FileEntry>,
+    pub items: Vec<ViewRow>,
     pub state: ListState,
-    pub selected_items: Vec<bool>,
+    /// Checked files by path, so checks survive filtering and searching.
+    /// Only the shown ones count for actions.
+    pub checked: HashSet<PathBuf>,
     viewport_size: usize,
+    scrollbar: ScrollTrack,
 }
 
 impl FileList {
-    pub fn new(items: Vec<FileEntry>) -> Self {
-        let selected_items = vec![false; items.len()];
+    pub fn new(items: Vec<ViewRow>) -> Self {
         let mut state = ListState::default();
         if !items.is_empty() {
             state.select(Some(0));
@@ -26,8 +33,9 @@ impl FileList {
         Self {
             items,
             state,
-            selected_items,
+            checked: HashSet::new(),
             viewport_size: 0,
+            scrollbar: ScrollTrack::default(),
         }
     }
 
@@ -61,11 +69,23 @@ impl FileList {
                 self.toggle_selected();
                 true
             }
+            KeyCode::Char('a') => {
+                self.select_all();
+                true
+            }
+            KeyCode::Char('A') | KeyCode::Char('i') => {
+                self.invert_selection();
+                true
+            }
             _ => false,
         }
     }
 
     pub fn handle_mouse(&mut self, mouse: MouseEvent) -> bool {
+        if let Some(offset) = self.scrollbar.handle_mouse(mouse) {
+            self.scroll_to(offset);
+            return true;
+        }
         match mouse.kind {
             MouseEventKind::ScrollUp => {
                 self.scroll_up();
@@ -175,94 +195,217 @@ impl FileList {
         self.adjust_offset();
     }
 
+    /// Show the rows from `offset` on, moving the highlight only as far as
+    /// it takes to stay in view.
+    fn scroll_to(&mut self, offset: usize) {
+        if self.items.is_empty() {
+            return;
+        }
+        let viewport = self.viewport_size.max(1);
+        let offset = offset.min(self.items.len().saturating_sub(viewport));
+        let selected = self.state.selected().unwrap_or(0);
+        self.state
+            .select(Some(selected.clamp(offset, offset + viewport - 1)));
+        *self.state.offset_mut() = offset;
+        self.adjust_offset();
+    }
+
+    /// Check the highlighted row's files, or uncheck them if all are checked.
     pub fn toggle_selected(&mut self) {
-        if let Some(i) = self.state.selected()
-            && i < self.selected_items.len()
-        {
-            self.selected_items[i] = !self.selected_items[i];
+        let paths: Vec<PathBuf> = self
+            .get_selected_row()
+            .map(|row| row.files().iter().map(|file| file.path.clone()).collect())
+            .unwrap_or_default();
+        if paths.iter().all(|path| self.checked.contains(path)) {
+            for path in &paths {
+                self.checked.remove(path);
+            }
+        } else {
+            self.checked.extend(paths);
         }
     }
 
-    pub fn get_selected(&self) -> Option<&FileEntry> {
+    /// Check every shown file.
+    pub fn select_all(&mut self) {
+        let paths: Vec<PathBuf> = self.shown_files().map(|file| file.path.clone()).collect();
+        self.checked.extend(paths);
+    }
+
+    /// Check the shown files that are unchecked and uncheck the others.
+    /// Checked files hidden by the filter stay checked.
+    pub fn invert_selection(&mut self) {
+        let paths: Vec<PathBuf> = self.shown_files().map(|file| file.path.clone()).collect();
+        for path in paths {
+            if !self.checked.remove(&path) {
+                self.checked.insert(path);
+            }
+        }
+    }
+
+    /// Every file behind the shown rows, once each.
+    fn shown_files(&self) -> impl Iterator<Item = &FileEntry> {
+        unique_files(&self.items).into_iter()
+    }
+
+    /// Forget checks on files that no longer exist.
+    pub fn retain_checked(&mut self, exists: impl Fn(&PathBuf) -> bool) {
+        self.checked.retain(|path| exists(path));
+    }
+
+    pub fn get_selected_row(&self) -> Option<&ViewRow> {
         self.state.selected().and_then(|i| self.items.get(i))
     }
 
+    /// The highlighted file; none on a video row.
+    pub fn get_selected(&self) -> Option<&FileEntry> {
+        self.get_selected_row().and_then(ViewRow::file)
+    }
+
+    /// The checked files among those shown, so an action never touches rows
+    /// the user cannot see. A collapsed video's files count as shown.
     pub fn get_selected_items(&self) -> Vec<&FileEntry> {
-        self.selected_items
-            .iter()
-            .enumerate()
-            .filter_map(
-                |(i, &selected)| {
-                    if selected { self.items.get(i) } else { None }
-                },
-            )
+        self.shown_files()
+            .filter(|file| self.checked.contains(&file.path))
             .collect()
     }
 
-    pub fn render(&mut self, f: &mut Frame, area: Rect, title: &str) {
+    pub fn render(&mut self, f: &mut Frame, area: Rect, title: &str, theme: &Theme) {
         self.viewport_size = area.height.saturating_sub(2) as usize;
         if self.viewport_size == 0 {
             self.viewport_size = 1;
         }
         self.adjust_offset();
 
+        let symbols = symbols();
+        let grouped = self
+            .items
+            .iter()
+            .any(|row| matches!(row, ViewRow::Video { .. }));
         let items: Vec<ListItem> = self
             .items
             .iter()
-            .enumerate()
-            .map(|(i, file)| {
-                let checkbox = if self.selected_items.get(i).copied().unwrap_or(false) {
-                    "☑ "
-                } else {
-                    "☐ "
+            .map(|row| {
+                let file = match row {
+                    ViewRow::File(file) => file,
+                    ViewRow::Video {
+                        video,
+                        expanded,
+                        has_transcript,
+                        has_report,
+                    } => {
+                        let files = row.files();
+                        let checkbox =
+                            symbols.checkbox(files.iter().all(|f| self.checked.contains(&f.path)));
+                        let has = |present: bool| {
+                            if present {
+                                symbols.present
+                            } else {
+                                symbols.missing
+                            }
+                        };
+                        let arrow = if *expanded {
+                            symbols.expanded
+                        } else {
+                            symbols.collapsed
+                        };
+                        let mut spans = vec![
+                            Span::raw(format!("{arrow} {checkbox} ")),
+                            Span::styled(
+                                video.title.clone().unwrap_or_else(|| video.id.clone()),
+                                Style::default().fg(theme.text),
+                            ),
+                            Span::raw(format!(
+                                "  {} {} {} {}",
+                                symbols.transcript,
+                                has(*has_transcript),
+                                symbols.report,
+                                has(*has_report)
+                            )),
+                        ];
+                        if !video.others.is_empty() {
+                            spans.push(Span::styled(
+                                format!("  +{}", video.others.len()),
+                                Style::default().fg(theme.dim),
+                            ));
+                        }
+                        return ListItem::new(Line::from(spans));
+                    }
                 };
+                let checkbox = symbols.checkbox(self.checked.contains(&file.path));
 
                 let icon = match file.file_type {
-                    crate::core::storage::FileType::Transcript => "📄",
-                    crate::core::storage::FileType::Report => "📊",
+                    FileType::Transcript => symbols.transcript,
+                    FileType::Report => symbols.report,
                 };
 
-                let size_kb = file.size / 1024;
-                let size_str = if size_kb < 1024 {
-                    format!("{size_kb}KB")
-                } else {
-                    format!("{:.1}MB", size_kb as f64 / 1024.0)
-                };
+                let size_str = format_size(file.size);
 
-                let line = Line::from(vec![
-                    Span::raw(checkbox),
-                    Span::raw(icon),
+                // Files under a video row are indented past its arrow
+                let indent = if grouped { "    " } else { "" };
+                let mut spans = vec![Span::raw(indent), Span::raw(format!("{checkbox} {icon}"))];
+                if file.auto_generated {
+                    spans.push(Span::raw(symbols.auto_generated));
+                }
+                spans.extend([
                     Span::raw(" "),
-                    Span::styled(&file.name, Style::default().fg(Color::White)),
+                    Span::styled(file.display_name(), Style::default().fg(theme.text)),
                     Span::raw(format!(" ({size_str})")),
                 ]);
+                if file.bookmarks > 0 {
+                    spans.push(Span::styled(
+                        format!("  {}{}", symbols.bookmark, file.bookmarks),
+                        Style::default().fg(theme.mark),
+                    ));
+                }
+                let line = Line::from(spans);
 
                 ListItem::new(line)
             })
             .collect();
 
+        let title = match self.get_selected_items().len() {
+            0 => title.to_string(),
+            checked => format!("{title} ({checked} seleccionados)"),
+        };
         let list = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title(title))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_set(symbols.frame)
+                    .title(title),
+            )
             .highlight_style(
                 Style::default()
-                    .bg(Color::DarkGray)
+                    .bg(theme.highlight)
                     .add_modifier(Modifier::BOLD),
             );
 
         f.render_stateful_widget(list, area, &mut self.state);
+        self.scrollbar.render(
+            f,
+            area,
+            self.state.offset(),
+            self.items.len(),
+            self.viewport_size,
+            theme,
+        );
     }
 
-    pub fn update_items(&mut self, new_items: Vec<FileEntry>) {
+    /// Replace the rows, keeping the highlight on the same row. Checks are
+    /// kept by path, so a file filtered out and shown again is still checked.
+    pub fn update_items(&mut self, new_items: Vec<ViewRow>) {
         let current_selected = self.state.selected();
+        let highlighted = self.get_selected_row().map(ViewRow::id);
+
         self.items = new_items;
-        self.selected_items = vec![false; self.items.len()];
 
+        let same_file = highlighted.and_then(|id| self.items.iter().position(|row| row.id() == id));
         if self.items.is_empty() {
             self.state.select(None);
+        } else if let Some(index) = same_file {
+            self.state.select(Some(index));
         } else if let Some(selected) = current_selected {
-            if selected >= self.items.len() {
-                self.state.select(Some(self.items.len() - 1));
-            }
+            self.state.select(Some(selected.min(self.items.len() - 1)));
         } else {
             self.state.select(Some(0));
         }
@@ -296,3 +439,110 @@ impl FileList {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::FileList;
+    use crate::core::storage::{FileEntry, FileType, group_by_video};
+    use crate::tui::browser::ViewRow;
+    use std::time::SystemTime;
+
+    fn file(name: &str) -> FileEntry {
+        FileEntry {
+            path: name.into(),
+            name: name.to_string(),
+            file_type: FileType::Report,
+            size: 0,
+            modified: SystemTime::UNIX_EPOCH,
+            bookmarks: 0,
+            title: None,
+            auto_generated: false,
+        }
+    }
+
+    fn entry(name: &str) -> ViewRow {
+        ViewRow::File(file(name))
+    }
+
+    fn names(files: &[&FileEntry]) -> Vec<String> {
+        files.iter().map(|file| file.name.clone()).collect()
+    }
+
+    #[test]
+    fn highlight_and_checks_follow_files_across_updates() {
+        let mut list = FileList::new(vec![entry("a"), entry("b"), entry("c"), entry("d")]);
+        list.toggle_selected(); // a
+        list.next();
+        list.next();
+        list.toggle_selected(); // c
+        list.next(); // highlight d
+
+        // d moves up, a is filtered out
+        list.update_items(vec![entry("b"), entry("c"), entry("d")]);
+        assert_eq!(list.get_selected().unwrap().name, "d");
+        assert_eq!(names(&list.get_selected_items()), ["c"]);
+
+        // a comes back still checked; the highlighted file is gone so the index is clamped
+        list.update_items(vec![entry("a"), entry("c")]);
+        assert_eq!(list.get_selected().unwrap().name, "c");
+        assert_eq!(names(&list.get_selected_items()), ["a", "c"]);
+
+        list.update_items(Vec::new());
+        assert!(list.get_selected().is_none());
+        assert!(list.get_selected_items().is_empty());
+    }
+
+    #[test]
+    fn select_all_and_invert_only_touch_shown_files() {
+        let mut list = FileList::new(vec![entry("a"), entry("b"), entry("c")]);
+        list.toggle_selected(); // a
+        list.update_items(vec![entry("b"), entry("c")]);
+
+        list.select_all();
+        assert_eq!(names(&list.get_selected_items()), ["b", "c"]);
+        list.invert_selection();
+        assert!(list.get_selected_items().is_empty());
+        list.toggle_selected(); // b
+        list.invert_selection();
+        assert_eq!(names(&list.get_selected_items()), ["c"]);
+
+        // a was hidden all along and kept its check
+        list.update_items(vec![entry("a"), entry("b"), entry("c")]);
+        assert_eq!(names(&list.get_selected_items()), ["a", "c"]);
+
+        list.retain_checked(|path| path.as_os_str() != "a");
+        list.update_items(vec![entry("a"), entry("b"), entry("c")]);
+        assert_eq!(names(&list.get_selected_items()), ["c"]);
+    }
+
+    #[test]
+    fn video_rows_check_all_their_files() {
+        let files = [file("report_a.md"), file("report_a.summary.md")];
+        let video = group_by_video(&files).remove(0);
+        let collapsed = ViewRow::Video {
+            video: video.clone(),
+            expanded: false,
+            has_transcript: false,
+            has_report: true,
+        };
+        let mut list = FileList::new(vec![collapsed, entry("report_b.md")]);
+        assert!(list.get_selected().is_none());
+
+        list.toggle_selected();
+        let checked = ["report_a.md", "report_a.summary.md"];
+        assert_eq!(names(&list.get_selected_items()), checked);
+
+        // Expanded, the files are listed twice but checked and counted once
+        let expanded = ViewRow::Video {
+            video,
+            expanded: true,
+            has_transcript: false,
+            has_report: true,
+        };
+        let rows = vec![expanded, entry("report_a.md"), entry("report_a.summary.md")];
+        list.update_items(rows);
+        assert_eq!(names(&list.get_selected_items()), checked);
+        list.invert_selection();
+        assert!(list.get_selected_items().is_empty());
+    }
+}
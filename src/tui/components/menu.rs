@@ -0,0 +1,278 @@
+// Grid of selectable entries: two columns on wide terminals, one on narrow.
+// Disabled entries stay visible but dim, with the reason in place of the
+// description.
+use crate::tui::{Theme, symbols};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+const WIDE_LAYOUT_MIN_WIDTH: u16 = 80;
+const CELL_HEIGHT: u16 = 3;
+
+pub struct MenuItem<'a> {
+    pub icon: &'a str,
+    pub title: &'a str,
+    pub description: &'a str,
+    pub shortcut: char,
+    pub disabled: Option<&'a str>,
+    pub pinned: bool,
+}
+
+pub struct MenuGrid {
+    pub selected: usize,
+    columns: usize, // from the last render, so arrow keys match what is on screen
+}
+
+impl MenuGrid {
+    pub fn new() -> Self {
+        Self {
+            selected: 0,
+            columns: 1,
+        }
+    }
+
+    /// Move the selection with the arrow keys. Returns `false` for other keys.
+    pub fn handle_key(&mut self, key: KeyEvent, len: usize) -> bool {
+        if len == 0 {
+            return false;
+        }
+        let last = len - 1;
+        match key.code {
+            KeyCode::Up => {
+                self.selected = self.selected.saturating_sub(self.columns);
+            }
+            KeyCode::Down => {
+                if self.selected + self.columns <= last {
+                    self.selected += self.columns;
+                }
+            }
+            KeyCode::Left => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            KeyCode::Right => {
+                self.selected = (self.selected + 1).min(last);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn render(
+        &mut self,
+        f: &mut Frame,
+        area: Rect,
+        title: &str,
+        items: &[MenuItem],
+        theme: &Theme,
+    ) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame)
+            .title(title.to_string());
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        self.columns = if area.width >= WIDE_LAYOUT_MIN_WIDTH {
+            2
+        } else {
+            1
+        };
+        let cell_width = inner.width / self.columns as u16;
+
+        for (i, item) in items.iter().enumerate() {
+            let row = (i / self.columns) as u16;
+            let col = (i % self.columns) as u16;
+            let y = inner.y + row * CELL_HEIGHT;
+            if y >= inner.bottom() {
+                break;
+            }
+            let cell = Rect {
+                x: inner.x + col * cell_width,
+                y,
+                width: cell_width,
+                height: CELL_HEIGHT.min(inner.bottom() - y),
+            };
+            f.render_widget(
+                Paragraph::new(item_lines(item, i == self.selected, theme)),
+                cell,
+            );
+        }
+    }
+}
+
+impl Default for MenuGrid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn item_lines(item: &MenuItem, selected: bool, theme: &Theme) -> Vec<Line<'static>> {
+    let symbols = symbols();
+    let marker = symbols.radio(selected);
+    let title_style = match (item.disabled.is_some(), selected) {
+        (true, _) => Style::default().fg(theme.dim).add_modifier(Modifier::DIM),
+        (false, true) => Style::default()
+            .fg(theme.accent)
+            .add_modifier(Modifier::BOLD),
+        (false, false) => Style::default().fg(theme.text),
+    };
+
+    let mut heading = vec![Span::styled(
+        format!("{marker} [{}] {} {}", item.shortcut, item.icon, item.title),
+        title_style,
+    )];
+    if item.pinned {
+        heading.push(Span::styled(
+            format!(" {}", symbols.pinned),
+            Style::default().fg(theme.mark),
+        ));
+    }
+
+    let detail = match item.disabled {
+        Some(reason) => Span::styled(
+            format!("      Unavailable: {reason}"),
+            Style::default().fg(theme.dim).add_modifier(Modifier::DIM),
+        ),
+        None => Span::styled(
+            format!("      {}", item.description),
+            Style::default().fg(theme.secondary),
+        ),
+    };
+
+    vec![Line::from(heading), Line::from(detail)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MenuGrid, MenuItem};
+    use crate::tui::Theme;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use ratatui::style::{Color, Modifier};
+
+    fn items(disabled: Option<&'static str>) -> Vec<MenuItem<'static>> {
+        vec![
+            MenuItem {
+                icon: "+",
+                title: "New",
+                description: "Start a job",
+                shortcut: '1',
+                disabled,
+                pinned: false,
+            },
+            MenuItem {
+                icon: "≡",
+                title: "Files",
+                description: "Browse files",
+                shortcut: '2',
+                disabled: None,
+                pinned: true,
+            },
+            MenuItem {
+                icon: "⚙",
+                title: "Settings",
+                description: "Preferences",
+                shortcut: '3',
+                disabled: None,
+                pinned: false,
+            },
+        ]
+    }
+
+    fn draw(grid: &mut MenuGrid, width: u16, height: u16, items: &[MenuItem]) -> TestBackend {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|f| grid.render(f, f.area(), "Mode", items, &Theme::dark()))
+            .unwrap();
+        terminal.backend().clone()
+    }
+
+    /// Buffer text only; styles are checked separately where they matter.
+    fn assert_lines(backend: &TestBackend, expected: &[&str]) {
+        let buffer = backend.buffer();
+        let lines: Vec<String> = buffer
+            .content
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect())
+            .collect();
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn wide_layout_uses_two_columns() {
+        let mut grid = MenuGrid::new();
+        let backend = draw(&mut grid, 80, 8, &items(None));
+        assert_lines(
+            &backend,
+            &[
+                "┌Mode──────────────────────────────────────────────────────────────────────────┐",
+                "│● [1] + New                            ○ [2] ≡ Files ★                        │",
+                "│      Start a job                            Browse files                     │",
+                "│                                                                              │",
+                "│○ [3] ⚙ Settings                                                              │",
+                "│      Preferences                                                             │",
+                "│                                                                              │",
+                "└──────────────────────────────────────────────────────────────────────────────┘",
+            ],
+        );
+    }
+
+    #[test]
+    fn narrow_layout_uses_one_column() {
+        let mut grid = MenuGrid::new();
+        let backend = draw(&mut grid, 30, 10, &items(None));
+        assert_lines(
+            &backend,
+            &[
+                "┌Mode────────────────────────┐",
+                "│● [1] + New                 │",
+                "│      Start a job           │",
+                "│                            │",
+                "│○ [2] ≡ Files ★             │",
+                "│      Browse files          │",
+                "│                            │",
+                "│○ [3] ⚙ Settings            │",
+                "│      Preferences           │",
+                "└────────────────────────────┘",
+            ],
+        );
+    }
+
+    #[test]
+    fn disabled_entry_is_dim_with_reason() {
+        let mut grid = MenuGrid::new();
+        grid.selected = 1;
+        let backend = draw(&mut grid, 30, 5, &items(Some("offline mode")));
+        assert_lines(
+            &backend,
+            &[
+                "┌Mode────────────────────────┐",
+                "│○ [1] + New                 │",
+                "│      Unavailable: offline m│",
+                "│                            │",
+                "└────────────────────────────┘",
+            ],
+        );
+        let cell = &backend.buffer()[(1, 1)];
+        assert_eq!(cell.fg, Color::DarkGray);
+        assert!(cell.modifier.contains(Modifier::DIM));
+    }
+
+    #[test]
+    fn arrows_follow_the_rendered_column_count() {
+        let mut grid = MenuGrid::new();
+        let key = |code| crossterm::event::KeyEvent::from(code);
+        draw(&mut grid, 80, 8, &items(None));
+        grid.handle_key(key(crossterm::event::KeyCode::Down), 3);
+        assert_eq!(grid.selected, 2);
+
+        draw(&mut grid, 30, 10, &items(None));
+        grid.handle_key(key(crossterm::event::KeyCode::Up), 3);
+        assert_eq!(grid.selected, 1);
+    }
+}
@@ -1,9 +1,15 @@
 pub mod input;
 pub mod list;
+pub mod menu;
+pub mod preview;
 pub mod progress;
+pub mod scrollbar;
 pub mod viewer;
 
 pub use input::*;
 pub use list::*;
+pub use menu::*;
+pub use preview::*;
 pub use progress::*;
+pub use scrollbar::*;
 pub use viewer::*;
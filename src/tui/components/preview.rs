@@ -0,0 +1,135 @@
+// Read-only look at the start of the file highlighted in the Browser
+use crate::core::FileType;
+use crate::core::report::front_matter;
+use crate::tui::components::viewer::parse_markdown_to_lines;
+use crate::tui::{Theme, symbols};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Lines read from the top of the file.
+const PREVIEW_LINES: usize = 40;
+/// How long the highlight must rest on a file before it is read, so holding
+/// Down does not read every file on the way.
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+#[derive(Debug, Default)]
+pub struct Preview {
+    /// The file shown, with what was read from it.
+    shown: Option<(PathBuf, FileType, String)>,
+    /// The highlighted file waiting out the debounce, and since when.
+    pending: Option<(PathBuf, FileType, Instant)>,
+}
+
+impl Preview {
+    /// Follow the highlight to `path`, or to nothing when `None`.
+    pub fn request(&mut self, file: Option<(&Path, &FileType)>) {
+        let Some((path, file_type)) = file else {
+            self.shown = None;
+            self.pending = None;
+            return;
+        };
+        let already = |target: Option<&PathBuf>| target.is_some_and(|p| p == path);
+        if already(self.shown.as_ref().map(|(p, ..)| p))
+            || already(self.pending.as_ref().map(|(p, ..)| p))
+        {
+            return;
+        }
+        self.pending = Some((path.to_path_buf(), file_type.clone(), Instant::now()));
+    }
+
+    /// Read the pending file once the highlight has rested on it long enough.
+    pub fn tick(&mut self) {
+        if self
+            .pending
+            .as_ref()
+            .is_some_and(|(.., since)| since.elapsed() >= DEBOUNCE)
+            && let Some((path, file_type, _)) = self.pending.take()
+        {
+            let text = read_head(&path).unwrap_or_else(|e| format!("Cannot read the file: {e}"));
+            self.shown = Some((path, file_type, text));
+        }
+    }
+
+    /// Forget what was read, e.g. after the file was edited.
+    pub fn reset(&mut self) {
+        self.shown = None;
+        self.pending = None;
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame)
+            .title("Preview");
+        let Some((_, file_type, text)) = &self.shown else {
+            f.render_widget(block, area);
+            return;
+        };
+        let lines = match file_type {
+            FileType::Report => {
+                let (_, body) = front_matter::split(text);
+                let width = area.width.saturating_sub(2) as usize;
+                parse_markdown_to_lines(body, width, theme)
+            }
+            FileType::Transcript => text
+                .lines()
+                .map(|line| Line::from(line.to_string()))
+                .collect(),
+        };
+        let preview = Paragraph::new(lines)
+            .style(Style::default().fg(theme.secondary))
+            .wrap(Wrap { trim: false })
+            .block(block);
+        f.render_widget(preview, area);
+    }
+}
+
+fn read_head(path: &Path) -> std::io::Result<String> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut head = String::new();
+    for line in reader.lines().take(PREVIEW_LINES) {
+        head.push_str(&line?);
+        head.push('\n');
+    }
+    Ok(head)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DEBOUNCE, PREVIEW_LINES, Preview};
+    use crate::core::FileType;
+
+    #[test]
+    fn files_are_read_once_the_highlight_rests() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("transcript_a.txt");
+        let second = dir.path().join("transcript_b.txt");
+        let long: Vec<String> = (0..100).map(|n| format!("line {n}")).collect();
+        std::fs::write(&first, long.join("\n")).unwrap();
+        std::fs::write(&second, "b").unwrap();
+
+        let mut preview = Preview::default();
+        preview.request(Some((&first, &FileType::Transcript)));
+        preview.tick();
+        assert!(preview.shown.is_none(), "read before the debounce");
+
+        std::thread::sleep(DEBOUNCE);
+        preview.tick();
+        let (path, _, text) = preview.shown.as_ref().unwrap();
+        assert_eq!(path, &first);
+        assert_eq!(text.lines().count(), PREVIEW_LINES);
+
+        preview.request(Some((&second, &FileType::Transcript)));
+        preview.request(None);
+        assert!(preview.shown.is_none() && preview.pending.is_none());
+    }
+}
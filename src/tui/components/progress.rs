@@ -1,16 +1,53 @@
+use crate::core::pipeline::Stage;
+use crate::tui::{Theme, symbols};
 use ratatui::{
     Frame,
     layout::Rect,
-    style::{Color, Style},
+    style::Style,
     text::{Line, Span},
     widgets::{Block, Borders, Gauge, Paragraph},
 };
+use std::time::{Duration, Instant};
+
+/// Log lines kept for scrolling back; older ones are dropped.
+const MAX_LOGS: usize = 1000;
+/// Report length assumed when there is no estimate, in bytes of Markdown.
+pub const TYPICAL_REPORT_BYTES: usize = 12_000;
+/// Bytes of Markdown per output token, to turn an estimate into a length.
+pub const BYTES_PER_TOKEN: u64 = 4;
+/// Writing speed assumed until the report has streamed long enough to
+/// measure it: about 50 tokens a second.
+const DEFAULT_BYTES_PER_SEC: f64 = 200.0;
+/// Report text needed before its own speed replaces the default.
+const MEASURE_AFTER_BYTES: usize = 500;
 
 pub struct ProgressBar {
     pub progress: f64,
     pub message: String,
     pub logs: Vec<String>,
     pub max_logs: usize,
+    /// Lines scrolled back from the newest; 0 follows new lines as they come.
+    pub log_scroll: usize,
+    /// Rows of log shown at the last draw, the size of a page.
+    pub log_height: usize,
+    /// The report as the model writes it, shown beside the log.
+    pub live: String,
+    /// Report text received for the current job, across restarts.
+    pub streamed: usize,
+    /// The stage running and when it started, for its elapsed time.
+    pub stage: Option<(Stage, Instant)>,
+    /// When the first report text arrived, to measure the writing speed.
+    pub first_text: Option<Instant>,
+    /// How long the report should be, from the cost estimate.
+    pub expected_report_bytes: Option<usize>,
+    /// When the current job started, for its total time.
+    pub start: Option<Instant>,
+    /// When the progress last moved, to tell how fast it moves.
+    pub last_progress: Option<Instant>,
+    /// Spinner frame, advanced on every tick.
+    pub spinner: usize,
+    /// The last job failed; its status shows in red and the spinner stops.
+    pub failed: bool,
 }
 
 impl ProgressBar {
@@ -19,12 +56,33 @@ impl ProgressBar {
             progress: 0.0,
             message: String::new(),
             logs: Vec::new(),
-            max_logs: 10,
+            max_logs: MAX_LOGS,
+            log_scroll: 0,
+            log_height: 0,
+            live: String::new(),
+            streamed: 0,
+            stage: None,
+            first_text: None,
+            expected_report_bytes: None,
+            start: None,
+            last_progress: None,
+            spinner: 0,
+            failed: false,
         }
     }
 
     pub fn set_progress(&mut self, progress: f64) {
-        self.progress = progress.clamp(0.0, 1.0);
+        let now = Instant::now();
+        self.start.get_or_insert(now);
+        let progress = progress.clamp(0.0, 1.0);
+        if progress > self.progress {
+            self.last_progress = Some(now);
+        }
+        self.progress = progress;
+    }
+
+    pub fn tick(&mut self) {
+        self.spinner = self.spinner.wrapping_add(1);
     }
 
     pub fn set_message(&mut self, message: String) {
@@ -36,13 +94,102 @@ impl ProgressBar {
         let log_entry = format!("[{timestamp}] {log}");
 
         self.logs.push(log_entry);
+        // Scrolled back, the view stays on the lines being read
+        if self.log_scroll > 0 {
+            self.log_scroll += 1;
+        }
 
         if self.logs.len() > self.max_logs {
             self.logs.remove(0);
         }
+        self.log_scroll = self.log_scroll.min(self.max_log_scroll());
+    }
+
+    fn max_log_scroll(&self) -> usize {
+        self.logs.len().saturating_sub(self.log_height.max(1))
+    }
+
+    /// Scroll the log back by `lines`, or towards the newest for a negative
+    /// count. Reaching the newest line follows the log again.
+    pub fn scroll_log(&mut self, lines: isize) {
+        self.log_scroll = self
+            .log_scroll
+            .saturating_add_signed(lines)
+            .min(self.max_log_scroll());
+    }
+
+    /// One page of log, as last drawn.
+    pub fn log_page(&self) -> isize {
+        self.log_height.max(1) as isize
+    }
+
+    pub fn start_stage(&mut self, stage: Stage) {
+        self.stage = Some((stage, Instant::now()));
+    }
+
+    pub fn append_live(&mut self, text: &str) {
+        self.first_text.get_or_insert_with(Instant::now);
+        self.live.push_str(text);
+        self.streamed += text.len();
+    }
+
+    /// Drop the live text of a request that is starting over.
+    pub fn restart_live(&mut self) {
+        self.live.clear();
+    }
+
+    /// Forget the previous job's report and timings.
+    pub fn clear_live(&mut self) {
+        self.live.clear();
+        self.streamed = 0;
+        self.stage = None;
+        self.first_text = None;
+        self.expected_report_bytes = None;
+        self.start = None;
+        self.last_progress = None;
+        self.failed = false;
+    }
+
+    /// Time spent in the current stage, and for the report a rough time left:
+    /// its expected length at the speed it is being written, or at a typical
+    /// speed until there is enough text to tell.
+    pub fn timing(&self, now: Instant) -> Option<String> {
+        let (stage, started) = self.stage?;
+        let mut timing = format!("{} elapsed", clock(now - started));
+        if stage == Stage::GenerateReport {
+            let expected = self.expected_report_bytes.unwrap_or(TYPICAL_REPORT_BYTES);
+            let rate = match self.first_text {
+                Some(first) if self.streamed >= MEASURE_AFTER_BYTES && now > first => {
+                    self.streamed as f64 / (now - first).as_secs_f64()
+                }
+                _ => DEFAULT_BYTES_PER_SEC,
+            };
+            let left = expected.saturating_sub(self.streamed) as f64 / rate;
+            timing.push_str(&time_left(left));
+        }
+        Some(timing)
+    }
+
+    /// Time since the job started, and a naive time left from how fast the
+    /// progress moved until its last update. The report stage gives its own,
+    /// better estimate, so none is added then.
+    pub fn job_timing(&self, now: Instant) -> Option<String> {
+        let start = self.start?;
+        let mut timing = format!("{} total", clock(now - start));
+        let writing_report = matches!(self.stage, Some((Stage::GenerateReport, _)));
+        if let Some(last) = self.last_progress
+            && self.progress > 0.0
+            && self.progress < 1.0
+            && !writing_report
+        {
+            let spent = (last - start).as_secs_f64();
+            let left = spent * (1.0 - self.progress) / self.progress - (now - last).as_secs_f64();
+            timing.push_str(&time_left(left));
+        }
+        Some(timing)
     }
 
-    pub fn render(&self, f: &mut Frame, area: Rect, video_id: &str) {
+    pub fn render(&mut self, f: &mut Frame, area: Rect, video_id: &str, theme: &Theme) {
         let chunks = ratatui::layout::Layout::default()
             .direction(ratatui::layout::Direction::Vertical)
             .constraints([
@@ -54,39 +201,129 @@ impl ProgressBar {
             .split(area);
 
         // Video ID
-        let video_paragraph = Paragraph::new(format!("Video ID: {video_id}"))
-            .style(Style::default().fg(Color::White));
+        let video_paragraph =
+            Paragraph::new(format!("Video ID: {video_id}")).style(Style::default().fg(theme.text));
         f.render_widget(video_paragraph, chunks[0]);
 
         // Progress bar
         let progress_percent = (self.progress * 100.0) as u16;
         let gauge = Gauge::default()
-            .block(Block::default().borders(Borders::ALL).title("Progress"))
-            .gauge_style(Style::default().fg(Color::Green))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_set(symbols().frame)
+                    .title("Progress"),
+            )
+            .gauge_style(Style::default().fg(theme.success))
             .percent(progress_percent);
         f.render_widget(gauge, chunks[1]);
 
-        // Status message
-        let status_paragraph = Paragraph::new(format!("Status: {}", self.message))
-            .style(Style::default().fg(Color::Yellow));
+        // Status message, with a spinner while the job runs
+        let now = Instant::now();
+        let mut status = "Status: ".to_string();
+        if self.start.is_some() && self.progress < 1.0 && !self.failed {
+            let spinner = symbols().spinner;
+            status.push_str(spinner[self.spinner % spinner.len()]);
+            status.push(' ');
+        }
+        status.push_str(&self.message);
+        if let Some(timing) = self.timing(now) {
+            status.push_str(&format!(" {timing}"));
+        }
+        if let Some(timing) = self.job_timing(now) {
+            status.push_str(&format!(" · {timing}"));
+        }
+        let color = if self.failed {
+            theme.error
+        } else {
+            theme.accent
+        };
+        let status_paragraph = Paragraph::new(status).style(Style::default().fg(color));
         f.render_widget(status_paragraph, chunks[2]);
 
-        // Logs
-        let log_lines: Vec<Line> = self
-            .logs
+        // Logs, beside the live report while one is being written
+        let halves = if self.live.is_empty() {
+            vec![chunks[3]]
+        } else {
+            ratatui::layout::Layout::default()
+                .direction(ratatui::layout::Direction::Horizontal)
+                .constraints([
+                    ratatui::layout::Constraint::Percentage(40),
+                    ratatui::layout::Constraint::Percentage(60),
+                ])
+                .split(chunks[3])
+                .to_vec()
+        };
+        self.log_height = halves[0].height.saturating_sub(2) as usize;
+        self.log_scroll = self.log_scroll.min(self.max_log_scroll());
+        let end = self.logs.len() - self.log_scroll;
+        let log_lines: Vec<Line> = self.logs[end.saturating_sub(self.log_height)..end]
             .iter()
-            .map(|log| Line::from(Span::raw(log)))
+            .map(|log| {
+                if log.contains("Error") {
+                    Line::from(Span::styled(log, Style::default().fg(theme.error)))
+                } else {
+                    Line::from(Span::raw(log))
+                }
+            })
             .collect();
+        let title = if self.log_scroll > 0 {
+            format!("Log ({} newer lines, [End] to follow)", self.log_scroll)
+        } else {
+            "Log".to_string()
+        };
+        let logs_paragraph = Paragraph::new(log_lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(symbols().frame)
+                .title(title),
+        );
+        f.render_widget(logs_paragraph, halves[0]);
+        if self.live.is_empty() {
+            return;
+        }
 
-        let logs_paragraph =
-            Paragraph::new(log_lines).block(Block::default().borders(Borders::ALL).title("Log"));
-        f.render_widget(logs_paragraph, chunks[3]);
+        // The newest lines of the report, following the text as it grows
+        let width = halves[1].width.saturating_sub(2).max(1) as usize;
+        let height = halves[1].height.saturating_sub(2) as usize;
+        let lines = textwrap::wrap(&self.live, width);
+        let live_lines: Vec<Line> = lines[lines.len().saturating_sub(height)..]
+            .iter()
+            .map(|line| Line::from(Span::raw(line.to_string())))
+            .collect();
+        let live_paragraph = Paragraph::new(live_lines)
+            .style(Style::default().fg(theme.secondary))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_set(symbols().frame)
+                    .title("Report"),
+            );
+        f.render_widget(live_paragraph, halves[1]);
     }
 
     pub fn reset(&mut self) {
         self.progress = 0.0;
         self.message.clear();
         self.logs.clear();
+        self.log_scroll = 0;
+        self.clear_live();
+    }
+}
+
+/// `mm:ss`, with minutes past the hour counted on.
+pub fn clock(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+/// An estimate of `secs` left. Once the job has outrun it there is nothing
+/// useful to say, so a dash shows instead of a clock stuck at zero.
+fn time_left(secs: f64) -> String {
+    if secs >= 1.0 {
+        format!(" (≈{} left)", clock(Duration::from_secs_f64(secs)))
+    } else {
+        " (– left)".to_string()
     }
 }
 
@@ -95,3 +332,74 @@ impl Default for ProgressBar {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ProgressBar;
+    use crate::core::pipeline::Stage;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn scrolled_back_log_stays_put_until_it_reaches_the_end() {
+        let mut bar = ProgressBar::new();
+        bar.log_height = 3;
+        for i in 0..10 {
+            bar.add_log(format!("line {i}"));
+        }
+        assert_eq!(bar.log_scroll, 0);
+
+        bar.scroll_log(bar.log_page());
+        bar.add_log("line 10".to_string());
+        assert_eq!(bar.log_scroll, 4);
+
+        bar.scroll_log(isize::MAX);
+        assert_eq!(bar.log_scroll, 8);
+        bar.scroll_log(-20);
+        assert_eq!(bar.log_scroll, 0);
+        bar.add_log("line 11".to_string());
+        assert_eq!(bar.log_scroll, 0);
+    }
+
+    #[test]
+    fn report_time_left_follows_the_writing_speed() {
+        let mut bar = ProgressBar::new();
+        let start = Instant::now();
+        bar.stage = Some((Stage::Fetch, start));
+        let later = start + Duration::from_secs(83);
+        assert_eq!(bar.timing(later).unwrap(), "01:23 elapsed");
+
+        // 12,000 bytes at the default 200 a second
+        bar.stage = Some((Stage::GenerateReport, start));
+        assert_eq!(bar.timing(later).unwrap(), "01:23 elapsed (≈01:00 left)");
+
+        // 1,000 of 4,000 bytes in 10 seconds: 30 more
+        bar.expected_report_bytes = Some(4_000);
+        bar.streamed = 1_000;
+        bar.first_text = Some(start + Duration::from_secs(73));
+        assert_eq!(bar.timing(later).unwrap(), "01:23 elapsed (≈00:30 left)");
+
+        // Longer than expected: no time left to show
+        bar.streamed = 5_000;
+        assert_eq!(bar.timing(later).unwrap(), "01:23 elapsed (– left)");
+    }
+
+    #[test]
+    fn job_time_left_follows_the_progress_rate() {
+        let mut bar = ProgressBar::new();
+        let start = Instant::now();
+        bar.start = Some(start);
+        let later = start + Duration::from_secs(40);
+        assert_eq!(bar.job_timing(later).unwrap(), "00:40 total");
+
+        // A quarter done after 30 seconds: 90 more, 10 of them gone since
+        bar.progress = 0.25;
+        bar.last_progress = Some(start + Duration::from_secs(30));
+        assert_eq!(bar.job_timing(later).unwrap(), "00:40 total (≈01:20 left)");
+        let overdue = start + Duration::from_secs(150);
+        assert_eq!(bar.job_timing(overdue).unwrap(), "02:30 total (– left)");
+
+        // The report stage estimates its own time left
+        bar.stage = Some((Stage::GenerateReport, start));
+        assert_eq!(bar.job_timing(later).unwrap(), "00:40 total");
+    }
+}
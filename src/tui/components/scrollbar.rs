@@ -0,0 +1,146 @@
+// A scrollbar over the right border of a bordered view, which also turns a
+// click or drag on it into a scroll offset.
+use crate::tui::{Theme, symbols};
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    layout::{Margin, Position, Rect},
+    style::Style,
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState},
+};
+
+/// Where the scrollbar was last drawn and for how many rows, so mouse events
+/// map onto the same track the user sees.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScrollTrack {
+    area: Rect,
+    total: usize,
+    visible: usize,
+    dragging: bool,
+}
+
+impl ScrollTrack {
+    /// Draw the scrollbar for `total` rows, `visible` of them from `offset`
+    /// on. Nothing is drawn when they all fit.
+    pub fn render(
+        &mut self,
+        f: &mut Frame,
+        area: Rect,
+        offset: usize,
+        total: usize,
+        visible: usize,
+        theme: &Theme,
+    ) {
+        let inner = area.inner(Margin::new(0, 1));
+        self.total = total;
+        self.visible = visible;
+        if total <= visible || inner.width == 0 || inner.height == 0 {
+            self.area = Rect::default();
+            self.dragging = false;
+            return;
+        }
+        self.area = Rect {
+            x: inner.right() - 1,
+            width: 1,
+            ..inner
+        };
+
+        let max_offset = total - visible;
+        let mut state = ScrollbarState::new(max_offset + 1)
+            .position(offset.min(max_offset))
+            .viewport_content_length(visible);
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .track_symbol(Some(symbols().scrollbar_track))
+            .thumb_symbol(symbols().scrollbar_thumb)
+            .track_style(Style::default().fg(theme.border))
+            .thumb_style(Style::default().fg(theme.accent));
+        f.render_stateful_widget(scrollbar, inner, &mut state);
+    }
+
+    /// The offset to scroll to for a left click on the track, or a drag
+    /// that started there, wherever the pointer has moved since.
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) -> Option<usize> {
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left)
+                if self.area.contains(Position::new(mouse.column, mouse.row)) =>
+            {
+                self.dragging = true;
+            }
+            MouseEventKind::Drag(MouseButton::Left) if self.dragging => {}
+            MouseEventKind::Up(MouseButton::Left) if self.dragging => {
+                self.dragging = false;
+                return None;
+            }
+            _ => return None,
+        }
+        Some(self.offset_at(mouse.row))
+    }
+
+    /// The top of the track is the start and the bottom the end, with the
+    /// rows between spread evenly.
+    fn offset_at(&self, row: u16) -> usize {
+        let max_offset = self.total.saturating_sub(self.visible);
+        let span = self.area.height.saturating_sub(1) as usize;
+        if span == 0 {
+            return 0;
+        }
+        let along = (row.saturating_sub(self.area.y) as usize).min(span);
+        (along * max_offset + span / 2) / span
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScrollTrack;
+    use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+    use ratatui::layout::Rect;
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn clicks_and_drags_map_to_proportional_offsets() {
+        // 100 rows, 10 visible, track on column 9 from row 1 to row 11
+        let mut track = ScrollTrack {
+            area: Rect::new(9, 1, 1, 11),
+            total: 100,
+            visible: 10,
+            dragging: false,
+        };
+        let left = MouseButton::Left;
+
+        assert_eq!(
+            track.handle_mouse(mouse(MouseEventKind::Down(left), 3, 6)),
+            None
+        );
+        assert_eq!(
+            track.handle_mouse(mouse(MouseEventKind::Down(left), 9, 1)),
+            Some(0)
+        );
+        assert_eq!(
+            track.handle_mouse(mouse(MouseEventKind::Drag(left), 9, 6)),
+            Some(45)
+        );
+        // Off the track sideways and past its end still counts while dragging
+        assert_eq!(
+            track.handle_mouse(mouse(MouseEventKind::Drag(left), 2, 40)),
+            Some(90)
+        );
+        assert_eq!(
+            track.handle_mouse(mouse(MouseEventKind::Up(left), 2, 40)),
+            None
+        );
+        assert_eq!(
+            track.handle_mouse(mouse(MouseEventKind::Drag(left), 9, 6)),
+            None
+        );
+    }
+}
@@ -1,16 +1,23 @@
 // Colorized markdown viewer
+use crate::core::bookmarks::BookmarkList;
+use crate::core::report::front_matter;
+use crate::core::transcript::{find_timestamps, format_timestamp};
+use crate::tui::components::ScrollTrack;
+use crate::tui::{Theme, symbols};
 use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
 use html_escape::decode_html_entities;
 use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
 use ratatui::{
     Frame,
-    layout::Rect,
+    layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
 };
+use std::ops::Range;
 use textwrap::wrap;
-use unicode_width::UnicodeWidthStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+use url::Url;
 
 #[derive(Debug, Clone, Default)]
 pub struct Viewer {
@@ -19,6 +26,54 @@ pub struct Viewer {
     pub scroll: usize,
     wrapped_lines: Vec<Line<'static>>, // parsed and wrapped lines for current width
     last_known_width: u16,
+    search: ViewerSearch,
+    bookmarks: Option<TranscriptBookmarks>,
+    warnings: Vec<String>,       // shown in a banner above the content
+    raw: bool,                   // show source lines verbatim instead of rendered markdown
+    pending_scroll: Option<f64>, // relative position to restore after a raw toggle
+    /// Columns scrolled off the left edge; only non-zero when a line is wider
+    /// than the view, i.e. with natural-width tables.
+    pub h_scroll: usize,
+    max_h_scroll: usize,
+    wide_tables: bool, // keep natural column widths instead of shrinking tables to fit
+    links: Vec<String>, // link targets in `[n]` marker order, from the last render
+    link_prompt: Option<String>, // Some while the user is typing a link number
+    link_status: Option<String>,
+    word_count: usize, // counted from the decoded source whenever the lines are rebuilt
+    theme: Theme,      // the lines are restyled when the theme changes
+    scrollbar: ScrollTrack,
+}
+
+const H_SCROLL_STEP: usize = 8;
+const READING_WPM: usize = 200;
+
+/// Transcript-only state: bookmarks plus the index mapping snippet start
+/// times onto wrapped lines, rebuilt whenever the content re-wraps.
+#[derive(Debug, Clone, Default)]
+struct TranscriptBookmarks {
+    list: BookmarkList,
+    timestamp_index: Vec<(usize, f64)>,
+    note_prompt: Option<(f64, String)>,
+    picker: Option<ListState>,
+    status: Option<String>,
+}
+
+const BOOKMARK_MARGIN: usize = 2;
+
+#[derive(Debug, Clone, Default)]
+struct ViewerSearch {
+    prompt: Option<String>, // Some while the user is typing a query
+    query: String,
+    matches: Vec<SearchMatch>,
+    current: usize,
+}
+
+/// A match inside `wrapped_lines`: the line index and the byte range within
+/// the line's concatenated span text.
+#[derive(Debug, Clone, PartialEq)]
+struct SearchMatch {
+    line: usize,
+    range: Range<usize>,
 }
 
 impl Viewer {
@@ -29,10 +84,87 @@ impl Viewer {
             scroll: 0,
             wrapped_lines: Vec::new(),
             last_known_width: 0,
+            search: ViewerSearch::default(),
+            bookmarks: None,
+            warnings: Vec::new(),
+            raw: false,
+            pending_scroll: None,
+            h_scroll: 0,
+            max_h_scroll: 0,
+            wide_tables: false,
+            links: Vec::new(),
+            link_prompt: None,
+            link_status: None,
+            word_count: 0,
+            theme: Theme::default(),
+            scrollbar: ScrollTrack::default(),
         }
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent, area_height: u16) {
+    /// Switch tables between shrinking to fit and keeping their natural
+    /// column widths, scrolled with Left/Right.
+    pub fn toggle_wide_tables(&mut self) {
+        self.wide_tables = !self.wide_tables;
+        self.h_scroll = 0;
+        self.wrapped_lines = Vec::new();
+    }
+
+    /// Switch between rendered and raw markdown, keeping the same relative
+    /// position in the document since the two views wrap to different lengths.
+    pub fn toggle_raw(&mut self) {
+        let lines = self.wrapped_lines.len();
+        if lines > 0 {
+            self.pending_scroll = Some(self.scroll as f64 / lines as f64);
+        }
+        self.raw = !self.raw;
+        self.wrapped_lines = Vec::new();
+    }
+
+    /// Show a banner above the content, e.g. for corruption found in a report.
+    pub fn set_warnings(&mut self, warnings: Vec<String>) {
+        self.warnings = warnings;
+    }
+
+    /// Switch on transcript mode: `m` bookmarks the top visible snippet and
+    /// `'` lists bookmarks for jumping.
+    pub fn enable_bookmarks(&mut self, list: BookmarkList) {
+        self.bookmarks = Some(TranscriptBookmarks {
+            list,
+            ..TranscriptBookmarks::default()
+        });
+        self.wrapped_lines = Vec::new();
+    }
+
+    /// Whether a prompt (search or bookmark note) is capturing character input.
+    pub fn is_capturing_input(&self) -> bool {
+        self.search.prompt.is_some()
+            || self.link_prompt.is_some()
+            || self
+                .bookmarks
+                .as_ref()
+                .is_some_and(|b| b.note_prompt.is_some())
+    }
+
+    /// Add pasted text to the search query or bookmark note being typed.
+    /// Link numbers are short enough to type.
+    pub fn paste(&mut self, text: &str) {
+        let text = text
+            .trim_end_matches(['\r', '\n'])
+            .replace(['\r', '\n'], " ");
+        if let Some(prompt) = &mut self.search.prompt {
+            prompt.push_str(&text);
+        } else if let Some((_, note)) = self
+            .bookmarks
+            .as_mut()
+            .and_then(|bookmarks| bookmarks.note_prompt.as_mut())
+        {
+            note.push_str(&text);
+        }
+    }
+
+    /// Handle a key, returning `false` if the viewer did not use it so the
+    /// caller can apply its own bindings (e.g. Esc to leave).
+    pub fn handle_key(&mut self, key: KeyEvent, area_height: u16) -> bool {
         let area_height = area_height as usize;
         let lines = self.wrapped_lines.len();
         let mut page_size = area_height.saturating_sub(2);
@@ -40,23 +172,77 @@ impl Viewer {
             page_size = 1;
         }
 
-        match key.code {
-            KeyCode::Up => {
-                if self.scroll > 0 {
-                    self.scroll -= 1;
+        if self.handle_bookmark_key(key, page_size) {
+            return true;
+        }
+
+        self.link_status = None;
+        if let Some(prompt) = &mut self.link_prompt {
+            match key.code {
+                KeyCode::Char(c) if c.is_ascii_digit() => prompt.push(c),
+                KeyCode::Backspace => {
+                    prompt.pop();
+                }
+                KeyCode::Enter => {
+                    let number = self.link_prompt.take().unwrap_or_default();
+                    self.open_link(&number);
                 }
+                KeyCode::Esc => self.link_prompt = None,
+                _ => {}
             }
-            KeyCode::Down => {
-                if self.scroll < lines.saturating_sub(page_size) {
-                    self.scroll += 1;
+            return true;
+        }
+
+        if let Some(prompt) = &mut self.search.prompt {
+            match key.code {
+                KeyCode::Char(c) => prompt.push(c),
+                KeyCode::Backspace => {
+                    prompt.pop();
                 }
+                KeyCode::Enter => {
+                    self.search.query = self.search.prompt.take().unwrap_or_default();
+                    self.run_search();
+                    self.jump_to_first_visible_match(page_size);
+                }
+                KeyCode::Esc => self.clear_search(),
+                _ => {}
             }
-            KeyCode::Char('k') => {
-                if self.scroll > 0 {
-                    self.scroll -= 1;
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Char('/') => {
+                self.search.prompt = Some(String::new());
+            }
+            KeyCode::Char('L') => {
+                if self.links.is_empty() {
+                    self.link_status = Some(" No links in this document ".to_string());
+                } else {
+                    self.link_prompt = Some(String::new());
                 }
             }
-            KeyCode::Char('j') => {
+            KeyCode::Char('n') if !self.search.matches.is_empty() => {
+                self.search.current = (self.search.current + 1) % self.search.matches.len();
+                self.scroll_to_current_match(page_size);
+            }
+            KeyCode::Char('N') if !self.search.matches.is_empty() => {
+                let len = self.search.matches.len();
+                self.search.current = (self.search.current + len - 1) % len;
+                self.scroll_to_current_match(page_size);
+            }
+            KeyCode::Esc if !self.search.query.is_empty() => {
+                self.clear_search();
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.scroll = self.scroll.saturating_sub(1);
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.h_scroll = self.h_scroll.saturating_sub(H_SCROLL_STEP);
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.h_scroll = (self.h_scroll + H_SCROLL_STEP).min(self.max_h_scroll);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
                 if self.scroll < lines.saturating_sub(page_size) {
                     self.scroll += 1;
                 }
@@ -85,8 +271,9 @@ impl Viewer {
             KeyCode::Char('G') => {
                 self.scroll = lines.saturating_sub(page_size);
             }
-            _ => {}
+            _ => return false,
         }
+        true
     }
 
     pub fn handle_mouse(&mut self, mouse: MouseEvent, area_height: u16) {
@@ -97,28 +284,82 @@ impl Viewer {
             page_size = 1;
         }
 
+        if let Some(offset) = self.scrollbar.handle_mouse(mouse) {
+            self.scroll = offset;
+            return;
+        }
         match mouse.kind {
-            MouseEventKind::ScrollUp => {
-                if self.scroll > 0 {
-                    self.scroll = self.scroll.saturating_sub(1);
-                }
+            MouseEventKind::ScrollUp if self.scroll > 0 => {
+                self.scroll = self.scroll.saturating_sub(1);
             }
-            MouseEventKind::ScrollDown => {
-                if self.scroll < lines.saturating_sub(page_size) {
-                    self.scroll += 1;
-                }
+            MouseEventKind::ScrollDown if self.scroll < lines.saturating_sub(page_size) => {
+                self.scroll += 1;
             }
             _ => {}
         }
     }
 
-    pub fn render(&mut self, f: &mut Frame, area: Rect) {
-        let view_width = area.width.saturating_sub(2) as usize;
+    pub fn render(&mut self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let area = if self.warnings.is_empty() {
+            area
+        } else {
+            let banner_height = (self.warnings.len() as u16).min(area.height / 2);
+            let [banner_area, content_area] =
+                Layout::vertical([Constraint::Length(banner_height), Constraint::Min(1)])
+                    .areas(area);
+            let banner: Vec<Line> = self
+                .warnings
+                .iter()
+                .map(|warning| Line::from(format!(" {} {warning}", symbols().warning)))
+                .collect();
+            f.render_widget(
+                Paragraph::new(banner).style(
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                banner_area,
+            );
+            content_area
+        };
+
+        let mut view_width = area.width.saturating_sub(2) as usize;
+        if self.bookmarks.is_some() {
+            view_width = view_width.saturating_sub(BOOKMARK_MARGIN);
+        }
 
-        if area.width != self.last_known_width || self.wrapped_lines.is_empty() {
-            let decoded_content = decode_html_entities(&self.content).to_string();
-            self.wrapped_lines = parse_markdown_to_lines(&decoded_content, view_width);
+        if area.width != self.last_known_width
+            || self.wrapped_lines.is_empty()
+            || self.theme != *theme
+        {
+            self.theme = *theme;
+            let (fields, body) = front_matter::split(&self.content);
+            let decoded_body = decode_html_entities(body);
+            self.word_count = count_words(&decoded_body);
+            (self.wrapped_lines, self.links) = if self.raw {
+                (raw_lines(&self.content, view_width), Vec::new())
+            } else {
+                let (lines, links) =
+                    parse_markdown(&decoded_body, view_width, !self.wide_tables, theme);
+                let mut boxed = metadata_box(&fields, theme);
+                boxed.extend(lines);
+                (boxed, links)
+            };
+            let widest = self.wrapped_lines.iter().map(Line::width).max();
+            self.max_h_scroll = widest.unwrap_or(0).saturating_sub(view_width);
+            self.h_scroll = self.h_scroll.min(self.max_h_scroll);
             self.last_known_width = area.width;
+            if let Some(ratio) = self.pending_scroll.take() {
+                self.scroll = (ratio * self.wrapped_lines.len() as f64).round() as usize;
+            }
+            // line indices shift when the content re-wraps
+            if !self.search.query.is_empty() {
+                self.run_search();
+            }
+            if let Some(bookmarks) = &mut self.bookmarks {
+                bookmarks.timestamp_index = build_timestamp_index(&self.wrapped_lines);
+            }
             // clamp scroll if width change reduced content height
             let visible = area.height.saturating_sub(2) as usize;
             let max_scroll = self.wrapped_lines.len().saturating_sub(visible);
@@ -128,11 +369,18 @@ impl Viewer {
         }
 
         let title = format!(
-            "Viewer: {}",
+            "Viewer: {}{}",
             std::path::Path::new(&self.title)
                 .file_name()
                 .unwrap_or_default()
-                .to_string_lossy()
+                .to_string_lossy(),
+            if self.raw {
+                " [raw]"
+            } else if self.wide_tables {
+                " [wide tables]"
+            } else {
+                ""
+            }
         );
 
         let total_lines = self.wrapped_lines.len();
@@ -147,25 +395,87 @@ impl Viewer {
         } else {
             String::new()
         };
+        let reading_info = if self.word_count > 0 {
+            format!(
+                " · {} words, ~{} min read",
+                self.word_count,
+                self.word_count.div_ceil(READING_WPM)
+            )
+        } else {
+            String::new()
+        };
 
-        let block = Block::default()
+        let mut block = Block::default()
             .borders(Borders::ALL)
-            .title(format!("{title}{scroll_info}"));
+            .border_set(symbols().frame)
+            .title(format!("{title}{scroll_info}{reading_info}"));
+        if let Some(status) = self
+            .bookmark_status()
+            .or_else(|| self.link_status())
+            .or_else(|| self.search_status())
+        {
+            block = block.title_bottom(Span::styled(status, Style::default().fg(theme.accent)));
+        }
+
+        let bookmarked_lines: Vec<usize> = self
+            .bookmarks
+            .as_ref()
+            .map(|b| {
+                b.list
+                    .entries
+                    .iter()
+                    .filter_map(|bookmark| b.line_for(bookmark.timestamp))
+                    .collect()
+            })
+            .unwrap_or_default();
 
         // Slice the lines for current viewport
         let slice: Vec<Line> = self
             .wrapped_lines
             .iter()
+            .enumerate()
             .skip(self.scroll)
             .take(visible_lines)
-            .cloned()
+            .map(|(idx, line)| {
+                let line = slice_columns(self.highlight_matches(idx, line, theme), self.h_scroll);
+                if self.bookmarks.is_none() {
+                    return line;
+                }
+                let marker = if bookmarked_lines.contains(&idx) {
+                    Span::styled(
+                        format!("{} ", symbols().bookmark),
+                        Style::default().fg(theme.mark),
+                    )
+                } else {
+                    Span::raw(" ".repeat(BOOKMARK_MARGIN))
+                };
+                let mut spans = vec![marker];
+                spans.extend(line.spans);
+                Line::from(spans).style(line.style)
+            })
             .collect();
 
-        let paragraph = Paragraph::new(slice)
-            .block(block)
-            .wrap(Wrap { trim: false });
+        // Natural-width tables are cut at the edge and scrolled instead
+        let mut paragraph = Paragraph::new(slice).block(block);
+        if self.max_h_scroll == 0 {
+            paragraph = paragraph.wrap(Wrap { trim: false });
+        }
 
         f.render_widget(paragraph, area);
+        self.scrollbar
+            .render(f, area, self.scroll, total_lines, visible_lines, theme);
+
+        if let Some(bookmarks) = &mut self.bookmarks
+            && let Some(picker) = &mut bookmarks.picker
+        {
+            render_bookmark_picker(f, area, &bookmarks.list, picker, theme);
+        }
+    }
+
+    /// Wrap the content again on the next render even if the width is
+    /// unchanged, so a shorter terminal also clamps the scroll.
+    pub fn relayout(&mut self) {
+        self.last_known_width = 0;
     }
 
     #[allow(dead_code)]
@@ -175,10 +485,515 @@ impl Viewer {
         self.scroll = 0;
         self.wrapped_lines = Vec::new();
         self.last_known_width = 0;
+        self.search = ViewerSearch::default();
     }
+
+    /// Bookmark prompts and the picker take priority over everything else;
+    /// `m` and `'` are only bound in transcript mode.
+    fn handle_bookmark_key(&mut self, key: KeyEvent, page_size: usize) -> bool {
+        let scroll = self.scroll;
+        let Some(bookmarks) = &mut self.bookmarks else {
+            return false;
+        };
+
+        if let Some((timestamp, note)) = &mut bookmarks.note_prompt {
+            match key.code {
+                KeyCode::Char(c) => note.push(c),
+                KeyCode::Backspace => {
+                    note.pop();
+                }
+                KeyCode::Enter => {
+                    let (timestamp, note) = (*timestamp, note.clone());
+                    bookmarks.note_prompt = None;
+                    bookmarks.list.add(timestamp, Some(note));
+                    bookmarks.status = Some(match bookmarks.list.save() {
+                        Ok(()) => format!(" Bookmarked {} ", format_timestamp(timestamp)),
+                        Err(e) => format!(" Failed to save bookmark: {e} "),
+                    });
+                }
+                KeyCode::Esc => bookmarks.note_prompt = None,
+                _ => {}
+            }
+            return true;
+        }
+
+        if let Some(picker) = &mut bookmarks.picker {
+            let len = bookmarks.list.entries.len();
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    let selected = picker.selected().unwrap_or(0);
+                    picker.select(Some(selected.saturating_sub(1)));
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let selected = picker.selected().unwrap_or(0);
+                    picker.select(Some((selected + 1).min(len.saturating_sub(1))));
+                }
+                KeyCode::Enter => {
+                    let target = picker
+                        .selected()
+                        .and_then(|i| bookmarks.list.entries.get(i))
+                        .and_then(|b| bookmarks.line_for(b.timestamp));
+                    bookmarks.picker = None;
+                    if let Some(line) = target {
+                        let max_scroll = self.wrapped_lines.len().saturating_sub(page_size);
+                        self.scroll = line.min(max_scroll);
+                    }
+                }
+                KeyCode::Esc | KeyCode::Char('\'') => bookmarks.picker = None,
+                _ => {}
+            }
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Char('m') => {
+                bookmarks.status = None;
+                match bookmarks.top_visible_timestamp(scroll, page_size) {
+                    Some(timestamp) => bookmarks.note_prompt = Some((timestamp, String::new())),
+                    None => bookmarks.status = Some(" No timestamp to bookmark ".to_string()),
+                }
+                true
+            }
+            KeyCode::Char('\'') => {
+                bookmarks.status = None;
+                if bookmarks.list.entries.is_empty() {
+                    bookmarks.status = Some(" No bookmarks yet — press m to add one ".to_string());
+                } else {
+                    let mut picker = ListState::default();
+                    picker.select(Some(0));
+                    bookmarks.picker = Some(picker);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn bookmark_status(&self) -> Option<String> {
+        let bookmarks = self.bookmarks.as_ref()?;
+        if let Some((timestamp, note)) = &bookmarks.note_prompt {
+            return Some(format!(
+                " Note for {} (optional): {note}\u{2588} ",
+                format_timestamp(*timestamp)
+            ));
+        }
+        bookmarks.status.clone()
+    }
+
+    fn run_search(&mut self) {
+        self.search.matches.clear();
+        self.search.current = 0;
+        if self.search.query.is_empty() {
+            return;
+        }
+
+        for (idx, line) in self.wrapped_lines.iter().enumerate() {
+            let text = line_text(line);
+            for range in find_matches(&text, &self.search.query) {
+                self.search.matches.push(SearchMatch { line: idx, range });
+            }
+        }
+    }
+
+    fn clear_search(&mut self) {
+        self.search = ViewerSearch::default();
+    }
+
+    /// Start from the first match at or below the current scroll position so
+    /// a new search does not yank the view back to the top.
+    fn jump_to_first_visible_match(&mut self, page_size: usize) {
+        if let Some(pos) = self
+            .search
+            .matches
+            .iter()
+            .position(|m| m.line >= self.scroll)
+        {
+            self.search.current = pos;
+        }
+        self.scroll_to_current_match(page_size);
+    }
+
+    fn scroll_to_current_match(&mut self, page_size: usize) {
+        let Some(current) = self.search.matches.get(self.search.current) else {
+            return;
+        };
+
+        if current.line < self.scroll || current.line >= self.scroll + page_size {
+            let max_scroll = self.wrapped_lines.len().saturating_sub(page_size);
+            self.scroll = current.line.saturating_sub(page_size / 2).min(max_scroll);
+        }
+    }
+
+    fn link_status(&self) -> Option<String> {
+        match &self.link_prompt {
+            Some(prompt) => Some(format!(
+                " Open link [1-{}]: {prompt}\u{2588} ",
+                self.links.len()
+            )),
+            None => self.link_status.clone(),
+        }
+    }
+
+    /// Open the link with the given `[n]` number in the system browser. Links
+    /// come from model output, so only web pages are opened, never local
+    /// files or other handlers.
+    fn open_link(&mut self, number: &str) {
+        let Some(url) = number
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| self.links.get(n.checked_sub(1)?))
+        else {
+            self.link_status = Some(format!(" No link [{number}] "));
+            return;
+        };
+        let scheme =
+            Url::parse(url).map_or_else(|_| "relative".to_string(), |url| url.scheme().to_string());
+        if !matches!(scheme.as_str(), "http" | "https") {
+            self.link_status = Some(format!(" Not opening {scheme} links "));
+            return;
+        }
+        self.link_status = Some(match open::that_detached(url) {
+            Ok(()) => format!(" Opened {url} "),
+            Err(e) => format!(" Could not open {url}: {e} "),
+        });
+    }
+
+    fn search_status(&self) -> Option<String> {
+        if let Some(prompt) = &self.search.prompt {
+            return Some(format!(" /{prompt}\u{2588} "));
+        }
+        if self.search.query.is_empty() {
+            return None;
+        }
+        if self.search.matches.is_empty() {
+            Some(format!(" '{}': no matches ", self.search.query))
+        } else {
+            Some(format!(
+                " '{}': {}/{}  [n/N] next/prev  [Esc] clear ",
+                self.search.query,
+                self.search.current + 1,
+                self.search.matches.len()
+            ))
+        }
+    }
+
+    fn highlight_matches(&self, idx: usize, line: &Line<'static>, theme: &Theme) -> Line<'static> {
+        let ranges: Vec<(Range<usize>, bool)> = self
+            .search
+            .matches
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.line == idx)
+            .map(|(i, m)| (m.range.clone(), i == self.search.current))
+            .collect();
+
+        if ranges.is_empty() {
+            line.clone()
+        } else {
+            highlight_ranges(line, &ranges, theme)
+        }
+    }
+}
+
+impl TranscriptBookmarks {
+    /// First snippet start visible in the viewport, falling back to the
+    /// snippet the viewport is scrolled into.
+    fn top_visible_timestamp(&self, scroll: usize, page_size: usize) -> Option<f64> {
+        self.timestamp_index
+            .iter()
+            .find(|(line, _)| *line >= scroll && *line < scroll + page_size)
+            .or_else(|| {
+                self.timestamp_index
+                    .iter()
+                    .rev()
+                    .find(|(line, _)| *line < scroll)
+            })
+            .map(|(_, seconds)| *seconds)
+    }
+
+    /// Wrapped line holding the latest snippet that starts at or before `timestamp`.
+    fn line_for(&self, timestamp: f64) -> Option<usize> {
+        self.timestamp_index
+            .iter()
+            .take_while(|(_, seconds)| *seconds <= timestamp + 0.0005)
+            .last()
+            .or(self.timestamp_index.first())
+            .map(|(line, _)| *line)
+    }
+}
+
+/// Map snippet start times onto wrapped lines. Each line is searched together
+/// with the next one so a `[start - end]` prefix split by wrapping is still
+/// attributed to the line where it begins.
+fn build_timestamp_index(lines: &[Line<'static>]) -> Vec<(usize, f64)> {
+    let texts: Vec<String> = lines.iter().map(line_text).collect();
+    let mut index = Vec::new();
+
+    for (idx, text) in texts.iter().enumerate() {
+        let joined = match texts.get(idx + 1) {
+            Some(next) => format!("{text} {next}"),
+            None => text.clone(),
+        };
+        for (offset, seconds) in find_timestamps(&joined) {
+            if offset < text.len() {
+                index.push((idx, seconds));
+            }
+        }
+    }
+
+    index
+}
+
+fn render_bookmark_picker(
+    f: &mut Frame,
+    area: Rect,
+    list: &BookmarkList,
+    state: &mut ListState,
+    theme: &Theme,
+) {
+    let items: Vec<ListItem> = list
+        .entries
+        .iter()
+        .enumerate()
+        .map(|(i, bookmark)| {
+            let mut spans = vec![
+                Span::styled(format!("{:>2}. ", i + 1), Style::default().fg(theme.dim)),
+                Span::styled(
+                    format_timestamp(bookmark.timestamp),
+                    Style::default().fg(theme.mark),
+                ),
+            ];
+            if let Some(note) = &bookmark.note {
+                spans.push(Span::raw(format!("  {note}")));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let height = (items.len() as u16 + 2)
+        .min(area.height.saturating_sub(2))
+        .max(3);
+    let popup = crate::tui::help::centered_rect(60, height, area);
+    let picker = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(symbols().frame)
+                .border_style(Style::default().fg(theme.mark))
+                .title("Bookmarks")
+                .title_bottom(" [Enter] Jump  [Esc] Close "),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    f.render_widget(Clear, popup);
+    f.render_stateful_widget(picker, popup, state);
+}
+
+/// Words in the source, skipping Markdown syntax and other tokens without a
+/// letter (`#`, `|---|`, bullets, timestamps).
+fn count_words(src: &str) -> usize {
+    src.split_whitespace()
+        .filter(|token| token.chars().any(char::is_alphabetic))
+        .count()
+}
+
+/// The report's front matter as a dim box above it, one `key: value` per row.
+fn metadata_box(fields: &[(&str, &str)], theme: &Theme) -> Vec<Line<'static>> {
+    if fields.is_empty() {
+        return Vec::new();
+    }
+    let rows: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| format!("{key}: {value}"))
+        .collect();
+    let inner = rows.iter().map(|row| display_width(row)).max().unwrap_or(0);
+    let style = Style::default().fg(theme.dim).add_modifier(Modifier::DIM);
+
+    let b = symbols().border;
+    let rule = b.horizontal.to_string().repeat(inner + 2);
+    let mut lines = vec![Line::styled(
+        format!("{}{rule}{}", b.top_left, b.top_right),
+        style,
+    )];
+    for row in rows {
+        lines.push(Line::styled(
+            format!("{} {} {}", b.vertical, pad_right(&row, inner), b.vertical),
+            style,
+        ));
+    }
+    lines.push(Line::styled(
+        format!("{}{rule}{}", b.bottom_left, b.bottom_right),
+        style,
+    ));
+    lines.push(Line::from(""));
+    lines
 }
 
-fn parse_markdown_to_lines(src: &str, width: usize) -> Vec<Line<'static>> {
+/// Source lines verbatim and unstyled, wrapped to the view width.
+fn raw_lines(src: &str, width: usize) -> Vec<Line<'static>> {
+    let width = width.max(1);
+    src.lines()
+        .flat_map(|line| {
+            if line.is_empty() {
+                vec![Line::from("")]
+            } else {
+                wrap(line, width)
+                    .into_iter()
+                    .map(|part| Line::from(part.into_owned()))
+                    .collect()
+            }
+        })
+        .collect()
+}
+
+fn line_text(line: &Line<'_>) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+/// Non-overlapping, case-insensitive occurrences of `needle` in `haystack`,
+/// as byte ranges into `haystack`.
+fn find_matches(haystack: &str, needle: &str) -> Vec<Range<usize>> {
+    let needle: Vec<char> = needle.chars().flat_map(char::to_lowercase).collect();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches = Vec::new();
+    let mut start_at = 0;
+    for (start, _) in haystack.char_indices() {
+        if start < start_at {
+            continue;
+        }
+
+        let mut candidate = haystack[start..].char_indices().peekable();
+        let mut needle_pos = 0;
+        let mut end = start;
+        while needle_pos < needle.len() {
+            let Some((offset, c)) = candidate.next() else {
+                break;
+            };
+            let lowered: Vec<char> = c.to_lowercase().collect();
+            if needle[needle_pos..].starts_with(&lowered) {
+                needle_pos += lowered.len();
+                end = start + offset + c.len_utf8();
+            } else {
+                break;
+            }
+        }
+
+        if needle_pos == needle.len() {
+            matches.push(start..end);
+            start_at = end;
+        }
+    }
+
+    matches
+}
+
+/// Re-split a line's spans at match boundaries and restyle the matched parts.
+/// The bool marks the current match, which gets a distinct color.
+fn highlight_ranges(
+    line: &Line<'static>,
+    ranges: &[(Range<usize>, bool)],
+    theme: &Theme,
+) -> Line<'static> {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut offset = 0;
+
+    for span in &line.spans {
+        let text = span.content.as_ref();
+        let span_range = offset..offset + text.len();
+        let mut cursor = span_range.start;
+
+        for (range, current) in ranges {
+            let start = range.start.max(span_range.start);
+            let end = range.end.min(span_range.end);
+            if start >= end {
+                continue;
+            }
+            if cursor < start {
+                spans.push(Span::styled(
+                    text[cursor - offset..start - offset].to_string(),
+                    span.style,
+                ));
+            }
+            let highlight = if *current {
+                span.style.fg(Color::Black).bg(theme.current_match)
+            } else {
+                span.style.fg(Color::Black).bg(theme.accent)
+            };
+            spans.push(Span::styled(
+                text[start - offset..end - offset].to_string(),
+                highlight,
+            ));
+            cursor = end;
+        }
+
+        if cursor < span_range.end {
+            spans.push(Span::styled(
+                text[cursor - offset..].to_string(),
+                span.style,
+            ));
+        }
+        offset = span_range.end;
+    }
+
+    Line::from(spans).style(line.style)
+}
+
+/// Drop the first `offset` display columns of a line, keeping span styles. A
+/// wide character cut in half becomes a space.
+fn slice_columns(line: Line<'static>, offset: usize) -> Line<'static> {
+    if offset == 0 {
+        return line;
+    }
+
+    let mut skipped = 0;
+    let mut spans = Vec::new();
+    for span in line.spans {
+        let mut text = String::new();
+        for c in span.content.chars() {
+            if skipped < offset {
+                skipped += UnicodeWidthChar::width(c).unwrap_or(0);
+                if skipped > offset {
+                    text.push(' ');
+                }
+            } else {
+                text.push(c);
+            }
+        }
+        if !text.is_empty() {
+            spans.push(Span::styled(text, span.style));
+        }
+    }
+    Line::from(spans).style(line.style)
+}
+
+pub(crate) fn parse_markdown_to_lines(
+    src: &str,
+    width: usize,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    parse_markdown(src, width, true, theme).0
+}
+
+/// Markdown to styled lines wrapped at `full_width`, plus the link targets
+/// numbered by the `[n]` markers placed after each link. With `fit_tables`
+/// off, tables keep their natural column widths and may be wider than that.
+fn parse_markdown(
+    src: &str,
+    full_width: usize,
+    fit_tables: bool,
+    theme: &Theme,
+) -> (Vec<Line<'static>>, Vec<String>) {
+    let table_width = |width: usize| if fit_tables { width } else { usize::MAX };
     let mut opts = Options::empty();
     opts.insert(Options::ENABLE_FOOTNOTES);
     opts.insert(Options::ENABLE_TABLES);
@@ -200,7 +1015,27 @@ fn parse_markdown_to_lines(src: &str, width: usize) -> Vec<Line<'static>> {
     let mut current_row: Vec<String> = Vec::new();
     let mut in_table_head = false;
 
+    // List state: one entry per open list, holding the next number for ordered
+    // lists, plus the hanging indent for wrapped lines of the current item
+    let mut list_stack: Vec<Option<u64>> = Vec::new();
+    let mut item_indent = String::new();
+
+    // Blockquote state: lines produced while inside a quote get one `│ ` bar
+    // per level once the event that produced them has been handled
+    let mut quote_depth: usize = 0;
+    let mut quoted_upto = 0;
+
+    // Link targets: the open links' destinations, and every distinct target
+    // seen so far (its index + 1 is the marker number)
+    let mut link_stack: Vec<String> = Vec::new();
+    let mut links: Vec<String> = Vec::new();
+
     for ev in parser {
+        let width = full_width
+            .saturating_sub(QUOTE_PREFIX_WIDTH * quote_depth)
+            .max(1);
+        let mut leaving_quote = false;
+
         match ev {
             Event::Start(tag) => match tag {
                 Tag::Heading { level, .. } => {
@@ -213,19 +1048,40 @@ fn parse_markdown_to_lines(src: &str, width: usize) -> Vec<Line<'static>> {
                 Tag::CodeBlock(_) => {
                     // in_code_block = true;
                 }
+                Tag::List(start) => {
+                    // A nested list starts inside its parent item's text
+                    if !current.is_empty() {
+                        let style = style_from_mods(&mods_stack, theme);
+                        lines.extend(wrap_indented(&current, width, &item_indent, style));
+                        current.clear();
+                    }
+                    list_stack.push(start);
+                }
                 Tag::Item => {
-                    // prepend bullet to current buffer
-                    current.push_str("\u{2022} ");
+                    let depth = list_stack.len().saturating_sub(1);
+                    let marker = match list_stack.last_mut() {
+                        Some(Some(number)) => {
+                            let marker = format!("{number}. ");
+                            *number += 1;
+                            marker
+                        }
+                        _ => "\u{2022} ".to_string(),
+                    };
+                    let indent = "  ".repeat(depth);
+                    item_indent = " ".repeat(indent.len() + marker.chars().count());
+                    current.push_str(&indent);
+                    current.push_str(&marker);
                 }
-                Tag::Link { .. } => {
+                Tag::Link { dest_url, .. } => {
                     mods_stack.push(Modifier::UNDERLINED);
+                    link_stack.push(dest_url.to_string());
                 }
                 Tag::Table(_) => {
                     // Flush any running paragraph
                     if !current.is_empty() {
-                        let mut style = style_from_mods(&mods_stack);
+                        let mut style = style_from_mods(&mods_stack, theme);
                         if mods_stack.contains(&Modifier::BOLD) && header_level.is_some() {
-                            style = style.fg(Color::Cyan);
+                            style = style.fg(theme.heading);
                         }
                         for wrapped in wrap(current.trim_end(), width) {
                             lines.push(Line::from(Span::styled(wrapped.to_string(), style)));
@@ -245,7 +1101,17 @@ fn parse_markdown_to_lines(src: &str, width: usize) -> Vec<Line<'static>> {
                     current_row.clear();
                 }
                 Tag::TableCell => { /* cells handled via Event::Text accumulation */ }
-                Tag::Paragraph | Tag::List(_) | Tag::BlockQuote(_) => { /* no-op */ }
+                Tag::BlockQuote(_) => {
+                    if !current.is_empty() {
+                        let style = style_from_mods(&mods_stack, theme);
+                        for wrapped in wrap(current.trim_end(), width) {
+                            lines.push(Line::from(Span::styled(wrapped.to_string(), style)));
+                        }
+                        current.clear();
+                    }
+                    quote_depth += 1;
+                }
+                Tag::Paragraph => { /* no-op */ }
                 _ => {}
             },
             Event::End(tag_end) => match tag_end {
@@ -256,7 +1122,7 @@ fn parse_markdown_to_lines(src: &str, width: usize) -> Vec<Line<'static>> {
                     {
                         mods.push(Modifier::UNDERLINED);
                     }
-                    let style = style_from_mods(&mods).fg(Color::Cyan);
+                    let style = style_from_mods(&mods, theme).fg(theme.heading);
                     if !current.is_empty() {
                         for wrapped in wrap(current.trim_end(), width) {
                             lines.push(Line::from(Span::styled(wrapped.to_string(), style)));
@@ -269,14 +1135,27 @@ fn parse_markdown_to_lines(src: &str, width: usize) -> Vec<Line<'static>> {
                         mods_stack.remove(pos);
                     }
                 }
-                TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link => {
+                TagEnd::Link => {
+                    mods_stack.pop();
+                    if let Some(url) = link_stack.pop().filter(|url| !url.is_empty()) {
+                        let number = match links.iter().position(|known| *known == url) {
+                            Some(index) => index + 1,
+                            None => {
+                                links.push(url);
+                                links.len()
+                            }
+                        };
+                        current.push_str(&format!("[{number}]"));
+                    }
+                }
+                TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
                     // flush current with existing mods (including link blue) before popping?
                     // We keep behavior: just pop style marker
                     mods_stack.pop();
                 }
                 TagEnd::CodeBlock => {
                     if !current.is_empty() {
-                        let style = style_from_mods(&mods_stack);
+                        let style = style_from_mods(&mods_stack, theme);
                         for wrapped in wrap(current.trim_end(), width) {
                             lines.push(Line::from(Span::styled(wrapped.to_string(), style)));
                         }
@@ -284,44 +1163,63 @@ fn parse_markdown_to_lines(src: &str, width: usize) -> Vec<Line<'static>> {
                     }
                     lines.push(Line::from(""));
                 }
-                TagEnd::Item => {
+                TagEnd::Item if !current.is_empty() => {
+                    let style = style_from_mods(&mods_stack, theme);
+                    lines.extend(wrap_indented(&current, width, &item_indent, style));
+                    current.clear();
+                }
+                TagEnd::List(_) => {
                     if !current.is_empty() {
-                        let style = style_from_mods(&mods_stack);
-                        for wrapped in wrap(current.trim_end(), width) {
-                            lines.push(Line::from(Span::styled(wrapped.to_string(), style)));
-                        }
+                        let style = style_from_mods(&mods_stack, theme);
+                        lines.extend(wrap_indented(&current, width, &item_indent, style));
                         current.clear();
                     }
-                }
-                TagEnd::TableCell => {
-                    if in_table {
-                        current_row.push(std::mem::take(&mut current));
+                    list_stack.pop();
+                    // Only the outermost list is followed by a blank line
+                    if list_stack.is_empty() {
+                        item_indent.clear();
+                        lines.push(Line::from(""));
                     }
                 }
-                TagEnd::TableRow => {
-                    if in_table {
-                        if in_table_head {
-                            table_headers = current_row.clone();
-                        } else {
-                            table_rows.push(current_row.clone());
-                        }
-                        current_row.clear();
+                TagEnd::TableCell if in_table => {
+                    current_row.push(std::mem::take(&mut current));
+                }
+                TagEnd::TableRow if in_table => {
+                    if in_table_head {
+                        table_headers = current_row.clone();
+                    } else {
+                        table_rows.push(current_row.clone());
                     }
+                    current_row.clear();
                 }
                 TagEnd::TableHead => {
+                    // pulldown-cmark puts header cells directly in the head,
+                    // without a row around them
+                    if !current_row.is_empty() {
+                        table_headers = std::mem::take(&mut current_row);
+                    }
                     in_table_head = false;
                 }
-                TagEnd::Table => {
-                    if in_table {
-                        let mut table_lines = render_table(&table_headers, &table_rows, width);
-                        lines.append(&mut table_lines);
-                        lines.push(Line::from(""));
-                        in_table = false;
+                TagEnd::Table if in_table => {
+                    let mut table_lines =
+                        render_table(&table_headers, &table_rows, table_width(width), theme);
+                    lines.append(&mut table_lines);
+                    lines.push(Line::from(""));
+                    in_table = false;
+                }
+                TagEnd::BlockQuote(_) => {
+                    if !current.is_empty() {
+                        let style = style_from_mods(&mods_stack, theme);
+                        for wrapped in wrap(current.trim_end(), width) {
+                            lines.push(Line::from(Span::styled(wrapped.to_string(), style)));
+                        }
+                        current.clear();
                     }
+                    leaving_quote = true;
                 }
-                TagEnd::Paragraph | TagEnd::List(_) | TagEnd::BlockQuote(_) => {
+                TagEnd::Paragraph => {
                     if !current.is_empty() {
-                        let style = style_from_mods(&mods_stack);
+                        let style = style_from_mods(&mods_stack, theme);
                         for wrapped in wrap(current.trim_end(), width) {
                             lines.push(Line::from(Span::styled(wrapped.to_string(), style)));
                         }
@@ -337,7 +1235,7 @@ fn parse_markdown_to_lines(src: &str, width: usize) -> Vec<Line<'static>> {
             Event::Code(code) => {
                 // inline code: yellow + reversed
                 if !current.is_empty() {
-                    let style = style_from_mods(&mods_stack);
+                    let style = style_from_mods(&mods_stack, theme);
                     for wrapped in wrap(current.trim_end(), width) {
                         lines.push(Line::from(Span::styled(wrapped.to_string(), style)));
                     }
@@ -347,52 +1245,118 @@ fn parse_markdown_to_lines(src: &str, width: usize) -> Vec<Line<'static>> {
                     lines.push(Line::from(Span::styled(
                         wrapped.to_string(),
                         Style::default()
-                            .fg(Color::Yellow)
+                            .fg(theme.code)
                             .add_modifier(Modifier::REVERSED),
                     )));
                 }
             }
             Event::SoftBreak => current.push(' '),
-            Event::HardBreak => {
-                if !current.is_empty() {
-                    let style = style_from_mods(&mods_stack);
-                    for wrapped in wrap(current.trim_end(), width) {
-                        lines.push(Line::from(Span::styled(wrapped.to_string(), style)));
-                    }
-                    current.clear();
+            Event::HardBreak if !current.is_empty() => {
+                let style = style_from_mods(&mods_stack, theme);
+                for wrapped in wrap(current.trim_end(), width) {
+                    lines.push(Line::from(Span::styled(wrapped.to_string(), style)));
                 }
+                current.clear();
             }
             _ => {}
         }
+
+        if quote_depth > 0 {
+            for line in &mut lines[quoted_upto..] {
+                quote_line(line, quote_depth, theme);
+            }
+        }
+        quoted_upto = lines.len();
+        if leaving_quote {
+            quote_depth -= 1;
+            lines.push(Line::from(""));
+        }
     }
 
     if in_table {
-        let mut table_lines = render_table(&table_headers, &table_rows, width);
+        let mut table_lines =
+            render_table(&table_headers, &table_rows, table_width(full_width), theme);
         lines.append(&mut table_lines);
     }
 
     if !current.is_empty() {
-        let style = style_from_mods(&mods_stack);
-        for wrapped in wrap(current.trim_end(), width) {
+        let style = style_from_mods(&mods_stack, theme);
+        for wrapped in wrap(current.trim_end(), full_width) {
             lines.push(Line::from(Span::styled(wrapped.to_string(), style)));
         }
     }
 
-    lines
+    if !links.is_empty() {
+        if lines.last().is_some_and(|line| line.width() > 0) {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(
+            "Links",
+            Style::default()
+                .fg(theme.heading)
+                .add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Line::from(""));
+        let link_style = Style::default().fg(theme.link);
+        for (i, url) in links.iter().enumerate() {
+            let marker = format!("[{}] ", i + 1);
+            let indent = " ".repeat(marker.len());
+            lines.extend(wrap_indented(
+                &format!("{marker}{url}"),
+                full_width,
+                &indent,
+                link_style,
+            ));
+        }
+    }
+
+    (lines, links)
+}
+
+/// A quote bar and the space after it.
+const QUOTE_PREFIX_WIDTH: usize = 2;
+
+/// Prefix a line with one quote bar per level and dim/italicize its text.
+fn quote_line(line: &mut Line<'static>, depth: usize, theme: &Theme) {
+    let quote_style = Modifier::DIM | Modifier::ITALIC;
+    for span in &mut line.spans {
+        span.style = span.style.add_modifier(quote_style);
+    }
+    line.spans.insert(
+        0,
+        Span::styled(
+            format!("{} ", symbols().border.vertical).repeat(depth),
+            Style::default().fg(theme.dim),
+        ),
+    );
+}
+
+/// Wrap list item text so continuation lines line up after the item marker.
+fn wrap_indented(text: &str, width: usize, indent: &str, style: Style) -> Vec<Line<'static>> {
+    let options = textwrap::Options::new(width.max(1)).subsequent_indent(indent);
+    wrap(text.trim_end(), options)
+        .into_iter()
+        .map(|wrapped| Line::from(Span::styled(wrapped.to_string(), style)))
+        .collect()
 }
 
-fn style_from_mods(mods: &[Modifier]) -> Style {
+fn style_from_mods(mods: &[Modifier], theme: &Theme) -> Style {
     let mut style = Style::default();
     for &m in mods {
         style = style.add_modifier(m);
     }
     if mods.contains(&Modifier::UNDERLINED) {
-        style = style.fg(Color::Blue);
+        style = style.fg(theme.link);
     }
     style
 }
 
-fn render_table(headers: &[String], rows: &[Vec<String>], max_width: usize) -> Vec<Line<'static>> {
+fn render_table(
+    headers: &[String],
+    rows: &[Vec<String>],
+    max_width: usize,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
     // Determine column count
     let cols = headers
         .len()
@@ -474,18 +1438,30 @@ fn render_table(headers: &[String], rows: &[Vec<String>], max_width: usize) -> V
 
     // Render borders
     let mut out: Vec<Line<'static>> = Vec::new();
-    let top = draw_border('┌', '┬', '┐', '─', &col_widths);
-    let sep = draw_border('├', '┼', '┤', '─', &col_widths);
-    let bottom = draw_border('└', '┴', '┘', '─', &col_widths);
+    let b = symbols().border;
+    let top = draw_border(
+        b.top_left,
+        b.top_tee,
+        b.top_right,
+        b.horizontal,
+        &col_widths,
+    );
+    let sep = draw_border(b.left_tee, b.cross, b.right_tee, b.horizontal, &col_widths);
+    let bottom = draw_border(
+        b.bottom_left,
+        b.bottom_tee,
+        b.bottom_right,
+        b.horizontal,
+        &col_widths,
+    );
 
-    // borders gray
-    let gray = Style::default().fg(Color::Gray);
+    let gray = Style::default().fg(theme.border);
     out.push(Line::from(Span::styled(top, gray)));
 
     // Header (centered + bold)
     if cols > 0 {
         for phys in wrap_row(&norm_headers) {
-            out.push(render_row_styled(&phys, &col_widths, true));
+            out.push(render_row_styled(&phys, &col_widths, true, theme));
         }
         out.push(Line::from(Span::styled(sep.clone(), gray)));
     }
@@ -493,7 +1469,7 @@ fn render_table(headers: &[String], rows: &[Vec<String>], max_width: usize) -> V
     // Body rows
     for row in &norm_rows {
         for phys in wrap_row(row) {
-            out.push(render_row_styled(&phys, &col_widths, false));
+            out.push(render_row_styled(&phys, &col_widths, false, theme));
         }
         out.push(Line::from(Span::styled(sep.clone(), gray)));
     }
@@ -519,10 +1495,15 @@ fn draw_border(left: char, mid: char, right: char, horiz: char, col_widths: &[us
     s
 }
 
-fn render_row_styled(cells: &[String], col_widths: &[usize], header: bool) -> Line<'static> {
+fn render_row_styled(
+    cells: &[String],
+    col_widths: &[usize],
+    header: bool,
+    theme: &Theme,
+) -> Line<'static> {
+    let bar = symbols().border.vertical.to_string();
     let mut spans: Vec<Span<'static>> = Vec::new();
-    // left border in gray
-    spans.push(Span::styled("│", Style::default().fg(Color::Gray)));
+    spans.push(Span::styled(bar.clone(), Style::default().fg(theme.border)));
     for (i, cell) in cells.iter().enumerate() {
         let w = col_widths[i];
         let content = if header {
@@ -536,12 +1517,12 @@ fn render_row_styled(cells: &[String], col_widths: &[usize], header: bool) -> Li
                 format!(" {content} "),
                 Style::default()
                     .add_modifier(Modifier::BOLD)
-                    .fg(Color::Cyan),
+                    .fg(theme.table_header),
             );
         }
         spans.push(styled);
         // sep border between cols
-        spans.push(Span::styled("│", Style::default().fg(Color::Gray)));
+        spans.push(Span::styled(bar.clone(), Style::default().fg(theme.border)));
     }
     Line::from(spans)
 }
@@ -568,3 +1549,332 @@ fn pad_right(s: &str, width: usize) -> String {
 fn display_width(s: &str) -> usize {
     UnicodeWidthStr::width(s)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Viewer, count_words, find_matches, highlight_ranges, line_text, parse_markdown,
+        parse_markdown_to_lines, slice_columns,
+    };
+    use crate::core::bookmarks::BookmarkList;
+    use crate::tui::Theme;
+    use crossterm::event::{KeyCode, KeyEvent};
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use ratatui::style::{Color, Modifier, Style};
+    use ratatui::text::{Line, Span};
+
+    fn render(viewer: &mut Viewer, width: u16) {
+        render_sized(viewer, width, 12);
+    }
+
+    fn render_sized(viewer: &mut Viewer, width: u16, height: u16) {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|f| viewer.render(f, f.area(), &Theme::dark()))
+            .unwrap();
+    }
+
+    #[test]
+    fn relayout_clamps_the_scroll_to_a_taller_view() {
+        let content = (1..=50)
+            .map(|n| format!("line {n}"))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let mut viewer = Viewer::new(content, "transcript_x.txt".to_string());
+        render_sized(&mut viewer, 40, 12);
+        viewer.handle_key(KeyEvent::from(KeyCode::End), 12);
+        let bottom = viewer.scroll;
+        assert_eq!(bottom, viewer.wrapped_lines.len() - 10);
+
+        render_sized(&mut viewer, 40, 30);
+        assert_eq!(viewer.scroll, bottom);
+        viewer.relayout();
+        render_sized(&mut viewer, 40, 30);
+        assert_eq!(viewer.scroll, viewer.wrapped_lines.len() - 28);
+    }
+
+    #[test]
+    fn finds_case_insensitive_non_overlapping_matches() {
+        assert_eq!(
+            find_matches("Canción canción", "CANCIÓN"),
+            vec![0..8, 9..17]
+        );
+        assert_eq!(find_matches("aaaa", "aa"), vec![0..2, 2..4]);
+        assert!(find_matches("abc", "").is_empty());
+    }
+
+    #[test]
+    fn highlight_splits_across_spans() {
+        let line = Line::from(vec![
+            Span::raw("foo b"),
+            Span::styled("ar baz", Style::default().fg(Color::Cyan)),
+        ]);
+        let highlighted = highlight_ranges(&line, &[(4..7, true)], &Theme::dark());
+
+        assert_eq!(line_text(&highlighted), "foo bar baz");
+        let pieces: Vec<&str> = highlighted
+            .spans
+            .iter()
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(pieces, vec!["foo ", "b", "ar", " baz"]);
+        assert_eq!(highlighted.spans[1].style.bg, Some(Color::LightMagenta));
+        assert_eq!(highlighted.spans[3].style.fg, Some(Color::Cyan));
+    }
+
+    #[test]
+    fn title_shows_word_count_and_reading_time() {
+        let content = "# Título\n\n| a | b |\n|---|---|\n| uno | dos |\n\n- Tom &amp; Jerry [00:01.000 - 00:02.000]";
+        assert_eq!(count_words(&html_escape::decode_html_entities(content)), 7);
+
+        let long = "palabra ".repeat(401);
+        let mut viewer = Viewer::new(long, "report_x.md".to_string());
+        let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+        terminal
+            .draw(|f| viewer.render(f, f.area(), &Theme::dark()))
+            .unwrap();
+        let title: String = (0..80)
+            .map(|x| terminal.backend().buffer()[(x, 0)].symbol().to_string())
+            .collect();
+        assert!(title.contains("401 words, ~3 min read"), "{title}");
+    }
+
+    #[test]
+    fn front_matter_is_a_dim_box_outside_the_word_count() {
+        let content = "---\nmodel: gpt-5.2\nduration_secs: 93.4\n---\n\n# Título\n\nHola mundo";
+        let mut viewer = Viewer::new(content.to_string(), "report_x.md".to_string());
+        render(&mut viewer, 60);
+
+        let texts: Vec<String> = viewer.wrapped_lines.iter().map(line_text).collect();
+        assert_eq!(texts[0], "┌─────────────────────┐");
+        assert_eq!(texts[1], "│ model: gpt-5.2      │");
+        assert_eq!(texts[2], "│ duration_secs: 93.4 │");
+        assert_eq!(texts[3], "└─────────────────────┘");
+        assert!(texts.iter().all(|text| text != "---"));
+        assert!(
+            viewer.wrapped_lines[1]
+                .style
+                .add_modifier
+                .contains(Modifier::DIM)
+        );
+        assert_eq!(viewer.word_count, 3);
+
+        viewer.toggle_raw();
+        render(&mut viewer, 60);
+        assert_eq!(line_text(&viewer.wrapped_lines[0]), "---");
+    }
+
+    #[test]
+    fn raw_toggle_shows_source_and_keeps_position() {
+        let content = (1..=40)
+            .map(|i| format!("| cell {i} | **bold** |"))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let mut viewer = Viewer::new(content, "report_x.md".to_string());
+        render(&mut viewer, 60);
+        viewer.scroll = viewer.wrapped_lines.len() / 2;
+
+        viewer.toggle_raw();
+        render(&mut viewer, 60);
+        assert_eq!(line_text(&viewer.wrapped_lines[0]), "| cell 1 | **bold** |");
+        let ratio = viewer.scroll as f64 / viewer.wrapped_lines.len() as f64;
+        assert!((ratio - 0.5).abs() < 0.05);
+
+        viewer.toggle_raw();
+        render(&mut viewer, 60);
+        assert!(!line_text(&viewer.wrapped_lines[0]).contains("**"));
+    }
+
+    #[test]
+    fn wide_tables_keep_natural_widths_and_scroll_sideways() {
+        let content = "| Línea | Comentario |\n|---|---|\n| 1 | una explicación bastante larga de la línea |\n";
+        let mut viewer = Viewer::new(content.to_string(), "report_x.md".to_string());
+        render(&mut viewer, 30);
+        assert!(viewer.wrapped_lines.iter().all(|line| line.width() <= 28));
+
+        viewer.toggle_wide_tables();
+        render(&mut viewer, 30);
+        let widest = viewer.wrapped_lines.iter().map(Line::width).max().unwrap();
+        assert_eq!(widest, 54);
+        assert!(line_text(&viewer.wrapped_lines[1]).contains("Comentario"));
+
+        let right = KeyEvent::from(KeyCode::Right);
+        for _ in 0..10 {
+            viewer.handle_key(right, 12);
+        }
+        assert_eq!(viewer.h_scroll, widest - 28);
+        viewer.handle_key(KeyEvent::from(KeyCode::Char('h')), 12);
+        assert_eq!(viewer.h_scroll, widest - 28 - 8);
+    }
+
+    #[test]
+    fn links_get_numbered_markers_and_a_links_section() {
+        let src = "Ver [docs](https://a.example/docs), [otra](https://b.example) y [docs](https://a.example/docs).\n";
+        let (lines, links) = parse_markdown(src, 80, true, &Theme::dark());
+        let text: Vec<String> = lines.iter().map(line_text).collect();
+
+        assert_eq!(links, vec!["https://a.example/docs", "https://b.example"]);
+        assert_eq!(text[0], "Ver docs[1], otra[2] y docs[1].");
+        assert_eq!(
+            &text[text.len() - 4..],
+            &[
+                "Links",
+                "",
+                "[1] https://a.example/docs",
+                "[2] https://b.example"
+            ]
+        );
+    }
+
+    #[test]
+    fn link_prompt_needs_links() {
+        let key = |c| KeyEvent::from(KeyCode::Char(c));
+        let mut viewer = Viewer::new("Sin enlaces.".to_string(), "report_x.md".to_string());
+        render(&mut viewer, 40);
+        viewer.handle_key(key('L'), 12);
+        assert!(!viewer.is_capturing_input());
+        assert_eq!(
+            viewer.link_status().as_deref(),
+            Some(" No links in this document ")
+        );
+
+        let mut viewer = Viewer::new(
+            "[a](https://a.example)".to_string(),
+            "report_x.md".to_string(),
+        );
+        render(&mut viewer, 40);
+        viewer.handle_key(key('L'), 12);
+        viewer.handle_key(key('x'), 12);
+        viewer.handle_key(key('1'), 12);
+        assert_eq!(
+            viewer.link_status().as_deref(),
+            Some(" Open link [1-1]: 1\u{2588} ")
+        );
+        viewer.handle_key(KeyEvent::from(KeyCode::Esc), 12);
+        assert!(!viewer.is_capturing_input());
+    }
+
+    #[test]
+    fn only_web_links_are_opened() {
+        let mut viewer = Viewer::new(
+            "[a](file:///etc/passwd) [b](javascript:alert(1)) [c](notes.md)".to_string(),
+            "report_x.md".to_string(),
+        );
+        render(&mut viewer, 80);
+        for (number, scheme) in [("1", "file"), ("2", "javascript"), ("3", "relative")] {
+            viewer.open_link(number);
+            assert_eq!(
+                viewer.link_status.as_deref(),
+                Some(format!(" Not opening {scheme} links ").as_str())
+            );
+        }
+    }
+
+    #[test]
+    fn slicing_columns_keeps_styles_and_splits_wide_chars() {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let line = Line::from(vec![Span::raw("ab"), Span::styled("漢字cd", bold)]);
+        let sliced = slice_columns(line, 3);
+        assert_eq!(line_text(&sliced), " 字cd");
+        assert_eq!(sliced.spans[0].style, bold);
+    }
+
+    #[test]
+    fn ordered_and_nested_lists_keep_numbers_and_indent() {
+        let src = "1. First\n2. Second\n   - nested a\n     1. deep\n3. Third\n\nText\n\n7. Seven\n8. Eight\n";
+        let lines: Vec<String> = parse_markdown_to_lines(src, 40, &Theme::dark())
+            .iter()
+            .map(line_text)
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "1. First",
+                "2. Second",
+                "  \u{2022} nested a",
+                "    1. deep",
+                "3. Third",
+                "",
+                "Text",
+                "",
+                "7. Seven",
+                "8. Eight",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn wrapped_list_items_hang_after_the_marker() {
+        let lines: Vec<String> =
+            parse_markdown_to_lines("10. alpha beta gamma delta\n", 12, &Theme::dark())
+                .iter()
+                .map(line_text)
+                .collect();
+        assert_eq!(
+            lines,
+            vec!["10. alpha", "    beta", "    gamma", "    delta", ""]
+        );
+    }
+
+    #[test]
+    fn blockquotes_get_a_bar_per_level_and_dim_italic_text() {
+        let src = "> cita literal\n>\n> > anidada\n>\n> - punto a\n> - punto b\n\ncuerpo\n";
+        let lines = parse_markdown_to_lines(src, 40, &Theme::dark());
+        let text: Vec<String> = lines.iter().map(line_text).collect();
+        assert_eq!(
+            text,
+            vec![
+                "\u{2502} cita literal",
+                "\u{2502} ",
+                "\u{2502} \u{2502} anidada",
+                "\u{2502} \u{2502} ",
+                "\u{2502} ",
+                "\u{2502} \u{2022} punto a",
+                "\u{2502} \u{2022} punto b",
+                "\u{2502} ",
+                "",
+                "cuerpo",
+                "",
+            ]
+        );
+        let quoted = &lines[0].spans[1].style;
+        assert!(
+            quoted
+                .add_modifier
+                .contains(Modifier::DIM | Modifier::ITALIC)
+        );
+        assert!(lines[9].spans[0].style.add_modifier.is_empty());
+    }
+
+    #[test]
+    fn quoted_text_wraps_within_the_width() {
+        let lines = parse_markdown_to_lines("> > uno dos tres cuatro\n", 12, &Theme::dark());
+        for line in lines.iter().filter(|l| !line_text(l).is_empty()) {
+            assert!(
+                line_text(line).chars().count() <= 12,
+                "{:?}",
+                line_text(line)
+            );
+        }
+    }
+
+    #[test]
+    fn bookmark_lines_follow_rewrapping() {
+        let transcript = "[00:00.000 - 00:04.200] Hola a todos y bienvenidos de nuevo al canal.\n\
+            [00:04.200 - 00:09.800] Hoy vamos a hablar de cómo organizar el tiempo.\n\
+            [00:09.800 - 00:15.100] Durante años tuve el problema de empezar sin plan.";
+        let mut viewer = Viewer::new(transcript.to_string(), "transcript_x.txt".to_string());
+        let mut list = BookmarkList::default();
+        list.add(9.8, None);
+        viewer.enable_bookmarks(list);
+
+        for width in [18, 30, 120] {
+            render(&mut viewer, width);
+            let bookmarks = viewer.bookmarks.as_ref().unwrap();
+            let line = bookmarks.line_for(9.8).expect("resolves");
+            assert!(line_text(&viewer.wrapped_lines[line]).contains("[00:09.800"));
+        }
+    }
+}
@@ -0,0 +1,54 @@
+// Opening a stored file in the user's own editor. The TUI hands the terminal
+// over while the editor runs and takes it back once it exits.
+use crate::error::{Error, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// `$EDITOR`, then `$VISUAL`, then `vi`.
+fn editor() -> String {
+    ["EDITOR", "VISUAL"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Split an editor setting such as `code --wait` into the program and its
+/// arguments.
+fn command_line(editor: &str) -> Option<(&str, Vec<&str>)> {
+    let mut words = editor.split_whitespace();
+    let program = words.next()?;
+    Some((program, words.collect()))
+}
+
+/// Run the editor on `path` and wait for it to exit.
+pub fn open(path: &Path) -> Result<()> {
+    let editor = editor();
+    let (program, args) = command_line(&editor)
+        .ok_or_else(|| Error::custom("No editor set; export EDITOR to choose one"))?;
+    let status = Command::new(program)
+        .args(args)
+        .arg(path)
+        .status()
+        .map_err(|e| Error::custom(format!("Could not start `{editor}`: {e}")))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::custom(format!("`{editor}` exited with {status}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::command_line;
+
+    #[test]
+    fn editor_settings_keep_their_arguments() {
+        assert_eq!(command_line("vim"), Some(("vim", vec![])));
+        assert_eq!(
+            command_line(" code --wait  -n"),
+            Some(("code", vec!["--wait", "-n"]))
+        );
+        assert_eq!(command_line("  "), None);
+    }
+}
@@ -1,32 +1,159 @@
-use crossterm::event::{self, Event, KeyEvent, MouseEvent};
-use std::time::Duration;
+use crate::error::{Error, Result};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Ticks closer together than this would only burn CPU on redraws.
+const MIN_TICK_RATE: Duration = Duration::from_millis(10);
 
-#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub enum AppEvent {
     Quit,
     Key(KeyEvent),
     Mouse(MouseEvent),
+    /// Text pasted into the terminal, delivered whole rather than as keys.
+    Paste(String),
+    /// The terminal's new width and height.
+    Resize(u16, u16),
     Tick,
 }
 
-pub struct EventHandler;
+/// Terminal input and a steady `Tick` merged into one stream. A thread reads
+/// the terminal and sends a tick every `tick_rate` whether keys come in or
+/// not, so job progress keeps flowing while the user is idle.
+pub struct EventHandler {
+    rx: mpsc::Receiver<Result<AppEvent>>,
+    /// Set while another program owns the terminal, so its keys are not read.
+    paused: Arc<AtomicBool>,
+    /// The thread's answer to a pause, once it has stopped reading.
+    paused_rx: mpsc::Receiver<()>,
+}
 
 impl EventHandler {
-    pub fn new() -> Self {
-        Self
+    pub fn new(tick_rate: Duration) -> Self {
+        let tick_rate = tick_rate.max(MIN_TICK_RATE);
+        let (tx, rx) = mpsc::channel();
+        let paused = Arc::new(AtomicBool::new(false));
+        let (paused_tx, paused_rx) = mpsc::channel();
+        let pause = paused.clone();
+        // Stops at the first send after the handler is dropped
+        thread::spawn(move || {
+            let mut last_tick = Instant::now();
+            let mut acknowledged = false;
+            loop {
+                if pause.load(Ordering::Acquire) {
+                    if !acknowledged && paused_tx.send(()).is_err() {
+                        return;
+                    }
+                    acknowledged = true;
+                    thread::sleep(MIN_TICK_RATE);
+                    continue;
+                }
+                acknowledged = false;
+                let timeout = tick_rate.saturating_sub(last_tick.elapsed());
+                let input = match event::poll(timeout) {
+                    Ok(true) => Some(event::read().map(map_event)),
+                    Ok(false) => None,
+                    Err(e) => Some(Err(e)),
+                };
+                if let Some(input) = input {
+                    let failed = input.is_err();
+                    if tx.send(input.map_err(Error::from)).is_err() || failed {
+                        return;
+                    }
+                }
+                if last_tick.elapsed() >= tick_rate {
+                    if tx.send(Ok(AppEvent::Tick)).is_err() {
+                        return;
+                    }
+                    last_tick = Instant::now();
+                }
+            }
+        });
+        Self {
+            rx,
+            paused,
+            paused_rx,
+        }
     }
 
-    pub fn next_event(&self) -> crate::error::Result<AppEvent> {
-        if event::poll(Duration::from_millis(100))? {
-            match event::read()? {
-                Event::Key(key) => Ok(AppEvent::Key(key)),
-                Event::Mouse(mouse) => Ok(AppEvent::Mouse(mouse)),
-                Event::Resize(_, _) => Ok(AppEvent::Tick),
-                _ => Ok(AppEvent::Tick),
-            }
-        } else {
-            Ok(AppEvent::Tick)
+    /// Stop reading the terminal until `resume`, returning once the input
+    /// thread has finished any read in progress.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Release);
+        let _ = self.paused_rx.recv();
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Release);
+    }
+
+    /// The next key, mouse event or tick, waiting for one if need be.
+    pub fn next_event(&self) -> Result<AppEvent> {
+        self.rx
+            .recv()
+            .map_err(|_| Error::custom("Terminal input stopped"))?
+    }
+}
+
+/// Translate a raw terminal event into an application event.
+/// Ctrl+C and Ctrl+Q quit from every screen, so they are resolved here
+/// rather than in the per-screen key handlers.
+pub fn map_event(event: Event) -> AppEvent {
+    match event {
+        Event::Key(key)
+            if matches!(key.code, KeyCode::Char('c') | KeyCode::Char('q'))
+                && key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            AppEvent::Quit
         }
+        Event::Key(key) => AppEvent::Key(key),
+        Event::Mouse(mouse) => AppEvent::Mouse(mouse),
+        Event::Paste(text) => AppEvent::Paste(text),
+        Event::Resize(width, height) => AppEvent::Resize(width, height),
+        _ => AppEvent::Tick,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AppEvent, map_event};
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    #[test]
+    fn ctrl_c_and_ctrl_q_map_to_quit() {
+        let event = Event::Key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(matches!(map_event(event), AppEvent::Quit));
+        let event = Event::Key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL));
+        assert!(matches!(map_event(event), AppEvent::Quit));
+    }
+
+    #[test]
+    fn plain_keys_pass_through() {
+        let event = Event::Key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE));
+        assert!(matches!(
+            map_event(event),
+            AppEvent::Key(KeyEvent {
+                code: KeyCode::Char('q'),
+                ..
+            })
+        ));
+
+        let event = Event::Key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE));
+        assert!(matches!(map_event(event), AppEvent::Key(_)));
+
+        let event = Event::Paste("https://youtu.be/x".to_string());
+        assert!(matches!(map_event(event), AppEvent::Paste(text) if text == "https://youtu.be/x"));
+    }
+
+    #[test]
+    fn resize_carries_the_new_size() {
+        assert!(matches!(
+            map_event(Event::Resize(80, 24)),
+            AppEvent::Resize(80, 24)
+        ));
     }
 }
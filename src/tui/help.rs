@@ -0,0 +1,202 @@
+// Keybinding reference shown in the `?` overlay. Each screen has its own
+// static table so the overlay stays in sync with the handlers in app.rs.
+use crate::tui::app::AppState;
+use crate::tui::{Theme, symbols};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+pub type Binding = (&'static str, &'static str);
+
+const GLOBAL_BINDINGS: &[Binding] = &[
+    ("? / F1", "Show this help"),
+    ("Ctrl+C / Ctrl+Q", "Quit from any screen"),
+];
+
+const HOME_BINDINGS: &[Binding] = &[
+    ("↑ / ↓ / ← / →", "Move selection"),
+    ("1-5", "Jump to option"),
+    ("Enter", "Open selected option"),
+    ("q", "Quit"),
+];
+
+const NEW_TRANSCRIPT_BINDINGS: &[Binding] = &[
+    ("Tab", "Next field"),
+    ("Enter", "Next field / start processing"),
+    ("Space", "Toggle focused option"),
+    ("↑ / ↓", "Recall previous URLs (empty URL field)"),
+    ("Ctrl+R", "Search previous URLs by URL or title"),
+    ("← / →", "Move cursor"),
+    ("Ctrl+← / Ctrl+→", "Move cursor by word"),
+    ("Home / End", "Start / end of field"),
+    ("Ctrl+U / Ctrl+K", "Delete to start / end of field"),
+    ("Ctrl+W", "Delete the word before the cursor"),
+    ("Paste", "Insert the clipboard into the focused field"),
+    ("Esc", "Back to home"),
+];
+
+const PROCESSING_BINDINGS: &[Binding] = &[
+    ("y / n", "Accept or refuse a report's estimated cost"),
+    ("n", "Queue another video (job keeps running)"),
+    (
+        "↑ / ↓ / PgUp / PgDn",
+        "Scroll the log back; the newest line follows again",
+    ),
+    ("Home / End", "Oldest / newest log line"),
+    ("s", "Save the log to logs/processing_<time>.log"),
+    ("Enter", "Back, once the queue is done and a job failed"),
+    ("Esc / q", "Cancel the running job and the queue"),
+];
+
+const BROWSER_BINDINGS: &[Binding] = &[
+    ("↑ / ↓ / Wheel", "Move selection"),
+    ("PgUp / PgDn", "Page up / down"),
+    ("Click / drag scrollbar", "Jump through the list"),
+    ("Home / End", "First / last file"),
+    ("Enter", "Open file"),
+    ("Space", "Check / uncheck file"),
+    ("a", "Check every shown file"),
+    ("A / i", "Invert the checks of shown files"),
+    (
+        "r",
+        "Generate reports for checked (or highlighted) transcripts",
+    ),
+    ("R", "Regenerate the highlighted transcript's report"),
+    ("Del", "Delete checked files"),
+    ("u", "Undo the last delete"),
+    ("e", "Open the highlighted file in $EDITOR"),
+    ("p", "Show / hide the preview"),
+    ("g", "Group files by video"),
+    ("Enter / → / ←", "Expand / collapse a video (grouped)"),
+    ("F2", "Rename the highlighted file"),
+    ("1 / 2 / 3", "Show all / transcripts / reports"),
+    ("s / S", "Sort by date, name or size / reverse"),
+    ("/", "Search (Enter keeps, Esc clears)"),
+    ("Ctrl+s", "Save search and filter as a chip"),
+    ("Alt+1..9 / Click", "Apply a saved search"),
+    ("m", "Rename or delete saved searches"),
+    ("Esc / q", "Back to home"),
+];
+
+const VIEWER_BINDINGS: &[Binding] = &[
+    ("↑ / ↓ / j / k", "Scroll one line"),
+    ("Wheel", "Scroll one line"),
+    ("Click / drag scrollbar", "Jump through the document"),
+    ("PgUp / PgDn", "Page up / down"),
+    ("b / Space", "Page up / down"),
+    ("Home / g", "Jump to top"),
+    ("End / G", "Jump to bottom"),
+    ("/", "Search (Enter confirms)"),
+    ("n / N", "Next / previous match"),
+    ("r", "Toggle raw / rendered Markdown"),
+    ("t", "Toggle full-width tables"),
+    ("L", "Open a numbered link in the browser"),
+    ("e", "Edit in $EDITOR, then reload"),
+    ("← / → / h / l", "Scroll wide tables sideways"),
+    ("Esc", "Clear search, then back to browser"),
+    ("q", "Back to browser"),
+];
+
+const CHANNELS_BINDINGS: &[Binding] = &[("Esc / q", "Back to home")];
+
+const SETTINGS_BINDINGS: &[Binding] = &[
+    ("Tab", "Next field"),
+    ("Space", "Toggle the log file or URL history"),
+    ("← / →", "Switch theme (on the theme row)"),
+    ("Enter", "Save settings"),
+    ("← / →", "Move cursor"),
+    ("Ctrl+U / Ctrl+K", "Delete to start / end of field"),
+    ("Esc", "Back to home"),
+];
+
+pub fn screen_title(state: &AppState) -> &'static str {
+    match state {
+        AppState::Home => "Home",
+        AppState::NewTranscript => "New Transcript",
+        AppState::Processing { .. } => "Processing",
+        AppState::Browser => "Browser",
+        AppState::Viewer { .. } => "Viewer",
+        AppState::Channels => "Canales",
+        AppState::Settings => "Settings",
+    }
+}
+
+pub fn bindings(state: &AppState) -> &'static [Binding] {
+    match state {
+        AppState::Home => HOME_BINDINGS,
+        AppState::NewTranscript => NEW_TRANSCRIPT_BINDINGS,
+        AppState::Processing { .. } => PROCESSING_BINDINGS,
+        AppState::Browser => BROWSER_BINDINGS,
+        AppState::Viewer { .. } => VIEWER_BINDINGS,
+        AppState::Channels => CHANNELS_BINDINGS,
+        AppState::Settings => SETTINGS_BINDINGS,
+    }
+}
+
+/// Draw the help popup centered over whatever screen is already rendered.
+pub fn render_overlay(f: &mut Frame, state: &AppState, theme: &Theme) {
+    let entries: Vec<&Binding> = bindings(state).iter().chain(GLOBAL_BINDINGS).collect();
+    let key_width = entries
+        .iter()
+        .map(|(key, _)| key.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut lines: Vec<Line> = entries
+        .iter()
+        .map(|(key, description)| {
+            Line::from(vec![
+                Span::styled(
+                    format!(" {key:<key_width$}  "),
+                    Style::default()
+                        .fg(theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(*description),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        " Press any key to close",
+        Style::default().fg(theme.dim),
+    )));
+
+    let area = centered_rect(60, lines.len() as u16 + 2, f.area());
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame)
+            .border_style(Style::default().fg(theme.accent))
+            .title(format!("Help: {}", screen_title(state))),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+/// A rectangle `percent_x` wide and `height` rows tall, centered in `area`.
+pub fn centered_rect(percent_x: u16, height: u16, area: Rect) -> Rect {
+    let height = height.min(area.height);
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
@@ -0,0 +1,93 @@
+// Entries shown on the Home screen. Adding a screen is one line in
+// `HOME_ENTRIES`: the grid layout, shortcuts and pinning are driven from here.
+use crate::core::Config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HomeAction {
+    NewTranscript,
+    Transcripts,
+    Reports,
+    Channels,
+    Settings,
+}
+
+pub struct HomeEntry {
+    /// Stable name used by `[home] pinned` in the config file.
+    pub id: &'static str,
+    pub icon: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub shortcut: char,
+    pub action: HomeAction,
+    /// Why the entry cannot be opened right now, or `None` if it can.
+    pub disabled: fn(&Config) -> Option<&'static str>,
+}
+
+pub const MAX_PINNED: usize = 3;
+
+fn always_enabled(_: &Config) -> Option<&'static str> {
+    None
+}
+
+fn needs_network(config: &Config) -> Option<&'static str> {
+    config.offline.then_some("offline mode")
+}
+
+#[rustfmt::skip]
+pub const HOME_ENTRIES: &[HomeEntry] = &[
+    HomeEntry { id: "new", icon: "+", title: "New Transcript", description: "Download a transcript and generate a report", shortcut: '1', action: HomeAction::NewTranscript, disabled: needs_network },
+    HomeEntry { id: "transcripts", icon: "≡", title: "View Transcripts", description: "Browse downloaded transcripts", shortcut: '2', action: HomeAction::Transcripts, disabled: always_enabled },
+    HomeEntry { id: "reports", icon: "¶", title: "View Reports", description: "Browse generated reports", shortcut: '3', action: HomeAction::Reports, disabled: always_enabled },
+    HomeEntry { id: "channels", icon: "◉", title: "Canales", description: "Watched channels and their last sync", shortcut: '4', action: HomeAction::Channels, disabled: always_enabled },
+    HomeEntry { id: "settings", icon: "⚙", title: "Settings", description: "Preferences", shortcut: '5', action: HomeAction::Settings, disabled: always_enabled },
+];
+
+/// Registry order with up to `MAX_PINNED` pinned ids moved to the front in the
+/// order given. Unknown ids are ignored.
+pub fn ordered_entries(pinned: &[String]) -> Vec<&'static HomeEntry> {
+    let mut entries: Vec<&'static HomeEntry> = Vec::with_capacity(HOME_ENTRIES.len());
+    for id in pinned.iter().take(MAX_PINNED) {
+        if let Some(entry) = HOME_ENTRIES.iter().find(|entry| entry.id == id)
+            && !entries.iter().any(|e| e.id == entry.id)
+        {
+            entries.push(entry);
+        }
+    }
+    let rest: Vec<&'static HomeEntry> = HOME_ENTRIES
+        .iter()
+        .filter(|entry| !entries.iter().any(|e| e.id == entry.id))
+        .collect();
+    entries.extend(rest);
+    entries
+}
+
+pub fn is_pinned(entry: &HomeEntry, pinned: &[String]) -> bool {
+    pinned.iter().take(MAX_PINNED).any(|id| id == entry.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HOME_ENTRIES, ordered_entries};
+
+    fn ids(pinned: &[&str]) -> Vec<&'static str> {
+        let pinned: Vec<String> = pinned.iter().map(|s| s.to_string()).collect();
+        ordered_entries(&pinned).iter().map(|e| e.id).collect()
+    }
+
+    #[test]
+    fn pins_move_to_front_in_config_order() {
+        assert_eq!(
+            ids(&["settings", "reports"]),
+            vec!["settings", "reports", "new", "transcripts", "channels"]
+        );
+    }
+
+    #[test]
+    fn unknown_duplicate_and_extra_pins_are_ignored() {
+        assert_eq!(
+            ids(&["nope", "reports", "reports", "settings", "transcripts"]),
+            vec!["reports", "new", "transcripts", "channels", "settings"]
+        );
+        assert_eq!(ids(&[]).len(), HOME_ENTRIES.len());
+    }
+}
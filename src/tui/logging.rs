@@ -0,0 +1,38 @@
+// While the TUI owns the terminal nothing may print to it, so log records
+// from the core go to the processing log instead.
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use tokio::sync::mpsc;
+
+struct ChannelLogger {
+    tx: mpsc::UnboundedSender<String>,
+}
+
+impl Log for ChannelLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        // Other crates' info chatter would only crowd the log
+        metadata.level() <= Level::Warn
+            || (metadata.level() <= Level::Info && metadata.target().starts_with("vidio"))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = match record.level() {
+            Level::Info => record.args().to_string(),
+            level => format!("{}: {}", level.as_str().to_lowercase(), record.args()),
+        };
+        let _ = self.tx.send(line);
+    }
+
+    fn flush(&self) {}
+}
+
+/// Route log records into a channel whose lines the app adds to its log.
+pub fn init() -> mpsc::UnboundedReceiver<String> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    if log::set_boxed_logger(Box::new(ChannelLogger { tx })).is_ok() {
+        log::set_max_level(LevelFilter::Info);
+    }
+    rx
+}
@@ -1,34 +1,89 @@
 pub mod app;
+pub mod browser;
 pub mod components;
+pub mod editor;
 pub mod events;
+pub mod help;
+pub mod home;
+pub mod logging;
+pub mod notify;
+pub mod symbols;
+pub mod theme;
 pub mod ui;
 
 use crate::error::Result;
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
 use std::io;
+use std::sync::Once;
 
 pub use app::App;
 pub use events::EventHandler;
+pub use symbols::symbols;
+pub use theme::Theme;
 
 pub type Tui = Terminal<CrosstermBackend<io::Stdout>>;
 
 pub fn init() -> Result<Tui> {
-    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
-    enable_raw_mode()?;
+    install_panic_hook();
+    enter()?;
 
     let backend = CrosstermBackend::new(io::stdout());
-    let terminal = Terminal::new(backend)?;
-
-    Ok(terminal)
+    Terminal::new(backend).map_err(|e| {
+        let _ = restore();
+        e.into()
+    })
 }
 
+/// Give the terminal back to the shell. Safe to call more than once, and
+/// leaves raw mode even when the screen can't be switched back.
 pub fn restore() -> Result<()> {
-    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
-    disable_raw_mode()?;
+    // Unsupported on the legacy Windows console, like enabling it
+    let _ = execute!(io::stdout(), DisableBracketedPaste);
+    let screen = execute!(io::stdout(), DisableMouseCapture, LeaveAlternateScreen);
+    let raw = disable_raw_mode();
+    screen?;
+    raw?;
+    Ok(())
+}
+
+/// Take the terminal back after `restore`, e.g. once an external editor
+/// exits, and redraw everything on the next frame.
+pub fn resume(terminal: &mut Tui) -> Result<()> {
+    enter()?;
+    terminal.clear()?;
     Ok(())
 }
+
+/// Raw mode first: Windows consoles apply it to the active screen buffer, so
+/// switching it on after entering the alternate screen doesn't take. A failed
+/// switch gives the terminal back rather than leaving it half set up.
+fn enter() -> Result<()> {
+    enable_raw_mode()?;
+    let entered = execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture);
+    if let Err(e) = entered {
+        let _ = restore();
+        return Err(e.into());
+    }
+    // The legacy Windows console API has no bracketed paste; pastes then
+    // arrive as keys
+    let _ = execute!(io::stdout(), EnableBracketedPaste);
+    Ok(())
+}
+
+/// Restore the terminal before the default panic message is printed, so a
+/// panic never leaves the shell in raw mode on the alternate screen.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let original_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            let _ = restore();
+            original_hook(panic_info);
+        }));
+    });
+}
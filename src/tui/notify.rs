@@ -0,0 +1,128 @@
+// Desktop notifications for jobs that finish while the terminal is in the
+// background. They go through the platform's own notifier rather than a
+// notification crate, so nothing extra is linked in: `notify-send` on Linux
+// and the BSDs, `osascript` on macOS and a PowerShell toast on Windows. A
+// desktop without one, or without a notification daemon, simply gets none.
+use std::env::consts::OS;
+use std::process::{Command, Stdio};
+
+/// The app id Windows shows PowerShell's own toasts under; an unregistered
+/// id like "vidio" would have them dropped silently.
+const POWERSHELL_APP_ID: &str =
+    r"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\WindowsPowerShell\v1.0\powershell.exe";
+
+/// Show `title` and `body` as a desktop notification, if there is a way to.
+pub fn send(title: &str, body: &str) {
+    let Some(mut command) = notifier(OS, title, body) else {
+        return;
+    };
+    let child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    // Waited on elsewhere, so a slow notifier never holds up the screen
+    if let Ok(mut child) = child {
+        std::thread::spawn(move || child.wait());
+    }
+}
+
+/// The command that shows a notification on `os`, as named by
+/// [`std::env::consts::OS`].
+fn notifier(os: &str, title: &str, body: &str) -> Option<Command> {
+    match os {
+        "macos" => {
+            let mut command = Command::new("osascript");
+            command.arg("-e").arg(format!(
+                "display notification {} with title {}",
+                applescript_string(body),
+                applescript_string(title)
+            ));
+            Some(command)
+        }
+        "windows" => {
+            let mut command = Command::new("powershell");
+            command.args([
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                &toast_script(title, body),
+            ]);
+            Some(command)
+        }
+        "linux" | "freebsd" | "openbsd" | "netbsd" | "dragonfly" => {
+            let mut command = Command::new("notify-send");
+            command.args(["--app-name=vidio", title, body]);
+            Some(command)
+        }
+        _ => None,
+    }
+}
+
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn powershell_string(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// A two-line toast through the Windows Runtime, which every PowerShell
+/// since Windows 10 can reach without extra modules.
+fn toast_script(title: &str, body: &str) -> String {
+    format!(
+        "$m = [Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime]; \
+         $t = $m::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+         $x = $t.GetElementsByTagName('text'); \
+         [void]$x.Item(0).AppendChild($t.CreateTextNode({})); \
+         [void]$x.Item(1).AppendChild($t.CreateTextNode({})); \
+         $m::CreateToastNotifier({}).Show([Windows.UI.Notifications.ToastNotification]::new($t))",
+        powershell_string(title),
+        powershell_string(body),
+        powershell_string(POWERSHELL_APP_ID)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::notifier;
+
+    fn command_line(os: &str) -> Option<(String, Vec<String>)> {
+        let command = notifier(os, "vidio: \"Talk\" done", "It's saved")?;
+        Some((
+            command.get_program().to_string_lossy().into_owned(),
+            command
+                .get_args()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect(),
+        ))
+    }
+
+    #[test]
+    fn each_platform_gets_its_own_notifier_with_quoting() {
+        let (program, args) = command_line("linux").unwrap();
+        assert_eq!(program, "notify-send");
+        assert_eq!(
+            args,
+            ["--app-name=vidio", "vidio: \"Talk\" done", "It's saved"]
+        );
+
+        let (program, args) = command_line("macos").unwrap();
+        assert_eq!(program, "osascript");
+        assert_eq!(
+            args[1],
+            r#"display notification "It's saved" with title "vidio: \"Talk\" done""#
+        );
+
+        let (program, args) = command_line("windows").unwrap();
+        assert_eq!(program, "powershell");
+        let script = args.last().unwrap();
+        assert!(
+            script.contains("CreateTextNode('vidio: \"Talk\" done')"),
+            "{script}"
+        );
+        assert!(script.contains("CreateTextNode('It''s saved')"), "{script}");
+
+        assert!(command_line("android").is_none());
+    }
+}
@@ -0,0 +1,232 @@
+// Every glyph the TUI draws besides plain text, so terminals that can't show
+// them (older Windows consoles) get an ASCII set instead of mojibake. The set
+// is chosen once at startup; until then, and in tests, it is the Unicode one.
+use crate::core::Config;
+use ratatui::symbols::border;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbols {
+    pub checked: &'static str,
+    pub unchecked: &'static str,
+    /// Radio buttons, e.g. the selected Home entry or file filter.
+    pub selected: &'static str,
+    pub unselected: &'static str,
+    /// Whether a video has a transcript or report yet.
+    pub present: &'static str,
+    pub missing: &'static str,
+    pub expanded: &'static str,
+    pub collapsed: &'static str,
+    pub transcript: &'static str,
+    pub report: &'static str,
+    /// Transcripts from auto-generated captions.
+    pub auto_generated: &'static str,
+    pub pinned: &'static str,
+    pub bookmark: &'static str,
+    pub warning: &'static str,
+    /// The text cursor in input fields.
+    pub cursor: &'static str,
+    pub spinner: &'static [&'static str],
+    pub scrollbar_track: &'static str,
+    pub scrollbar_thumb: &'static str,
+    /// Tables, the front matter box and the quote bar in the Viewer.
+    pub border: BorderSymbols,
+    /// The border around every panel and popup.
+    pub frame: border::Set<'static>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BorderSymbols {
+    pub horizontal: char,
+    pub vertical: char,
+    pub top_left: char,
+    pub top_right: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+    pub top_tee: char,
+    pub bottom_tee: char,
+    pub left_tee: char,
+    pub right_tee: char,
+    pub cross: char,
+}
+
+pub const UNICODE: Symbols = Symbols {
+    checked: "☑",
+    unchecked: "☐",
+    selected: "●",
+    unselected: "○",
+    present: "✓",
+    missing: "✗",
+    expanded: "▾",
+    collapsed: "▸",
+    transcript: "📄",
+    report: "📊",
+    auto_generated: "🤖",
+    pinned: "★",
+    bookmark: "▌",
+    warning: "⚠",
+    cursor: "│",
+    spinner: &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+    scrollbar_track: "│",
+    scrollbar_thumb: "█",
+    border: BorderSymbols {
+        horizontal: '─',
+        vertical: '│',
+        top_left: '┌',
+        top_right: '┐',
+        bottom_left: '└',
+        bottom_right: '┘',
+        top_tee: '┬',
+        bottom_tee: '┴',
+        left_tee: '├',
+        right_tee: '┤',
+        cross: '┼',
+    },
+    frame: border::PLAIN,
+};
+
+pub const ASCII: Symbols = Symbols {
+    checked: "[x]",
+    unchecked: "[ ]",
+    selected: "(*)",
+    unselected: "( )",
+    present: "y",
+    missing: "n",
+    expanded: "v",
+    collapsed: ">",
+    transcript: "T",
+    report: "R",
+    auto_generated: "(auto)",
+    pinned: "*",
+    bookmark: "#",
+    warning: "!",
+    cursor: "|",
+    spinner: &["|", "/", "-", "\\"],
+    scrollbar_track: "|",
+    scrollbar_thumb: "#",
+    border: BorderSymbols {
+        horizontal: '-',
+        vertical: '|',
+        top_left: '+',
+        top_right: '+',
+        bottom_left: '+',
+        bottom_right: '+',
+        top_tee: '+',
+        bottom_tee: '+',
+        left_tee: '+',
+        right_tee: '+',
+        cross: '+',
+    },
+    frame: border::Set {
+        top_left: "+",
+        top_right: "+",
+        bottom_left: "+",
+        bottom_right: "+",
+        vertical_left: "|",
+        vertical_right: "|",
+        horizontal_top: "-",
+        horizontal_bottom: "-",
+    },
+};
+
+impl Symbols {
+    pub fn checkbox(&self, checked: bool) -> &'static str {
+        if checked {
+            self.checked
+        } else {
+            self.unchecked
+        }
+    }
+
+    pub fn radio(&self, selected: bool) -> &'static str {
+        if selected {
+            self.selected
+        } else {
+            self.unselected
+        }
+    }
+}
+
+static CHOSEN: OnceLock<Symbols> = OnceLock::new();
+
+/// Whether to draw the ASCII set: `--ascii`, else `ascii` in the config,
+/// else on Windows.
+pub fn wants_ascii(flag: bool, config: &Config) -> bool {
+    flag || config.ascii.unwrap_or(cfg!(windows))
+}
+
+/// Pick the set for the rest of the run. Only the first call counts.
+pub fn init(ascii: bool) {
+    let _ = CHOSEN.set(if ascii { ASCII } else { UNICODE });
+}
+
+pub fn symbols() -> &'static Symbols {
+    CHOSEN.get().unwrap_or(&UNICODE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ASCII, wants_ascii};
+    use crate::core::Config;
+
+    #[test]
+    fn ascii_set_is_plain_ascii() {
+        let border = ASCII.border;
+        let glyphs = [
+            ASCII.checked,
+            ASCII.unchecked,
+            ASCII.selected,
+            ASCII.unselected,
+            ASCII.present,
+            ASCII.missing,
+            ASCII.expanded,
+            ASCII.collapsed,
+            ASCII.transcript,
+            ASCII.report,
+            ASCII.auto_generated,
+            ASCII.pinned,
+            ASCII.bookmark,
+            ASCII.warning,
+            ASCII.cursor,
+            ASCII.scrollbar_track,
+            ASCII.scrollbar_thumb,
+            ASCII.frame.top_left,
+            ASCII.frame.top_right,
+            ASCII.frame.bottom_left,
+            ASCII.frame.bottom_right,
+            ASCII.frame.vertical_left,
+            ASCII.frame.vertical_right,
+            ASCII.frame.horizontal_top,
+            ASCII.frame.horizontal_bottom,
+        ];
+        assert!(glyphs.iter().chain(ASCII.spinner).all(|g| g.is_ascii()));
+        assert!(
+            [
+                border.horizontal,
+                border.vertical,
+                border.top_left,
+                border.top_right,
+                border.bottom_left,
+                border.bottom_right,
+                border.top_tee,
+                border.bottom_tee,
+                border.left_tee,
+                border.right_tee,
+                border.cross,
+            ]
+            .iter()
+            .all(char::is_ascii)
+        );
+    }
+
+    #[test]
+    fn flag_and_config_choose_the_set() {
+        let mut config = Config::default();
+        assert!(wants_ascii(true, &config));
+        assert_eq!(wants_ascii(false, &config), cfg!(windows));
+        config.ascii = Some(false);
+        assert!(!wants_ascii(false, &config));
+        config.ascii = Some(true);
+        assert!(wants_ascii(false, &config));
+    }
+}
@@ -0,0 +1,206 @@
+// TUI colors by role rather than by name, so the same screens stay readable on
+// dark and light terminals. Custom themes come from `[themes]` in `vidio.toml`.
+use crate::core::settings::Settings;
+use crate::core::{Config, ThemeConfig};
+use crate::error::{Error, Result};
+use ratatui::style::Color;
+
+pub const BUILT_IN_THEMES: [&str; 2] = ["dark", "light"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Titles, focused fields and the selected entry.
+    pub accent: Color,
+    pub text: Color,
+    /// Help lines and other secondary text.
+    pub secondary: Color,
+    /// Placeholders and disabled entries.
+    pub dim: Color,
+    pub border: Color,
+    /// Background of the highlighted row.
+    pub highlight: Color,
+    pub heading: Color,
+    pub table_header: Color,
+    /// Inline code, shown reversed.
+    pub code: Color,
+    pub link: Color,
+    /// Bookmarks, pins and checked files.
+    pub mark: Color,
+    /// Background of the current search match; the others use `accent`.
+    pub current_match: Color,
+    pub success: Color,
+    pub error: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            accent: Color::Yellow,
+            text: Color::White,
+            secondary: Color::Gray,
+            dim: Color::DarkGray,
+            border: Color::Gray,
+            highlight: Color::DarkGray,
+            heading: Color::Cyan,
+            table_header: Color::Cyan,
+            code: Color::Yellow,
+            link: Color::Blue,
+            mark: Color::Magenta,
+            current_match: Color::LightMagenta,
+            success: Color::Green,
+            error: Color::Red,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            accent: Color::Blue,
+            text: Color::Black,
+            secondary: Color::DarkGray,
+            dim: Color::Gray,
+            border: Color::DarkGray,
+            highlight: Color::Gray,
+            heading: Color::Blue,
+            table_header: Color::Blue,
+            code: Color::Magenta,
+            link: Color::Blue,
+            mark: Color::Magenta,
+            current_match: Color::LightMagenta,
+            success: Color::Green,
+            error: Color::Red,
+        }
+    }
+
+    /// The built-in themes, then the custom ones in name order.
+    pub fn names(config: &Config) -> Vec<String> {
+        BUILT_IN_THEMES
+            .iter()
+            .map(|name| name.to_string())
+            .chain(
+                config
+                    .themes
+                    .keys()
+                    .filter(|name| !BUILT_IN_THEMES.contains(&name.as_str()))
+                    .cloned(),
+            )
+            .collect()
+    }
+
+    /// A built-in theme, or one defined in the config on top of its base.
+    pub fn named(name: &str, config: &Config) -> Result<Self> {
+        if let Some(custom) = config.themes.get(name) {
+            return Self::custom(name, custom);
+        }
+        Self::built_in(name)
+            .ok_or_else(|| Error::custom(format!("Unknown theme '{name}' in the config")))
+    }
+
+    fn built_in(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            _ => None,
+        }
+    }
+
+    fn custom(name: &str, config: &ThemeConfig) -> Result<Self> {
+        let base = config.base.as_deref().unwrap_or("dark");
+        let mut theme = Self::built_in(base).ok_or_else(|| {
+            Error::custom(format!(
+                "Theme '{name}' is based on '{base}'; use dark or light"
+            ))
+        })?;
+        let colors = [
+            (&mut theme.accent, &config.accent),
+            (&mut theme.text, &config.text),
+            (&mut theme.secondary, &config.secondary),
+            (&mut theme.dim, &config.dim),
+            (&mut theme.border, &config.border),
+            (&mut theme.highlight, &config.highlight),
+            (&mut theme.heading, &config.heading),
+            (&mut theme.table_header, &config.table_header),
+            (&mut theme.code, &config.code),
+            (&mut theme.link, &config.link),
+            (&mut theme.mark, &config.mark),
+            (&mut theme.current_match, &config.current_match),
+            (&mut theme.success, &config.success),
+            (&mut theme.error, &config.error),
+        ];
+        for (color, value) in colors {
+            if let Some(value) = value {
+                *color = value.parse().map_err(|_| {
+                    Error::custom(format!(
+                        "Theme '{name}' has an invalid color '{value}'; use a name such as lightblue or a hex value such as #268bd2"
+                    ))
+                })?;
+            }
+        }
+        Ok(theme)
+    }
+}
+
+/// The theme picked in Settings, else the one `vidio.toml` names.
+pub fn saved_theme<'a>(settings: &'a Settings, config: &'a Config) -> &'a str {
+    settings.theme.as_deref().unwrap_or(&config.theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Theme;
+    use crate::core::{Config, ThemeConfig};
+    use ratatui::style::Color;
+
+    fn config_with(name: &str, theme: ThemeConfig) -> Config {
+        let mut config = Config::default();
+        config.themes.insert(name.to_string(), theme);
+        config
+    }
+
+    #[test]
+    fn custom_themes_override_their_base() {
+        let config = config_with(
+            "paper",
+            ThemeConfig {
+                base: Some("light".to_string()),
+                accent: Some("#268bd2".to_string()),
+                mark: Some("lightred".to_string()),
+                ..ThemeConfig::default()
+            },
+        );
+        let theme = Theme::named("paper", &config).unwrap();
+        assert_eq!(theme.accent, Color::Rgb(0x26, 0x8b, 0xd2));
+        assert_eq!(theme.mark, Color::LightRed);
+        assert_eq!(theme.text, Theme::light().text);
+        assert_eq!(Theme::names(&config), ["dark", "light", "paper"]);
+    }
+
+    #[test]
+    fn bad_names_and_colors_are_refused() {
+        assert!(Theme::named("solarized", &Config::default()).is_err());
+
+        let config = config_with(
+            "bad",
+            ThemeConfig {
+                accent: Some("not-a-color".to_string()),
+                ..ThemeConfig::default()
+            },
+        );
+        let error = Theme::named("bad", &config).unwrap_err();
+        assert!(error.to_string().contains("not-a-color"));
+
+        let config = config_with(
+            "loop",
+            ThemeConfig {
+                base: Some("loop".to_string()),
+                ..ThemeConfig::default()
+            },
+        );
+        assert!(Theme::named("loop", &config).is_err());
+    }
+}
@@ -1,162 +1,439 @@
-use crate::tui::app::{App, AppState, FileFilter};
+use crate::core::searches::FileFilter;
+use crate::core::{StorageService, StorageStats, TranscriptFormat, format_size};
+use crate::error::Error;
+use crate::tui::app::{App, AppState, ReportCheck, TitleLookup};
+use crate::tui::components::MenuItem;
+use crate::tui::{Theme, symbols};
+use crate::tui::{browser, help, home};
+use chrono::Local;
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
 };
 
 pub fn draw(f: &mut Frame, app: &mut App) {
+    let [screen, footer] = screen_layout(f.area());
     match &app.state {
-        AppState::Home => draw_home(f, app),
-        AppState::NewTranscript => draw_new_transcript(f, app),
-        AppState::Processing { video_id, .. } => draw_processing(f, app, video_id),
-        AppState::Browser { .. } => draw_browser(f, app),
-        AppState::Viewer { .. } => draw_viewer(f, app),
-        AppState::Settings => draw_settings(f, app),
+        AppState::Home => draw_home(f, app, screen),
+        AppState::NewTranscript => draw_new_transcript(f, app, screen),
+        AppState::Processing { video_id, .. } => {
+            let video_id = video_id.clone();
+            draw_processing(f, app, &video_id, screen)
+        }
+        AppState::Browser => draw_browser(f, app, screen),
+        AppState::Viewer { .. } => draw_viewer(f, app, screen),
+        AppState::Channels => draw_channels(f, app, screen),
+        AppState::Settings => draw_settings(f, app, screen),
     }
+    draw_footer(f, app, footer);
+
+    if let Some(error) = &app.error_popup {
+        draw_error_popup(f, error, &app.theme);
+    }
+    if app.show_help {
+        help::render_overlay(f, &app.state, &app.theme);
+    }
+}
+
+/// The screen, and the one-line footer every screen shares below it.
+pub fn screen_layout(area: Rect) -> [Rect; 2] {
+    Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(area)
+}
+
+/// Where files go, whether reports may be sent, the model writing them and
+/// the jobs under way, so none of it comes as a surprise mid-job.
+fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
+    let data_dir = StorageService::data_dir()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_default();
+    let (opt_in, opt_in_color) = if app.report_service.is_opted_in() {
+        ("habilitado", app.theme.dim)
+    } else {
+        ("deshabilitado", app.theme.accent)
+    };
+    let running = usize::from(app.current_job.is_some());
+    let jobs = match (running, app.job_queue.len()) {
+        (0, 0) => "sin trabajos".to_string(),
+        (running, queued) => format!("{running} en curso · {queued} en cola"),
+    };
+    let dim = Style::default().fg(app.theme.dim);
+    let footer = Line::from(vec![
+        Span::styled(format!(" {data_dir} · "), dim),
+        Span::styled(
+            format!("OpenAI: {opt_in}"),
+            Style::default().fg(opt_in_color),
+        ),
+        Span::styled(
+            format!(" · modelo {} · {jobs}", app.report_service.model()),
+            dim,
+        ),
+    ]);
+    f.render_widget(Paragraph::new(footer), area);
+}
+
+fn draw_error_popup(f: &mut Frame, error: &str, theme: &Theme) {
+    let area = help::centered_rect(60, 7, f.area());
+    let popup = Paragraph::new(vec![
+        Line::from(error.to_string()),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Press any key to close",
+            Style::default().fg(theme.dim),
+        )),
+    ])
+    .wrap(Wrap { trim: true })
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame)
+            .border_style(Style::default().fg(theme.error))
+            .title("Error"),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
 }
 
-fn draw_home(f: &mut Frame, app: &App) {
+fn draw_home(f: &mut Frame, app: &mut App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3), // Title
             Constraint::Min(1),    // Menu
             Constraint::Length(3), // Help
+            Constraint::Length(1), // Status bar
         ])
-        .split(f.area());
+        .split(area);
 
     // Title
     let title = Paragraph::new("Vidio TUI")
         .style(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(app.theme.accent)
                 .add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(symbols().frame),
+        );
     f.render_widget(title, chunks[0]);
 
-    // Menu options
-    let options = [
-        "● New Transcript",
-        "○ View Transcripts",
-        "○ View Reports",
-        "○ Settings",
-    ];
-
-    let menu_items: Vec<ListItem> = options
-        .iter()
-        .enumerate()
-        .map(|(i, option)| {
-            let style = if i == app.selected_option {
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::White)
-            };
-
-            let text = if i == app.selected_option {
-                option.replace("○", "●")
-            } else {
-                option.replace("●", "○")
-            };
-
-            ListItem::new(Line::from(Span::styled(text, style)))
+    // Menu entries from the Home registry, pinned ones first
+    let pinned = &app.config.home.pinned;
+    let items: Vec<MenuItem> = app
+        .home_entries()
+        .into_iter()
+        .map(|entry| MenuItem {
+            icon: entry.icon,
+            title: entry.title,
+            description: entry.description,
+            shortcut: entry.shortcut,
+            disabled: (entry.disabled)(&app.config),
+            pinned: home::is_pinned(entry, pinned),
         })
         .collect();
-
-    let menu = List::new(menu_items)
-        .block(Block::default().borders(Borders::ALL).title("Mode"))
-        .style(Style::default().fg(Color::White));
-    f.render_widget(menu, chunks[1]);
+    app.home_menu
+        .render(f, chunks[1], "Mode", &items, &app.theme);
 
     // Help
-    let help = Paragraph::new("[↑↓] Navigate  [Enter] Select  [q] Exit")
-        .style(Style::default().fg(Color::Gray))
+    let help = Paragraph::new("[↑↓←→] Navigate  [Enter] Select  [?] Help  [q] Exit")
+        .style(Style::default().fg(app.theme.secondary))
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(symbols().frame),
+        );
     f.render_widget(help, chunks[2]);
+    draw_status_bar(f, app, chunks[3]);
 }
 
-fn draw_new_transcript(f: &mut Frame, app: &mut App) {
+fn draw_new_transcript(f: &mut Frame, app: &mut App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3), // Title
             Constraint::Length(3), // URL input
+            Constraint::Length(1), // Detected video
             Constraint::Length(3), // Languages input
-            Constraint::Length(5), // Checkboxes
+            Constraint::Length(3), // Translation input
+            Constraint::Length(7), // Checkboxes and template
             Constraint::Length(3), // Help
         ])
-        .split(f.area());
+        .split(area);
 
     // Title
     let title = Paragraph::new("New Transcript")
         .style(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(app.theme.accent)
                 .add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(symbols().frame),
+        );
     f.render_widget(title, chunks[0]);
 
-    // URL input
-    app.url_input.render(f, chunks[1]);
+    // URL input, and the video it names
+    app.url_input.render(f, chunks[1], &app.theme);
+    draw_detected_video(f, app, chunks[2]);
 
     // Languages input
-    app.languages_input.render(f, chunks[2]);
+    app.languages_input.render(f, chunks[3], &app.theme);
+
+    // Translation input
+    app.translate_input.render(f, chunks[4], &app.theme);
 
     // Checkboxes
-    let checkbox_block = Block::default().borders(Borders::ALL).title("Options");
-    f.render_widget(checkbox_block, chunks[3]);
+    let checkbox_block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(symbols().frame)
+        .title("Options");
+    f.render_widget(checkbox_block, chunks[5]);
 
     let checkbox_area = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
-        .constraints([Constraint::Length(1), Constraint::Length(1)])
-        .split(chunks[3]);
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .split(chunks[5]);
+
+    let preserve_style = if app.input_focus == 3 {
+        Style::default().fg(app.theme.accent)
+    } else {
+        Style::default().fg(app.theme.text)
+    };
 
-    let preserve_style = if app.input_focus == 2 {
-        Style::default().fg(Color::Yellow)
+    let manual_style = if app.input_focus == 4 {
+        Style::default().fg(app.theme.accent)
     } else {
-        Style::default().fg(Color::White)
+        Style::default().fg(app.theme.text)
     };
 
-    let report_style = if app.input_focus == 3 {
-        Style::default().fg(Color::Yellow)
+    let copy_style = if app.input_focus == 5 {
+        Style::default().fg(app.theme.accent)
     } else {
-        Style::default().fg(Color::White)
+        Style::default().fg(app.theme.text)
     };
 
-    let preserve_checkbox = if app.preserve_formatting {
-        "☑"
+    let report_style = if app.input_focus == 6 {
+        Style::default().fg(app.theme.accent)
     } else {
-        "☐"
+        Style::default().fg(app.theme.text)
     };
-    let report_checkbox = if app.generate_report { "☑" } else { "☐" };
+
+    let preserve_checkbox = symbols().checkbox(app.preserve_formatting);
+    let manual_checkbox = symbols().checkbox(app.manual_only);
+    let report_checkbox = symbols().checkbox(app.generate_report);
 
     let preserve_text =
         Paragraph::new(format!("{preserve_checkbox} Preserve formatting")).style(preserve_style);
     f.render_widget(preserve_text, checkbox_area[0]);
 
+    let manual_text = Paragraph::new(format!(
+        "{manual_checkbox} Manual captions only (no auto-generated)"
+    ))
+    .style(manual_style);
+    f.render_widget(manual_text, checkbox_area[1]);
+
+    let copy = match app.transcript_format {
+        TranscriptFormat::Timestamped => "none",
+        TranscriptFormat::Plain => "plain text",
+        TranscriptFormat::Markdown => "Markdown",
+    };
+    let copy_text = Paragraph::new(format!("Also save as: < {copy} >")).style(copy_style);
+    f.render_widget(copy_text, checkbox_area[2]);
+
     let report_text = Paragraph::new(format!("{report_checkbox} Generate report automatically"))
         .style(report_style);
-    f.render_widget(report_text, checkbox_area[1]);
+    f.render_widget(report_text, checkbox_area[3]);
 
-    // Help
-    let help = Paragraph::new("[Enter] Process  [Esc] Back  [Tab] Next  [Space] Toggle")
-        .style(Style::default().fg(Color::Gray))
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
-    f.render_widget(help, chunks[4]);
+    let template_style = if app.input_focus == 7 {
+        Style::default().fg(app.theme.accent)
+    } else {
+        Style::default().fg(app.theme.text)
+    };
+    let template_text = Paragraph::new(format!("Template: < {} >", app.report_template.name()))
+        .style(template_style);
+    f.render_widget(template_text, checkbox_area[4]);
+
+    // Help, or why the job did not start
+    let help = match &app.new_transcript_status {
+        Some(status) => Paragraph::new(status.as_str()).style(Style::default().fg(app.theme.accent)),
+        None => {
+            Paragraph::new("[Enter] Process  [Esc] Back  [Tab] Next  [Space] Toggle  [↑↓] History  [Ctrl+R] Search")
+                .style(Style::default().fg(app.theme.secondary))
+        }
+    }
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame),
+    );
+    f.render_widget(help, chunks[6]);
+
+    draw_history_search(f, app);
+    draw_report_check(f, app);
+}
+
+/// The Ctrl+R popup: the URL history filtered by what is typed, with a ✓ on
+/// videos whose transcript is already saved.
+fn draw_history_search(f: &mut Frame, app: &mut App) {
+    let theme = app.theme;
+    let Some(search) = &mut app.history_search else {
+        return;
+    };
+    let area = help::centered_rect(80, 16, f.area());
+    f.render_widget(Clear, area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(area);
+    search.query.render(f, chunks[0], &theme);
+
+    let items: Vec<ListItem> = search
+        .matches
+        .iter()
+        .map(|(entry, saved)| {
+            let mut spans = vec![
+                Span::styled(
+                    if *saved {
+                        format!("{} ", symbols().present)
+                    } else {
+                        "  ".to_string()
+                    },
+                    Style::default().fg(theme.success),
+                ),
+                Span::raw(entry.url.clone()),
+            ];
+            if let Some(title) = &entry.title {
+                spans.push(Span::styled(
+                    format!("  {title}"),
+                    Style::default().fg(theme.secondary),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(symbols().frame)
+                .title(format!(
+                    "Recent URLs · {} transcript saved · [Enter] Use  [Esc] Cancel",
+                    symbols().present
+                )),
+        )
+        .highlight_style(Style::default().bg(theme.highlight));
+    f.render_stateful_widget(list, chunks[1], &mut search.state);
 }
 
-fn draw_processing(f: &mut Frame, app: &App, video_id: &str) {
+/// The report check of a submitted job or report batch: still running, or
+/// what it found missing, with the choice to go on without the report.
+fn draw_report_check(f: &mut Frame, app: &App) {
+    let (lines, border, title) = match &app.report_check {
+        None => return,
+        Some(ReportCheck::Checking { .. } | ReportCheck::CheckingBatch { .. }) => (
+            vec![
+                Line::from("Checking the API key, the opt-in and the report server..."),
+                Line::from(""),
+                Line::from(Span::styled(
+                    "[Esc] Cancel",
+                    Style::default().fg(app.theme.dim),
+                )),
+            ],
+            app.theme.accent,
+            "Report check",
+        ),
+        Some(ReportCheck::Failed { problem, .. }) => (
+            vec![
+                Line::from(problem.to_string()),
+                Line::from(""),
+                Line::from(Span::styled(
+                    "[Enter] Fetch the transcript only  [Esc] Back to the form",
+                    Style::default().fg(app.theme.dim),
+                )),
+            ],
+            app.theme.error,
+            match problem {
+                Error::OptInRequired(_) => "Opt-in required",
+                Error::Network(_) => "Report server unreachable",
+                Error::Api(_) => "Report server refused",
+                _ => "No report possible",
+            },
+        ),
+    };
+    let area = help::centered_rect(70, 8, f.area());
+    let popup = Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame)
+            .border_style(Style::default().fg(border))
+            .title(title),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+/// The video ID found in the URL and, once fetched, its title, so a wrong
+/// link shows before the job starts.
+fn draw_detected_video(f: &mut Frame, app: &App, area: Rect) {
+    let line = match &app.detected_video {
+        Some(video) => {
+            let title = match &video.title {
+                TitleLookup::Waiting(_) | TitleLookup::Loading => {
+                    Span::styled("looking up the title…", Style::default().fg(app.theme.dim))
+                }
+                TitleLookup::Found(metadata) => Span::raw(match metadata.length() {
+                    Some(length) => format!(
+                        "{} ({length})",
+                        metadata.title.as_deref().unwrap_or_default()
+                    ),
+                    None => metadata.title.clone().unwrap_or_default(),
+                }),
+                TitleLookup::Failed => {
+                    Span::styled("title unavailable", Style::default().fg(app.theme.dim))
+                }
+            };
+            let mut spans = vec![
+                Span::styled(
+                    format!(" Video ID: {} · ", video.id),
+                    Style::default().fg(app.theme.success),
+                ),
+                title,
+            ];
+            if StorageService::transcript_exists(&video.id, None) {
+                spans.push(Span::styled(
+                    " · transcript already saved",
+                    Style::default().fg(app.theme.accent),
+                ));
+            }
+            Line::from(spans)
+        }
+        None if !app.url_input.value.trim().is_empty() => Line::from(Span::styled(
+            " Not a YouTube URL or video ID",
+            Style::default().fg(app.theme.error),
+        )),
+        None => Line::default(),
+    };
+    f.render_widget(Paragraph::new(line), area);
+}
+
+fn draw_processing(f: &mut Frame, app: &mut App, video_id: &str, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -164,35 +441,57 @@ fn draw_processing(f: &mut Frame, app: &App, video_id: &str) {
             Constraint::Min(1),    // Progress area
             Constraint::Length(3), // Help
         ])
-        .split(f.area());
+        .split(area);
 
-    // Title
-    let title = Paragraph::new("Processing...")
+    // Title, with the number of jobs waiting behind the current one
+    let title = if app.job_queue.is_empty() {
+        "Processing...".to_string()
+    } else {
+        format!("Processing... ({} queued)", app.job_queue.len())
+    };
+    let title = Paragraph::new(title)
         .style(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(app.theme.accent)
                 .add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(symbols().frame),
+        );
     f.render_widget(title, chunks[0]);
 
     // Progress area
-    app.progress_bar.render(f, chunks[1], video_id);
+    app.progress_bar.render(f, chunks[1], video_id, &app.theme);
 
     // Help
-    let help = Paragraph::new("[Esc] Cancel")
-        .style(Style::default().fg(Color::Gray))
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
+    let help = if app.preflight.is_some() {
+        Paragraph::new("Generate the report at this cost? [y] Yes  [n] Skip it  [Esc] Cancel all")
+            .style(Style::default().fg(app.theme.accent))
+    } else if app.awaiting_dismissal() {
+        Paragraph::new("[Enter/Esc] Back  [↑↓ PgUp PgDn] Scroll log  [s] Save log  [n] New video")
+            .style(Style::default().fg(app.theme.error))
+    } else {
+        Paragraph::new(
+            "[n] Queue another  [↑↓ PgUp PgDn] Scroll log  [s] Save log  [Esc] Cancel all",
+        )
+        .style(Style::default().fg(app.theme.secondary))
+    };
+    let help = help.alignment(Alignment::Center).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame),
+    );
     f.render_widget(help, chunks[2]);
 }
 
-fn draw_browser(f: &mut Frame, app: &mut App) {
+fn draw_browser(f: &mut Frame, app: &mut App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Length(20), Constraint::Min(1)])
-        .split(f.area());
+        .split(area);
 
     let left_chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -203,113 +502,509 @@ fn draw_browser(f: &mut Frame, app: &mut App) {
         .split(chunks[0]);
 
     // Filter panel
-    let filter_options = ["● All", "○ Transcripts", "○ Reports"];
+    let filter_options = ["All", "Transcripts", "Reports"];
     let filter_items: Vec<ListItem> = filter_options
         .iter()
         .enumerate()
         .map(|(i, option)| {
             let is_selected = matches!(
-                (&app.filter, i),
+                (&app.query.filter, i),
                 (FileFilter::All, 0) | (FileFilter::Transcripts, 1) | (FileFilter::Reports, 2)
             );
 
             let style = if is_selected {
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(app.theme.accent)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::White)
+                Style::default().fg(app.theme.text)
             };
 
-            let text = if is_selected {
-                option.replace("○", "●")
-            } else {
-                option.replace("●", "○")
-            };
+            let text = format!("{} {option}", symbols().radio(is_selected));
 
             ListItem::new(Line::from(Span::styled(text, style)))
         })
         .collect();
 
-    let filters =
-        List::new(filter_items).block(Block::default().borders(Borders::ALL).title("Filters"));
+    let filters = List::new(filter_items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame)
+            .title("Filters"),
+    );
     f.render_widget(filters, left_chunks[0]);
 
     // Search
-    app.search_input.render(f, left_chunks[1]);
+    app.search_input.render(f, left_chunks[1], &app.theme);
 
-    // File list
+    // Saved search chips, file list
+    let chip_rows = if app.saved_searches.searches.is_empty() {
+        0
+    } else {
+        1
+    };
     let right_chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Min(1), Constraint::Length(3)])
+        .constraints([
+            Constraint::Length(chip_rows),
+            Constraint::Min(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
         .split(chunks[1]);
 
-    app.file_list.render(f, right_chunks[0], "Files");
+    draw_chips(f, app, right_chunks[0]);
+    let title = format!(
+        "Files · sorted by {}{}",
+        app.query.sort_label(),
+        if app.listing_files {
+            " · loading…"
+        } else {
+            ""
+        }
+    );
+    if app.show_preview {
+        let list_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(55), Constraint::Min(3)])
+            .split(right_chunks[1]);
+        app.file_list.render(f, list_chunks[0], &title, &app.theme);
+        app.preview.render(f, list_chunks[1], &app.theme);
+    } else {
+        app.file_list.render(f, right_chunks[1], &title, &app.theme);
+    }
 
-    // Help
-    let help = Paragraph::new(
-        "[1-3] Filters  [Enter] Open  [Del] Delete  [Space] Select  [/] Search  [PgUp/PgDn/Home/End/Wheel] Scroll",
-    )
-    .style(Style::default().fg(Color::Gray))
+    // Help, or the overwrite prompt for a pending report batch
+    let help = if let Some(status) = &app.browser_status {
+        Paragraph::new(status.as_str()).style(Style::default().fg(app.theme.accent))
+    } else if let Some(video_ids) = &app.pending_report_batch {
+        let existing = video_ids
+            .iter()
+            .filter(|id| StorageService::report_exists(id, app.report_template))
+            .count();
+        Paragraph::new(format!(
+            "{existing} of {} {} reports already exist. Overwrite? [y] Yes  [n] Skip existing  [Esc] Cancel",
+            video_ids.len(),
+            app.report_template.name()
+        ))
+        .style(Style::default().fg(app.theme.accent))
+    } else {
+        Paragraph::new(
+            "[1-3] Filters  [Enter] Open  [r/R] Report/Regenerate  [Del] Delete  [u] Undo  [F2] Rename  [e] Edit  [p] Preview  [g] Group  [Space] Select  [a/i] All/Invert  [s/S] Sort  [/] Search  [^S] Save search  [Alt+1-9] Saved  [m] Manage",
+        )
+        .style(Style::default().fg(app.theme.secondary))
+    }
     .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
-    f.render_widget(help, right_chunks[1]);
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame),
+    );
+    f.render_widget(help, right_chunks[2]);
+    draw_status_bar(f, app, right_chunks[3]);
+
+    if let Some(prompt) = app
+        .chip_prompt
+        .as_ref()
+        .or(app.rename_prompt.as_ref().map(|(_, prompt)| prompt))
+    {
+        let area = help::centered_rect(50, 3, f.area());
+        f.render_widget(Clear, area);
+        prompt.render(f, area, &app.theme);
+    }
+    draw_chips_overlay(f, app);
+    draw_report_check(f, app);
+}
+
+/// One line of orientation: in the Browser, how many files the filter and
+/// search let through and how many are checked; everywhere, the totals for
+/// everything stored. A log file that stopped being written trumps the counts.
+fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
+    if let Some(error) = &app.log_file_error {
+        f.render_widget(
+            Paragraph::new(error.as_str()).style(Style::default().fg(app.theme.error)),
+            area,
+        );
+    } else if app.state == AppState::Browser {
+        let total = app.file_cache.len();
+        let shown = browser::unique_files(&app.file_list.items).len();
+        let checked = app.file_list.get_selected_items().len();
+        let mut narrowed = Vec::new();
+        if app.query.filter != FileFilter::All {
+            narrowed.push(format!("{} only", app.query.filter.name()));
+        }
+        if !app.query.search.trim().is_empty() {
+            narrowed.push(format!("\"{}\"", app.query.search.trim()));
+        }
+        let mut counts = format!("{shown} of {total} files");
+        if checked > 0 {
+            counts.push_str(&format!(" · {checked} selected"));
+        }
+        if !narrowed.is_empty() {
+            counts.push_str(&format!(" · {}", narrowed.join(", ")));
+        }
+        // A filter hiding files should not pass for an empty library
+        let color = if shown < total {
+            app.theme.accent
+        } else {
+            app.theme.dim
+        };
+        f.render_widget(
+            Paragraph::new(counts).style(Style::default().fg(color)),
+            area,
+        );
+    }
+
+    let stats = StorageStats::from_files(&app.file_cache);
+    let footer = format!(
+        "{} {} · {} {} · {}",
+        stats.transcripts.count,
+        if stats.transcripts.count == 1 {
+            "transcripción"
+        } else {
+            "transcripciones"
+        },
+        stats.reports.count,
+        if stats.reports.count == 1 {
+            "reporte"
+        } else {
+            "reportes"
+        },
+        format_size(stats.total_size())
+    );
+    f.render_widget(
+        Paragraph::new(footer)
+            .style(Style::default().fg(app.theme.dim))
+            .alignment(Alignment::Right),
+        area,
+    );
+}
+
+/// One chip per saved search, numbered for Alt+1..9; the active one is highlighted.
+fn draw_chips(f: &mut Frame, app: &mut App, area: Rect) {
+    app.chip_areas.clear();
+    let mut spans = Vec::new();
+    let mut x = area.x;
+    for (i, search) in app.saved_searches.searches.iter().enumerate() {
+        let label = if i < 9 {
+            format!(" {}:{} ", i + 1, search.name)
+        } else {
+            format!(" {} ", search.name)
+        };
+        let width = unicode_width::UnicodeWidthStr::width(label.as_str()) as u16;
+        let style = if app.query.chip == Some(i) {
+            Style::default()
+                .fg(Color::Black)
+                .bg(app.theme.accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(app.theme.text).bg(app.theme.highlight)
+        };
+        app.chip_areas
+            .push(Rect::new(x, area.y, width, area.height).intersection(area));
+        spans.push(Span::styled(label, style));
+        spans.push(Span::raw(" "));
+        x = x.saturating_add(width + 1);
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
 }
 
-fn draw_viewer(f: &mut Frame, app: &mut App) {
+fn draw_chips_overlay(f: &mut Frame, app: &App) {
+    let Some(overlay) = &app.chips_overlay else {
+        return;
+    };
+
+    let mut lines: Vec<Line> = app
+        .saved_searches
+        .searches
+        .iter()
+        .enumerate()
+        .map(|(i, search)| {
+            let style = if i == overlay.selected {
+                Style::default()
+                    .fg(app.theme.accent)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(app.theme.text)
+            };
+            let filter = if search.filter_is_known() {
+                search.filter().name().to_string()
+            } else {
+                "all (saved filter no longer exists)".to_string()
+            };
+            Line::from(vec![
+                Span::styled(format!(" {:<16}", search.name), style),
+                Span::styled(
+                    format!(" \"{}\" in {filter}", search.query),
+                    Style::default().fg(app.theme.secondary),
+                ),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        " [Enter] Apply  [r] Rename  [d] Delete  [Esc] Close",
+        Style::default().fg(app.theme.dim),
+    )));
+
+    let rename_rows = if overlay.rename.is_some() { 3 } else { 0 };
+    let area = help::centered_rect(60, lines.len() as u16 + 2 + rename_rows, f.area());
+    f.render_widget(Clear, area);
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Min(1), Constraint::Length(3)])
-        .split(f.area());
+        .constraints([Constraint::Min(1), Constraint::Length(rename_rows)])
+        .split(area);
+
+    let list = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame)
+            .border_style(Style::default().fg(app.theme.accent))
+            .title("Saved searches"),
+    );
+    f.render_widget(list, chunks[0]);
+    if let Some(rename) = &overlay.rename {
+        rename.render(f, chunks[1], &app.theme);
+    }
+}
+
+/// The Viewer's content and help line, split from the screen above the
+/// footer. Also used on resize to know the page size before the next frame
+/// is drawn.
+pub fn viewer_layout(area: Rect) -> [Rect; 2] {
+    Layout::vertical([Constraint::Min(1), Constraint::Length(3)]).areas(area)
+}
+
+fn draw_viewer(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = viewer_layout(area);
 
     // Content viewer
     if let Some(viewer) = &mut app.content_viewer {
         app.viewer_height = chunks[0].height;
-        viewer.render(f, chunks[0]);
+        viewer.render(f, chunks[0], &app.theme);
     }
 
     // Help
     let help = Paragraph::new(
-        "[↑↓/j k/Wheel] Scroll  [PgUp/PgDn/Space/b] Page  [Home/End/g/G] Jump  [Esc] Back",
+        "[↑↓/j k/Wheel] Scroll  [PgUp/PgDn/Space/b] Page  [Home/End/g/G] Jump  [/] Search  [r] Raw  [t] Wide tables  [L] Links  [e] Edit  [Esc] Back",
     )
-    .style(Style::default().fg(Color::Gray))
+    .style(Style::default().fg(app.theme.secondary))
     .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame),
+    );
     f.render_widget(help, chunks[1]);
 }
 
-fn draw_settings(f: &mut Frame, _app: &App) {
+fn draw_channels(f: &mut Frame, app: &App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3), // Title
-            Constraint::Min(1),    // Settings content
+            Constraint::Min(1),    // Channel list
             Constraint::Length(3), // Help
         ])
-        .split(f.area());
+        .split(area);
 
-    // Title
-    let title = Paragraph::new("Settings")
+    let title = Paragraph::new("Canales")
         .style(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(app.theme.accent)
                 .add_modifier(Modifier::BOLD),
         )
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(symbols().frame),
+        );
     f.render_widget(title, chunks[0]);
 
-    // Settings content (placeholder)
-    let settings_content = Paragraph::new("Settings coming soon...")
-        .style(Style::default().fg(Color::Gray))
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
-    f.render_widget(settings_content, chunks[1]);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(symbols().frame)
+        .title(format!("Watched channels ({})", app.channels.len()));
+    if let Some(error) = &app.channels_error {
+        let message = Paragraph::new(error.as_str())
+            .style(Style::default().fg(app.theme.error))
+            .block(block);
+        f.render_widget(message, chunks[1]);
+    } else if app.channels.is_empty() {
+        let message =
+            Paragraph::new("No channels watched. Add one with 'vidio channels add <channel>'.")
+                .style(Style::default().fg(app.theme.secondary))
+                .alignment(Alignment::Center)
+                .block(block);
+        f.render_widget(message, chunks[1]);
+    } else {
+        let items: Vec<ListItem> = app
+            .channels
+            .iter()
+            .map(|channel| {
+                let last_sync = channel.last_sync.map_or("never synced".to_string(), |at| {
+                    at.with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M")
+                        .to_string()
+                });
+                let mut spans = vec![
+                    Span::styled(
+                        format!("{:<32} ", channel.name()),
+                        Style::default().fg(app.theme.text),
+                    ),
+                    Span::styled(
+                        format!("{last_sync:<17} "),
+                        Style::default().fg(app.theme.secondary),
+                    ),
+                    Span::styled(
+                        format!("{:>3} new", channel.last_new),
+                        Style::default().fg(if channel.last_new > 0 {
+                            app.theme.success
+                        } else {
+                            app.theme.dim
+                        }),
+                    ),
+                ];
+                if let Some(error) = &channel.error {
+                    spans.push(Span::styled(
+                        format!("  error: {error}"),
+                        Style::default().fg(app.theme.error),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        f.render_widget(List::new(items).block(block), chunks[1]);
+    }
 
-    // Help
-    let help = Paragraph::new("[Esc] Back")
-        .style(Style::default().fg(Color::Gray))
+    let help = Paragraph::new("Run 'vidio channels sync' to refresh  [Esc] Back")
+        .style(Style::default().fg(app.theme.secondary))
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(symbols().frame),
+        );
     f.render_widget(help, chunks[2]);
 }
+
+fn draw_settings(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Title
+            Constraint::Length(3), // Default prompt file
+            Constraint::Length(3), // Report language
+            Constraint::Length(3), // Log file
+            Constraint::Length(3), // URL history
+            Constraint::Length(3), // Theme
+            Constraint::Min(1),    // Explanation
+            Constraint::Length(3), // Help
+        ])
+        .split(area);
+
+    // Title
+    let title = Paragraph::new("Settings")
+        .style(
+            Style::default()
+                .fg(app.theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(symbols().frame),
+        );
+    f.render_widget(title, chunks[0]);
+
+    app.prompt_file_input.render(f, chunks[1], &app.theme);
+    app.report_language_input.render(f, chunks[2], &app.theme);
+
+    let checkbox = symbols().checkbox(app.file_log_input);
+    let file_log = Paragraph::new(format!(
+        "{checkbox} Keep the processing log in {}",
+        app.log_file.path().display()
+    ))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame)
+            .title("Log file")
+            .border_style(if app.file_log_focused {
+                Style::default().fg(app.theme.accent)
+            } else {
+                Style::default().fg(app.theme.border)
+            }),
+    );
+    f.render_widget(file_log, chunks[3]);
+
+    let checkbox = symbols().checkbox(app.url_history_input);
+    let url_history = Paragraph::new(format!(
+        "{checkbox} Remember submitted URLs in history.json (↑/↓ and Ctrl+R on New Transcript)"
+    ))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame)
+            .title("URL history")
+            .border_style(if app.url_history_focused {
+                Style::default().fg(app.theme.accent)
+            } else {
+                Style::default().fg(app.theme.border)
+            }),
+    );
+    f.render_widget(url_history, chunks[4]);
+
+    let theme = Paragraph::new(format!(
+        "\u{25c2} {} \u{25b8}  (themes come from [themes] in vidio.toml)",
+        app.theme_name
+    ))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame)
+            .title("Theme")
+            .border_style(if app.theme_focused {
+                Style::default().fg(app.theme.accent)
+            } else {
+                Style::default().fg(app.theme.border)
+            }),
+    );
+    f.render_widget(theme, chunks[5]);
+
+    let explanation = Paragraph::new(
+        "The prompt file is used by the custom report template. It must contain \
+         {{transcript}} and may use {{title}}, {{video_id}} and {{language}}. \
+         Reports are written in the report language, or else in the transcript's. \
+         Leave a field empty to clear it. The log file keeps the last 2 MB of \
+         processing messages for troubleshooting.",
+    )
+    .style(Style::default().fg(app.theme.secondary))
+    .wrap(Wrap { trim: true })
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame),
+    );
+    f.render_widget(explanation, chunks[6]);
+
+    // Help, or the outcome of the last save
+    let help = match &app.settings_status {
+        Some(status) => {
+            Paragraph::new(status.as_str()).style(Style::default().fg(app.theme.accent))
+        }
+        None => Paragraph::new(
+            "[Tab] Next field  [Space] Toggle  [←/→] Theme  [Enter] Save  [Esc] Back",
+        )
+        .style(Style::default().fg(app.theme.secondary)),
+    }
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(symbols().frame),
+    );
+    f.render_widget(help, chunks[7]);
+}
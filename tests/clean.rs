@@ -0,0 +1,199 @@
+use chrono::Local;
+use std::fs::{self, File};
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, SystemTime};
+
+fn vidio(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_vidio"))
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .output()
+        .expect("failed to run vidio")
+}
+
+/// A data directory with one video stored 60 days ago and one stored now.
+fn data_dir() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("transcripts")).unwrap();
+    fs::create_dir(dir.path().join("reports")).unwrap();
+
+    let old = SystemTime::now() - Duration::from_secs(60 * 24 * 60 * 60);
+    for (name, modified) in [
+        ("transcripts/transcript_oldoldoldol.txt", Some(old)),
+        ("transcripts/meta_oldoldoldol.json", None),
+        ("reports/report_oldoldoldol.md", Some(old)),
+        ("transcripts/transcript_newnewnewne.txt", None),
+        ("transcripts/bookmarks_newnewnewne.json", None),
+        ("reports/report_newnewnewne.md", None),
+    ] {
+        let path = dir.path().join(name);
+        fs::write(&path, "x".repeat(2048)).unwrap();
+        if let Some(modified) = modified {
+            File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        }
+    }
+    dir
+}
+
+#[test]
+fn dry_run_lists_old_files_without_deleting() {
+    let dir = data_dir();
+    let output = vidio(dir.path(), &["clean", "--older-than", "30d", "--dry-run"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert!(stdout.contains("transcript_oldoldoldol.txt"));
+    assert!(stdout.contains("meta_oldoldoldol.json"));
+    assert!(stdout.contains("report_oldoldoldol.md"));
+    assert!(!stdout.contains("newnewnewne"));
+    assert!(
+        stdout.contains("Would delete 3 files (6KB)"),
+        "stdout: {stdout}"
+    );
+    assert!(dir.path().join("reports/report_oldoldoldol.md").exists());
+}
+
+#[test]
+fn clean_removes_old_files_and_reports_reclaimed_space() {
+    let dir = data_dir();
+    fs::write(dir.path().join("vidio.toml"), "safe_delete = false\n").unwrap();
+    let output = vidio(dir.path(), &["clean", "--older-than", "30d", "--yes"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert!(
+        stdout.contains("Deleted 3 files (6KB reclaimed)"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        !dir.path()
+            .join("transcripts/transcript_oldoldoldol.txt")
+            .exists()
+    );
+    assert!(
+        !dir.path()
+            .join("transcripts/meta_oldoldoldol.json")
+            .exists()
+    );
+    assert!(!dir.path().join("reports/report_oldoldoldol.md").exists());
+    assert!(
+        dir.path()
+            .join("transcripts/transcript_newnewnewne.txt")
+            .exists()
+    );
+    assert!(dir.path().join("reports/report_newnewnewne.md").exists());
+}
+
+#[test]
+fn delete_without_a_terminal_needs_yes() {
+    let dir = data_dir();
+    let output = vidio(dir.path(), &["delete", "newnewnewne"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--yes"));
+    assert!(dir.path().join("reports/report_newnewnewne.md").exists());
+}
+
+#[test]
+fn delete_can_keep_either_side() {
+    let dir = data_dir();
+    let output = vidio(
+        dir.path(),
+        &["delete", "newnewnewne", "--report-only", "-y"],
+    );
+    assert!(output.status.success());
+    assert!(!dir.path().join("reports/report_newnewnewne.md").exists());
+    assert!(
+        dir.path()
+            .join("transcripts/transcript_newnewnewne.txt")
+            .exists()
+    );
+
+    let output = vidio(
+        dir.path(),
+        &["delete", "newnewnewne", "--transcript-only", "-y"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Moved 2 files (4KB) to the trash"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        !dir.path()
+            .join("transcripts/transcript_newnewnewne.txt")
+            .exists()
+    );
+    assert!(
+        !dir.path()
+            .join("transcripts/bookmarks_newnewnewne.json")
+            .exists()
+    );
+
+    let output = vidio(dir.path(), &["delete", "newnewnewne", "-y"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Nothing stored"));
+}
+
+#[test]
+fn deleted_files_can_be_restored_from_the_trash() {
+    let dir = data_dir();
+    let output = vidio(dir.path(), &["delete", "oldoldoldol", "-y"]);
+    assert!(output.status.success());
+    assert!(!dir.path().join("reports/report_oldoldoldol.md").exists());
+
+    let output = vidio(dir.path(), &["trash", "list", "--json"]);
+    let entries: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let entries = entries.as_array().unwrap();
+    assert_eq!(entries.len(), 3);
+    let report = entries
+        .iter()
+        .find(|entry| entry["original"] == "reports/report_oldoldoldol.md")
+        .unwrap();
+
+    let name = report["name"].as_str().unwrap();
+    let output = vidio(dir.path(), &["trash", "restore", name]);
+    assert!(output.status.success());
+    assert!(dir.path().join("reports/report_oldoldoldol.md").exists());
+
+    let output = vidio(dir.path(), &["trash", "restore", name]);
+    assert!(!output.status.success());
+
+    let output = vidio(dir.path(), &["trash", "empty", "-y"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Deleted 2 files"), "stdout: {stdout}");
+    let output = vidio(dir.path(), &["trash", "list"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("The trash is empty"));
+}
+
+#[test]
+fn expired_trash_is_purged_on_startup() {
+    let dir = data_dir();
+    fs::create_dir(dir.path().join(".trash")).unwrap();
+    let expired = dir
+        .path()
+        .join(".trash/report_gonegonegon.md.20200101-120000");
+    let recent = Local::now().format("%Y%m%d-%H%M%S");
+    let recent = dir
+        .path()
+        .join(format!(".trash/report_keptkeptkep.md.{recent}"));
+    fs::write(&expired, "x").unwrap();
+    fs::write(&recent, "x").unwrap();
+
+    // The trash commands leave it as listed
+    let output = vidio(dir.path(), &["trash", "list"]);
+    assert!(output.status.success());
+    assert!(expired.exists());
+
+    let output = vidio(dir.path(), &["list"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert!(!expired.exists());
+    assert!(recent.exists());
+}
@@ -0,0 +1,53 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn vidio(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_vidio"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("failed to run vidio")
+}
+
+#[test]
+fn export_site_is_incremental() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("transcripts")).unwrap();
+    fs::create_dir(dir.path().join("reports")).unwrap();
+    fs::write(
+        dir.path().join("transcripts/transcript_abcdefghijk.txt"),
+        "[00:00.000 - 00:01.000] Hello\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("reports/report_abcdefghijk.md"),
+        "# Notes\n",
+    )
+    .unwrap();
+
+    let args = ["export-site", "--dest", "site", "--engine", "mdbook"];
+    let output = vidio(dir.path(), &args);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert!(
+        stdout.contains("(1 updated, 0 unchanged)"),
+        "stdout: {stdout}"
+    );
+    assert!(dir.path().join("site/src/SUMMARY.md").exists());
+    assert!(dir.path().join("site/src/videos/abcdefghijk.md").exists());
+
+    let output = vidio(dir.path(), &args);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("(0 updated, 1 unchanged)"),
+        "stdout: {stdout}"
+    );
+
+    let output = vidio(
+        dir.path(),
+        &["export-site", "--dest", "site", "--engine", "jekyll"],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("expected hugo, zola or mdbook"));
+}
@@ -0,0 +1,407 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+const VIDEO_ID: &str = "dQw4w9WgXcQ";
+const OLD_REPORT: &str = "Reporte anterior";
+const NEW_REPORT: &str = "Reporte nuevo";
+
+/// A stand-in for a local OpenAI-compatible server without the Responses
+/// API, streaming [`NEW_REPORT`] in answer to every chat completion, or
+/// to every chat on Ollama's own API. With `cut_streams` the chat stream
+/// stops before its last chunk, as when a connection drops.
+struct FakeServer {
+    root: String,
+    url: String,
+    completions: Arc<AtomicUsize>,
+}
+
+impl FakeServer {
+    fn start() -> Self {
+        Self::serve(false)
+    }
+
+    fn cutting_streams() -> Self {
+        Self::serve(true)
+    }
+
+    fn serve(cut_streams: bool) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let root = format!("http://{}", listener.local_addr().unwrap());
+        let url = format!("{root}/v1");
+        let completions = Arc::new(AtomicUsize::new(0));
+        let counter = completions.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                answer(stream, &counter, cut_streams);
+            }
+        });
+        Self {
+            root,
+            url,
+            completions,
+        }
+    }
+
+    fn completions(&self) -> usize {
+        self.completions.load(Ordering::SeqCst)
+    }
+}
+
+fn answer(mut stream: TcpStream, completions: &AtomicUsize, cut_streams: bool) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).unwrap();
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            length = value.trim().parse().unwrap();
+        }
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).unwrap();
+
+    let (status, content_type, body) = if request_line.contains("/chat/completions") {
+        completions.fetch_add(1, Ordering::SeqCst);
+        let mut events = String::new();
+        let words: Vec<&str> = NEW_REPORT.split_inclusive(' ').collect();
+        for (i, word) in words.iter().enumerate() {
+            let last = i + 1 == words.len();
+            if last && cut_streams {
+                break;
+            }
+            let chunk = serde_json::json!({
+                "id": "chatcmpl-1",
+                "object": "chat.completion.chunk",
+                "created": 0,
+                "model": "llama3",
+                "choices": [{
+                    "index": 0,
+                    "delta": { "content": word },
+                    "finish_reason": if last { Some("stop") } else { None }
+                }]
+            });
+            events.push_str(&format!("data: {chunk}\n\n"));
+        }
+        if !cut_streams {
+            events.push_str("data: [DONE]\n\n");
+        }
+        ("200 OK", "text/event-stream", events)
+    } else if request_line.contains("/api/chat") {
+        completions.fetch_add(1, Ordering::SeqCst);
+        let mut lines = String::new();
+        for word in NEW_REPORT.split_inclusive(' ') {
+            let line = serde_json::json!({
+                "model": "llama3",
+                "message": { "role": "assistant", "content": word },
+                "done": false
+            });
+            lines.push_str(&format!("{line}\n"));
+        }
+        let done = serde_json::json!({
+            "model": "llama3",
+            "message": { "role": "assistant", "content": "" },
+            "done": true,
+            "prompt_eval_count": 120,
+            "eval_count": 4
+        });
+        lines.push_str(&format!("{done}\n"));
+        ("200 OK", "application/x-ndjson", lines)
+    } else {
+        (
+            "404 Not Found",
+            "text/plain",
+            "404 page not found".to_string(),
+        )
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+}
+
+/// A data directory with a saved transcript, reporting through `server`.
+fn data_dir(server: &FakeServer, report: Option<&str>) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("transcripts")).unwrap();
+    fs::create_dir(dir.path().join("reports")).unwrap();
+    fs::write(
+        dir.path()
+            .join(format!("transcripts/transcript_{VIDEO_ID}.txt")),
+        "[00:00:01] Hola a todos\n[00:00:05] Hoy hablamos de Rust\n",
+    )
+    .unwrap();
+    if let Some(report) = report {
+        fs::write(report_path(dir.path()), report).unwrap();
+    }
+    fs::write(
+        dir.path().join("vidio.toml"),
+        format!(
+            "[openai]\nmodel = \"llama3\"\nbase_url = \"{}\"\n",
+            server.url
+        ),
+    )
+    .unwrap();
+    dir
+}
+
+fn report_path(dir: &Path) -> std::path::PathBuf {
+    dir.join(format!("reports/report_{VIDEO_ID}.md"))
+}
+
+fn vidio(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_vidio"))
+        .args(args)
+        .current_dir(dir)
+        .env_remove("VIDIO_MODEL")
+        .env_remove("OPENAI_API_KEY")
+        .env_remove("YTRANSCRIPT_ALLOW_OPENAI")
+        .stdin(Stdio::null())
+        .output()
+        .expect("failed to run vidio")
+}
+
+/// The saved report, checking it records which model and template wrote it.
+fn saved_report(dir: &Path) -> String {
+    let report = fs::read_to_string(report_path(dir)).unwrap();
+    assert!(
+        report.starts_with("---\nmodel: llama3\ntemplate: detailed\ncreated_at: "),
+        "{report}"
+    );
+    let (_, body) = report.split_once("\n---\n\n").unwrap();
+    body.to_string()
+}
+
+fn history(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir.join("reports/history")) else {
+        return Vec::new();
+    };
+    entries
+        .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect()
+}
+
+#[test]
+fn saved_transcript_gets_a_report() {
+    let server = FakeServer::start();
+    let dir = data_dir(&server, None);
+    let output = vidio(dir.path(), &["get", VIDEO_ID, "--report"]);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {stderr}");
+    assert!(stderr.contains("Transcript already saved. Skipping download."));
+    assert_eq!(server.completions(), 1);
+    assert_eq!(saved_report(dir.path()), NEW_REPORT);
+
+    let output = vidio(dir.path(), &["info", VIDEO_ID]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Generated:   llama3 on "),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn cut_off_stream_is_retried_and_never_saved() {
+    let server = FakeServer::cutting_streams();
+    let dir = data_dir(&server, None);
+    let config = dir.path().join("vidio.toml");
+    let mut toml = fs::read_to_string(&config).unwrap();
+    toml.push_str("retries = 1\n");
+    fs::write(&config, toml).unwrap();
+
+    let output = vidio(dir.path(), &["get", VIDEO_ID, "--report"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "stderr: {stderr}");
+    assert!(
+        stderr.contains("ended before the report was finished"),
+        "{stderr}"
+    );
+    assert_eq!(server.completions(), 2);
+    assert!(!report_path(dir.path()).exists());
+}
+
+#[test]
+fn missing_api_key_fails_before_the_download() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("vidio.toml"),
+        "[openai]\nmodel = \"gpt-5.2\"\n",
+    )
+    .unwrap();
+    let output = vidio(dir.path(), &["get", VIDEO_ID, "--report"]);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("set OPENAI_API_KEY"), "stderr: {stderr}");
+    assert!(!stderr.contains("Fetching transcript"), "stderr: {stderr}");
+    assert!(!dir.path().join("transcripts").exists());
+}
+
+#[test]
+fn ollama_backend_reports_through_its_chat_api() {
+    let server = FakeServer::start();
+    let dir = data_dir(&server, None);
+    fs::write(
+        dir.path().join("vidio.toml"),
+        format!(
+            "[openai]\nbackend = \"ollama\"\nmodel = \"llama3\"\nbase_url = \"{}\"\n",
+            server.root
+        ),
+    )
+    .unwrap();
+    let output = vidio(dir.path(), &["get", VIDEO_ID, "--report"]);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert_eq!(server.completions(), 1);
+    assert_eq!(saved_report(dir.path()), NEW_REPORT);
+    let report = fs::read_to_string(report_path(dir.path())).unwrap();
+    assert!(
+        report.contains("\ninput_tokens: 120\noutput_tokens: 4\n"),
+        "{report}"
+    );
+}
+
+#[test]
+fn resumed_batch_skips_videos_already_done() {
+    let server = FakeServer::start();
+    let dir = data_dir(&server, None);
+    // The first video was reported before the run stopped; its transcript
+    // has since been deleted, so fetching it again would fail
+    let manifest = dir.path().join("batch_20261016-120000.json");
+    fs::write(
+        &manifest,
+        serde_json::json!({
+            "started_at": "2026-10-16T10:00:00Z",
+            "report": true,
+            "videos": [
+                { "video_id": "aaaaaaaaaaa", "status": "reported" },
+                { "video_id": VIDEO_ID, "status": "failed", "error": "Connection reset" }
+            ]
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    let output = vidio(dir.path(), &["get", "--resume", "--report"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {stderr}");
+    assert!(stderr.contains("1 of 2 videos already done"), "{stderr}");
+    assert!(!stderr.contains("aaaaaaaaaaa"), "{stderr}");
+    assert_eq!(server.completions(), 1);
+    assert_eq!(saved_report(dir.path()), NEW_REPORT);
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&manifest).unwrap()).unwrap();
+    assert_eq!(manifest["videos"][1]["status"], "reported");
+    let output = vidio(dir.path(), &["get", "--resume"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No unfinished batch"));
+}
+
+#[test]
+fn dry_run_prints_the_prompt_without_sending_it() {
+    let server = FakeServer::start();
+    let dir = data_dir(&server, None);
+    for args in [
+        &["get", VIDEO_ID, "--report", "--dry-run"][..],
+        &["report", VIDEO_ID, "--dry-run"],
+    ] {
+        let output = vidio(dir.path(), args);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(output.status.success(), "stdout: {stdout}");
+        assert!(stdout.starts_with(&format!(
+            "===== {VIDEO_ID}: llama3 =====\n----- system -----\n"
+        )));
+        assert!(stdout.contains("----- user -----\n"), "{stdout}");
+        assert!(
+            stdout.contains("[00:00:05] Hoy hablamos de Rust"),
+            "{stdout}"
+        );
+    }
+    assert_eq!(server.completions(), 0);
+    assert!(!report_path(dir.path()).exists());
+}
+
+#[test]
+fn existing_report_is_skipped() {
+    let server = FakeServer::start();
+    let dir = data_dir(&server, Some(OLD_REPORT));
+    let output = vidio(dir.path(), &["get", VIDEO_ID, "--report"]);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {stderr}");
+    assert!(stderr.contains("Report up to date as well."));
+    assert_eq!(server.completions(), 0);
+    assert_eq!(
+        fs::read_to_string(report_path(dir.path())).unwrap(),
+        OLD_REPORT
+    );
+    assert!(history(dir.path()).is_empty());
+
+    let output = vidio(dir.path(), &["get", VIDEO_ID, "--report", "--quiet"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+}
+
+#[test]
+fn report_is_generated_again_only_for_changed_inputs() {
+    let server = FakeServer::start();
+    let dir = data_dir(&server, None);
+    assert!(vidio(dir.path(), &["report", VIDEO_ID]).status.success());
+    assert_eq!(server.completions(), 1);
+
+    let output = vidio(dir.path(), &["report", VIDEO_ID]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {stderr}");
+    assert!(stderr.contains("Report up to date"), "{stderr}");
+    assert_eq!(server.completions(), 1);
+
+    fs::write(
+        dir.path()
+            .join(format!("transcripts/transcript_{VIDEO_ID}.txt")),
+        "[00:00:01] Hola a todos\n[00:00:05] Hoy hablamos de Go\n",
+    )
+    .unwrap();
+    let output = vidio(dir.path(), &["get", VIDEO_ID, "--report"]);
+    assert!(output.status.success());
+    assert_eq!(server.completions(), 2);
+    assert_eq!(history(dir.path()).len(), 1);
+
+    // Another model or language
+    for args in [&["--model", "llama3.1"][..], &["--report-language", "en"]] {
+        let output = vidio(dir.path(), &[&["report", VIDEO_ID][..], args].concat());
+        assert!(output.status.success());
+    }
+    assert_eq!(server.completions(), 4);
+}
+
+#[test]
+fn force_replaces_the_report_and_keeps_the_old_one() {
+    let server = FakeServer::start();
+    let dir = data_dir(&server, Some(OLD_REPORT));
+    let output = vidio(dir.path(), &["get", VIDEO_ID, "--report", "--force"]);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {stderr}");
+    assert!(stderr.contains("Generating report..."));
+    assert_eq!(server.completions(), 1);
+    assert_eq!(saved_report(dir.path()), NEW_REPORT);
+    assert_eq!(history(dir.path()), [OLD_REPORT]);
+}
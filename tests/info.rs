@@ -0,0 +1,58 @@
+use std::fs;
+use std::process::Command;
+
+fn vidio_info(dir: &std::path::Path, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_vidio"))
+        .arg("info")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("failed to run vidio")
+}
+
+#[test]
+fn json_combines_files_and_metadata_sidecar() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("transcripts")).unwrap();
+    fs::write(
+        dir.path().join("transcripts/transcript_zzzzzzzzzzz.txt"),
+        "[00:00.000 - 00:01.000] hola",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("transcripts/meta_zzzzzzzzzzz.json"),
+        r#"{"language":"Spanish","language_code":"es","is_generated":true,"fetched_at":"2026-10-01T12:00:00Z"}"#,
+    )
+    .unwrap();
+
+    let output = vidio_info(
+        dir.path(),
+        &["https://www.youtube.com/watch?v=zzzzzzzzzzz", "--json"],
+    );
+    assert!(output.status.success());
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert_eq!(info["video_id"], "zzzzzzzzzzz");
+    assert_eq!(info["transcript"]["exists"], true);
+    assert_eq!(info["transcript"]["size"], 28);
+    assert_eq!(info["report"]["exists"], false);
+    assert_eq!(info["report"]["size"], serde_json::Value::Null);
+    assert_eq!(info["metadata"]["language_code"], "es");
+    assert_eq!(info["metadata"]["is_generated"], true);
+}
+
+#[test]
+fn table_reports_missing_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = vidio_info(dir.path(), &["zzzzzzzzzzz"]);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert!(
+        stdout.contains("Video:       zzzzzzzzzzz"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("transcript_zzzzzzzzzzz.txt (missing)"));
+    assert!(stdout.contains("report_zzzzzzzzzzz.md (missing)"));
+    assert!(!stdout.contains("Language:"));
+}
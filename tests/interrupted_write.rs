@@ -0,0 +1,51 @@
+use std::fs;
+use std::process::Command;
+
+/// A zero-byte transcript left by an interrupted write must not be treated as
+/// already downloaded. Reaching the fetch is enough, but that still asks
+/// YouTube, so run it with `cargo test -- --ignored` when online.
+#[test]
+#[ignore = "contacts YouTube"]
+fn empty_transcript_is_fetched_again() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("transcripts")).unwrap();
+    fs::write(
+        dir.path().join("transcripts/transcript_zzzzzzzzzzz.txt"),
+        "",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vidio"))
+        .args(["get", "zzzzzzzzzzz"])
+        .current_dir(dir.path())
+        .output()
+        .expect("failed to run vidio");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Fetching transcript..."),
+        "stderr: {stderr}"
+    );
+    assert!(!stderr.contains("already"), "stderr: {stderr}");
+}
+
+#[test]
+fn non_empty_transcript_is_kept() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("transcripts")).unwrap();
+    fs::write(
+        dir.path().join("transcripts/transcript_zzzzzzzzzzz.txt"),
+        "[00:00.000 - 00:01.000] hola",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vidio"))
+        .args(["get", "zzzzzzzzzzz"])
+        .current_dir(dir.path())
+        .output()
+        .expect("failed to run vidio");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {stderr}");
+    assert!(stderr.contains("Transcript already exists locally"));
+}
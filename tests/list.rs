@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn vidio_list(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_vidio"))
+        .arg("list")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("failed to run vidio")
+}
+
+#[test]
+fn saved_search_filters_the_listing() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("transcripts")).unwrap();
+    fs::create_dir(dir.path().join("reports")).unwrap();
+    for name in [
+        "transcripts/transcript_rustconf001.txt",
+        "reports/report_rustconf001.md",
+        "reports/report_gophercon1.md",
+    ] {
+        fs::write(dir.path().join(name), "x").unwrap();
+    }
+    fs::write(
+        dir.path().join("saved_searches.json"),
+        r#"{"searches":[{"name":"Rust reports","query":"rust","filter":"reports"}]}"#,
+    )
+    .unwrap();
+
+    let output = vidio_list(dir.path(), &["--saved", "rust reports"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert!(stdout.contains("Found 1 files"), "stdout: {stdout}");
+    assert!(stdout.contains("report_rustconf001.md"));
+
+    let output = vidio_list(dir.path(), &["--saved", "go"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("saved: Rust reports"), "stderr: {stderr}");
+}
+
+#[test]
+fn titles_from_the_metadata_sidecar_replace_file_names() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("transcripts")).unwrap();
+    fs::create_dir(dir.path().join("reports")).unwrap();
+    for name in [
+        "transcripts/transcript_5_EJwYeQusM.txt",
+        "reports/report_5_EJwYeQusM.md",
+        "reports/report_untitled01.md",
+    ] {
+        fs::write(dir.path().join(name), "x").unwrap();
+    }
+    fs::write(
+        dir.path().join("transcripts/meta_5_EJwYeQusM.json"),
+        r#"{"language":"English","language_code":"en","is_generated":true,"fetched_at":"2026-09-21T14:13:20Z","title":"Rust in 100 Seconds"}"#,
+    )
+    .unwrap();
+
+    let output = vidio_list(dir.path(), &[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert_eq!(
+        stdout.matches("Rust in 100 Seconds (5_EJwYeQusM)").count(),
+        2,
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("report_untitled01.md"));
+
+    // Only the transcript itself came from auto-generated captions
+    let output = vidio_list(dir.path(), &["--json"]);
+    let files: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let auto_generated: Vec<&str> = files
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|file| file["auto_generated"] == true)
+        .map(|file| file["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(auto_generated, ["transcript_5_EJwYeQusM.txt"]);
+}
@@ -0,0 +1,70 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn run_prompt_test(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_vidio"))
+        .arg("prompt-test")
+        .args(args)
+        .current_dir(dir)
+        .env_remove("YTRANSCRIPT_ALLOW_OPENAI")
+        .output()
+        .expect("failed to run vidio")
+}
+
+fn results(dir: &Path) -> Vec<serde_json::Value> {
+    fs::read_to_string(dir.join("prompt-tests.jsonl"))
+        .expect("results file")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("valid JSON line"))
+        .collect()
+}
+
+#[test]
+fn demo_fixture_with_canned_response_passes() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run_prompt_test(dir.path(), &["detailed", "--canned", "demo"]);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert!(stdout.contains("Result: PASS"));
+
+    let records = results(dir.path());
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["passed"], true);
+    assert_eq!(records[0]["template"], "detailed");
+}
+
+#[test]
+fn truncated_canned_response_fails_and_is_recorded() {
+    let dir = tempfile::tempdir().unwrap();
+    let canned = dir.path().join("truncated.md");
+    fs::write(
+        &canned,
+        "#### 1. Metadata\n| Campo | Valor |\n|-------|-------|\n| Duración aproximada | 1 min |\n",
+    )
+    .unwrap();
+
+    let canned = canned.to_string_lossy().to_string();
+    let first = run_prompt_test(dir.path(), &["--canned", &canned]);
+    let second = run_prompt_test(dir.path(), &["--canned", "demo", "--expect", "basic"]);
+
+    assert!(!first.status.success());
+    assert!(String::from_utf8_lossy(&first.stdout).contains("[FAIL] section 2 present"));
+    assert!(second.status.success());
+
+    let records = results(dir.path());
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["passed"], false);
+    assert_eq!(records[1]["profile"], "basic");
+}
+
+#[test]
+fn live_run_requires_openai_opt_in() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run_prompt_test(dir.path(), &["detailed"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("explicit opt-in"));
+    assert!(!dir.path().join("prompt-tests.jsonl").exists());
+}